@@ -37,9 +37,9 @@ fn main() {
             println!();
 
             if tokens.len() >= 8 {
-                let expected = vec![
+                let expected = [
                     TokenKind::Keyword(Keyword::Let),
-                    TokenKind::Identifier("x".to_string()),
+                    TokenKind::Identifier("x".into()),
                     TokenKind::Assign,
                     TokenKind::Number(45.0),
                     TokenKind::Plus,
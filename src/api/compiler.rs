@@ -1,12 +1,13 @@
 use crate::bytecode::optimizer::BytecodeOptimizer;
 use crate::bytecode::BytecodeGenerator;
 
-use crate::parser::Parser;
+use crate::parser::{Parser, ParserOptions};
 use crate::semantic::SemanticAnalyzer;
 use crate::vm::instructions::Instruction;
 
 pub struct Compiler {
     optimize: bool,
+    parser_options: ParserOptions,
 }
 
 impl Default for Compiler {
@@ -17,7 +18,10 @@ impl Default for Compiler {
 
 impl Compiler {
     pub fn new() -> Self {
-        Self { optimize: false }
+        Self {
+            optimize: false,
+            parser_options: ParserOptions::default(),
+        }
     }
 
     pub fn with_optimization(mut self, optimize: bool) -> Self {
@@ -25,8 +29,13 @@ impl Compiler {
         self
     }
 
+    pub fn with_parser_options(mut self, parser_options: ParserOptions) -> Self {
+        self.parser_options = parser_options;
+        self
+    }
+
     pub fn compile(&mut self, source: &str) -> Result<Vec<Instruction>, String> {
-        let mut parser = Parser::new(source);
+        let mut parser = Parser::with_options(source, self.parser_options);
         let ast = parser.parse().map_err(|e| format!("Parser error: {e}"))?;
 
         let mut analyzer = SemanticAnalyzer::new();
@@ -36,6 +45,9 @@ impl Compiler {
 
         let mut generator = BytecodeGenerator::new();
         let mut instructions = generator.generate(&ast);
+        if let Some(error) = generator.errors().first() {
+            return Err(format!("Compile error: {error}"));
+        }
 
         if self.optimize {
             instructions = BytecodeOptimizer::optimize(instructions);
@@ -48,7 +60,7 @@ impl Compiler {
         &mut self,
         source: &str,
     ) -> Result<(Vec<Instruction>, Vec<String>), String> {
-        let mut parser = Parser::new(source);
+        let mut parser = Parser::with_options(source, self.parser_options);
         let ast = parser.parse().map_err(|e| format!("Parser error: {e}"))?;
 
         let mut analyzer = SemanticAnalyzer::new();
@@ -58,6 +70,9 @@ impl Compiler {
 
         let mut generator = BytecodeGenerator::new();
         let mut instructions = generator.generate(&ast);
+        if let Some(error) = generator.errors().first() {
+            return Err(format!("Compile error: {error}"));
+        }
         let constants = generator.get_constants().clone();
 
         if self.optimize {
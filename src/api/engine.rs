@@ -32,6 +32,9 @@ impl Engine {
 
         let mut generator = BytecodeGenerator::new();
         let instructions = generator.generate(&ast);
+        if let Some(error) = generator.errors().first() {
+            return Err(format!("Compile error: {error}"));
+        }
         let constants = generator.get_constants().clone();
 
         let values: Vec<Value> = constants
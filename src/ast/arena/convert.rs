@@ -0,0 +1,1022 @@
+use super::{AstArena, NodeId, NodeKind, NodeList, Symbol};
+use crate::ast::*;
+
+impl AstArena {
+    // Copies `node` and everything below it into the arena and returns the
+    // id of its root.
+    pub fn alloc_node(&mut self, node: &Node) -> NodeId {
+        let kind = match node {
+            Node::Program(program) => NodeKind::Program {
+                body: self.alloc_nodes(&program.body),
+                source_type: program.source_type,
+            },
+            Node::VariableDeclaration(decl) => {
+                let mark = self.scratch.len();
+                for declarator in &decl.declarations {
+                    let kind = NodeKind::VariableDeclarator {
+                        id: self.alloc_node(&declarator.id),
+                        init: self.alloc_opt(&declarator.init),
+                    };
+                    let id = self.alloc(kind, declarator.span.clone());
+                    self.scratch.push(id);
+                }
+                NodeKind::VariableDeclaration {
+                    kind: self.intern(&decl.kind),
+                    declarations: self.take_scratch(mark),
+                }
+            }
+            Node::FunctionDeclaration(decl) => NodeKind::FunctionDeclaration {
+                id: self.alloc_opt(&decl.id),
+                params: self.alloc_nodes(&decl.params),
+                body: self.alloc_node(&decl.body),
+                generator: decl.generator,
+                r#async: decl.r#async,
+            },
+            Node::ClassDeclaration(decl) => NodeKind::ClassDeclaration {
+                id: self.alloc_opt(&decl.id),
+                super_class: self.alloc_opt(&decl.super_class),
+                body: self.alloc_node(&decl.body),
+            },
+            Node::ClassBody(body) => NodeKind::ClassBody {
+                body: self.alloc_nodes(&body.body),
+            },
+            Node::MethodDefinition(method) => NodeKind::MethodDefinition {
+                key: self.alloc_node(&method.key),
+                value: self.alloc_node(&method.value),
+                kind: method.kind,
+                computed: method.computed,
+                r#static: method.r#static,
+            },
+            Node::PropertyDefinition(prop) => NodeKind::PropertyDefinition {
+                key: self.alloc_node(&prop.key),
+                value: self.alloc_opt(&prop.value),
+                computed: prop.computed,
+                r#static: prop.r#static,
+            },
+            Node::StaticBlock(block) => NodeKind::StaticBlock {
+                body: self.alloc_nodes(&block.body),
+            },
+            Node::ImportDeclaration(decl) => NodeKind::ImportDeclaration {
+                specifiers: self.alloc_nodes(&decl.specifiers),
+                source: self.alloc_node(&decl.source),
+                attributes: self.alloc_attributes(&decl.attributes),
+            },
+            Node::ExportDeclaration(decl) => NodeKind::ExportDeclaration {
+                declaration: self.alloc_opt(&decl.declaration),
+                specifiers: self.alloc_nodes(&decl.specifiers),
+                source: self.alloc_opt(&decl.source),
+                attributes: self.alloc_attributes(&decl.attributes),
+                default: decl.default,
+            },
+            Node::ImportSpecifier(spec) => NodeKind::ImportSpecifier {
+                local: self.alloc_node(&spec.local),
+                imported: self.alloc_node(&spec.imported),
+            },
+            Node::ImportDefaultSpecifier(spec) => NodeKind::ImportDefaultSpecifier {
+                local: self.alloc_node(&spec.local),
+            },
+            Node::ImportNamespaceSpecifier(spec) => NodeKind::ImportNamespaceSpecifier {
+                local: self.alloc_node(&spec.local),
+            },
+            Node::ExportSpecifier(spec) => NodeKind::ExportSpecifier {
+                local: self.alloc_node(&spec.local),
+                exported: self.alloc_node(&spec.exported),
+            },
+            Node::ExportNamespaceSpecifier(spec) => NodeKind::ExportNamespaceSpecifier {
+                exported: self.alloc_opt(&spec.exported),
+            },
+
+            Node::BinaryExpression(expr) => NodeKind::BinaryExpression {
+                left: self.alloc_node(&expr.left),
+                operator: self.intern(&expr.operator),
+                right: self.alloc_node(&expr.right),
+            },
+            Node::UnaryExpression(expr) => NodeKind::UnaryExpression {
+                operator: self.intern(&expr.operator),
+                argument: self.alloc_node(&expr.argument),
+                prefix: expr.prefix,
+            },
+            Node::UpdateExpression(expr) => NodeKind::UpdateExpression {
+                operator: self.intern(&expr.operator),
+                argument: self.alloc_node(&expr.argument),
+                prefix: expr.prefix,
+            },
+            Node::LogicalExpression(expr) => NodeKind::LogicalExpression {
+                left: self.alloc_node(&expr.left),
+                operator: self.intern(&expr.operator),
+                right: self.alloc_node(&expr.right),
+            },
+            Node::ConditionalExpression(expr) => NodeKind::ConditionalExpression {
+                test: self.alloc_node(&expr.test),
+                consequent: self.alloc_node(&expr.consequent),
+                alternate: self.alloc_node(&expr.alternate),
+            },
+            Node::AssignmentExpression(expr) => NodeKind::AssignmentExpression {
+                left: self.alloc_node(&expr.left),
+                operator: self.intern(&expr.operator),
+                right: self.alloc_node(&expr.right),
+            },
+            Node::CallExpression(expr) => NodeKind::CallExpression {
+                callee: self.alloc_node(&expr.callee),
+                arguments: self.alloc_nodes(&expr.arguments),
+            },
+            Node::NewExpression(expr) => NodeKind::NewExpression {
+                callee: self.alloc_node(&expr.callee),
+                arguments: self.alloc_nodes(&expr.arguments),
+            },
+            Node::MemberExpression(expr) => NodeKind::MemberExpression {
+                object: self.alloc_node(&expr.object),
+                property: self.alloc_node(&expr.property),
+                computed: expr.computed,
+                optional: expr.optional,
+            },
+            Node::ArrowFunctionExpression(expr) => NodeKind::ArrowFunctionExpression {
+                params: self.alloc_nodes(&expr.params),
+                body: self.alloc_node(&expr.body),
+                expression: expr.expression,
+                r#async: expr.r#async,
+            },
+            Node::FunctionExpression(expr) => NodeKind::FunctionExpression {
+                id: self.alloc_opt(&expr.id),
+                params: self.alloc_nodes(&expr.params),
+                body: self.alloc_node(&expr.body),
+                generator: expr.generator,
+                r#async: expr.r#async,
+            },
+            Node::ClassExpression(expr) => NodeKind::ClassExpression {
+                id: self.alloc_opt(&expr.id),
+                super_class: self.alloc_opt(&expr.super_class),
+                body: self.alloc_node(&expr.body),
+            },
+            Node::YieldExpression(expr) => NodeKind::YieldExpression {
+                argument: self.alloc_opt(&expr.argument),
+                delegate: expr.delegate,
+            },
+            Node::AwaitExpression(expr) => NodeKind::AwaitExpression {
+                argument: self.alloc_node(&expr.argument),
+            },
+
+            Node::BlockStatement(stmt) => NodeKind::BlockStatement {
+                body: self.alloc_nodes(&stmt.body),
+            },
+            Node::IfStatement(stmt) => NodeKind::IfStatement {
+                test: self.alloc_node(&stmt.test),
+                consequent: self.alloc_node(&stmt.consequent),
+                alternate: self.alloc_opt(&stmt.alternate),
+            },
+            Node::ForStatement(stmt) => NodeKind::ForStatement {
+                init: self.alloc_opt(&stmt.init),
+                test: self.alloc_opt(&stmt.test),
+                update: self.alloc_opt(&stmt.update),
+                body: self.alloc_node(&stmt.body),
+            },
+            Node::ForInStatement(stmt) => NodeKind::ForInStatement {
+                left: self.alloc_node(&stmt.left),
+                right: self.alloc_node(&stmt.right),
+                body: self.alloc_node(&stmt.body),
+            },
+            Node::ForOfStatement(stmt) => NodeKind::ForOfStatement {
+                left: self.alloc_node(&stmt.left),
+                right: self.alloc_node(&stmt.right),
+                body: self.alloc_node(&stmt.body),
+                r#await: stmt.r#await,
+            },
+            Node::WhileStatement(stmt) => NodeKind::WhileStatement {
+                test: self.alloc_node(&stmt.test),
+                body: self.alloc_node(&stmt.body),
+            },
+            Node::DoWhileStatement(stmt) => NodeKind::DoWhileStatement {
+                body: self.alloc_node(&stmt.body),
+                test: self.alloc_node(&stmt.test),
+            },
+            Node::SwitchStatement(stmt) => {
+                let discriminant = self.alloc_node(&stmt.discriminant);
+                let mark = self.scratch.len();
+                for case in &stmt.cases {
+                    let kind = NodeKind::SwitchCase {
+                        test: self.alloc_opt(&case.test),
+                        consequent: self.alloc_nodes(&case.consequent),
+                    };
+                    let id = self.alloc(kind, case.span.clone());
+                    self.scratch.push(id);
+                }
+                NodeKind::SwitchStatement {
+                    discriminant,
+                    cases: self.take_scratch(mark),
+                }
+            }
+            Node::TryStatement(stmt) => NodeKind::TryStatement {
+                block: self.alloc_node(&stmt.block),
+                handler: self.alloc_opt(&stmt.handler),
+                finalizer: self.alloc_opt(&stmt.finalizer),
+            },
+            Node::CatchClause(clause) => NodeKind::CatchClause {
+                param: self.alloc_node(&clause.param),
+                body: self.alloc_node(&clause.body),
+            },
+            Node::ThrowStatement(stmt) => NodeKind::ThrowStatement {
+                argument: self.alloc_node(&stmt.argument),
+            },
+            Node::ReturnStatement(stmt) => NodeKind::ReturnStatement {
+                argument: self.alloc_opt(&stmt.argument),
+            },
+            Node::BreakStatement(stmt) => NodeKind::BreakStatement {
+                label: self.alloc_opt(&stmt.label),
+            },
+            Node::ContinueStatement(stmt) => NodeKind::ContinueStatement {
+                label: self.alloc_opt(&stmt.label),
+            },
+            Node::LabeledStatement(stmt) => NodeKind::LabeledStatement {
+                label: self.alloc_node(&stmt.label),
+                body: self.alloc_node(&stmt.body),
+            },
+            Node::WithStatement(stmt) => NodeKind::WithStatement {
+                object: self.alloc_node(&stmt.object),
+                body: self.alloc_node(&stmt.body),
+            },
+            Node::DebuggerStatement(_) => NodeKind::DebuggerStatement,
+            Node::EmptyStatement(_) => NodeKind::EmptyStatement,
+            Node::ExpressionStatement(stmt) => NodeKind::ExpressionStatement {
+                expression: self.alloc_node(&stmt.expression),
+                directive: stmt.directive.as_deref().map(|text| self.intern(text)),
+            },
+
+            Node::ArrayLiteral(lit) => {
+                let elements: Vec<_> = lit
+                    .elements
+                    .iter()
+                    .map(|element| element.as_ref().map(|node| self.alloc_node(node)))
+                    .collect();
+                NodeKind::ArrayLiteral {
+                    elements: self.alloc_elements(elements),
+                }
+            }
+            Node::ObjectLiteral(lit) => NodeKind::ObjectLiteral {
+                properties: self.alloc_nodes(&lit.properties),
+            },
+            Node::TemplateLiteral(lit) => {
+                let mark = self.scratch.len();
+                for quasi in &lit.quasis {
+                    let kind = NodeKind::TemplateElement {
+                        cooked: quasi.cooked.as_deref().map(|text| self.intern(text)),
+                        raw: self.intern(&quasi.raw),
+                        tail: quasi.tail,
+                    };
+                    let id = self.alloc(kind, quasi.span.clone());
+                    self.scratch.push(id);
+                }
+                NodeKind::TemplateLiteral {
+                    quasis: self.take_scratch(mark),
+                    expressions: self.alloc_nodes(&lit.expressions),
+                }
+            }
+            Node::TaggedTemplateExpression(expr) => NodeKind::TaggedTemplateExpression {
+                tag: self.alloc_node(&expr.tag),
+                quasi: self.alloc_node(&expr.quasi),
+            },
+
+            Node::Property(prop) => NodeKind::Property {
+                key: self.alloc_node(&prop.key),
+                value: self.alloc_node(&prop.value),
+                kind: self.intern(&prop.kind),
+                computed: prop.computed,
+                method: prop.method,
+                shorthand: prop.shorthand,
+            },
+            Node::SpreadElement(elem) => NodeKind::SpreadElement {
+                argument: self.alloc_node(&elem.argument),
+            },
+            Node::RestElement(elem) => NodeKind::RestElement {
+                argument: self.alloc_node(&elem.argument),
+            },
+            Node::Super(_) => NodeKind::Super,
+            Node::MetaProperty(prop) => NodeKind::MetaProperty {
+                meta: self.alloc_node(&prop.meta),
+                property: self.alloc_node(&prop.property),
+            },
+            Node::ImportExpression(expr) => NodeKind::ImportExpression {
+                source: self.alloc_node(&expr.source),
+            },
+            Node::Identifier(id) => NodeKind::Identifier {
+                name: self.intern(&id.name),
+            },
+            Node::PrivateIdentifier(id) => NodeKind::PrivateIdentifier {
+                name: self.intern(&id.name),
+            },
+            Node::Number(lit) => NodeKind::Number { value: lit.value },
+            Node::String(lit) => NodeKind::String {
+                value: self.intern(&lit.value),
+            },
+            Node::Boolean(lit) => NodeKind::Boolean { value: lit.value },
+            Node::Null(_) => NodeKind::Null,
+            Node::Undefined(_) => NodeKind::Undefined,
+            Node::This(_) => NodeKind::This,
+            Node::RegExp(regexp) => NodeKind::RegExp {
+                pattern: self.intern(&regexp.pattern),
+                flags: self.intern(&regexp.flags),
+            },
+            Node::BigInt(lit) => NodeKind::BigInt {
+                value: self.intern(&lit.value),
+            },
+
+            #[cfg(feature = "jsx")]
+            Node::JsxElement(elem) => NodeKind::JsxElement {
+                name: self.alloc_node(&elem.name),
+                attributes: self.alloc_nodes(&elem.attributes),
+                children: self.alloc_nodes(&elem.children),
+                self_closing: elem.self_closing,
+            },
+            #[cfg(feature = "jsx")]
+            Node::JsxFragment(frag) => NodeKind::JsxFragment {
+                children: self.alloc_nodes(&frag.children),
+            },
+            #[cfg(feature = "jsx")]
+            Node::JsxAttribute(attr) => NodeKind::JsxAttribute {
+                name: self.alloc_node(&attr.name),
+                value: self.alloc_opt(&attr.value),
+            },
+            #[cfg(feature = "jsx")]
+            Node::JsxSpreadAttribute(attr) => NodeKind::JsxSpreadAttribute {
+                argument: self.alloc_node(&attr.argument),
+            },
+            #[cfg(feature = "jsx")]
+            Node::JsxExpressionContainer(container) => NodeKind::JsxExpressionContainer {
+                expression: self.alloc_opt(&container.expression),
+            },
+            #[cfg(feature = "jsx")]
+            Node::JsxText(text) => NodeKind::JsxText {
+                value: self.intern(&text.value),
+                raw: self.intern(&text.raw),
+            },
+            #[cfg(feature = "jsx")]
+            Node::JsxNamespacedName(name) => NodeKind::JsxNamespacedName {
+                namespace: self.alloc_node(&name.namespace),
+                name: self.alloc_node(&name.name),
+            },
+
+            Node::Error(error) => {
+                let skipped: Vec<_> = error.skipped.iter().map(|raw| self.intern(raw)).collect();
+                NodeKind::Error {
+                    message: self.intern(&error.message),
+                    skipped: self.alloc_symbols(skipped),
+                }
+            }
+        };
+
+        self.alloc(kind, node.span().clone())
+    }
+
+    fn alloc_opt(&mut self, node: &Option<Box<Node>>) -> Option<NodeId> {
+        node.as_deref().map(|node| self.alloc_node(node))
+    }
+
+    // Children are collected on `scratch` first, because allocating a child
+    // may itself add lists that would otherwise interleave with this one.
+    fn alloc_nodes(&mut self, nodes: &[Node]) -> NodeList {
+        let mark = self.scratch.len();
+        for node in nodes {
+            let id = self.alloc_node(node);
+            self.scratch.push(id);
+        }
+        self.take_scratch(mark)
+    }
+
+    fn alloc_attributes(&mut self, attributes: &[ImportAttribute]) -> NodeList {
+        let mark = self.scratch.len();
+        for attribute in attributes {
+            let kind = NodeKind::ImportAttribute {
+                key: self.alloc_node(&attribute.key),
+                value: self.alloc_node(&attribute.value),
+            };
+            let id = self.alloc(kind, attribute.span.clone());
+            self.scratch.push(id);
+        }
+        self.take_scratch(mark)
+    }
+
+    fn take_scratch(&mut self, mark: usize) -> NodeList {
+        let start = self.lists.len();
+        self.lists.extend(self.scratch.drain(mark..));
+        super::ArenaList::new(start, self.lists.len())
+    }
+
+    // Builds the owned tree rooted at `id`.
+    pub fn to_node(&self, id: NodeId) -> Node {
+        let span = self.span(id).clone();
+        match *self.kind(id) {
+            NodeKind::Program { body, source_type } => Node::Program(Program {
+                body: self.to_nodes(body),
+                source_type,
+                span,
+            }),
+            NodeKind::VariableDeclaration { kind, declarations } => {
+                Node::VariableDeclaration(VariableDeclaration {
+                    kind: self.owned(kind),
+                    declarations: self
+                        .list(declarations)
+                        .iter()
+                        .map(|&declarator| self.to_declarator(declarator))
+                        .collect(),
+                    span,
+                })
+            }
+            NodeKind::FunctionDeclaration {
+                id,
+                params,
+                body,
+                generator,
+                r#async,
+            } => Node::FunctionDeclaration(FunctionDeclaration {
+                id: self.to_opt(id),
+                params: self.to_nodes(params),
+                body: self.to_box(body),
+                generator,
+                r#async,
+                span,
+            }),
+            NodeKind::ClassDeclaration {
+                id,
+                super_class,
+                body,
+            } => Node::ClassDeclaration(ClassDeclaration {
+                id: self.to_opt(id),
+                super_class: self.to_opt(super_class),
+                body: self.to_box(body),
+                span,
+            }),
+            NodeKind::ClassBody { body } => Node::ClassBody(ClassBody {
+                body: self.to_nodes(body),
+                span,
+            }),
+            NodeKind::MethodDefinition {
+                key,
+                value,
+                kind,
+                computed,
+                r#static,
+            } => Node::MethodDefinition(MethodDefinition {
+                key: self.to_box(key),
+                value: self.to_box(value),
+                kind,
+                computed,
+                r#static,
+                span,
+            }),
+            NodeKind::PropertyDefinition {
+                key,
+                value,
+                computed,
+                r#static,
+            } => Node::PropertyDefinition(PropertyDefinition {
+                key: self.to_box(key),
+                value: self.to_opt(value),
+                computed,
+                r#static,
+                span,
+            }),
+            NodeKind::StaticBlock { body } => Node::StaticBlock(StaticBlock {
+                body: self.to_nodes(body),
+                span,
+            }),
+            NodeKind::ImportDeclaration {
+                specifiers,
+                source,
+                attributes,
+            } => Node::ImportDeclaration(ImportDeclaration {
+                specifiers: self.to_nodes(specifiers),
+                source: self.to_box(source),
+                attributes: self.to_attributes(attributes),
+                span,
+            }),
+            NodeKind::ExportDeclaration {
+                declaration,
+                specifiers,
+                source,
+                attributes,
+                default,
+            } => Node::ExportDeclaration(ExportDeclaration {
+                declaration: self.to_opt(declaration),
+                specifiers: self.to_nodes(specifiers),
+                source: self.to_opt(source),
+                attributes: self.to_attributes(attributes),
+                default,
+                span,
+            }),
+            NodeKind::ImportSpecifier { local, imported } => {
+                Node::ImportSpecifier(ImportSpecifier {
+                    local: self.to_box(local),
+                    imported: self.to_box(imported),
+                    span,
+                })
+            }
+            NodeKind::ImportDefaultSpecifier { local } => {
+                Node::ImportDefaultSpecifier(ImportDefaultSpecifier {
+                    local: self.to_box(local),
+                    span,
+                })
+            }
+            NodeKind::ImportNamespaceSpecifier { local } => {
+                Node::ImportNamespaceSpecifier(ImportNamespaceSpecifier {
+                    local: self.to_box(local),
+                    span,
+                })
+            }
+            NodeKind::ExportSpecifier { local, exported } => {
+                Node::ExportSpecifier(ExportSpecifier {
+                    local: self.to_box(local),
+                    exported: self.to_box(exported),
+                    span,
+                })
+            }
+            NodeKind::ExportNamespaceSpecifier { exported } => {
+                Node::ExportNamespaceSpecifier(ExportNamespaceSpecifier {
+                    exported: self.to_opt(exported),
+                    span,
+                })
+            }
+
+            NodeKind::BinaryExpression {
+                left,
+                operator,
+                right,
+            } => Node::BinaryExpression(BinaryExpression {
+                left: self.to_box(left),
+                operator: self.owned(operator),
+                right: self.to_box(right),
+                span,
+            }),
+            NodeKind::UnaryExpression {
+                operator,
+                argument,
+                prefix,
+            } => Node::UnaryExpression(UnaryExpression {
+                operator: self.owned(operator),
+                argument: self.to_box(argument),
+                prefix,
+                span,
+            }),
+            NodeKind::UpdateExpression {
+                operator,
+                argument,
+                prefix,
+            } => Node::UpdateExpression(UpdateExpression {
+                operator: self.owned(operator),
+                argument: self.to_box(argument),
+                prefix,
+                span,
+            }),
+            NodeKind::LogicalExpression {
+                left,
+                operator,
+                right,
+            } => Node::LogicalExpression(LogicalExpression {
+                left: self.to_box(left),
+                operator: self.owned(operator),
+                right: self.to_box(right),
+                span,
+            }),
+            NodeKind::ConditionalExpression {
+                test,
+                consequent,
+                alternate,
+            } => Node::ConditionalExpression(ConditionalExpression {
+                test: self.to_box(test),
+                consequent: self.to_box(consequent),
+                alternate: self.to_box(alternate),
+                span,
+            }),
+            NodeKind::AssignmentExpression {
+                left,
+                operator,
+                right,
+            } => Node::AssignmentExpression(AssignmentExpression {
+                left: self.to_box(left),
+                operator: self.owned(operator),
+                right: self.to_box(right),
+                span,
+            }),
+            NodeKind::CallExpression { callee, arguments } => {
+                Node::CallExpression(CallExpression {
+                    callee: self.to_box(callee),
+                    arguments: self.to_nodes(arguments),
+                    span,
+                })
+            }
+            NodeKind::NewExpression { callee, arguments } => Node::NewExpression(NewExpression {
+                callee: self.to_box(callee),
+                arguments: self.to_nodes(arguments),
+                span,
+            }),
+            NodeKind::MemberExpression {
+                object,
+                property,
+                computed,
+                optional,
+            } => Node::MemberExpression(MemberExpression {
+                object: self.to_box(object),
+                property: self.to_box(property),
+                computed,
+                optional,
+                span,
+            }),
+            NodeKind::ArrowFunctionExpression {
+                params,
+                body,
+                expression,
+                r#async,
+            } => Node::ArrowFunctionExpression(ArrowFunctionExpression {
+                params: self.to_nodes(params),
+                body: self.to_box(body),
+                expression,
+                r#async,
+                span,
+            }),
+            NodeKind::FunctionExpression {
+                id,
+                params,
+                body,
+                generator,
+                r#async,
+            } => Node::FunctionExpression(FunctionExpression {
+                id: self.to_opt(id),
+                params: self.to_nodes(params),
+                body: self.to_box(body),
+                generator,
+                r#async,
+                span,
+            }),
+            NodeKind::ClassExpression {
+                id,
+                super_class,
+                body,
+            } => Node::ClassExpression(ClassExpression {
+                id: self.to_opt(id),
+                super_class: self.to_opt(super_class),
+                body: self.to_box(body),
+                span,
+            }),
+            NodeKind::YieldExpression { argument, delegate } => {
+                Node::YieldExpression(YieldExpression {
+                    argument: self.to_opt(argument),
+                    delegate,
+                    span,
+                })
+            }
+            NodeKind::AwaitExpression { argument } => Node::AwaitExpression(AwaitExpression {
+                argument: self.to_box(argument),
+                span,
+            }),
+
+            NodeKind::BlockStatement { body } => Node::BlockStatement(BlockStatement {
+                body: self.to_nodes(body),
+                span,
+            }),
+            NodeKind::IfStatement {
+                test,
+                consequent,
+                alternate,
+            } => Node::IfStatement(IfStatement {
+                test: self.to_box(test),
+                consequent: self.to_box(consequent),
+                alternate: self.to_opt(alternate),
+                span,
+            }),
+            NodeKind::ForStatement {
+                init,
+                test,
+                update,
+                body,
+            } => Node::ForStatement(ForStatement {
+                init: self.to_opt(init),
+                test: self.to_opt(test),
+                update: self.to_opt(update),
+                body: self.to_box(body),
+                span,
+            }),
+            NodeKind::ForInStatement { left, right, body } => {
+                Node::ForInStatement(ForInStatement {
+                    left: self.to_box(left),
+                    right: self.to_box(right),
+                    body: self.to_box(body),
+                    span,
+                })
+            }
+            NodeKind::ForOfStatement {
+                left,
+                right,
+                body,
+                r#await,
+            } => Node::ForOfStatement(ForOfStatement {
+                left: self.to_box(left),
+                right: self.to_box(right),
+                body: self.to_box(body),
+                r#await,
+                span,
+            }),
+            NodeKind::WhileStatement { test, body } => Node::WhileStatement(WhileStatement {
+                test: self.to_box(test),
+                body: self.to_box(body),
+                span,
+            }),
+            NodeKind::DoWhileStatement { body, test } => Node::DoWhileStatement(DoWhileStatement {
+                body: self.to_box(body),
+                test: self.to_box(test),
+                span,
+            }),
+            NodeKind::SwitchStatement {
+                discriminant,
+                cases,
+            } => Node::SwitchStatement(SwitchStatement {
+                discriminant: self.to_box(discriminant),
+                cases: self
+                    .list(cases)
+                    .iter()
+                    .map(|&case| self.to_switch_case(case))
+                    .collect(),
+                span,
+            }),
+            NodeKind::TryStatement {
+                block,
+                handler,
+                finalizer,
+            } => Node::TryStatement(TryStatement {
+                block: self.to_box(block),
+                handler: self.to_opt(handler),
+                finalizer: self.to_opt(finalizer),
+                span,
+            }),
+            NodeKind::CatchClause { param, body } => Node::CatchClause(CatchClause {
+                param: self.to_box(param),
+                body: self.to_box(body),
+                span,
+            }),
+            NodeKind::ThrowStatement { argument } => Node::ThrowStatement(ThrowStatement {
+                argument: self.to_box(argument),
+                span,
+            }),
+            NodeKind::ReturnStatement { argument } => Node::ReturnStatement(ReturnStatement {
+                argument: self.to_opt(argument),
+                span,
+            }),
+            NodeKind::BreakStatement { label } => Node::BreakStatement(BreakStatement {
+                label: self.to_opt(label),
+                span,
+            }),
+            NodeKind::ContinueStatement { label } => Node::ContinueStatement(ContinueStatement {
+                label: self.to_opt(label),
+                span,
+            }),
+            NodeKind::LabeledStatement { label, body } => {
+                Node::LabeledStatement(LabeledStatement {
+                    label: self.to_box(label),
+                    body: self.to_box(body),
+                    span,
+                })
+            }
+            NodeKind::WithStatement { object, body } => Node::WithStatement(WithStatement {
+                object: self.to_box(object),
+                body: self.to_box(body),
+                span,
+            }),
+            NodeKind::DebuggerStatement => Node::DebuggerStatement(DebuggerStatement { span }),
+            NodeKind::EmptyStatement => Node::EmptyStatement(EmptyStatement { span }),
+            NodeKind::ExpressionStatement {
+                expression,
+                directive,
+            } => Node::ExpressionStatement(ExpressionStatement {
+                expression: self.to_box(expression),
+                directive: directive.map(|directive| self.owned(directive)),
+                span,
+            }),
+
+            NodeKind::ArrayLiteral { elements } => Node::ArrayLiteral(ArrayLiteral {
+                elements: self
+                    .elements(elements)
+                    .iter()
+                    .map(|element| element.map(|id| self.to_node(id)))
+                    .collect(),
+                span,
+            }),
+            NodeKind::ObjectLiteral { properties } => Node::ObjectLiteral(ObjectLiteral {
+                properties: self.to_nodes(properties),
+                span,
+            }),
+            NodeKind::TemplateLiteral {
+                quasis,
+                expressions,
+            } => Node::TemplateLiteral(TemplateLiteral {
+                quasis: self
+                    .list(quasis)
+                    .iter()
+                    .map(|&quasi| self.to_template_element(quasi))
+                    .collect(),
+                expressions: self.to_nodes(expressions),
+                span,
+            }),
+            NodeKind::TaggedTemplateExpression { tag, quasi } => {
+                Node::TaggedTemplateExpression(TaggedTemplateExpression {
+                    tag: self.to_box(tag),
+                    quasi: self.to_box(quasi),
+                    span,
+                })
+            }
+
+            NodeKind::Property {
+                key,
+                value,
+                kind,
+                computed,
+                method,
+                shorthand,
+            } => Node::Property(Property {
+                key: self.to_box(key),
+                value: self.to_box(value),
+                kind: self.owned(kind),
+                computed,
+                method,
+                shorthand,
+                span,
+            }),
+            NodeKind::SpreadElement { argument } => Node::SpreadElement(SpreadElement {
+                argument: self.to_box(argument),
+                span,
+            }),
+            NodeKind::RestElement { argument } => Node::RestElement(RestElement {
+                argument: self.to_box(argument),
+                span,
+            }),
+            NodeKind::Super => Node::Super(Super { span }),
+            NodeKind::MetaProperty { meta, property } => Node::MetaProperty(MetaProperty {
+                meta: self.to_box(meta),
+                property: self.to_box(property),
+                span,
+            }),
+            NodeKind::ImportExpression { source } => Node::ImportExpression(ImportExpression {
+                source: self.to_box(source),
+                span,
+            }),
+            NodeKind::Identifier { name } => Node::Identifier(Identifier {
+                name: self.owned(name),
+                span,
+            }),
+            NodeKind::PrivateIdentifier { name } => Node::PrivateIdentifier(PrivateIdentifier {
+                name: self.owned(name),
+                span,
+            }),
+            NodeKind::Number { value } => Node::Number(NumberLiteral { value, span }),
+            NodeKind::String { value } => Node::String(StringLiteral {
+                value: self.owned(value),
+                span,
+            }),
+            NodeKind::Boolean { value } => Node::Boolean(BooleanLiteral { value, span }),
+            NodeKind::Null => Node::Null(NullLiteral { span }),
+            NodeKind::Undefined => Node::Undefined(UndefinedLiteral { span }),
+            NodeKind::This => Node::This(ThisExpression { span }),
+            NodeKind::RegExp { pattern, flags } => Node::RegExp(RegExp {
+                pattern: self.owned(pattern),
+                flags: self.owned(flags),
+                span,
+            }),
+            NodeKind::BigInt { value } => Node::BigInt(BigIntLiteral {
+                value: self.owned(value),
+                span,
+            }),
+
+            #[cfg(feature = "jsx")]
+            NodeKind::JsxElement {
+                name,
+                attributes,
+                children,
+                self_closing,
+            } => Node::JsxElement(JsxElement {
+                name: self.to_box(name),
+                attributes: self.to_nodes(attributes),
+                children: self.to_nodes(children),
+                self_closing,
+                span,
+            }),
+            #[cfg(feature = "jsx")]
+            NodeKind::JsxFragment { children } => Node::JsxFragment(JsxFragment {
+                children: self.to_nodes(children),
+                span,
+            }),
+            #[cfg(feature = "jsx")]
+            NodeKind::JsxAttribute { name, value } => Node::JsxAttribute(JsxAttribute {
+                name: self.to_box(name),
+                value: self.to_opt(value),
+                span,
+            }),
+            #[cfg(feature = "jsx")]
+            NodeKind::JsxSpreadAttribute { argument } => {
+                Node::JsxSpreadAttribute(JsxSpreadAttribute {
+                    argument: self.to_box(argument),
+                    span,
+                })
+            }
+            #[cfg(feature = "jsx")]
+            NodeKind::JsxExpressionContainer { expression } => {
+                Node::JsxExpressionContainer(JsxExpressionContainer {
+                    expression: self.to_opt(expression),
+                    span,
+                })
+            }
+            #[cfg(feature = "jsx")]
+            NodeKind::JsxText { value, raw } => Node::JsxText(JsxText {
+                value: self.owned(value),
+                raw: self.owned(raw),
+                span,
+            }),
+            #[cfg(feature = "jsx")]
+            NodeKind::JsxNamespacedName { namespace, name } => {
+                Node::JsxNamespacedName(JsxNamespacedName {
+                    namespace: self.to_box(namespace),
+                    name: self.to_box(name),
+                    span,
+                })
+            }
+
+            NodeKind::Error { message, skipped } => Node::Error(ErrorNode {
+                message: self.owned(message),
+                skipped: self
+                    .symbols(skipped)
+                    .iter()
+                    .map(|&raw| self.owned(raw))
+                    .collect(),
+                span,
+            }),
+
+            NodeKind::VariableDeclarator { .. }
+            | NodeKind::SwitchCase { .. }
+            | NodeKind::TemplateElement { .. }
+            | NodeKind::ImportAttribute { .. } => {
+                panic!("{:?} only exists inside its parent node", self.kind(id))
+            }
+        }
+    }
+
+    fn to_box(&self, id: NodeId) -> Box<Node> {
+        Box::new(self.to_node(id))
+    }
+
+    fn to_opt(&self, id: Option<NodeId>) -> Option<Box<Node>> {
+        id.map(|id| self.to_box(id))
+    }
+
+    fn to_nodes(&self, list: NodeList) -> Vec<Node> {
+        self.list(list).iter().map(|&id| self.to_node(id)).collect()
+    }
+
+    fn owned(&self, symbol: Symbol) -> String {
+        self.resolve(symbol).to_string()
+    }
+
+    fn to_declarator(&self, id: NodeId) -> VariableDeclarator {
+        let NodeKind::VariableDeclarator { id: binding, init } = *self.kind(id) else {
+            panic!("expected a variable declarator, found {:?}", self.kind(id));
+        };
+        VariableDeclarator {
+            id: self.to_box(binding),
+            init: self.to_opt(init),
+            span: self.span(id).clone(),
+        }
+    }
+
+    fn to_switch_case(&self, id: NodeId) -> SwitchCase {
+        let NodeKind::SwitchCase { test, consequent } = *self.kind(id) else {
+            panic!("expected a switch case, found {:?}", self.kind(id));
+        };
+        SwitchCase {
+            test: self.to_opt(test),
+            consequent: self.to_nodes(consequent),
+            span: self.span(id).clone(),
+        }
+    }
+
+    fn to_template_element(&self, id: NodeId) -> TemplateElement {
+        let NodeKind::TemplateElement { cooked, raw, tail } = *self.kind(id) else {
+            panic!("expected a template element, found {:?}", self.kind(id));
+        };
+        TemplateElement {
+            cooked: cooked.map(|cooked| self.owned(cooked)),
+            raw: self.owned(raw),
+            tail,
+            span: self.span(id).clone(),
+        }
+    }
+
+    fn to_attributes(&self, list: NodeList) -> Vec<ImportAttribute> {
+        self.list(list)
+            .iter()
+            .map(|&id| {
+                let NodeKind::ImportAttribute { key, value } = *self.kind(id) else {
+                    panic!("expected an import attribute, found {:?}", self.kind(id));
+                };
+                ImportAttribute {
+                    key: self.to_box(key),
+                    value: self.to_box(value),
+                    span: self.span(id).clone(),
+                }
+            })
+            .collect()
+    }
+}
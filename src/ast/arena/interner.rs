@@ -0,0 +1,56 @@
+use indexmap::IndexSet;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    pub fn as_usize(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Symbol({})", self.0)
+    }
+}
+
+// Stores each distinct string once. Interning the same text again returns
+// the same `Symbol`, so names can be compared without touching the text.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    strings: IndexSet<Box<str>>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, text: &str) -> Symbol {
+        if let Some(index) = self.strings.get_index_of(text) {
+            return Symbol(index as u32);
+        }
+        let (index, _) = self.strings.insert_full(text.into());
+        Symbol(index as u32)
+    }
+
+    pub fn get(&self, text: &str) -> Option<Symbol> {
+        self.strings
+            .get_index_of(text)
+            .map(|index| Symbol(index as u32))
+    }
+
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.as_usize()]
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
@@ -0,0 +1,518 @@
+mod convert;
+mod interner;
+
+pub use interner::{Interner, Symbol};
+
+use crate::ast::{MethodKind, SourceType, Span};
+use std::marker::PhantomData;
+
+// The same tree as `Node`, stored in a few flat vectors instead of one
+// allocation per node. Nodes refer to their children by `NodeId`, child
+// lists are ranges of a shared vector and every name, string and operator
+// is interned as a `Symbol`. `alloc_node` copies an owned tree in and
+// `to_node` builds one back out.
+#[derive(Debug, Clone, Default)]
+pub struct AstArena {
+    nodes: Vec<ArenaNode>,
+    lists: Vec<NodeId>,
+    elements: Vec<Option<NodeId>>,
+    symbol_lists: Vec<Symbol>,
+    interner: Interner,
+    scratch: Vec<NodeId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(u32);
+
+impl NodeId {
+    pub fn as_usize(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+// A range of one of the arena's list vectors.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ArenaList<T> {
+    start: u32,
+    len: u32,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> ArenaList<T> {
+    fn new(start: usize, end: usize) -> Self {
+        Self {
+            start: start as u32,
+            len: (end - start) as u32,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn range(&self) -> std::ops::Range<usize> {
+        self.start as usize..(self.start + self.len) as usize
+    }
+}
+
+impl<T> Clone for ArenaList<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArenaList<T> {}
+
+impl<T> Default for ArenaList<T> {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+pub type NodeList = ArenaList<NodeId>;
+// Array elements, where `None` is a hole such as the middle of `[a, , b]`.
+pub type ElementList = ArenaList<Option<NodeId>>;
+pub type SymbolList = ArenaList<Symbol>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaNode {
+    pub kind: NodeKind,
+    pub span: Span,
+}
+
+// One variant per `Node` variant, plus the helper structs that `Node` keeps
+// inline (declarators, switch cases, template elements and import
+// attributes), which become nodes of their own here.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NodeKind {
+    Program {
+        body: NodeList,
+        source_type: SourceType,
+    },
+    VariableDeclaration {
+        kind: Symbol,
+        declarations: NodeList,
+    },
+    VariableDeclarator {
+        id: NodeId,
+        init: Option<NodeId>,
+    },
+    FunctionDeclaration {
+        id: Option<NodeId>,
+        params: NodeList,
+        body: NodeId,
+        generator: bool,
+        r#async: bool,
+    },
+    ClassDeclaration {
+        id: Option<NodeId>,
+        super_class: Option<NodeId>,
+        body: NodeId,
+    },
+    ClassBody {
+        body: NodeList,
+    },
+    MethodDefinition {
+        key: NodeId,
+        value: NodeId,
+        kind: MethodKind,
+        computed: bool,
+        r#static: bool,
+    },
+    PropertyDefinition {
+        key: NodeId,
+        value: Option<NodeId>,
+        computed: bool,
+        r#static: bool,
+    },
+    StaticBlock {
+        body: NodeList,
+    },
+    ImportDeclaration {
+        specifiers: NodeList,
+        source: NodeId,
+        attributes: NodeList,
+    },
+    ImportAttribute {
+        key: NodeId,
+        value: NodeId,
+    },
+    ExportDeclaration {
+        declaration: Option<NodeId>,
+        specifiers: NodeList,
+        source: Option<NodeId>,
+        attributes: NodeList,
+        default: bool,
+    },
+    ImportSpecifier {
+        local: NodeId,
+        imported: NodeId,
+    },
+    ImportDefaultSpecifier {
+        local: NodeId,
+    },
+    ImportNamespaceSpecifier {
+        local: NodeId,
+    },
+    ExportSpecifier {
+        local: NodeId,
+        exported: NodeId,
+    },
+    ExportNamespaceSpecifier {
+        exported: Option<NodeId>,
+    },
+
+    BinaryExpression {
+        left: NodeId,
+        operator: Symbol,
+        right: NodeId,
+    },
+    UnaryExpression {
+        operator: Symbol,
+        argument: NodeId,
+        prefix: bool,
+    },
+    UpdateExpression {
+        operator: Symbol,
+        argument: NodeId,
+        prefix: bool,
+    },
+    LogicalExpression {
+        left: NodeId,
+        operator: Symbol,
+        right: NodeId,
+    },
+    ConditionalExpression {
+        test: NodeId,
+        consequent: NodeId,
+        alternate: NodeId,
+    },
+    AssignmentExpression {
+        left: NodeId,
+        operator: Symbol,
+        right: NodeId,
+    },
+    CallExpression {
+        callee: NodeId,
+        arguments: NodeList,
+    },
+    NewExpression {
+        callee: NodeId,
+        arguments: NodeList,
+    },
+    MemberExpression {
+        object: NodeId,
+        property: NodeId,
+        computed: bool,
+        optional: bool,
+    },
+    ArrowFunctionExpression {
+        params: NodeList,
+        body: NodeId,
+        expression: bool,
+        r#async: bool,
+    },
+    FunctionExpression {
+        id: Option<NodeId>,
+        params: NodeList,
+        body: NodeId,
+        generator: bool,
+        r#async: bool,
+    },
+    ClassExpression {
+        id: Option<NodeId>,
+        super_class: Option<NodeId>,
+        body: NodeId,
+    },
+    YieldExpression {
+        argument: Option<NodeId>,
+        delegate: bool,
+    },
+    AwaitExpression {
+        argument: NodeId,
+    },
+
+    BlockStatement {
+        body: NodeList,
+    },
+    IfStatement {
+        test: NodeId,
+        consequent: NodeId,
+        alternate: Option<NodeId>,
+    },
+    ForStatement {
+        init: Option<NodeId>,
+        test: Option<NodeId>,
+        update: Option<NodeId>,
+        body: NodeId,
+    },
+    ForInStatement {
+        left: NodeId,
+        right: NodeId,
+        body: NodeId,
+    },
+    ForOfStatement {
+        left: NodeId,
+        right: NodeId,
+        body: NodeId,
+        r#await: bool,
+    },
+    WhileStatement {
+        test: NodeId,
+        body: NodeId,
+    },
+    DoWhileStatement {
+        body: NodeId,
+        test: NodeId,
+    },
+    SwitchStatement {
+        discriminant: NodeId,
+        cases: NodeList,
+    },
+    SwitchCase {
+        test: Option<NodeId>,
+        consequent: NodeList,
+    },
+    TryStatement {
+        block: NodeId,
+        handler: Option<NodeId>,
+        finalizer: Option<NodeId>,
+    },
+    CatchClause {
+        param: NodeId,
+        body: NodeId,
+    },
+    ThrowStatement {
+        argument: NodeId,
+    },
+    ReturnStatement {
+        argument: Option<NodeId>,
+    },
+    BreakStatement {
+        label: Option<NodeId>,
+    },
+    ContinueStatement {
+        label: Option<NodeId>,
+    },
+    LabeledStatement {
+        label: NodeId,
+        body: NodeId,
+    },
+    WithStatement {
+        object: NodeId,
+        body: NodeId,
+    },
+    DebuggerStatement,
+    EmptyStatement,
+    ExpressionStatement {
+        expression: NodeId,
+        directive: Option<Symbol>,
+    },
+
+    ArrayLiteral {
+        elements: ElementList,
+    },
+    ObjectLiteral {
+        properties: NodeList,
+    },
+    TemplateLiteral {
+        quasis: NodeList,
+        expressions: NodeList,
+    },
+    TemplateElement {
+        cooked: Option<Symbol>,
+        raw: Symbol,
+        tail: bool,
+    },
+    TaggedTemplateExpression {
+        tag: NodeId,
+        quasi: NodeId,
+    },
+
+    Property {
+        key: NodeId,
+        value: NodeId,
+        kind: Symbol,
+        computed: bool,
+        method: bool,
+        shorthand: bool,
+    },
+    SpreadElement {
+        argument: NodeId,
+    },
+    RestElement {
+        argument: NodeId,
+    },
+    Super,
+    MetaProperty {
+        meta: NodeId,
+        property: NodeId,
+    },
+    ImportExpression {
+        source: NodeId,
+    },
+    Identifier {
+        name: Symbol,
+    },
+    PrivateIdentifier {
+        name: Symbol,
+    },
+    Number {
+        value: f64,
+    },
+    String {
+        value: Symbol,
+    },
+    Boolean {
+        value: bool,
+    },
+    Null,
+    Undefined,
+    This,
+    RegExp {
+        pattern: Symbol,
+        flags: Symbol,
+    },
+    BigInt {
+        value: Symbol,
+    },
+
+    #[cfg(feature = "jsx")]
+    JsxElement {
+        name: NodeId,
+        attributes: NodeList,
+        children: NodeList,
+        self_closing: bool,
+    },
+    #[cfg(feature = "jsx")]
+    JsxFragment {
+        children: NodeList,
+    },
+    #[cfg(feature = "jsx")]
+    JsxAttribute {
+        name: NodeId,
+        value: Option<NodeId>,
+    },
+    #[cfg(feature = "jsx")]
+    JsxSpreadAttribute {
+        argument: NodeId,
+    },
+    #[cfg(feature = "jsx")]
+    JsxExpressionContainer {
+        expression: Option<NodeId>,
+    },
+    #[cfg(feature = "jsx")]
+    JsxText {
+        value: Symbol,
+        raw: Symbol,
+    },
+    #[cfg(feature = "jsx")]
+    JsxNamespacedName {
+        namespace: NodeId,
+        name: NodeId,
+    },
+
+    Error {
+        message: Symbol,
+        skipped: SymbolList,
+    },
+}
+
+impl AstArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(nodes: usize) -> Self {
+        Self {
+            nodes: Vec::with_capacity(nodes),
+            lists: Vec::with_capacity(nodes),
+            ..Self::default()
+        }
+    }
+
+    pub fn alloc(&mut self, kind: NodeKind, span: Span) -> NodeId {
+        let id = NodeId(self.nodes.len() as u32);
+        self.nodes.push(ArenaNode { kind, span });
+        id
+    }
+
+    pub fn alloc_list(&mut self, ids: impl IntoIterator<Item = NodeId>) -> NodeList {
+        let start = self.lists.len();
+        self.lists.extend(ids);
+        ArenaList::new(start, self.lists.len())
+    }
+
+    pub fn alloc_elements(&mut self, ids: impl IntoIterator<Item = Option<NodeId>>) -> ElementList {
+        let start = self.elements.len();
+        self.elements.extend(ids);
+        ArenaList::new(start, self.elements.len())
+    }
+
+    pub fn alloc_symbols(&mut self, symbols: impl IntoIterator<Item = Symbol>) -> SymbolList {
+        let start = self.symbol_lists.len();
+        self.symbol_lists.extend(symbols);
+        ArenaList::new(start, self.symbol_lists.len())
+    }
+
+    pub fn intern(&mut self, text: &str) -> Symbol {
+        self.interner.intern(text)
+    }
+
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        self.interner.resolve(symbol)
+    }
+
+    pub fn interner(&self) -> &Interner {
+        &self.interner
+    }
+
+    pub fn get(&self, id: NodeId) -> &ArenaNode {
+        &self.nodes[id.as_usize()]
+    }
+
+    pub fn get_mut(&mut self, id: NodeId) -> &mut ArenaNode {
+        &mut self.nodes[id.as_usize()]
+    }
+
+    pub fn kind(&self, id: NodeId) -> &NodeKind {
+        &self.get(id).kind
+    }
+
+    pub fn span(&self, id: NodeId) -> &Span {
+        &self.get(id).span
+    }
+
+    pub fn list(&self, list: NodeList) -> &[NodeId] {
+        &self.lists[list.range()]
+    }
+
+    pub fn elements(&self, list: ElementList) -> &[Option<NodeId>] {
+        &self.elements[list.range()]
+    }
+
+    pub fn symbols(&self, list: SymbolList) -> &[Symbol] {
+        &self.symbol_lists[list.range()]
+    }
+
+    // All nodes in allocation order, which puts children before parents.
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &ArenaNode)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (NodeId(index as u32), node))
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
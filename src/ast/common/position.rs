@@ -1,6 +1,7 @@
 use crate::vm::types::{ColumnNumber, LineNumber, SourcePosition};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::ops::Range;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Position {
@@ -50,15 +51,27 @@ impl fmt::Display for Position {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+// `range` holds the byte offsets of the same region in the source, so
+// `&source[span.range.clone()]` is the text the span covers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Span {
     pub start: Position,
     pub end: Position,
+    pub range: Range<usize>,
 }
 
 impl Span {
     pub fn new(start: Position, end: Position) -> Self {
-        Self { start, end }
+        Self {
+            start,
+            end,
+            range: 0..0,
+        }
+    }
+
+    pub fn with_range(mut self, range: Range<usize>) -> Self {
+        self.range = range;
+        self
     }
 
     pub fn from_positions(
@@ -67,10 +80,10 @@ impl Span {
         end_line: usize,
         end_col: usize,
     ) -> Self {
-        Self {
-            start: Position::new(start_line, start_col),
-            end: Position::new(end_line, end_col),
-        }
+        Self::new(
+            Position::new(start_line, start_col),
+            Position::new(end_line, end_col),
+        )
     }
 
     pub fn from_positions_typed(
@@ -79,9 +92,18 @@ impl Span {
         end_line: LineNumber,
         end_col: ColumnNumber,
     ) -> Self {
-        Self {
-            start: Position::new_typed(start_line, start_col),
-            end: Position::new_typed(end_line, end_col),
+        Self::new(
+            Position::new_typed(start_line, start_col),
+            Position::new_typed(end_line, end_col),
+        )
+    }
+
+    // From the start of `self` to the end of `other`.
+    pub fn to(&self, other: &Span) -> Span {
+        Span {
+            start: self.start,
+            end: other.end,
+            range: self.range.start..other.range.end,
         }
     }
 }
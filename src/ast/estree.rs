@@ -0,0 +1,1486 @@
+use crate::ast::*;
+use serde_json::{json, Map, Value};
+
+// Conversion between `Node` and ESTree JSON, the shape used by Acorn,
+// Esprima and most other JavaScript tools. Every node gets `loc` (1-based
+// lines, 0-based columns) and `range`, which holds byte offsets as in
+// `Span::range`.
+//
+// Bindings are written as ESTree patterns, so an object literal on the left
+// of `=` becomes an `ObjectPattern` and reads back as an `ObjectLiteral`.
+// `undefined` is an `Identifier` in ESTree and `export * from` has no
+// specifier node, so those parts do not keep their exact shape when read
+// back. Error recovery nodes use the non-standard type `Error`.
+//
+// JSX follows the JSX ESTree extension. `Node` keeps no spans for opening
+// and closing tags, so those take the span of the whole element.
+pub fn to_estree(node: &Node) -> Value {
+    match node {
+        Node::Program(program) => estree(
+            "Program",
+            &program.span,
+            json!({
+                "body": to_estree_list(&program.body),
+                "sourceType": match program.source_type {
+                    SourceType::Script => "script",
+                    SourceType::Module => "module",
+                },
+            }),
+        ),
+        Node::VariableDeclaration(decl) => estree(
+            "VariableDeclaration",
+            &decl.span,
+            json!({
+                "kind": decl.kind,
+                "declarations": decl
+                    .declarations
+                    .iter()
+                    .map(|declarator| estree(
+                        "VariableDeclarator",
+                        &declarator.span,
+                        json!({
+                            "id": to_pattern(&declarator.id),
+                            "init": to_estree_opt(&declarator.init),
+                        }),
+                    ))
+                    .collect::<Vec<_>>(),
+            }),
+        ),
+        Node::FunctionDeclaration(decl) => estree(
+            "FunctionDeclaration",
+            &decl.span,
+            function_fields(
+                &decl.id,
+                &decl.params,
+                &decl.body,
+                decl.generator,
+                decl.r#async,
+            ),
+        ),
+        Node::ClassDeclaration(decl) => estree(
+            "ClassDeclaration",
+            &decl.span,
+            class_fields(&decl.id, &decl.super_class, &decl.body),
+        ),
+        Node::ClassBody(body) => estree(
+            "ClassBody",
+            &body.span,
+            json!({ "body": to_estree_list(&body.body) }),
+        ),
+        Node::MethodDefinition(method) => estree(
+            "MethodDefinition",
+            &method.span,
+            json!({
+                "key": to_estree(&method.key),
+                "value": to_estree(&method.value),
+                "kind": match method.kind {
+                    MethodKind::Constructor => "constructor",
+                    MethodKind::Method => "method",
+                    MethodKind::Get => "get",
+                    MethodKind::Set => "set",
+                },
+                "computed": method.computed,
+                "static": method.r#static,
+            }),
+        ),
+        Node::PropertyDefinition(prop) => estree(
+            "PropertyDefinition",
+            &prop.span,
+            json!({
+                "key": to_estree(&prop.key),
+                "value": to_estree_opt(&prop.value),
+                "computed": prop.computed,
+                "static": prop.r#static,
+            }),
+        ),
+        Node::StaticBlock(block) => estree(
+            "StaticBlock",
+            &block.span,
+            json!({ "body": to_estree_list(&block.body) }),
+        ),
+        Node::ImportDeclaration(decl) => estree(
+            "ImportDeclaration",
+            &decl.span,
+            json!({
+                "specifiers": to_estree_list(&decl.specifiers),
+                "source": to_estree(&decl.source),
+                "attributes": attributes_to_estree(&decl.attributes),
+            }),
+        ),
+        Node::ExportDeclaration(decl) => export_to_estree(decl),
+        Node::ImportSpecifier(spec) => estree(
+            "ImportSpecifier",
+            &spec.span,
+            json!({
+                "local": to_estree(&spec.local),
+                "imported": to_estree(&spec.imported),
+            }),
+        ),
+        Node::ImportDefaultSpecifier(spec) => estree(
+            "ImportDefaultSpecifier",
+            &spec.span,
+            json!({ "local": to_estree(&spec.local) }),
+        ),
+        Node::ImportNamespaceSpecifier(spec) => estree(
+            "ImportNamespaceSpecifier",
+            &spec.span,
+            json!({ "local": to_estree(&spec.local) }),
+        ),
+        Node::ExportSpecifier(spec) => estree(
+            "ExportSpecifier",
+            &spec.span,
+            json!({
+                "local": to_estree(&spec.local),
+                "exported": to_estree(&spec.exported),
+            }),
+        ),
+        // Only reachable when the specifier is converted on its own; inside an
+        // export it becomes an `ExportAllDeclaration`.
+        Node::ExportNamespaceSpecifier(spec) => estree(
+            "ExportNamespaceSpecifier",
+            &spec.span,
+            json!({ "exported": to_estree_opt(&spec.exported) }),
+        ),
+
+        Node::BinaryExpression(expr) => estree(
+            "BinaryExpression",
+            &expr.span,
+            json!({
+                "operator": expr.operator,
+                "left": to_estree(&expr.left),
+                "right": to_estree(&expr.right),
+            }),
+        ),
+        Node::UnaryExpression(expr) => estree(
+            "UnaryExpression",
+            &expr.span,
+            json!({
+                "operator": expr.operator,
+                "prefix": expr.prefix,
+                "argument": to_estree(&expr.argument),
+            }),
+        ),
+        Node::UpdateExpression(expr) => estree(
+            "UpdateExpression",
+            &expr.span,
+            json!({
+                "operator": expr.operator,
+                "prefix": expr.prefix,
+                "argument": to_estree(&expr.argument),
+            }),
+        ),
+        Node::LogicalExpression(expr) => estree(
+            "LogicalExpression",
+            &expr.span,
+            json!({
+                "operator": expr.operator,
+                "left": to_estree(&expr.left),
+                "right": to_estree(&expr.right),
+            }),
+        ),
+        Node::ConditionalExpression(expr) => estree(
+            "ConditionalExpression",
+            &expr.span,
+            json!({
+                "test": to_estree(&expr.test),
+                "consequent": to_estree(&expr.consequent),
+                "alternate": to_estree(&expr.alternate),
+            }),
+        ),
+        Node::AssignmentExpression(expr) => estree(
+            "AssignmentExpression",
+            &expr.span,
+            json!({
+                "operator": expr.operator,
+                "left": if expr.operator == "=" {
+                    to_pattern(&expr.left)
+                } else {
+                    to_estree(&expr.left)
+                },
+                "right": to_estree(&expr.right),
+            }),
+        ),
+        Node::CallExpression(expr) => estree(
+            "CallExpression",
+            &expr.span,
+            json!({
+                "callee": to_estree(&expr.callee),
+                "arguments": to_estree_list(&expr.arguments),
+                "optional": false,
+            }),
+        ),
+        Node::NewExpression(expr) => estree(
+            "NewExpression",
+            &expr.span,
+            json!({
+                "callee": to_estree(&expr.callee),
+                "arguments": to_estree_list(&expr.arguments),
+            }),
+        ),
+        Node::MemberExpression(expr) => estree(
+            "MemberExpression",
+            &expr.span,
+            json!({
+                "object": to_estree(&expr.object),
+                "property": to_estree(&expr.property),
+                "computed": expr.computed,
+                "optional": expr.optional,
+            }),
+        ),
+        Node::ArrowFunctionExpression(expr) => estree(
+            "ArrowFunctionExpression",
+            &expr.span,
+            json!({
+                "id": null,
+                "params": to_pattern_list(&expr.params),
+                "body": to_estree(&expr.body),
+                "generator": false,
+                "async": expr.r#async,
+                "expression": expr.expression,
+            }),
+        ),
+        Node::FunctionExpression(expr) => estree(
+            "FunctionExpression",
+            &expr.span,
+            function_fields(
+                &expr.id,
+                &expr.params,
+                &expr.body,
+                expr.generator,
+                expr.r#async,
+            ),
+        ),
+        Node::ClassExpression(expr) => estree(
+            "ClassExpression",
+            &expr.span,
+            class_fields(&expr.id, &expr.super_class, &expr.body),
+        ),
+        Node::YieldExpression(expr) => estree(
+            "YieldExpression",
+            &expr.span,
+            json!({
+                "argument": to_estree_opt(&expr.argument),
+                "delegate": expr.delegate,
+            }),
+        ),
+        Node::AwaitExpression(expr) => estree(
+            "AwaitExpression",
+            &expr.span,
+            json!({ "argument": to_estree(&expr.argument) }),
+        ),
+
+        Node::BlockStatement(stmt) => estree(
+            "BlockStatement",
+            &stmt.span,
+            json!({ "body": to_estree_list(&stmt.body) }),
+        ),
+        Node::IfStatement(stmt) => estree(
+            "IfStatement",
+            &stmt.span,
+            json!({
+                "test": to_estree(&stmt.test),
+                "consequent": to_estree(&stmt.consequent),
+                "alternate": to_estree_opt(&stmt.alternate),
+            }),
+        ),
+        Node::ForStatement(stmt) => estree(
+            "ForStatement",
+            &stmt.span,
+            json!({
+                "init": to_estree_opt(&stmt.init),
+                "test": to_estree_opt(&stmt.test),
+                "update": to_estree_opt(&stmt.update),
+                "body": to_estree(&stmt.body),
+            }),
+        ),
+        Node::ForInStatement(stmt) => estree(
+            "ForInStatement",
+            &stmt.span,
+            json!({
+                "left": to_pattern(&stmt.left),
+                "right": to_estree(&stmt.right),
+                "body": to_estree(&stmt.body),
+            }),
+        ),
+        Node::ForOfStatement(stmt) => estree(
+            "ForOfStatement",
+            &stmt.span,
+            json!({
+                "left": to_pattern(&stmt.left),
+                "right": to_estree(&stmt.right),
+                "body": to_estree(&stmt.body),
+                "await": stmt.r#await,
+            }),
+        ),
+        Node::WhileStatement(stmt) => estree(
+            "WhileStatement",
+            &stmt.span,
+            json!({
+                "test": to_estree(&stmt.test),
+                "body": to_estree(&stmt.body),
+            }),
+        ),
+        Node::DoWhileStatement(stmt) => estree(
+            "DoWhileStatement",
+            &stmt.span,
+            json!({
+                "body": to_estree(&stmt.body),
+                "test": to_estree(&stmt.test),
+            }),
+        ),
+        Node::SwitchStatement(stmt) => estree(
+            "SwitchStatement",
+            &stmt.span,
+            json!({
+                "discriminant": to_estree(&stmt.discriminant),
+                "cases": stmt
+                    .cases
+                    .iter()
+                    .map(|case| estree(
+                        "SwitchCase",
+                        &case.span,
+                        json!({
+                            "test": to_estree_opt(&case.test),
+                            "consequent": to_estree_list(&case.consequent),
+                        }),
+                    ))
+                    .collect::<Vec<_>>(),
+            }),
+        ),
+        Node::TryStatement(stmt) => estree(
+            "TryStatement",
+            &stmt.span,
+            json!({
+                "block": to_estree(&stmt.block),
+                "handler": to_estree_opt(&stmt.handler),
+                "finalizer": to_estree_opt(&stmt.finalizer),
+            }),
+        ),
+        Node::CatchClause(clause) => estree(
+            "CatchClause",
+            &clause.span,
+            json!({
+                "param": to_pattern(&clause.param),
+                "body": to_estree(&clause.body),
+            }),
+        ),
+        Node::ThrowStatement(stmt) => estree(
+            "ThrowStatement",
+            &stmt.span,
+            json!({ "argument": to_estree(&stmt.argument) }),
+        ),
+        Node::ReturnStatement(stmt) => estree(
+            "ReturnStatement",
+            &stmt.span,
+            json!({ "argument": to_estree_opt(&stmt.argument) }),
+        ),
+        Node::BreakStatement(stmt) => estree(
+            "BreakStatement",
+            &stmt.span,
+            json!({ "label": to_estree_opt(&stmt.label) }),
+        ),
+        Node::ContinueStatement(stmt) => estree(
+            "ContinueStatement",
+            &stmt.span,
+            json!({ "label": to_estree_opt(&stmt.label) }),
+        ),
+        Node::LabeledStatement(stmt) => estree(
+            "LabeledStatement",
+            &stmt.span,
+            json!({
+                "label": to_estree(&stmt.label),
+                "body": to_estree(&stmt.body),
+            }),
+        ),
+        Node::WithStatement(stmt) => estree(
+            "WithStatement",
+            &stmt.span,
+            json!({
+                "object": to_estree(&stmt.object),
+                "body": to_estree(&stmt.body),
+            }),
+        ),
+        Node::DebuggerStatement(stmt) => estree("DebuggerStatement", &stmt.span, json!({})),
+        Node::EmptyStatement(stmt) => estree("EmptyStatement", &stmt.span, json!({})),
+        Node::ExpressionStatement(stmt) => {
+            let mut fields = json!({ "expression": to_estree(&stmt.expression) });
+            if let Some(directive) = &stmt.directive {
+                fields["directive"] = json!(directive);
+            }
+            estree("ExpressionStatement", &stmt.span, fields)
+        }
+
+        Node::ArrayLiteral(lit) => estree(
+            "ArrayExpression",
+            &lit.span,
+            json!({
+                "elements": lit
+                    .elements
+                    .iter()
+                    .map(|element| element.as_ref().map_or(Value::Null, to_estree))
+                    .collect::<Vec<_>>(),
+            }),
+        ),
+        Node::ObjectLiteral(lit) => estree(
+            "ObjectExpression",
+            &lit.span,
+            json!({ "properties": to_estree_list(&lit.properties) }),
+        ),
+        Node::TemplateLiteral(lit) => estree(
+            "TemplateLiteral",
+            &lit.span,
+            json!({
+                "quasis": lit
+                    .quasis
+                    .iter()
+                    .map(|quasi| estree(
+                        "TemplateElement",
+                        &quasi.span,
+                        json!({
+                            "value": { "raw": quasi.raw, "cooked": quasi.cooked },
+                            "tail": quasi.tail,
+                        }),
+                    ))
+                    .collect::<Vec<_>>(),
+                "expressions": to_estree_list(&lit.expressions),
+            }),
+        ),
+        Node::TaggedTemplateExpression(expr) => estree(
+            "TaggedTemplateExpression",
+            &expr.span,
+            json!({
+                "tag": to_estree(&expr.tag),
+                "quasi": to_estree(&expr.quasi),
+            }),
+        ),
+
+        Node::Property(prop) => property_to_estree(prop, to_estree(&prop.value)),
+        Node::SpreadElement(elem) => estree(
+            "SpreadElement",
+            &elem.span,
+            json!({ "argument": to_estree(&elem.argument) }),
+        ),
+        Node::RestElement(elem) => estree(
+            "RestElement",
+            &elem.span,
+            json!({ "argument": to_pattern(&elem.argument) }),
+        ),
+        Node::Super(expr) => estree("Super", &expr.span, json!({})),
+        Node::MetaProperty(prop) => estree(
+            "MetaProperty",
+            &prop.span,
+            json!({
+                "meta": to_estree(&prop.meta),
+                "property": to_estree(&prop.property),
+            }),
+        ),
+        Node::ImportExpression(expr) => estree(
+            "ImportExpression",
+            &expr.span,
+            json!({ "source": to_estree(&expr.source) }),
+        ),
+        Node::Identifier(id) => estree("Identifier", &id.span, json!({ "name": id.name })),
+        Node::PrivateIdentifier(id) => {
+            estree("PrivateIdentifier", &id.span, json!({ "name": id.name }))
+        }
+        Node::Number(lit) => estree("Literal", &lit.span, json!({ "value": lit.value })),
+        Node::String(lit) => estree("Literal", &lit.span, json!({ "value": lit.value })),
+        Node::Boolean(lit) => estree("Literal", &lit.span, json!({ "value": lit.value })),
+        Node::Null(lit) => estree("Literal", &lit.span, json!({ "value": null })),
+        Node::Undefined(lit) => estree("Identifier", &lit.span, json!({ "name": "undefined" })),
+        Node::This(expr) => estree("ThisExpression", &expr.span, json!({})),
+        Node::RegExp(regexp) => estree(
+            "Literal",
+            &regexp.span,
+            json!({
+                "value": null,
+                "regex": { "pattern": regexp.pattern, "flags": regexp.flags },
+            }),
+        ),
+        Node::BigInt(lit) => estree(
+            "Literal",
+            &lit.span,
+            json!({ "value": null, "bigint": lit.value }),
+        ),
+
+        #[cfg(feature = "jsx")]
+        Node::JsxElement(elem) => {
+            let name = jsx_name_to_estree(&elem.name);
+            estree(
+                "JSXElement",
+                &elem.span,
+                json!({
+                    "openingElement": estree(
+                        "JSXOpeningElement",
+                        &elem.span,
+                        json!({
+                            "name": name,
+                            "attributes": to_estree_list(&elem.attributes),
+                            "selfClosing": elem.self_closing,
+                        }),
+                    ),
+                    "children": to_estree_list(&elem.children),
+                    "closingElement": if elem.self_closing {
+                        Value::Null
+                    } else {
+                        estree("JSXClosingElement", &elem.span, json!({ "name": name }))
+                    },
+                }),
+            )
+        }
+        #[cfg(feature = "jsx")]
+        Node::JsxFragment(frag) => estree(
+            "JSXFragment",
+            &frag.span,
+            json!({
+                "openingFragment": estree("JSXOpeningFragment", &frag.span, json!({})),
+                "children": to_estree_list(&frag.children),
+                "closingFragment": estree("JSXClosingFragment", &frag.span, json!({})),
+            }),
+        ),
+        #[cfg(feature = "jsx")]
+        Node::JsxAttribute(attr) => estree(
+            "JSXAttribute",
+            &attr.span,
+            json!({
+                "name": jsx_name_to_estree(&attr.name),
+                "value": to_estree_opt(&attr.value),
+            }),
+        ),
+        #[cfg(feature = "jsx")]
+        Node::JsxSpreadAttribute(attr) => estree(
+            "JSXSpreadAttribute",
+            &attr.span,
+            json!({ "argument": to_estree(&attr.argument) }),
+        ),
+        #[cfg(feature = "jsx")]
+        Node::JsxExpressionContainer(container) => estree(
+            "JSXExpressionContainer",
+            &container.span,
+            json!({
+                "expression": match &container.expression {
+                    Some(expression) => to_estree(expression),
+                    None => estree("JSXEmptyExpression", &container.span, json!({})),
+                },
+            }),
+        ),
+        #[cfg(feature = "jsx")]
+        Node::JsxText(text) => estree(
+            "JSXText",
+            &text.span,
+            json!({ "value": text.value, "raw": text.raw }),
+        ),
+        #[cfg(feature = "jsx")]
+        Node::JsxNamespacedName(_) => jsx_name_to_estree(node),
+
+        Node::Error(error) => estree(
+            "Error",
+            &error.span,
+            json!({ "message": error.message, "skipped": error.skipped }),
+        ),
+    }
+}
+
+// Names inside JSX tags and attributes have their own node types.
+#[cfg(feature = "jsx")]
+fn jsx_name_to_estree(node: &Node) -> Value {
+    match node {
+        Node::Identifier(id) => estree("JSXIdentifier", &id.span, json!({ "name": id.name })),
+        Node::MemberExpression(expr) => estree(
+            "JSXMemberExpression",
+            &expr.span,
+            json!({
+                "object": jsx_name_to_estree(&expr.object),
+                "property": jsx_name_to_estree(&expr.property),
+            }),
+        ),
+        Node::JsxNamespacedName(name) => estree(
+            "JSXNamespacedName",
+            &name.span,
+            json!({
+                "namespace": jsx_name_to_estree(&name.namespace),
+                "name": jsx_name_to_estree(&name.name),
+            }),
+        ),
+        other => to_estree(other),
+    }
+}
+
+fn estree(kind: &str, span: &Span, fields: Value) -> Value {
+    let Value::Object(mut object) = fields else {
+        unreachable!("ESTree fields are always an object");
+    };
+    object.insert("type".to_string(), json!(kind));
+    object.insert(
+        "loc".to_string(),
+        json!({
+            "start": { "line": span.start.line.as_usize(), "column": span.start.column.as_usize().saturating_sub(1) },
+            "end": { "line": span.end.line.as_usize(), "column": span.end.column.as_usize().saturating_sub(1) },
+        }),
+    );
+    object.insert(
+        "range".to_string(),
+        json!([span.range.start, span.range.end]),
+    );
+    Value::Object(object)
+}
+
+fn to_estree_opt(node: &Option<Box<Node>>) -> Value {
+    node.as_deref().map_or(Value::Null, to_estree)
+}
+
+fn to_estree_list(nodes: &[Node]) -> Vec<Value> {
+    nodes.iter().map(to_estree).collect()
+}
+
+// Literals in binding position are written as the matching pattern types.
+fn to_pattern(node: &Node) -> Value {
+    match node {
+        Node::ObjectLiteral(lit) => estree(
+            "ObjectPattern",
+            &lit.span,
+            json!({
+                "properties": lit
+                    .properties
+                    .iter()
+                    .map(|property| match property {
+                        Node::Property(prop) => property_to_estree(prop, to_pattern(&prop.value)),
+                        Node::SpreadElement(elem) => estree(
+                            "RestElement",
+                            &elem.span,
+                            json!({ "argument": to_pattern(&elem.argument) }),
+                        ),
+                        other => to_estree(other),
+                    })
+                    .collect::<Vec<_>>(),
+            }),
+        ),
+        Node::ArrayLiteral(lit) => estree(
+            "ArrayPattern",
+            &lit.span,
+            json!({
+                "elements": lit
+                    .elements
+                    .iter()
+                    .map(|element| match element {
+                        Some(Node::SpreadElement(elem)) => estree(
+                            "RestElement",
+                            &elem.span,
+                            json!({ "argument": to_pattern(&elem.argument) }),
+                        ),
+                        Some(element) => to_pattern(element),
+                        None => Value::Null,
+                    })
+                    .collect::<Vec<_>>(),
+            }),
+        ),
+        Node::AssignmentExpression(expr) if expr.operator == "=" => estree(
+            "AssignmentPattern",
+            &expr.span,
+            json!({
+                "left": to_pattern(&expr.left),
+                "right": to_estree(&expr.right),
+            }),
+        ),
+        other => to_estree(other),
+    }
+}
+
+fn to_pattern_list(nodes: &[Node]) -> Vec<Value> {
+    nodes.iter().map(to_pattern).collect()
+}
+
+fn property_to_estree(prop: &Property, value: Value) -> Value {
+    estree(
+        "Property",
+        &prop.span,
+        json!({
+            "key": to_estree(&prop.key),
+            "value": value,
+            "kind": prop.kind,
+            "computed": prop.computed,
+            "method": prop.method,
+            "shorthand": prop.shorthand,
+        }),
+    )
+}
+
+fn function_fields(
+    id: &Option<Box<Node>>,
+    params: &[Node],
+    body: &Node,
+    generator: bool,
+    r#async: bool,
+) -> Value {
+    json!({
+        "id": to_estree_opt(id),
+        "params": to_pattern_list(params),
+        "body": to_estree(body),
+        "generator": generator,
+        "async": r#async,
+        "expression": false,
+    })
+}
+
+fn class_fields(id: &Option<Box<Node>>, super_class: &Option<Box<Node>>, body: &Node) -> Value {
+    json!({
+        "id": to_estree_opt(id),
+        "superClass": to_estree_opt(super_class),
+        "body": to_estree(body),
+    })
+}
+
+fn attributes_to_estree(attributes: &[ImportAttribute]) -> Vec<Value> {
+    attributes
+        .iter()
+        .map(|attribute| {
+            estree(
+                "ImportAttribute",
+                &attribute.span,
+                json!({
+                    "key": to_estree(&attribute.key),
+                    "value": to_estree(&attribute.value),
+                }),
+            )
+        })
+        .collect()
+}
+
+fn export_to_estree(decl: &ExportDeclaration) -> Value {
+    if decl.default {
+        return estree(
+            "ExportDefaultDeclaration",
+            &decl.span,
+            json!({ "declaration": to_estree_opt(&decl.declaration) }),
+        );
+    }
+    if let [Node::ExportNamespaceSpecifier(spec)] = &decl.specifiers[..] {
+        return estree(
+            "ExportAllDeclaration",
+            &decl.span,
+            json!({
+                "exported": to_estree_opt(&spec.exported),
+                "source": to_estree_opt(&decl.source),
+                "attributes": attributes_to_estree(&decl.attributes),
+            }),
+        );
+    }
+    estree(
+        "ExportNamedDeclaration",
+        &decl.span,
+        json!({
+            "declaration": to_estree_opt(&decl.declaration),
+            "specifiers": to_estree_list(&decl.specifiers),
+            "source": to_estree_opt(&decl.source),
+            "attributes": attributes_to_estree(&decl.attributes),
+        }),
+    )
+}
+
+// Reads an ESTree tree back into `Node`. `loc` and `range` are optional;
+// Acorn's `start` and `end` offsets are used when `range` is missing.
+pub fn from_estree(value: &Value) -> Result<Node, AstError> {
+    let object = EstreeObject::new(value)?;
+    let span = object.span.clone();
+
+    let node = match object.kind {
+        "Program" => Node::Program(Program {
+            body: object.nodes("body")?,
+            source_type: match object.opt_str("sourceType")? {
+                Some("module") => SourceType::Module,
+                _ => SourceType::Script,
+            },
+            span,
+        }),
+        "VariableDeclaration" => Node::VariableDeclaration(VariableDeclaration {
+            kind: object.str("kind")?.to_string(),
+            declarations: object
+                .array("declarations")?
+                .iter()
+                .map(|value| {
+                    let declarator = EstreeObject::expect(value, "VariableDeclarator")?;
+                    Ok(VariableDeclarator {
+                        id: declarator.node("id")?,
+                        init: declarator.opt_node("init")?,
+                        span: declarator.span,
+                    })
+                })
+                .collect::<Result<_, AstError>>()?,
+            span,
+        }),
+        "FunctionDeclaration" => Node::FunctionDeclaration(FunctionDeclaration {
+            id: object.opt_node("id")?,
+            params: object.nodes("params")?,
+            body: object.node("body")?,
+            generator: object.bool("generator")?,
+            r#async: object.bool("async")?,
+            span,
+        }),
+        "ClassDeclaration" => Node::ClassDeclaration(ClassDeclaration {
+            id: object.opt_node("id")?,
+            super_class: object.opt_node("superClass")?,
+            body: object.node("body")?,
+            span,
+        }),
+        "ClassBody" => Node::ClassBody(ClassBody {
+            body: object.nodes("body")?,
+            span,
+        }),
+        "MethodDefinition" => Node::MethodDefinition(MethodDefinition {
+            key: object.node("key")?,
+            value: object.node("value")?,
+            kind: match object.str("kind")? {
+                "constructor" => MethodKind::Constructor,
+                "get" => MethodKind::Get,
+                "set" => MethodKind::Set,
+                "method" => MethodKind::Method,
+                other => return Err(object.invalid(&format!("unknown method kind '{other}'"))),
+            },
+            computed: object.bool("computed")?,
+            r#static: object.bool("static")?,
+            span,
+        }),
+        "PropertyDefinition" => Node::PropertyDefinition(PropertyDefinition {
+            key: object.node("key")?,
+            value: object.opt_node("value")?,
+            computed: object.bool("computed")?,
+            r#static: object.bool("static")?,
+            span,
+        }),
+        "StaticBlock" => Node::StaticBlock(StaticBlock {
+            body: object.nodes("body")?,
+            span,
+        }),
+        "ImportDeclaration" => Node::ImportDeclaration(ImportDeclaration {
+            specifiers: object.nodes("specifiers")?,
+            source: object.node("source")?,
+            attributes: object.attributes()?,
+            span,
+        }),
+        "ExportNamedDeclaration" => Node::ExportDeclaration(ExportDeclaration {
+            declaration: object.opt_node("declaration")?,
+            specifiers: object.nodes("specifiers")?,
+            source: object.opt_node("source")?,
+            attributes: object.attributes()?,
+            default: false,
+            span,
+        }),
+        "ExportDefaultDeclaration" => Node::ExportDeclaration(ExportDeclaration {
+            declaration: Some(object.node("declaration")?),
+            specifiers: Vec::new(),
+            source: None,
+            attributes: Vec::new(),
+            default: true,
+            span,
+        }),
+        "ExportAllDeclaration" => {
+            let exported = object.opt_node("exported")?;
+            let specifier_span = exported
+                .as_deref()
+                .map_or_else(|| span.clone(), |exported| exported.span().clone());
+            Node::ExportDeclaration(ExportDeclaration {
+                declaration: None,
+                specifiers: vec![Node::ExportNamespaceSpecifier(ExportNamespaceSpecifier {
+                    exported,
+                    span: specifier_span,
+                })],
+                source: Some(object.node("source")?),
+                attributes: object.attributes()?,
+                default: false,
+                span,
+            })
+        }
+        "ImportSpecifier" => Node::ImportSpecifier(ImportSpecifier {
+            local: object.node("local")?,
+            imported: object.node("imported")?,
+            span,
+        }),
+        "ImportDefaultSpecifier" => Node::ImportDefaultSpecifier(ImportDefaultSpecifier {
+            local: object.node("local")?,
+            span,
+        }),
+        "ImportNamespaceSpecifier" => Node::ImportNamespaceSpecifier(ImportNamespaceSpecifier {
+            local: object.node("local")?,
+            span,
+        }),
+        "ExportSpecifier" => Node::ExportSpecifier(ExportSpecifier {
+            local: object.node("local")?,
+            exported: object.node("exported")?,
+            span,
+        }),
+        "ExportNamespaceSpecifier" => Node::ExportNamespaceSpecifier(ExportNamespaceSpecifier {
+            exported: object.opt_node("exported")?,
+            span,
+        }),
+
+        "BinaryExpression" => Node::BinaryExpression(BinaryExpression {
+            left: object.node("left")?,
+            operator: object.str("operator")?.to_string(),
+            right: object.node("right")?,
+            span,
+        }),
+        "UnaryExpression" => Node::UnaryExpression(UnaryExpression {
+            operator: object.str("operator")?.to_string(),
+            argument: object.node("argument")?,
+            prefix: object.bool("prefix")?,
+            span,
+        }),
+        "UpdateExpression" => Node::UpdateExpression(UpdateExpression {
+            operator: object.str("operator")?.to_string(),
+            argument: object.node("argument")?,
+            prefix: object.bool("prefix")?,
+            span,
+        }),
+        "LogicalExpression" => Node::LogicalExpression(LogicalExpression {
+            left: object.node("left")?,
+            operator: object.str("operator")?.to_string(),
+            right: object.node("right")?,
+            span,
+        }),
+        "ConditionalExpression" => Node::ConditionalExpression(ConditionalExpression {
+            test: object.node("test")?,
+            consequent: object.node("consequent")?,
+            alternate: object.node("alternate")?,
+            span,
+        }),
+        "AssignmentExpression" => Node::AssignmentExpression(AssignmentExpression {
+            left: object.node("left")?,
+            operator: object.str("operator")?.to_string(),
+            right: object.node("right")?,
+            span,
+        }),
+        "CallExpression" => Node::CallExpression(CallExpression {
+            callee: object.node("callee")?,
+            arguments: object.nodes("arguments")?,
+            span,
+        }),
+        "NewExpression" => Node::NewExpression(NewExpression {
+            callee: object.node("callee")?,
+            arguments: object.nodes("arguments")?,
+            span,
+        }),
+        "MemberExpression" => Node::MemberExpression(MemberExpression {
+            object: object.node("object")?,
+            property: object.node("property")?,
+            computed: object.bool("computed")?,
+            optional: object.bool("optional")?,
+            span,
+        }),
+        // `a?.b` is a MemberExpression with `optional` set, so the chain
+        // wrapper adds nothing.
+        "ChainExpression" => return Ok(*object.node("expression")?),
+        "ArrowFunctionExpression" => Node::ArrowFunctionExpression(ArrowFunctionExpression {
+            params: object.nodes("params")?,
+            body: object.node("body")?,
+            expression: object.bool("expression")?,
+            r#async: object.bool("async")?,
+            span,
+        }),
+        "FunctionExpression" => Node::FunctionExpression(FunctionExpression {
+            id: object.opt_node("id")?,
+            params: object.nodes("params")?,
+            body: object.node("body")?,
+            generator: object.bool("generator")?,
+            r#async: object.bool("async")?,
+            span,
+        }),
+        "ClassExpression" => Node::ClassExpression(ClassExpression {
+            id: object.opt_node("id")?,
+            super_class: object.opt_node("superClass")?,
+            body: object.node("body")?,
+            span,
+        }),
+        "YieldExpression" => Node::YieldExpression(YieldExpression {
+            argument: object.opt_node("argument")?,
+            delegate: object.bool("delegate")?,
+            span,
+        }),
+        "AwaitExpression" => Node::AwaitExpression(AwaitExpression {
+            argument: object.node("argument")?,
+            span,
+        }),
+
+        "BlockStatement" => Node::BlockStatement(BlockStatement {
+            body: object.nodes("body")?,
+            span,
+        }),
+        "IfStatement" => Node::IfStatement(IfStatement {
+            test: object.node("test")?,
+            consequent: object.node("consequent")?,
+            alternate: object.opt_node("alternate")?,
+            span,
+        }),
+        "ForStatement" => Node::ForStatement(ForStatement {
+            init: object.opt_node("init")?,
+            test: object.opt_node("test")?,
+            update: object.opt_node("update")?,
+            body: object.node("body")?,
+            span,
+        }),
+        "ForInStatement" => Node::ForInStatement(ForInStatement {
+            left: object.node("left")?,
+            right: object.node("right")?,
+            body: object.node("body")?,
+            span,
+        }),
+        "ForOfStatement" => Node::ForOfStatement(ForOfStatement {
+            left: object.node("left")?,
+            right: object.node("right")?,
+            body: object.node("body")?,
+            r#await: object.bool("await")?,
+            span,
+        }),
+        "WhileStatement" => Node::WhileStatement(WhileStatement {
+            test: object.node("test")?,
+            body: object.node("body")?,
+            span,
+        }),
+        "DoWhileStatement" => Node::DoWhileStatement(DoWhileStatement {
+            body: object.node("body")?,
+            test: object.node("test")?,
+            span,
+        }),
+        "SwitchStatement" => Node::SwitchStatement(SwitchStatement {
+            discriminant: object.node("discriminant")?,
+            cases: object
+                .array("cases")?
+                .iter()
+                .map(|value| {
+                    let case = EstreeObject::expect(value, "SwitchCase")?;
+                    Ok(SwitchCase {
+                        test: case.opt_node("test")?,
+                        consequent: case.nodes("consequent")?,
+                        span: case.span,
+                    })
+                })
+                .collect::<Result<_, AstError>>()?,
+            span,
+        }),
+        "TryStatement" => Node::TryStatement(TryStatement {
+            block: object.node("block")?,
+            handler: object.opt_node("handler")?,
+            finalizer: object.opt_node("finalizer")?,
+            span,
+        }),
+        "CatchClause" => Node::CatchClause(CatchClause {
+            param: object.node("param")?,
+            body: object.node("body")?,
+            span,
+        }),
+        "ThrowStatement" => Node::ThrowStatement(ThrowStatement {
+            argument: object.node("argument")?,
+            span,
+        }),
+        "ReturnStatement" => Node::ReturnStatement(ReturnStatement {
+            argument: object.opt_node("argument")?,
+            span,
+        }),
+        "BreakStatement" => Node::BreakStatement(BreakStatement {
+            label: object.opt_node("label")?,
+            span,
+        }),
+        "ContinueStatement" => Node::ContinueStatement(ContinueStatement {
+            label: object.opt_node("label")?,
+            span,
+        }),
+        "LabeledStatement" => Node::LabeledStatement(LabeledStatement {
+            label: object.node("label")?,
+            body: object.node("body")?,
+            span,
+        }),
+        "WithStatement" => Node::WithStatement(WithStatement {
+            object: object.node("object")?,
+            body: object.node("body")?,
+            span,
+        }),
+        "DebuggerStatement" => Node::DebuggerStatement(DebuggerStatement { span }),
+        "EmptyStatement" => Node::EmptyStatement(EmptyStatement { span }),
+        "ExpressionStatement" => Node::ExpressionStatement(ExpressionStatement {
+            expression: object.node("expression")?,
+            directive: object.opt_str("directive")?.map(str::to_string),
+            span,
+        }),
+
+        "ArrayExpression" | "ArrayPattern" => Node::ArrayLiteral(ArrayLiteral {
+            elements: object
+                .array("elements")?
+                .iter()
+                .map(|element| match element {
+                    Value::Null => Ok(None),
+                    element => pattern_element(element).map(Some),
+                })
+                .collect::<Result<_, AstError>>()?,
+            span,
+        }),
+        "ObjectExpression" | "ObjectPattern" => Node::ObjectLiteral(ObjectLiteral {
+            properties: object
+                .array("properties")?
+                .iter()
+                .map(pattern_element)
+                .collect::<Result<_, AstError>>()?,
+            span,
+        }),
+        "AssignmentPattern" => Node::AssignmentExpression(AssignmentExpression {
+            left: object.node("left")?,
+            operator: "=".to_string(),
+            right: object.node("right")?,
+            span,
+        }),
+        "TemplateLiteral" => Node::TemplateLiteral(TemplateLiteral {
+            quasis: object
+                .array("quasis")?
+                .iter()
+                .map(|value| {
+                    let quasi = EstreeObject::expect(value, "TemplateElement")?;
+                    let text = EstreeObject::field_object(&quasi, "value")?;
+                    Ok(TemplateElement {
+                        cooked: text.opt_str("cooked")?.map(str::to_string),
+                        raw: text.str("raw")?.to_string(),
+                        tail: quasi.bool("tail")?,
+                        span: quasi.span,
+                    })
+                })
+                .collect::<Result<_, AstError>>()?,
+            expressions: object.nodes("expressions")?,
+            span,
+        }),
+        "TaggedTemplateExpression" => Node::TaggedTemplateExpression(TaggedTemplateExpression {
+            tag: object.node("tag")?,
+            quasi: object.node("quasi")?,
+            span,
+        }),
+
+        "Property" => Node::Property(Property {
+            key: object.node("key")?,
+            value: object.node("value")?,
+            kind: object.str("kind")?.to_string(),
+            computed: object.bool("computed")?,
+            method: object.bool("method")?,
+            shorthand: object.bool("shorthand")?,
+            span,
+        }),
+        "SpreadElement" => Node::SpreadElement(SpreadElement {
+            argument: object.node("argument")?,
+            span,
+        }),
+        "RestElement" => Node::RestElement(RestElement {
+            argument: object.node("argument")?,
+            span,
+        }),
+        "Super" => Node::Super(Super { span }),
+        "MetaProperty" => Node::MetaProperty(MetaProperty {
+            meta: object.node("meta")?,
+            property: object.node("property")?,
+            span,
+        }),
+        "ImportExpression" => Node::ImportExpression(ImportExpression {
+            source: object.node("source")?,
+            span,
+        }),
+        "Identifier" => match object.str("name")? {
+            "undefined" => Node::Undefined(UndefinedLiteral { span }),
+            name => Node::Identifier(Identifier {
+                name: name.to_string(),
+                span,
+            }),
+        },
+        "PrivateIdentifier" => Node::PrivateIdentifier(PrivateIdentifier {
+            name: object.str("name")?.to_string(),
+            span,
+        }),
+        "ThisExpression" => Node::This(ThisExpression { span }),
+        "Literal" => literal_from_estree(&object)?,
+
+        #[cfg(feature = "jsx")]
+        "JSXElement" => {
+            let opening = object.field_object("openingElement")?;
+            Node::JsxElement(JsxElement {
+                name: opening.node("name")?,
+                attributes: opening.nodes("attributes")?,
+                children: object.nodes("children")?,
+                self_closing: opening.bool("selfClosing")?,
+                span,
+            })
+        }
+        #[cfg(feature = "jsx")]
+        "JSXFragment" => Node::JsxFragment(JsxFragment {
+            children: object.nodes("children")?,
+            span,
+        }),
+        #[cfg(feature = "jsx")]
+        "JSXAttribute" => Node::JsxAttribute(JsxAttribute {
+            name: object.node("name")?,
+            value: object.opt_node("value")?,
+            span,
+        }),
+        #[cfg(feature = "jsx")]
+        "JSXSpreadAttribute" => Node::JsxSpreadAttribute(JsxSpreadAttribute {
+            argument: object.node("argument")?,
+            span,
+        }),
+        #[cfg(feature = "jsx")]
+        "JSXExpressionContainer" => {
+            let expression = EstreeObject::new(object.field("expression")?)?;
+            Node::JsxExpressionContainer(JsxExpressionContainer {
+                expression: match expression.kind {
+                    "JSXEmptyExpression" => None,
+                    _ => Some(object.node("expression")?),
+                },
+                span,
+            })
+        }
+        #[cfg(feature = "jsx")]
+        "JSXText" => Node::JsxText(JsxText {
+            value: object.str("value")?.to_string(),
+            raw: object.str("raw")?.to_string(),
+            span,
+        }),
+        #[cfg(feature = "jsx")]
+        "JSXIdentifier" => Node::Identifier(Identifier {
+            name: object.str("name")?.to_string(),
+            span,
+        }),
+        #[cfg(feature = "jsx")]
+        "JSXMemberExpression" => Node::MemberExpression(MemberExpression {
+            object: object.node("object")?,
+            property: object.node("property")?,
+            computed: false,
+            optional: false,
+            span,
+        }),
+        #[cfg(feature = "jsx")]
+        "JSXNamespacedName" => Node::JsxNamespacedName(JsxNamespacedName {
+            namespace: object.node("namespace")?,
+            name: object.node("name")?,
+            span,
+        }),
+
+        "Error" => Node::Error(ErrorNode {
+            message: object.str("message")?.to_string(),
+            skipped: object
+                .array("skipped")?
+                .iter()
+                .map(|raw| {
+                    raw.as_str()
+                        .map(str::to_string)
+                        .ok_or_else(|| object.invalid("'skipped' must hold strings"))
+                })
+                .collect::<Result<_, AstError>>()?,
+            span,
+        }),
+
+        other => {
+            return Err(AstError::InvalidNode {
+                node_type: other.to_string(),
+                message: "unsupported ESTree node type".to_string(),
+                position: Some(object.span.start),
+            })
+        }
+    };
+
+    Ok(node)
+}
+
+// Rest elements inside object and array patterns are spread elements in
+// `Node`, as that is what the parser produces for destructuring.
+fn pattern_element(value: &Value) -> Result<Node, AstError> {
+    let object = EstreeObject::new(value)?;
+    if object.kind == "RestElement" {
+        return Ok(Node::SpreadElement(SpreadElement {
+            argument: object.node("argument")?,
+            span: object.span,
+        }));
+    }
+    from_estree(value)
+}
+
+fn literal_from_estree(object: &EstreeObject) -> Result<Node, AstError> {
+    let span = object.span.clone();
+    if let Some(regex) = object.map.get("regex").filter(|regex| !regex.is_null()) {
+        let regex = EstreeObject::from_map(regex, "regex", span.clone())?;
+        return Ok(Node::RegExp(RegExp {
+            pattern: regex.str("pattern")?.to_string(),
+            flags: regex.str("flags")?.to_string(),
+            span,
+        }));
+    }
+    if let Some(bigint) = object.opt_str("bigint")? {
+        return Ok(Node::BigInt(BigIntLiteral {
+            value: bigint.to_string(),
+            span,
+        }));
+    }
+
+    Ok(match object.map.get("value") {
+        Some(Value::Number(number)) => Node::Number(NumberLiteral {
+            value: number.as_f64().unwrap_or(f64::NAN),
+            span,
+        }),
+        Some(Value::String(value)) => Node::String(StringLiteral {
+            value: value.clone(),
+            span,
+        }),
+        Some(Value::Bool(value)) => Node::Boolean(BooleanLiteral {
+            value: *value,
+            span,
+        }),
+        Some(Value::Null) => Node::Null(NullLiteral { span }),
+        _ => return Err(object.invalid("literal has no supported 'value'")),
+    })
+}
+
+struct EstreeObject<'a> {
+    kind: &'a str,
+    map: &'a Map<String, Value>,
+    span: Span,
+}
+
+impl<'a> EstreeObject<'a> {
+    fn new(value: &'a Value) -> Result<Self, AstError> {
+        let map = value
+            .as_object()
+            .ok_or_else(|| AstError::DeserializationError {
+                message: format!("expected an ESTree node, found {value}"),
+                position: None,
+            })?;
+        let kind = map.get("type").and_then(Value::as_str).ok_or_else(|| {
+            AstError::DeserializationError {
+                message: "ESTree node has no 'type'".to_string(),
+                position: None,
+            }
+        })?;
+        Ok(Self {
+            kind,
+            map,
+            span: span_from_estree(map),
+        })
+    }
+
+    fn expect(value: &'a Value, kind: &str) -> Result<Self, AstError> {
+        let object = Self::new(value)?;
+        if object.kind != kind {
+            return Err(object.invalid(&format!("expected a {kind}")));
+        }
+        Ok(object)
+    }
+
+    // A plain object that is not a node, like `regex` on a literal.
+    fn from_map(value: &'a Value, kind: &'a str, span: Span) -> Result<Self, AstError> {
+        let map = value.as_object().ok_or_else(|| AstError::InvalidNode {
+            node_type: kind.to_string(),
+            message: "expected an object".to_string(),
+            position: Some(span.start),
+        })?;
+        Ok(Self { kind, map, span })
+    }
+
+    fn field_object(&self, name: &'a str) -> Result<EstreeObject<'a>, AstError> {
+        Self::from_map(self.field(name)?, name, self.span.clone())
+    }
+
+    fn invalid(&self, message: &str) -> AstError {
+        AstError::InvalidNode {
+            node_type: self.kind.to_string(),
+            message: message.to_string(),
+            position: Some(self.span.start),
+        }
+    }
+
+    fn field(&self, name: &str) -> Result<&'a Value, AstError> {
+        self.map
+            .get(name)
+            .ok_or_else(|| self.invalid(&format!("missing field '{name}'")))
+    }
+
+    fn node(&self, name: &str) -> Result<Box<Node>, AstError> {
+        from_estree(self.field(name)?).map(Box::new)
+    }
+
+    fn opt_node(&self, name: &str) -> Result<Option<Box<Node>>, AstError> {
+        match self.map.get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => from_estree(value).map(|node| Some(Box::new(node))),
+        }
+    }
+
+    fn array(&self, name: &str) -> Result<&'a Vec<Value>, AstError> {
+        self.field(name)?
+            .as_array()
+            .ok_or_else(|| self.invalid(&format!("'{name}' must be an array")))
+    }
+
+    fn nodes(&self, name: &str) -> Result<Vec<Node>, AstError> {
+        self.array(name)?.iter().map(from_estree).collect()
+    }
+
+    fn str(&self, name: &str) -> Result<&'a str, AstError> {
+        self.field(name)?
+            .as_str()
+            .ok_or_else(|| self.invalid(&format!("'{name}' must be a string")))
+    }
+
+    fn opt_str(&self, name: &str) -> Result<Option<&'a str>, AstError> {
+        match self.map.get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => value
+                .as_str()
+                .map(Some)
+                .ok_or_else(|| self.invalid(&format!("'{name}' must be a string"))),
+        }
+    }
+
+    // Tools leave out flags that are false, so a missing one reads as false.
+    fn bool(&self, name: &str) -> Result<bool, AstError> {
+        match self.map.get(name) {
+            None | Some(Value::Null) => Ok(false),
+            Some(value) => value
+                .as_bool()
+                .ok_or_else(|| self.invalid(&format!("'{name}' must be a boolean"))),
+        }
+    }
+
+    // `attributes` is the current name; older tools used `assertions`.
+    fn attributes(&self) -> Result<Vec<ImportAttribute>, AstError> {
+        let Some(values) = ["attributes", "assertions"]
+            .iter()
+            .find_map(|name| self.map.get(*name).and_then(Value::as_array))
+        else {
+            return Ok(Vec::new());
+        };
+        values
+            .iter()
+            .map(|value| {
+                let attribute = EstreeObject::expect(value, "ImportAttribute")?;
+                Ok(ImportAttribute {
+                    key: attribute.node("key")?,
+                    value: attribute.node("value")?,
+                    span: attribute.span,
+                })
+            })
+            .collect()
+    }
+}
+
+fn span_from_estree(map: &Map<String, Value>) -> Span {
+    let offset = |value: Option<&Value>| value.and_then(Value::as_u64).map(|n| n as usize);
+    let range = match map.get("range").and_then(Value::as_array) {
+        Some(range) => offset(range.first()).zip(offset(range.get(1))),
+        None => offset(map.get("start")).zip(offset(map.get("end"))),
+    };
+
+    let position = |name: &str| {
+        let position = map.get("loc")?.get(name)?;
+        let line = position.get("line")?.as_u64()? as usize;
+        let column = position.get("column")?.as_u64()? as usize;
+        Some(Position::new(line, column + 1))
+    };
+
+    let span = Span::new(
+        position("start").unwrap_or_default(),
+        position("end").unwrap_or_default(),
+    );
+    match range {
+        Some((start, end)) => span.with_range(start..end),
+        None => span,
+    }
+}
@@ -6,7 +6,7 @@ pub struct BinaryExpression {
     pub left: Box<crate::ast::node::Node>,
     pub operator: String,
     pub right: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -14,7 +14,7 @@ pub struct UnaryExpression {
     pub operator: String,
     pub argument: Box<crate::ast::node::Node>,
     pub prefix: bool,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -22,5 +22,5 @@ pub struct UpdateExpression {
     pub operator: String,
     pub argument: Box<crate::ast::node::Node>,
     pub prefix: bool,
-    pub span: Option<Span>,
+    pub span: Span,
 }
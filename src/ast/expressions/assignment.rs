@@ -6,21 +6,21 @@ pub struct AssignmentExpression {
     pub left: Box<crate::ast::node::Node>,
     pub operator: String,
     pub right: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CallExpression {
     pub callee: Box<crate::ast::node::Node>,
     pub arguments: Vec<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NewExpression {
     pub callee: Box<crate::ast::node::Node>,
     pub arguments: Vec<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -29,5 +29,5 @@ pub struct MemberExpression {
     pub property: Box<crate::ast::node::Node>,
     pub computed: bool,
     pub optional: bool,
-    pub span: Option<Span>,
+    pub span: Span,
 }
@@ -6,7 +6,7 @@ pub struct LogicalExpression {
     pub left: Box<crate::ast::node::Node>,
     pub operator: String,
     pub right: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -14,5 +14,5 @@ pub struct ConditionalExpression {
     pub test: Box<crate::ast::node::Node>,
     pub consequent: Box<crate::ast::node::Node>,
     pub alternate: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
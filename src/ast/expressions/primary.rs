@@ -1,34 +1,60 @@
 use crate::ast::common::Span;
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Identifier {
+    pub name: String,
+    pub span: Span,
+}
+
+// `#name` as a class member key, after `.`, or before `in`; `name` is
+// without the `#`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrivateIdentifier {
+    pub name: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThisExpression {
+    pub span: Span,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Super {
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MetaProperty {
     pub meta: Box<crate::ast::node::Node>,
     pub property: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
+}
+
+// Dynamic `import(source)`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportExpression {
+    pub source: Box<crate::ast::node::Node>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct YieldExpression {
     pub argument: Option<Box<crate::ast::node::Node>>,
     pub delegate: bool,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AwaitExpression {
     pub argument: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RegExp {
     pub pattern: String,
     pub flags: String,
-    pub span: Option<Span>,
+    pub span: Span,
 }
@@ -0,0 +1,695 @@
+use crate::ast::{Node, *};
+
+// Owned counterpart of `VisitMut`: each method takes a node by value and
+// returns the node that replaces it, which may be of another variant. The
+// defaults fold every child node and rebuild the node around the results.
+//
+// Overriding `fold_node` sees every node in the tree; calling
+// `fold_children` from the override carries on with the default fold.
+pub trait Fold {
+    fn fold_node(&mut self, node: Node) -> Node {
+        self.fold_children(node)
+    }
+
+    // Dispatches to the method for the node's variant, each of which folds
+    // the node's children by default.
+    fn fold_children(&mut self, node: Node) -> Node {
+        match node {
+            Node::Program(program) => self.fold_program(program),
+            Node::VariableDeclaration(decl) => self.fold_variable_declaration(decl),
+            Node::FunctionDeclaration(decl) => self.fold_function_declaration(decl),
+            Node::ClassDeclaration(decl) => self.fold_class_declaration(decl),
+            Node::ClassBody(body) => self.fold_class_body(body),
+            Node::MethodDefinition(method) => self.fold_method_definition(method),
+            Node::PropertyDefinition(prop) => self.fold_property_definition(prop),
+            Node::StaticBlock(block) => self.fold_static_block(block),
+            Node::ImportDeclaration(decl) => self.fold_import_declaration(decl),
+            Node::ExportDeclaration(decl) => self.fold_export_declaration(decl),
+            Node::ImportSpecifier(spec) => self.fold_import_specifier(spec),
+            Node::ImportDefaultSpecifier(spec) => self.fold_import_default_specifier(spec),
+            Node::ImportNamespaceSpecifier(spec) => self.fold_import_namespace_specifier(spec),
+            Node::ExportSpecifier(spec) => self.fold_export_specifier(spec),
+            Node::ExportNamespaceSpecifier(spec) => self.fold_export_namespace_specifier(spec),
+            Node::BinaryExpression(expr) => self.fold_binary_expression(expr),
+            Node::UnaryExpression(expr) => self.fold_unary_expression(expr),
+            Node::UpdateExpression(expr) => self.fold_update_expression(expr),
+            Node::LogicalExpression(expr) => self.fold_logical_expression(expr),
+            Node::ConditionalExpression(expr) => self.fold_conditional_expression(expr),
+            Node::AssignmentExpression(expr) => self.fold_assignment_expression(expr),
+            Node::CallExpression(expr) => self.fold_call_expression(expr),
+            Node::NewExpression(expr) => self.fold_new_expression(expr),
+            Node::MemberExpression(expr) => self.fold_member_expression(expr),
+            Node::ArrowFunctionExpression(expr) => self.fold_arrow_function_expression(expr),
+            Node::FunctionExpression(expr) => self.fold_function_expression(expr),
+            Node::ClassExpression(expr) => self.fold_class_expression(expr),
+            Node::YieldExpression(expr) => self.fold_yield_expression(expr),
+            Node::AwaitExpression(expr) => self.fold_await_expression(expr),
+            Node::BlockStatement(stmt) => self.fold_block_statement(stmt),
+            Node::IfStatement(stmt) => self.fold_if_statement(stmt),
+            Node::ForStatement(stmt) => self.fold_for_statement(stmt),
+            Node::ForInStatement(stmt) => self.fold_for_in_statement(stmt),
+            Node::ForOfStatement(stmt) => self.fold_for_of_statement(stmt),
+            Node::WhileStatement(stmt) => self.fold_while_statement(stmt),
+            Node::DoWhileStatement(stmt) => self.fold_do_while_statement(stmt),
+            Node::SwitchStatement(stmt) => self.fold_switch_statement(stmt),
+            Node::TryStatement(stmt) => self.fold_try_statement(stmt),
+            Node::CatchClause(clause) => self.fold_catch_clause(clause),
+            Node::ThrowStatement(stmt) => self.fold_throw_statement(stmt),
+            Node::ReturnStatement(stmt) => self.fold_return_statement(stmt),
+            Node::BreakStatement(stmt) => self.fold_break_statement(stmt),
+            Node::ContinueStatement(stmt) => self.fold_continue_statement(stmt),
+            Node::LabeledStatement(stmt) => self.fold_labeled_statement(stmt),
+            Node::WithStatement(stmt) => self.fold_with_statement(stmt),
+            Node::DebuggerStatement(stmt) => self.fold_debugger_statement(stmt),
+            Node::EmptyStatement(stmt) => self.fold_empty_statement(stmt),
+            Node::ExpressionStatement(stmt) => self.fold_expression_statement(stmt),
+            Node::ArrayLiteral(lit) => self.fold_array_literal(lit),
+            Node::ObjectLiteral(lit) => self.fold_object_literal(lit),
+            Node::TemplateLiteral(lit) => self.fold_template_literal(lit),
+            Node::TaggedTemplateExpression(expr) => self.fold_tagged_template_expression(expr),
+            Node::Property(prop) => self.fold_property(prop),
+            Node::SpreadElement(elem) => self.fold_spread_element(elem),
+            Node::RestElement(elem) => self.fold_rest_element(elem),
+            Node::Super(super_expr) => self.fold_super(super_expr),
+            Node::MetaProperty(prop) => self.fold_meta_property(prop),
+            Node::ImportExpression(expr) => self.fold_import_expression(expr),
+            Node::Identifier(id) => self.fold_identifier(id),
+            Node::PrivateIdentifier(id) => self.fold_private_identifier(id),
+            Node::Number(lit) => self.fold_number(lit),
+            Node::String(lit) => self.fold_string(lit),
+            Node::Boolean(lit) => self.fold_boolean(lit),
+            Node::Null(lit) => self.fold_null(lit),
+            Node::Undefined(lit) => self.fold_undefined(lit),
+            Node::This(expr) => self.fold_this(expr),
+            Node::RegExp(regexp) => self.fold_regexp(regexp),
+            Node::BigInt(lit) => self.fold_bigint(lit),
+            #[cfg(feature = "jsx")]
+            Node::JsxElement(elem) => self.fold_jsx_element(elem),
+            #[cfg(feature = "jsx")]
+            Node::JsxFragment(frag) => self.fold_jsx_fragment(frag),
+            #[cfg(feature = "jsx")]
+            Node::JsxAttribute(attr) => self.fold_jsx_attribute(attr),
+            #[cfg(feature = "jsx")]
+            Node::JsxSpreadAttribute(attr) => self.fold_jsx_spread_attribute(attr),
+            #[cfg(feature = "jsx")]
+            Node::JsxExpressionContainer(container) => {
+                self.fold_jsx_expression_container(container)
+            }
+            #[cfg(feature = "jsx")]
+            Node::JsxText(text) => self.fold_jsx_text(text),
+            #[cfg(feature = "jsx")]
+            Node::JsxNamespacedName(name) => self.fold_jsx_namespaced_name(name),
+            Node::Error(error) => self.fold_error(error),
+        }
+    }
+
+    fn fold_nodes(&mut self, nodes: Vec<Node>) -> Vec<Node> {
+        nodes.into_iter().map(|node| self.fold_node(node)).collect()
+    }
+
+    // Reuses the allocation of the node it replaces.
+    fn fold_boxed(&mut self, mut node: Box<Node>) -> Box<Node> {
+        *node = self.fold_node(*node);
+        node
+    }
+
+    fn fold_opt(&mut self, node: Option<Box<Node>>) -> Option<Box<Node>> {
+        node.map(|node| self.fold_boxed(node))
+    }
+
+    fn fold_program(&mut self, program: Program) -> Node {
+        Node::Program(Program {
+            body: self.fold_nodes(program.body),
+            ..program
+        })
+    }
+
+    fn fold_variable_declaration(&mut self, decl: VariableDeclaration) -> Node {
+        let declarations = decl
+            .declarations
+            .into_iter()
+            .map(|declarator| VariableDeclarator {
+                id: self.fold_boxed(declarator.id),
+                init: self.fold_opt(declarator.init),
+                ..declarator
+            })
+            .collect();
+        Node::VariableDeclaration(VariableDeclaration {
+            declarations,
+            ..decl
+        })
+    }
+
+    fn fold_function_declaration(&mut self, decl: FunctionDeclaration) -> Node {
+        Node::FunctionDeclaration(FunctionDeclaration {
+            id: self.fold_opt(decl.id),
+            params: self.fold_nodes(decl.params),
+            body: self.fold_boxed(decl.body),
+            ..decl
+        })
+    }
+
+    fn fold_class_declaration(&mut self, decl: ClassDeclaration) -> Node {
+        Node::ClassDeclaration(ClassDeclaration {
+            id: self.fold_opt(decl.id),
+            super_class: self.fold_opt(decl.super_class),
+            body: self.fold_boxed(decl.body),
+            ..decl
+        })
+    }
+
+    fn fold_class_body(&mut self, body: ClassBody) -> Node {
+        Node::ClassBody(ClassBody {
+            body: self.fold_nodes(body.body),
+            ..body
+        })
+    }
+
+    fn fold_method_definition(&mut self, method: MethodDefinition) -> Node {
+        Node::MethodDefinition(MethodDefinition {
+            key: self.fold_boxed(method.key),
+            value: self.fold_boxed(method.value),
+            ..method
+        })
+    }
+
+    fn fold_property_definition(&mut self, prop: PropertyDefinition) -> Node {
+        Node::PropertyDefinition(PropertyDefinition {
+            key: self.fold_boxed(prop.key),
+            value: self.fold_opt(prop.value),
+            ..prop
+        })
+    }
+
+    fn fold_static_block(&mut self, block: StaticBlock) -> Node {
+        Node::StaticBlock(StaticBlock {
+            body: self.fold_nodes(block.body),
+            ..block
+        })
+    }
+
+    fn fold_import_attributes(&mut self, attributes: Vec<ImportAttribute>) -> Vec<ImportAttribute> {
+        attributes
+            .into_iter()
+            .map(|attribute| ImportAttribute {
+                key: self.fold_boxed(attribute.key),
+                value: self.fold_boxed(attribute.value),
+                ..attribute
+            })
+            .collect()
+    }
+
+    fn fold_import_declaration(&mut self, decl: ImportDeclaration) -> Node {
+        Node::ImportDeclaration(ImportDeclaration {
+            specifiers: self.fold_nodes(decl.specifiers),
+            source: self.fold_boxed(decl.source),
+            attributes: self.fold_import_attributes(decl.attributes),
+            ..decl
+        })
+    }
+
+    fn fold_export_declaration(&mut self, decl: ExportDeclaration) -> Node {
+        Node::ExportDeclaration(ExportDeclaration {
+            declaration: self.fold_opt(decl.declaration),
+            specifiers: self.fold_nodes(decl.specifiers),
+            source: self.fold_opt(decl.source),
+            attributes: self.fold_import_attributes(decl.attributes),
+            ..decl
+        })
+    }
+
+    fn fold_import_specifier(&mut self, spec: ImportSpecifier) -> Node {
+        Node::ImportSpecifier(ImportSpecifier {
+            imported: self.fold_boxed(spec.imported),
+            local: self.fold_boxed(spec.local),
+            ..spec
+        })
+    }
+
+    fn fold_import_default_specifier(&mut self, spec: ImportDefaultSpecifier) -> Node {
+        Node::ImportDefaultSpecifier(ImportDefaultSpecifier {
+            local: self.fold_boxed(spec.local),
+            ..spec
+        })
+    }
+
+    fn fold_import_namespace_specifier(&mut self, spec: ImportNamespaceSpecifier) -> Node {
+        Node::ImportNamespaceSpecifier(ImportNamespaceSpecifier {
+            local: self.fold_boxed(spec.local),
+            ..spec
+        })
+    }
+
+    fn fold_export_specifier(&mut self, spec: ExportSpecifier) -> Node {
+        Node::ExportSpecifier(ExportSpecifier {
+            local: self.fold_boxed(spec.local),
+            exported: self.fold_boxed(spec.exported),
+            ..spec
+        })
+    }
+
+    fn fold_export_namespace_specifier(&mut self, spec: ExportNamespaceSpecifier) -> Node {
+        Node::ExportNamespaceSpecifier(ExportNamespaceSpecifier {
+            exported: self.fold_opt(spec.exported),
+            ..spec
+        })
+    }
+
+    fn fold_binary_expression(&mut self, expr: BinaryExpression) -> Node {
+        Node::BinaryExpression(BinaryExpression {
+            left: self.fold_boxed(expr.left),
+            right: self.fold_boxed(expr.right),
+            ..expr
+        })
+    }
+
+    fn fold_unary_expression(&mut self, expr: UnaryExpression) -> Node {
+        Node::UnaryExpression(UnaryExpression {
+            argument: self.fold_boxed(expr.argument),
+            ..expr
+        })
+    }
+
+    fn fold_update_expression(&mut self, expr: UpdateExpression) -> Node {
+        Node::UpdateExpression(UpdateExpression {
+            argument: self.fold_boxed(expr.argument),
+            ..expr
+        })
+    }
+
+    fn fold_logical_expression(&mut self, expr: LogicalExpression) -> Node {
+        Node::LogicalExpression(LogicalExpression {
+            left: self.fold_boxed(expr.left),
+            right: self.fold_boxed(expr.right),
+            ..expr
+        })
+    }
+
+    fn fold_conditional_expression(&mut self, expr: ConditionalExpression) -> Node {
+        Node::ConditionalExpression(ConditionalExpression {
+            test: self.fold_boxed(expr.test),
+            consequent: self.fold_boxed(expr.consequent),
+            alternate: self.fold_boxed(expr.alternate),
+            ..expr
+        })
+    }
+
+    fn fold_assignment_expression(&mut self, expr: AssignmentExpression) -> Node {
+        Node::AssignmentExpression(AssignmentExpression {
+            left: self.fold_boxed(expr.left),
+            right: self.fold_boxed(expr.right),
+            ..expr
+        })
+    }
+
+    fn fold_call_expression(&mut self, expr: CallExpression) -> Node {
+        Node::CallExpression(CallExpression {
+            callee: self.fold_boxed(expr.callee),
+            arguments: self.fold_nodes(expr.arguments),
+            ..expr
+        })
+    }
+
+    fn fold_new_expression(&mut self, expr: NewExpression) -> Node {
+        Node::NewExpression(NewExpression {
+            callee: self.fold_boxed(expr.callee),
+            arguments: self.fold_nodes(expr.arguments),
+            ..expr
+        })
+    }
+
+    fn fold_member_expression(&mut self, expr: MemberExpression) -> Node {
+        Node::MemberExpression(MemberExpression {
+            object: self.fold_boxed(expr.object),
+            property: self.fold_boxed(expr.property),
+            ..expr
+        })
+    }
+
+    fn fold_arrow_function_expression(&mut self, expr: ArrowFunctionExpression) -> Node {
+        Node::ArrowFunctionExpression(ArrowFunctionExpression {
+            params: self.fold_nodes(expr.params),
+            body: self.fold_boxed(expr.body),
+            ..expr
+        })
+    }
+
+    fn fold_function_expression(&mut self, expr: FunctionExpression) -> Node {
+        Node::FunctionExpression(FunctionExpression {
+            id: self.fold_opt(expr.id),
+            params: self.fold_nodes(expr.params),
+            body: self.fold_boxed(expr.body),
+            ..expr
+        })
+    }
+
+    fn fold_class_expression(&mut self, expr: ClassExpression) -> Node {
+        Node::ClassExpression(ClassExpression {
+            id: self.fold_opt(expr.id),
+            super_class: self.fold_opt(expr.super_class),
+            body: self.fold_boxed(expr.body),
+            ..expr
+        })
+    }
+
+    fn fold_yield_expression(&mut self, expr: YieldExpression) -> Node {
+        Node::YieldExpression(YieldExpression {
+            argument: self.fold_opt(expr.argument),
+            ..expr
+        })
+    }
+
+    fn fold_await_expression(&mut self, expr: AwaitExpression) -> Node {
+        Node::AwaitExpression(AwaitExpression {
+            argument: self.fold_boxed(expr.argument),
+            ..expr
+        })
+    }
+
+    fn fold_block_statement(&mut self, stmt: BlockStatement) -> Node {
+        Node::BlockStatement(BlockStatement {
+            body: self.fold_nodes(stmt.body),
+            ..stmt
+        })
+    }
+
+    fn fold_if_statement(&mut self, stmt: IfStatement) -> Node {
+        Node::IfStatement(IfStatement {
+            test: self.fold_boxed(stmt.test),
+            consequent: self.fold_boxed(stmt.consequent),
+            alternate: self.fold_opt(stmt.alternate),
+            ..stmt
+        })
+    }
+
+    fn fold_for_statement(&mut self, stmt: ForStatement) -> Node {
+        Node::ForStatement(ForStatement {
+            init: self.fold_opt(stmt.init),
+            test: self.fold_opt(stmt.test),
+            update: self.fold_opt(stmt.update),
+            body: self.fold_boxed(stmt.body),
+            ..stmt
+        })
+    }
+
+    fn fold_for_in_statement(&mut self, stmt: ForInStatement) -> Node {
+        Node::ForInStatement(ForInStatement {
+            left: self.fold_boxed(stmt.left),
+            right: self.fold_boxed(stmt.right),
+            body: self.fold_boxed(stmt.body),
+            ..stmt
+        })
+    }
+
+    fn fold_for_of_statement(&mut self, stmt: ForOfStatement) -> Node {
+        Node::ForOfStatement(ForOfStatement {
+            left: self.fold_boxed(stmt.left),
+            right: self.fold_boxed(stmt.right),
+            body: self.fold_boxed(stmt.body),
+            ..stmt
+        })
+    }
+
+    fn fold_while_statement(&mut self, stmt: WhileStatement) -> Node {
+        Node::WhileStatement(WhileStatement {
+            test: self.fold_boxed(stmt.test),
+            body: self.fold_boxed(stmt.body),
+            ..stmt
+        })
+    }
+
+    fn fold_do_while_statement(&mut self, stmt: DoWhileStatement) -> Node {
+        Node::DoWhileStatement(DoWhileStatement {
+            body: self.fold_boxed(stmt.body),
+            test: self.fold_boxed(stmt.test),
+            ..stmt
+        })
+    }
+
+    fn fold_switch_statement(&mut self, stmt: SwitchStatement) -> Node {
+        let discriminant = self.fold_boxed(stmt.discriminant);
+        let cases = stmt
+            .cases
+            .into_iter()
+            .map(|case| SwitchCase {
+                test: self.fold_opt(case.test),
+                consequent: self.fold_nodes(case.consequent),
+                ..case
+            })
+            .collect();
+        Node::SwitchStatement(SwitchStatement {
+            discriminant,
+            cases,
+            ..stmt
+        })
+    }
+
+    fn fold_try_statement(&mut self, stmt: TryStatement) -> Node {
+        Node::TryStatement(TryStatement {
+            block: self.fold_boxed(stmt.block),
+            handler: self.fold_opt(stmt.handler),
+            finalizer: self.fold_opt(stmt.finalizer),
+            ..stmt
+        })
+    }
+
+    fn fold_catch_clause(&mut self, clause: CatchClause) -> Node {
+        Node::CatchClause(CatchClause {
+            param: self.fold_boxed(clause.param),
+            body: self.fold_boxed(clause.body),
+            ..clause
+        })
+    }
+
+    fn fold_throw_statement(&mut self, stmt: ThrowStatement) -> Node {
+        Node::ThrowStatement(ThrowStatement {
+            argument: self.fold_boxed(stmt.argument),
+            ..stmt
+        })
+    }
+
+    fn fold_return_statement(&mut self, stmt: ReturnStatement) -> Node {
+        Node::ReturnStatement(ReturnStatement {
+            argument: self.fold_opt(stmt.argument),
+            ..stmt
+        })
+    }
+
+    fn fold_break_statement(&mut self, stmt: BreakStatement) -> Node {
+        Node::BreakStatement(BreakStatement {
+            label: self.fold_opt(stmt.label),
+            ..stmt
+        })
+    }
+
+    fn fold_continue_statement(&mut self, stmt: ContinueStatement) -> Node {
+        Node::ContinueStatement(ContinueStatement {
+            label: self.fold_opt(stmt.label),
+            ..stmt
+        })
+    }
+
+    fn fold_labeled_statement(&mut self, stmt: LabeledStatement) -> Node {
+        Node::LabeledStatement(LabeledStatement {
+            label: self.fold_boxed(stmt.label),
+            body: self.fold_boxed(stmt.body),
+            ..stmt
+        })
+    }
+
+    fn fold_with_statement(&mut self, stmt: WithStatement) -> Node {
+        Node::WithStatement(WithStatement {
+            object: self.fold_boxed(stmt.object),
+            body: self.fold_boxed(stmt.body),
+            ..stmt
+        })
+    }
+
+    fn fold_debugger_statement(&mut self, stmt: DebuggerStatement) -> Node {
+        Node::DebuggerStatement(stmt)
+    }
+
+    fn fold_empty_statement(&mut self, stmt: EmptyStatement) -> Node {
+        Node::EmptyStatement(stmt)
+    }
+
+    fn fold_expression_statement(&mut self, stmt: ExpressionStatement) -> Node {
+        Node::ExpressionStatement(ExpressionStatement {
+            expression: self.fold_boxed(stmt.expression),
+            ..stmt
+        })
+    }
+
+    fn fold_array_literal(&mut self, lit: ArrayLiteral) -> Node {
+        let elements = lit
+            .elements
+            .into_iter()
+            .map(|element| element.map(|element| self.fold_node(element)))
+            .collect();
+        Node::ArrayLiteral(ArrayLiteral { elements, ..lit })
+    }
+
+    fn fold_object_literal(&mut self, lit: ObjectLiteral) -> Node {
+        Node::ObjectLiteral(ObjectLiteral {
+            properties: self.fold_nodes(lit.properties),
+            ..lit
+        })
+    }
+
+    fn fold_template_literal(&mut self, lit: TemplateLiteral) -> Node {
+        Node::TemplateLiteral(TemplateLiteral {
+            expressions: self.fold_nodes(lit.expressions),
+            ..lit
+        })
+    }
+
+    fn fold_tagged_template_expression(&mut self, expr: TaggedTemplateExpression) -> Node {
+        Node::TaggedTemplateExpression(TaggedTemplateExpression {
+            tag: self.fold_boxed(expr.tag),
+            quasi: self.fold_boxed(expr.quasi),
+            ..expr
+        })
+    }
+
+    fn fold_property(&mut self, prop: Property) -> Node {
+        Node::Property(Property {
+            key: self.fold_boxed(prop.key),
+            value: self.fold_boxed(prop.value),
+            ..prop
+        })
+    }
+
+    fn fold_spread_element(&mut self, elem: SpreadElement) -> Node {
+        Node::SpreadElement(SpreadElement {
+            argument: self.fold_boxed(elem.argument),
+            ..elem
+        })
+    }
+
+    fn fold_rest_element(&mut self, elem: RestElement) -> Node {
+        Node::RestElement(RestElement {
+            argument: self.fold_boxed(elem.argument),
+            ..elem
+        })
+    }
+
+    fn fold_super(&mut self, super_expr: Super) -> Node {
+        Node::Super(super_expr)
+    }
+
+    fn fold_meta_property(&mut self, prop: MetaProperty) -> Node {
+        Node::MetaProperty(MetaProperty {
+            meta: self.fold_boxed(prop.meta),
+            property: self.fold_boxed(prop.property),
+            ..prop
+        })
+    }
+
+    fn fold_import_expression(&mut self, expr: ImportExpression) -> Node {
+        Node::ImportExpression(ImportExpression {
+            source: self.fold_boxed(expr.source),
+            ..expr
+        })
+    }
+
+    fn fold_identifier(&mut self, id: Identifier) -> Node {
+        Node::Identifier(id)
+    }
+
+    fn fold_private_identifier(&mut self, id: PrivateIdentifier) -> Node {
+        Node::PrivateIdentifier(id)
+    }
+
+    fn fold_number(&mut self, lit: NumberLiteral) -> Node {
+        Node::Number(lit)
+    }
+
+    fn fold_string(&mut self, lit: StringLiteral) -> Node {
+        Node::String(lit)
+    }
+
+    fn fold_boolean(&mut self, lit: BooleanLiteral) -> Node {
+        Node::Boolean(lit)
+    }
+
+    fn fold_null(&mut self, lit: NullLiteral) -> Node {
+        Node::Null(lit)
+    }
+
+    fn fold_undefined(&mut self, lit: UndefinedLiteral) -> Node {
+        Node::Undefined(lit)
+    }
+
+    fn fold_this(&mut self, expr: ThisExpression) -> Node {
+        Node::This(expr)
+    }
+
+    fn fold_regexp(&mut self, regexp: RegExp) -> Node {
+        Node::RegExp(regexp)
+    }
+
+    fn fold_bigint(&mut self, lit: BigIntLiteral) -> Node {
+        Node::BigInt(lit)
+    }
+
+    #[cfg(feature = "jsx")]
+    fn fold_jsx_element(&mut self, elem: JsxElement) -> Node {
+        Node::JsxElement(JsxElement {
+            name: self.fold_boxed(elem.name),
+            attributes: self.fold_nodes(elem.attributes),
+            children: self.fold_nodes(elem.children),
+            ..elem
+        })
+    }
+
+    #[cfg(feature = "jsx")]
+    fn fold_jsx_fragment(&mut self, frag: JsxFragment) -> Node {
+        Node::JsxFragment(JsxFragment {
+            children: self.fold_nodes(frag.children),
+            ..frag
+        })
+    }
+
+    #[cfg(feature = "jsx")]
+    fn fold_jsx_attribute(&mut self, attr: JsxAttribute) -> Node {
+        Node::JsxAttribute(JsxAttribute {
+            name: self.fold_boxed(attr.name),
+            value: self.fold_opt(attr.value),
+            ..attr
+        })
+    }
+
+    #[cfg(feature = "jsx")]
+    fn fold_jsx_spread_attribute(&mut self, attr: JsxSpreadAttribute) -> Node {
+        Node::JsxSpreadAttribute(JsxSpreadAttribute {
+            argument: self.fold_boxed(attr.argument),
+            ..attr
+        })
+    }
+
+    #[cfg(feature = "jsx")]
+    fn fold_jsx_expression_container(&mut self, container: JsxExpressionContainer) -> Node {
+        Node::JsxExpressionContainer(JsxExpressionContainer {
+            expression: self.fold_opt(container.expression),
+            ..container
+        })
+    }
+
+    #[cfg(feature = "jsx")]
+    fn fold_jsx_text(&mut self, text: JsxText) -> Node {
+        Node::JsxText(text)
+    }
+
+    #[cfg(feature = "jsx")]
+    fn fold_jsx_namespaced_name(&mut self, name: JsxNamespacedName) -> Node {
+        Node::JsxNamespacedName(JsxNamespacedName {
+            namespace: self.fold_boxed(name.namespace),
+            name: self.fold_boxed(name.name),
+            ..name
+        })
+    }
+
+    fn fold_error(&mut self, error: ErrorNode) -> Node {
+        Node::Error(error)
+    }
+}
@@ -0,0 +1,265 @@
+use crate::ast::common::Span;
+use crate::ast::jsx::{JsxAttribute, JsxElement, JsxFragment};
+use crate::ast::*;
+
+// The functions JSX is lowered to, written as dotted paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsxOptions {
+    pub pragma: String,
+    pub pragma_frag: String,
+}
+
+impl Default for JsxOptions {
+    fn default() -> Self {
+        Self {
+            pragma: "React.createElement".to_string(),
+            pragma_frag: "React.Fragment".to_string(),
+        }
+    }
+}
+
+impl JsxOptions {
+    pub fn with_pragma(mut self, pragma: impl Into<String>) -> Self {
+        self.pragma = pragma.into();
+        self
+    }
+
+    pub fn with_pragma_frag(mut self, pragma_frag: impl Into<String>) -> Self {
+        self.pragma_frag = pragma_frag.into();
+        self
+    }
+}
+
+// Returns `node` with every JSX element and fragment replaced by a call,
+// `<a b="c">d</a>` becoming `React.createElement("a", { b: "c" }, "d")`.
+// Lowercase and dashed tag names are strings, other names are references.
+// Spread attributes merge through `Object.assign`. The generated nodes
+// take the span of the JSX they replace.
+pub fn lower_jsx(node: &Node, options: &JsxOptions) -> Node {
+    let mut node = node.clone();
+    JsxLowering { options }.visit_mut_node(&mut node);
+    node
+}
+
+struct JsxLowering<'a> {
+    options: &'a JsxOptions,
+}
+
+impl VisitMut for JsxLowering<'_> {
+    // Children go first, so an element only ever holds lowered JSX.
+    fn visit_mut_node(&mut self, node: &mut Node) {
+        self.visit_mut_children(node);
+        match node {
+            Node::JsxElement(elem) => *node = lower_element(elem, self.options),
+            Node::JsxFragment(frag) => *node = lower_fragment(frag, self.options),
+            _ => {}
+        }
+    }
+}
+
+fn lower_element(elem: &JsxElement, options: &JsxOptions) -> Node {
+    let mut arguments = vec![tag(&elem.name), props(&elem.attributes, &elem.span)];
+    arguments.extend(elem.children.iter().filter_map(child));
+    call(path(&options.pragma, &elem.span), arguments, &elem.span)
+}
+
+fn lower_fragment(frag: &JsxFragment, options: &JsxOptions) -> Node {
+    let mut arguments = vec![
+        path(&options.pragma_frag, &frag.span),
+        Node::Null(NullLiteral {
+            span: frag.span.clone(),
+        }),
+    ];
+    arguments.extend(frag.children.iter().filter_map(child));
+    call(path(&options.pragma, &frag.span), arguments, &frag.span)
+}
+
+// Intrinsic elements such as `div` or `my-widget` are named by string;
+// anything else is a component looked up in scope.
+fn tag(name: &Node) -> Node {
+    match name {
+        Node::Identifier(id) if id.name == "this" => Node::This(ThisExpression {
+            span: id.span.clone(),
+        }),
+        Node::Identifier(id) if id.name.starts_with(|c: char| c.is_ascii_lowercase()) => {
+            string(&id.name, &id.span)
+        }
+        Node::Identifier(id) if id.name.contains('-') => string(&id.name, &id.span),
+        Node::MemberExpression(expr) => Node::MemberExpression(MemberExpression {
+            object: Box::new(reference(&expr.object)),
+            property: expr.property.clone(),
+            computed: false,
+            optional: false,
+            span: expr.span.clone(),
+        }),
+        Node::JsxNamespacedName(name) => string(&name_text(name), &name.span),
+        other => other.clone(),
+    }
+}
+
+// The object of a member tag is always a reference, even when lowercase.
+fn reference(node: &Node) -> Node {
+    match node {
+        Node::Identifier(id) if id.name == "this" => Node::This(ThisExpression {
+            span: id.span.clone(),
+        }),
+        Node::MemberExpression(_) => tag(node),
+        other => other.clone(),
+    }
+}
+
+fn name_text(name: &JsxNamespacedName) -> String {
+    let part = |node: &Node| match node {
+        Node::Identifier(id) => id.name.clone(),
+        _ => String::new(),
+    };
+    format!("{}:{}", part(&name.namespace), part(&name.name))
+}
+
+// `null` without attributes, an object literal without spreads and
+// `Object.assign({}, ...)` over the runs between spreads otherwise.
+fn props(attributes: &[Node], span: &Span) -> Node {
+    if attributes.is_empty() {
+        return Node::Null(NullLiteral { span: span.clone() });
+    }
+
+    let mut sources = Vec::new();
+    let mut properties = Vec::new();
+    for attribute in attributes {
+        match attribute {
+            Node::JsxAttribute(attr) => properties.push(property(attr)),
+            Node::JsxSpreadAttribute(attr) => {
+                if !properties.is_empty() {
+                    sources.push(object(std::mem::take(&mut properties), span));
+                }
+                sources.push(attr.argument.as_ref().clone());
+            }
+            _ => {}
+        }
+    }
+    if sources.is_empty() {
+        return object(properties, span);
+    }
+    if !properties.is_empty() {
+        sources.push(object(properties, span));
+    }
+
+    let mut arguments = vec![object(Vec::new(), span)];
+    arguments.extend(sources);
+    call(path("Object.assign", span), arguments, span)
+}
+
+fn property(attr: &JsxAttribute) -> Node {
+    let key = match attr.name.as_ref() {
+        Node::Identifier(id) if id.name.contains('-') => string(&id.name, &id.span),
+        Node::JsxNamespacedName(name) => string(&name_text(name), &name.span),
+        other => other.clone(),
+    };
+    let value = match attr.value.as_deref() {
+        None => Node::Boolean(BooleanLiteral {
+            value: true,
+            span: attr.span.clone(),
+        }),
+        Some(Node::JsxExpressionContainer(container)) => match &container.expression {
+            Some(expression) => expression.as_ref().clone(),
+            None => Node::Undefined(UndefinedLiteral {
+                span: container.span.clone(),
+            }),
+        },
+        Some(value) => value.clone(),
+    };
+    Node::Property(Property {
+        key: Box::new(key),
+        value: Box::new(value),
+        kind: "init".to_string(),
+        computed: false,
+        method: false,
+        shorthand: false,
+        span: attr.span.clone(),
+    })
+}
+
+// Empty containers and whitespace-only text between lines add nothing.
+fn child(node: &Node) -> Option<Node> {
+    match node {
+        Node::JsxText(text) => {
+            let value = clean_text(&text.value);
+            (!value.is_empty()).then(|| string(&value, &text.span))
+        }
+        Node::JsxExpressionContainer(container) => container.expression.as_deref().cloned(),
+        other => Some(other.clone()),
+    }
+}
+
+// Babel's rule for text children: lines are trimmed where they meet a line
+// break, lines left empty are dropped and the rest are joined by a space.
+fn clean_text(text: &str) -> String {
+    let lines: Vec<&str> = text
+        .split("\r\n")
+        .flat_map(|line| line.split(['\n', '\r']))
+        .collect();
+    let last_non_empty = lines
+        .iter()
+        .rposition(|line| line.contains(|c: char| c != ' ' && c != '\t'));
+
+    let mut out = String::new();
+    for (index, line) in lines.iter().enumerate() {
+        let mut line = line.replace('\t', " ");
+        if index > 0 {
+            line = line.trim_start_matches(' ').to_string();
+        }
+        if index + 1 < lines.len() {
+            line = line.trim_end_matches(' ').to_string();
+        }
+        if line.is_empty() {
+            continue;
+        }
+        out.push_str(&line);
+        if Some(index) != last_non_empty {
+            out.push(' ');
+        }
+    }
+    out
+}
+
+// `a.b.c` as a member expression chain.
+fn path(path: &str, span: &Span) -> Node {
+    let mut parts = path.split('.').map(|name| {
+        Node::Identifier(Identifier {
+            name: name.to_string(),
+            span: span.clone(),
+        })
+    });
+    let first = parts.next().expect("split always yields one part");
+    parts.fold(first, |object, property| {
+        Node::MemberExpression(MemberExpression {
+            object: Box::new(object),
+            property: Box::new(property),
+            computed: false,
+            optional: false,
+            span: span.clone(),
+        })
+    })
+}
+
+fn call(callee: Node, arguments: Vec<Node>, span: &Span) -> Node {
+    Node::CallExpression(CallExpression {
+        callee: Box::new(callee),
+        arguments,
+        span: span.clone(),
+    })
+}
+
+fn object(properties: Vec<Node>, span: &Span) -> Node {
+    Node::ObjectLiteral(ObjectLiteral {
+        properties,
+        span: span.clone(),
+    })
+}
+
+fn string(value: &str, span: &Span) -> Node {
+    Node::String(StringLiteral {
+        value: value.to_string(),
+        span: span.clone(),
+    })
+}
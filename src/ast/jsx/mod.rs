@@ -0,0 +1,66 @@
+mod lower;
+
+pub use lower::{lower_jsx, JsxOptions};
+
+use crate::ast::common::Span;
+use crate::ast::node::Node;
+use serde::{Deserialize, Serialize};
+
+// `<name attributes>children</name>`, or `<name attributes />` when
+// `self_closing`. The name is an `Identifier` (dashes allowed), a
+// `MemberExpression` for `a.b` or a `JsxNamespacedName` for `a:b`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsxElement {
+    pub name: Box<Node>,
+    pub attributes: Vec<Node>,
+    pub children: Vec<Node>,
+    pub self_closing: bool,
+    pub span: Span,
+}
+
+// `<>children</>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsxFragment {
+    pub children: Vec<Node>,
+    pub span: Span,
+}
+
+// `name="value"`, `name={expression}`, `name=<element />` or a bare `name`
+// with no value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsxAttribute {
+    pub name: Box<Node>,
+    pub value: Option<Box<Node>>,
+    pub span: Span,
+}
+
+// `{...argument}` among the attributes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsxSpreadAttribute {
+    pub argument: Box<Node>,
+    pub span: Span,
+}
+
+// `{expression}`, or `{}` with no expression.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsxExpressionContainer {
+    pub expression: Option<Box<Node>>,
+    pub span: Span,
+}
+
+// Text between tags. `value` has HTML entities decoded; `raw` is the text
+// as written.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsxText {
+    pub value: String,
+    pub raw: String,
+    pub span: Span,
+}
+
+// `namespace:name`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsxNamespacedName {
+    pub namespace: Box<Node>,
+    pub name: Box<Node>,
+    pub span: Span,
+}
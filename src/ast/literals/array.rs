@@ -4,5 +4,5 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ArrayLiteral {
     pub elements: Vec<Option<crate::ast::node::Node>>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
@@ -7,7 +7,7 @@ pub struct ArrowFunctionExpression {
     pub body: Box<crate::ast::node::Node>,
     pub expression: bool,
     pub r#async: bool,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -17,7 +17,7 @@ pub struct FunctionExpression {
     pub body: Box<crate::ast::node::Node>,
     pub generator: bool,
     pub r#async: bool,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -25,26 +25,27 @@ pub struct ClassExpression {
     pub id: Option<Box<crate::ast::node::Node>>,
     pub super_class: Option<Box<crate::ast::node::Node>>,
     pub body: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TemplateLiteral {
     pub quasis: Vec<TemplateElement>,
     pub expressions: Vec<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TemplateElement {
-    pub value: String,
+    pub cooked: Option<String>,
+    pub raw: String,
     pub tail: bool,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TaggedTemplateExpression {
     pub tag: Box<crate::ast::node::Node>,
     pub quasi: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
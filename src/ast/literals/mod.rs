@@ -1,7 +1,9 @@
 pub mod array;
 pub mod function;
 pub mod object;
+pub mod primitive;
 
 pub use array::*;
 pub use function::*;
 pub use object::*;
+pub use primitive::*;
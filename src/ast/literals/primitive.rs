@@ -0,0 +1,38 @@
+use crate::ast::common::Span;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NumberLiteral {
+    pub value: f64,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StringLiteral {
+    pub value: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BooleanLiteral {
+    pub value: bool,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NullLiteral {
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UndefinedLiteral {
+    pub span: Span,
+}
+
+// The digits of a BigInt literal without the `n` suffix, e.g. `"10"` for
+// `10n`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BigIntLiteral {
+    pub value: String,
+    pub span: Span,
+}
@@ -1,38 +1,61 @@
+pub mod arena;
 pub mod common;
 pub mod error;
+pub mod estree;
 pub mod expressions;
+pub mod fold;
+#[cfg(feature = "jsx")]
+pub mod jsx;
 pub mod literals;
 pub mod node;
+pub mod printer;
 pub mod serialization;
+pub mod source_map;
 pub mod statements;
+pub mod visit_mut;
 pub mod visitor;
 
+pub use arena::AstArena;
 pub use common::{Position, Span};
 pub use error::AstError;
+pub use fold::Fold;
+#[cfg(feature = "jsx")]
+pub use jsx::{
+    lower_jsx, JsxAttribute, JsxElement, JsxExpressionContainer, JsxFragment, JsxNamespacedName,
+    JsxOptions, JsxSpreadAttribute, JsxText,
+};
 pub use node::{
-    ExportDeclaration, ExportSpecifier, ImportDeclaration, ImportDefaultSpecifier,
-    ImportNamespaceSpecifier, ImportSpecifier, Node, Program,
+    ErrorNode, ExportDeclaration, ExportNamespaceSpecifier, ExportSpecifier, ImportAttribute,
+    ImportDeclaration, ImportDefaultSpecifier, ImportNamespaceSpecifier, ImportSpecifier, Node,
+    Program, SourceType,
 };
+pub use printer::{Indent, Printer, PrinterOptions, QuoteStyle};
+pub use source_map::{SourceMap, SourceMapBuilder};
+pub use visit_mut::VisitMut;
 pub use visitor::Visitor;
 
 // Re-export all statement types
 pub use statements::{
-    BlockStatement, BreakStatement, CatchClause, ClassDeclaration, ContinueStatement,
-    DebuggerStatement, DoWhileStatement, ExpressionStatement, ForStatement, FunctionDeclaration,
-    IfStatement, LabeledStatement, ReturnStatement, SwitchCase, SwitchStatement, ThrowStatement,
-    TryStatement, VariableDeclaration, VariableDeclarator, WhileStatement, WithStatement,
+    BlockStatement, BreakStatement, CatchClause, ClassBody, ClassDeclaration, ContinueStatement,
+    DebuggerStatement, DoWhileStatement, EmptyStatement, ExpressionStatement, ForInStatement,
+    ForOfStatement, ForStatement, FunctionDeclaration, IfStatement, LabeledStatement,
+    MethodDefinition, MethodKind, PropertyDefinition, ReturnStatement, StaticBlock, SwitchCase,
+    SwitchStatement, ThrowStatement, TryStatement, VariableDeclaration, VariableDeclarator,
+    WhileStatement, WithStatement,
 };
 
 // Re-export all expression types
 pub use expressions::{
     AssignmentExpression, AwaitExpression, BinaryExpression, CallExpression, ConditionalExpression,
-    LogicalExpression, MemberExpression, MetaProperty, NewExpression, RegExp, Super,
-    UnaryExpression, UpdateExpression, YieldExpression,
+    Identifier, ImportExpression, LogicalExpression, MemberExpression, MetaProperty, NewExpression,
+    PrivateIdentifier, RegExp, Super, ThisExpression, UnaryExpression, UpdateExpression,
+    YieldExpression,
 };
 
 // Re-export all literal types
 pub use literals::{
-    ArrayLiteral, ArrowFunctionExpression, ClassExpression, FunctionExpression, ObjectLiteral,
-    Property, RestElement, SpreadElement, TaggedTemplateExpression, TemplateElement,
-    TemplateLiteral,
+    ArrayLiteral, ArrowFunctionExpression, BigIntLiteral, BooleanLiteral, ClassExpression,
+    FunctionExpression, NullLiteral, NumberLiteral, ObjectLiteral, Property, RestElement,
+    SpreadElement, StringLiteral, TaggedTemplateExpression, TemplateElement, TemplateLiteral,
+    UndefinedLiteral,
 };
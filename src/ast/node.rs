@@ -1,5 +1,7 @@
 use crate::ast::common::Span;
 use crate::ast::expressions::*;
+#[cfg(feature = "jsx")]
+use crate::ast::jsx::*;
 use crate::ast::literals::*;
 use crate::ast::statements::*;
 use serde::{Deserialize, Serialize};
@@ -7,34 +9,52 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Program {
     pub body: Vec<Node>,
-    pub source_type: String,
-    pub span: Option<Span>,
+    pub source_type: SourceType,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceType {
+    #[default]
+    Script,
+    Module,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ImportDeclaration {
     pub specifiers: Vec<Node>,
     pub source: Box<Node>,
-    pub span: Option<Span>,
+    pub attributes: Vec<ImportAttribute>,
+    pub span: Span,
+}
+
+// One `key: "value"` entry of an import attributes clause, e.g.
+// `with { type: "json" }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportAttribute {
+    pub key: Box<Node>,
+    pub value: Box<Node>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ImportSpecifier {
     pub local: Box<Node>,
     pub imported: Box<Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ImportDefaultSpecifier {
     pub local: Box<Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ImportNamespaceSpecifier {
     pub local: Box<Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -42,15 +62,33 @@ pub struct ExportDeclaration {
     pub declaration: Option<Box<Node>>,
     pub specifiers: Vec<Node>,
     pub source: Option<Box<Node>>,
+    pub attributes: Vec<ImportAttribute>,
     pub default: bool,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExportSpecifier {
     pub local: Box<Node>,
     pub exported: Box<Node>,
-    pub span: Option<Span>,
+    pub span: Span,
+}
+
+// `export * from "mod"` has no exported name; `export * as ns from "mod"`
+// exports the namespace as `ns`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportNamespaceSpecifier {
+    pub exported: Option<Box<Node>>,
+    pub span: Span,
+}
+
+// A statement that failed to parse, kept in the tree by error recovery.
+// `skipped` holds the raw text of each token it covers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorNode {
+    pub message: String,
+    pub skipped: Vec<String>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -60,8 +98,17 @@ pub enum Node {
     VariableDeclaration(VariableDeclaration),
     FunctionDeclaration(FunctionDeclaration),
     ClassDeclaration(ClassDeclaration),
+    ClassBody(ClassBody),
+    MethodDefinition(MethodDefinition),
+    PropertyDefinition(PropertyDefinition),
+    StaticBlock(StaticBlock),
     ImportDeclaration(ImportDeclaration),
     ExportDeclaration(ExportDeclaration),
+    ImportSpecifier(ImportSpecifier),
+    ImportDefaultSpecifier(ImportDefaultSpecifier),
+    ImportNamespaceSpecifier(ImportNamespaceSpecifier),
+    ExportSpecifier(ExportSpecifier),
+    ExportNamespaceSpecifier(ExportNamespaceSpecifier),
 
     BinaryExpression(BinaryExpression),
     UnaryExpression(UnaryExpression),
@@ -81,6 +128,8 @@ pub enum Node {
     BlockStatement(BlockStatement),
     IfStatement(IfStatement),
     ForStatement(ForStatement),
+    ForInStatement(ForInStatement),
+    ForOfStatement(ForOfStatement),
     WhileStatement(WhileStatement),
     DoWhileStatement(DoWhileStatement),
     SwitchStatement(SwitchStatement),
@@ -93,6 +142,7 @@ pub enum Node {
     LabeledStatement(LabeledStatement),
     WithStatement(WithStatement),
     DebuggerStatement(DebuggerStatement),
+    EmptyStatement(EmptyStatement),
     ExpressionStatement(ExpressionStatement),
 
     ArrayLiteral(ArrayLiteral),
@@ -105,13 +155,122 @@ pub enum Node {
     RestElement(RestElement),
     Super(Super),
     MetaProperty(MetaProperty),
-    Identifier(String),
-    Number(f64),
-    String(String),
-    Boolean(bool),
-    Null,
-    Undefined,
-    This,
+    ImportExpression(ImportExpression),
+    Identifier(Identifier),
+    PrivateIdentifier(PrivateIdentifier),
+    Number(NumberLiteral),
+    String(StringLiteral),
+    Boolean(BooleanLiteral),
+    Null(NullLiteral),
+    Undefined(UndefinedLiteral),
+    This(ThisExpression),
     RegExp(RegExp),
-    BigInt(String),
+    BigInt(BigIntLiteral),
+
+    #[cfg(feature = "jsx")]
+    JsxElement(JsxElement),
+    #[cfg(feature = "jsx")]
+    JsxFragment(JsxFragment),
+    #[cfg(feature = "jsx")]
+    JsxAttribute(JsxAttribute),
+    #[cfg(feature = "jsx")]
+    JsxSpreadAttribute(JsxSpreadAttribute),
+    #[cfg(feature = "jsx")]
+    JsxExpressionContainer(JsxExpressionContainer),
+    #[cfg(feature = "jsx")]
+    JsxText(JsxText),
+    #[cfg(feature = "jsx")]
+    JsxNamespacedName(JsxNamespacedName),
+
+    Error(ErrorNode),
+}
+
+impl Node {
+    pub fn span(&self) -> &Span {
+        match self {
+            Node::Program(node) => &node.span,
+            Node::VariableDeclaration(node) => &node.span,
+            Node::FunctionDeclaration(node) => &node.span,
+            Node::ClassDeclaration(node) => &node.span,
+            Node::ClassBody(node) => &node.span,
+            Node::MethodDefinition(node) => &node.span,
+            Node::PropertyDefinition(node) => &node.span,
+            Node::StaticBlock(node) => &node.span,
+            Node::ImportDeclaration(node) => &node.span,
+            Node::ExportDeclaration(node) => &node.span,
+            Node::ImportSpecifier(node) => &node.span,
+            Node::ImportDefaultSpecifier(node) => &node.span,
+            Node::ImportNamespaceSpecifier(node) => &node.span,
+            Node::ExportSpecifier(node) => &node.span,
+            Node::ExportNamespaceSpecifier(node) => &node.span,
+            Node::BinaryExpression(node) => &node.span,
+            Node::UnaryExpression(node) => &node.span,
+            Node::UpdateExpression(node) => &node.span,
+            Node::LogicalExpression(node) => &node.span,
+            Node::ConditionalExpression(node) => &node.span,
+            Node::AssignmentExpression(node) => &node.span,
+            Node::CallExpression(node) => &node.span,
+            Node::NewExpression(node) => &node.span,
+            Node::MemberExpression(node) => &node.span,
+            Node::ArrowFunctionExpression(node) => &node.span,
+            Node::FunctionExpression(node) => &node.span,
+            Node::ClassExpression(node) => &node.span,
+            Node::YieldExpression(node) => &node.span,
+            Node::AwaitExpression(node) => &node.span,
+            Node::BlockStatement(node) => &node.span,
+            Node::IfStatement(node) => &node.span,
+            Node::ForStatement(node) => &node.span,
+            Node::ForInStatement(node) => &node.span,
+            Node::ForOfStatement(node) => &node.span,
+            Node::WhileStatement(node) => &node.span,
+            Node::DoWhileStatement(node) => &node.span,
+            Node::SwitchStatement(node) => &node.span,
+            Node::TryStatement(node) => &node.span,
+            Node::CatchClause(node) => &node.span,
+            Node::ThrowStatement(node) => &node.span,
+            Node::ReturnStatement(node) => &node.span,
+            Node::BreakStatement(node) => &node.span,
+            Node::ContinueStatement(node) => &node.span,
+            Node::LabeledStatement(node) => &node.span,
+            Node::WithStatement(node) => &node.span,
+            Node::DebuggerStatement(node) => &node.span,
+            Node::EmptyStatement(node) => &node.span,
+            Node::ExpressionStatement(node) => &node.span,
+            Node::ArrayLiteral(node) => &node.span,
+            Node::ObjectLiteral(node) => &node.span,
+            Node::TemplateLiteral(node) => &node.span,
+            Node::TaggedTemplateExpression(node) => &node.span,
+            Node::Property(node) => &node.span,
+            Node::SpreadElement(node) => &node.span,
+            Node::RestElement(node) => &node.span,
+            Node::Super(node) => &node.span,
+            Node::MetaProperty(node) => &node.span,
+            Node::ImportExpression(node) => &node.span,
+            Node::Identifier(node) => &node.span,
+            Node::PrivateIdentifier(node) => &node.span,
+            Node::Number(node) => &node.span,
+            Node::String(node) => &node.span,
+            Node::Boolean(node) => &node.span,
+            Node::Null(node) => &node.span,
+            Node::Undefined(node) => &node.span,
+            Node::This(node) => &node.span,
+            Node::RegExp(node) => &node.span,
+            Node::BigInt(node) => &node.span,
+            #[cfg(feature = "jsx")]
+            Node::JsxElement(node) => &node.span,
+            #[cfg(feature = "jsx")]
+            Node::JsxFragment(node) => &node.span,
+            #[cfg(feature = "jsx")]
+            Node::JsxAttribute(node) => &node.span,
+            #[cfg(feature = "jsx")]
+            Node::JsxSpreadAttribute(node) => &node.span,
+            #[cfg(feature = "jsx")]
+            Node::JsxExpressionContainer(node) => &node.span,
+            #[cfg(feature = "jsx")]
+            Node::JsxText(node) => &node.span,
+            #[cfg(feature = "jsx")]
+            Node::JsxNamespacedName(node) => &node.span,
+            Node::Error(node) => &node.span,
+        }
+    }
 }
@@ -0,0 +1,1275 @@
+use crate::ast::source_map::{SourceMap, SourceMapBuilder};
+use crate::ast::*;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QuoteStyle {
+    #[default]
+    Double,
+    Single,
+}
+
+impl QuoteStyle {
+    fn as_char(self) -> char {
+        match self {
+            QuoteStyle::Double => '"',
+            QuoteStyle::Single => '\'',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    Spaces(usize),
+    Tabs,
+}
+
+impl Default for Indent {
+    fn default() -> Self {
+        Indent::Spaces(2)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrinterOptions {
+    pub indent: Indent,
+    pub quote_style: QuoteStyle,
+}
+
+impl PrinterOptions {
+    pub fn with_indent(mut self, indent: Indent) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    pub fn with_quote_style(mut self, quote_style: QuoteStyle) -> Self {
+        self.quote_style = quote_style;
+        self
+    }
+}
+
+// Operator precedence, from loosest to tightest. An expression is wrapped in
+// parentheses when it binds looser than its position requires.
+const ASSIGNMENT: u8 = 3;
+const CONDITIONAL: u8 = 4;
+const COALESCE: u8 = 5;
+const EXPONENT: u8 = 16;
+const PREFIX: u8 = 17;
+const POSTFIX: u8 = 18;
+const MEMBER: u8 = 19;
+const PRIMARY: u8 = 20;
+
+// Turns a `Node` back into JavaScript source. Statements go on their own
+// lines, indented by block depth; expressions are printed on one line with
+// the fewest parentheses that keep the tree the same when parsed again.
+// Error nodes print the tokens they skipped, so the output has the same
+// errors as the input.
+#[derive(Debug, Clone)]
+pub struct Printer {
+    options: PrinterOptions,
+    out: String,
+    depth: usize,
+    source_map: Option<SourceMapBuilder>,
+    // The position at the end of `out[..scanned]`.
+    line: usize,
+    column: usize,
+    scanned: usize,
+}
+
+impl Default for Printer {
+    fn default() -> Self {
+        Self::new(PrinterOptions::default())
+    }
+}
+
+impl Printer {
+    pub fn new(options: PrinterOptions) -> Self {
+        Self {
+            options,
+            out: String::new(),
+            depth: 0,
+            source_map: None,
+            line: 1,
+            column: 1,
+            scanned: 0,
+        }
+    }
+
+    pub fn print(mut self, node: &Node) -> String {
+        self.write(node);
+        self.out
+    }
+
+    // Also maps the start of every printed node that has a span back to
+    // that position in the file named `source_name`, which the tree was
+    // parsed from. The name goes into the map's `sources` as is.
+    pub fn print_with_source_map(mut self, node: &Node, source_name: &str) -> (String, SourceMap) {
+        let mut builder = SourceMapBuilder::new();
+        builder.add_source(source_name, None);
+        self.source_map = Some(builder);
+        self.write(node);
+        let map = self
+            .source_map
+            .take()
+            .map(SourceMapBuilder::build)
+            .unwrap_or_default();
+        (self.out, map)
+    }
+
+    fn write(&mut self, node: &Node) {
+        match node {
+            Node::Program(program) => {
+                for stmt in &program.body {
+                    self.statement(stmt);
+                    self.out.push('\n');
+                }
+            }
+            node if is_statement(node) => self.statement(node),
+            node => self.expression(node, 0),
+        }
+    }
+
+    fn map(&mut self, node: &Node) {
+        if self.source_map.is_none() || *node.span() == Span::default() {
+            return;
+        }
+        // Generated columns count UTF-16 code units, as stack traces do.
+        for c in self.out[self.scanned..].chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += c.len_utf16();
+            }
+        }
+        self.scanned = self.out.len();
+
+        let generated = Position::new(self.line, self.column);
+        let name = match node {
+            Node::Identifier(id) => Some(id.name.as_str()),
+            _ => None,
+        };
+        if let Some(builder) = &mut self.source_map {
+            builder.add_span_mapping(generated, 0, node.span(), name);
+        }
+    }
+
+    fn newline(&mut self) {
+        self.out.push('\n');
+        for _ in 0..self.depth {
+            match self.options.indent {
+                Indent::Spaces(width) => self.out.push_str(&" ".repeat(width)),
+                Indent::Tabs => self.out.push('\t'),
+            }
+        }
+    }
+
+    fn block(&mut self, body: &[Node]) {
+        if body.is_empty() {
+            self.out.push_str("{}");
+            return;
+        }
+        self.out.push('{');
+        self.depth += 1;
+        for stmt in body {
+            self.newline();
+            self.statement(stmt);
+        }
+        self.depth -= 1;
+        self.newline();
+        self.out.push('}');
+    }
+
+    // The body of `if` and loops: blocks stay on the same line, any
+    // other statement goes on the next one.
+    fn body(&mut self, body: &Node) {
+        if let Node::BlockStatement(block) = body {
+            self.out.push(' ');
+            self.block(&block.body);
+        } else {
+            self.depth += 1;
+            self.newline();
+            self.statement(body);
+            self.depth -= 1;
+        }
+    }
+
+    // Separates a body from a following `else` or `while`.
+    fn after_body(&mut self, body: &Node) {
+        if matches!(body, Node::BlockStatement(_)) {
+            self.out.push(' ');
+        } else {
+            self.newline();
+        }
+    }
+
+    fn statement(&mut self, node: &Node) {
+        self.map(node);
+        match node {
+            Node::VariableDeclaration(decl) => {
+                self.variable_declaration(decl, false);
+                self.out.push(';');
+            }
+            Node::FunctionDeclaration(decl) => self.function(
+                decl.r#async,
+                decl.generator,
+                decl.id.as_deref(),
+                &decl.params,
+                &decl.body,
+            ),
+            Node::ClassDeclaration(decl) => {
+                self.class(decl.id.as_deref(), decl.super_class.as_deref(), &decl.body)
+            }
+            Node::ImportDeclaration(decl) => self.import(decl),
+            Node::ExportDeclaration(decl) => self.export(decl),
+            Node::BlockStatement(block) => self.block(&block.body),
+            Node::IfStatement(stmt) => {
+                self.out.push_str("if (");
+                self.expression(&stmt.test, 0);
+                self.out.push(')');
+                match &stmt.alternate {
+                    // Without braces the `else` would attach to the inner `if`.
+                    Some(_) if ends_with_open_if(&stmt.consequent) => {
+                        self.out.push(' ');
+                        self.block(std::slice::from_ref(&stmt.consequent));
+                        self.out.push(' ');
+                    }
+                    Some(_) => {
+                        self.body(&stmt.consequent);
+                        self.after_body(&stmt.consequent);
+                    }
+                    None => self.body(&stmt.consequent),
+                }
+                if let Some(alternate) = &stmt.alternate {
+                    self.out.push_str("else");
+                    if matches!(**alternate, Node::IfStatement(_)) {
+                        self.out.push(' ');
+                        self.statement(alternate);
+                    } else {
+                        self.body(alternate);
+                    }
+                }
+            }
+            Node::ForStatement(stmt) => {
+                self.out.push_str("for (");
+                match stmt.init.as_deref() {
+                    Some(Node::VariableDeclaration(decl)) => self.variable_declaration(decl, true),
+                    Some(init) => self.expression_without_in(init, 0),
+                    None => {}
+                }
+                self.out.push(';');
+                if let Some(test) = &stmt.test {
+                    self.out.push(' ');
+                    self.expression(test, 0);
+                }
+                self.out.push(';');
+                if let Some(update) = &stmt.update {
+                    self.out.push(' ');
+                    self.expression(update, 0);
+                }
+                self.out.push(')');
+                self.body(&stmt.body);
+            }
+            Node::ForInStatement(stmt) => {
+                self.out.push_str("for (");
+                self.for_left(&stmt.left);
+                self.out.push_str(" in ");
+                self.expression(&stmt.right, 0);
+                self.out.push(')');
+                self.body(&stmt.body);
+            }
+            Node::ForOfStatement(stmt) => {
+                self.out
+                    .push_str(if stmt.r#await { "for await (" } else { "for (" });
+                self.for_left(&stmt.left);
+                self.out.push_str(" of ");
+                self.expression(&stmt.right, ASSIGNMENT);
+                self.out.push(')');
+                self.body(&stmt.body);
+            }
+            Node::WhileStatement(stmt) => {
+                self.out.push_str("while (");
+                self.expression(&stmt.test, 0);
+                self.out.push(')');
+                self.body(&stmt.body);
+            }
+            Node::DoWhileStatement(stmt) => {
+                self.out.push_str("do");
+                self.body(&stmt.body);
+                self.after_body(&stmt.body);
+                self.out.push_str("while (");
+                self.expression(&stmt.test, 0);
+                self.out.push_str(");");
+            }
+            Node::SwitchStatement(stmt) => {
+                self.out.push_str("switch (");
+                self.expression(&stmt.discriminant, 0);
+                self.out.push_str(") {");
+                self.depth += 1;
+                for case in &stmt.cases {
+                    self.newline();
+                    match &case.test {
+                        Some(test) => {
+                            self.out.push_str("case ");
+                            self.expression(test, 0);
+                            self.out.push(':');
+                        }
+                        None => self.out.push_str("default:"),
+                    }
+                    self.depth += 1;
+                    for stmt in &case.consequent {
+                        self.newline();
+                        self.statement(stmt);
+                    }
+                    self.depth -= 1;
+                }
+                self.depth -= 1;
+                self.newline();
+                self.out.push('}');
+            }
+            Node::TryStatement(stmt) => {
+                self.out.push_str("try ");
+                self.statement(&stmt.block);
+                if let Some(handler) = &stmt.handler {
+                    self.out.push(' ');
+                    self.statement(handler);
+                }
+                if let Some(finalizer) = &stmt.finalizer {
+                    self.out.push_str(" finally ");
+                    self.statement(finalizer);
+                }
+            }
+            Node::CatchClause(clause) => {
+                self.out.push_str("catch (");
+                self.expression(&clause.param, 0);
+                self.out.push_str(") ");
+                self.statement(&clause.body);
+            }
+            Node::ThrowStatement(stmt) => {
+                self.out.push_str("throw ");
+                self.expression(&stmt.argument, 0);
+                self.out.push(';');
+            }
+            Node::ReturnStatement(stmt) => {
+                self.out.push_str("return");
+                if let Some(argument) = &stmt.argument {
+                    self.out.push(' ');
+                    self.expression(argument, 0);
+                }
+                self.out.push(';');
+            }
+            Node::BreakStatement(stmt) => self.jump("break", stmt.label.as_deref()),
+            Node::ContinueStatement(stmt) => self.jump("continue", stmt.label.as_deref()),
+            Node::LabeledStatement(stmt) => {
+                self.expression(&stmt.label, 0);
+                self.out.push_str(": ");
+                self.statement(&stmt.body);
+            }
+            Node::WithStatement(stmt) => {
+                self.out.push_str("with (");
+                self.expression(&stmt.object, 0);
+                self.out.push(')');
+                self.body(&stmt.body);
+            }
+            Node::DebuggerStatement(_) => self.out.push_str("debugger;"),
+            Node::EmptyStatement(_) => self.out.push(';'),
+            Node::ExpressionStatement(stmt) => {
+                match (&stmt.directive, &*stmt.expression) {
+                    (Some(raw), _) => self.directive(raw),
+                    // A bare string would be read back as a directive.
+                    (None, Node::String(_)) => {
+                        self.out.push('(');
+                        self.expression(&stmt.expression, 0);
+                        self.out.push(')');
+                    }
+                    (None, expression) => self.expression_at_start(expression),
+                }
+                self.out.push(';');
+            }
+            Node::StaticBlock(_) | Node::MethodDefinition(_) | Node::PropertyDefinition(_) => {
+                self.class_member(node)
+            }
+            Node::Error(error) => self.out.push_str(&error.skipped.join(" ")),
+            node => {
+                self.expression_at_start(node);
+                self.out.push(';');
+            }
+        }
+    }
+
+    fn jump(&mut self, keyword: &str, label: Option<&Node>) {
+        self.out.push_str(keyword);
+        if let Some(label) = label {
+            self.out.push(' ');
+            self.expression(label, 0);
+        }
+        self.out.push(';');
+    }
+
+    // A directive is printed from its raw text so that escapes, and with
+    // them the directive itself, are kept.
+    fn directive(&mut self, raw: &str) {
+        let preferred = self.options.quote_style.as_char();
+        let quote = if raw.contains(preferred) {
+            if preferred == '"' {
+                '\''
+            } else {
+                '"'
+            }
+        } else {
+            preferred
+        };
+        self.out.push(quote);
+        self.out.push_str(raw);
+        self.out.push(quote);
+    }
+
+    fn variable_declaration(&mut self, decl: &VariableDeclaration, in_for_init: bool) {
+        self.out.push_str(&decl.kind);
+        for (index, declarator) in decl.declarations.iter().enumerate() {
+            self.out.push_str(if index == 0 { " " } else { ", " });
+            self.expression(&declarator.id, 0);
+            if let Some(init) = &declarator.init {
+                self.out.push_str(" = ");
+                if in_for_init {
+                    self.expression_without_in(init, ASSIGNMENT);
+                } else {
+                    self.expression(init, ASSIGNMENT);
+                }
+            }
+        }
+    }
+
+    fn for_left(&mut self, left: &Node) {
+        match left {
+            Node::VariableDeclaration(decl) => self.variable_declaration(decl, true),
+            left => self.expression(left, POSTFIX),
+        }
+    }
+
+    fn function(
+        &mut self,
+        r#async: bool,
+        generator: bool,
+        id: Option<&Node>,
+        params: &[Node],
+        body: &Node,
+    ) {
+        if r#async {
+            self.out.push_str("async ");
+        }
+        self.out.push_str("function");
+        if generator {
+            self.out.push('*');
+        }
+        if let Some(id) = id {
+            self.out.push(' ');
+            self.expression(id, 0);
+        }
+        self.params(params);
+        self.out.push(' ');
+        self.statement(body);
+    }
+
+    fn params(&mut self, params: &[Node]) {
+        self.out.push('(');
+        self.list(params);
+        self.out.push(')');
+    }
+
+    fn list(&mut self, nodes: &[Node]) {
+        for (index, node) in nodes.iter().enumerate() {
+            if index > 0 {
+                self.out.push_str(", ");
+            }
+            self.expression(node, ASSIGNMENT);
+        }
+    }
+
+    fn class(&mut self, id: Option<&Node>, super_class: Option<&Node>, body: &Node) {
+        self.out.push_str("class");
+        if let Some(id) = id {
+            self.out.push(' ');
+            self.expression(id, 0);
+        }
+        if let Some(super_class) = super_class {
+            self.out.push_str(" extends ");
+            self.expression(super_class, MEMBER);
+        }
+        self.out.push(' ');
+        self.class_body(body);
+    }
+
+    fn class_body(&mut self, body: &Node) {
+        let Node::ClassBody(body) = body else {
+            self.expression(body, 0);
+            return;
+        };
+        if body.body.is_empty() {
+            self.out.push_str("{}");
+            return;
+        }
+        self.out.push('{');
+        self.depth += 1;
+        for member in &body.body {
+            self.newline();
+            self.class_member(member);
+        }
+        self.depth -= 1;
+        self.newline();
+        self.out.push('}');
+    }
+
+    fn class_member(&mut self, member: &Node) {
+        self.map(member);
+        match member {
+            Node::MethodDefinition(method) => {
+                if method.r#static {
+                    self.out.push_str("static ");
+                }
+                let prefix = match method.kind {
+                    MethodKind::Get => "get ",
+                    MethodKind::Set => "set ",
+                    MethodKind::Constructor | MethodKind::Method => "",
+                };
+                self.method(prefix, &method.key, method.computed, &method.value);
+            }
+            Node::PropertyDefinition(prop) => {
+                if prop.r#static {
+                    self.out.push_str("static ");
+                }
+                self.property_key(&prop.key, prop.computed);
+                if let Some(value) = &prop.value {
+                    self.out.push_str(" = ");
+                    self.expression(value, ASSIGNMENT);
+                }
+                self.out.push(';');
+            }
+            Node::StaticBlock(block) => {
+                self.out.push_str("static ");
+                self.block(&block.body);
+            }
+            member => self.statement(member),
+        }
+    }
+
+    // Shared by class methods and object literal methods, getters and setters.
+    fn method(&mut self, prefix: &str, key: &Node, computed: bool, value: &Node) {
+        let Node::FunctionExpression(function) = value else {
+            self.out.push_str(prefix);
+            self.property_key(key, computed);
+            self.out.push_str(": ");
+            self.expression(value, ASSIGNMENT);
+            return;
+        };
+        if function.r#async {
+            self.out.push_str("async ");
+        }
+        if function.generator {
+            self.out.push('*');
+        }
+        self.out.push_str(prefix);
+        self.property_key(key, computed);
+        self.params(&function.params);
+        self.out.push(' ');
+        self.statement(&function.body);
+    }
+
+    fn property_key(&mut self, key: &Node, computed: bool) {
+        if computed {
+            self.out.push('[');
+            self.expression(key, ASSIGNMENT);
+            self.out.push(']');
+        } else {
+            self.expression(key, PRIMARY);
+        }
+    }
+
+    fn import(&mut self, decl: &ImportDeclaration) {
+        self.out.push_str("import ");
+        let mut named = Vec::new();
+        let mut first = true;
+        for specifier in &decl.specifiers {
+            match specifier {
+                Node::ImportDefaultSpecifier(spec) => {
+                    self.separator(&mut first);
+                    self.expression(&spec.local, 0);
+                }
+                Node::ImportNamespaceSpecifier(spec) => {
+                    self.separator(&mut first);
+                    self.out.push_str("* as ");
+                    self.expression(&spec.local, 0);
+                }
+                specifier => named.push(specifier),
+            }
+        }
+        if !named.is_empty() {
+            self.separator(&mut first);
+            self.named_specifiers(&named);
+        }
+        if !first {
+            self.out.push_str(" from ");
+        }
+        self.expression(&decl.source, 0);
+        self.attributes(&decl.attributes);
+        self.out.push(';');
+    }
+
+    fn separator(&mut self, first: &mut bool) {
+        if !std::mem::take(first) {
+            self.out.push_str(", ");
+        }
+    }
+
+    fn named_specifiers(&mut self, specifiers: &[&Node]) {
+        self.out.push_str("{ ");
+        for (index, specifier) in specifiers.iter().enumerate() {
+            if index > 0 {
+                self.out.push_str(", ");
+            }
+            let (first, second) = match specifier {
+                Node::ImportSpecifier(spec) => (&spec.imported, &spec.local),
+                Node::ExportSpecifier(spec) => (&spec.local, &spec.exported),
+                other => {
+                    self.expression(other, 0);
+                    continue;
+                }
+            };
+            self.expression(first, 0);
+            if !same_name(first, second) {
+                self.out.push_str(" as ");
+                self.expression(second, 0);
+            }
+        }
+        self.out.push_str(" }");
+    }
+
+    fn attributes(&mut self, attributes: &[ImportAttribute]) {
+        if attributes.is_empty() {
+            return;
+        }
+        self.out.push_str(" with { ");
+        for (index, attribute) in attributes.iter().enumerate() {
+            if index > 0 {
+                self.out.push_str(", ");
+            }
+            self.expression(&attribute.key, PRIMARY);
+            self.out.push_str(": ");
+            self.expression(&attribute.value, PRIMARY);
+        }
+        self.out.push_str(" }");
+    }
+
+    fn export(&mut self, decl: &ExportDeclaration) {
+        self.out.push_str("export ");
+        if decl.default {
+            self.out.push_str("default ");
+            match decl.declaration.as_deref() {
+                Some(declaration @ (Node::FunctionDeclaration(_) | Node::ClassDeclaration(_))) => {
+                    self.statement(declaration)
+                }
+                Some(expression) => {
+                    self.expression_at_start(expression);
+                    self.out.push(';');
+                }
+                None => {}
+            }
+            return;
+        }
+        if let Some(declaration) = &decl.declaration {
+            self.statement(declaration);
+            return;
+        }
+
+        match &decl.specifiers[..] {
+            [Node::ExportNamespaceSpecifier(spec)] => {
+                self.out.push('*');
+                if let Some(exported) = &spec.exported {
+                    self.out.push_str(" as ");
+                    self.expression(exported, 0);
+                }
+            }
+            [] => self.out.push_str("{}"),
+            specifiers => self.named_specifiers(&specifiers.iter().collect::<Vec<_>>()),
+        }
+        if let Some(source) = &decl.source {
+            self.out.push_str(" from ");
+            self.expression(source, 0);
+        }
+        self.attributes(&decl.attributes);
+        self.out.push(';');
+    }
+
+    // Prints an expression in statement position, where a leading `{`,
+    // `function`, `class` or `let [` would be read as something else.
+    fn expression_at_start(&mut self, node: &Node) {
+        let ambiguous = match leftmost(node, 0) {
+            Some(
+                Node::ObjectLiteral(_) | Node::FunctionExpression(_) | Node::ClassExpression(_),
+            ) => true,
+            Some(Node::Identifier(id)) => id.name == "let",
+            _ => false,
+        };
+        if ambiguous {
+            self.out.push('(');
+            self.expression(node, 0);
+            self.out.push(')');
+        } else {
+            self.expression(node, 0);
+        }
+    }
+
+    // A `for` initializer cannot contain a bare `in`, which would make it a
+    // `for`-`in` loop.
+    fn expression_without_in(&mut self, node: &Node, min: u8) {
+        if contains_in(node) {
+            self.out.push('(');
+            self.expression(node, 0);
+            self.out.push(')');
+        } else {
+            self.expression(node, min);
+        }
+    }
+
+    fn expression(&mut self, node: &Node, min: u8) {
+        if precedence(node) < min {
+            self.out.push('(');
+            self.expression(node, 0);
+            self.out.push(')');
+            return;
+        }
+
+        self.map(node);
+        match node {
+            Node::BinaryExpression(expr) => {
+                self.binary(&expr.left, &expr.operator, &expr.right, precedence(node))
+            }
+            Node::LogicalExpression(expr) => {
+                let prec = precedence(node);
+                self.operand(&expr.left, prec, &expr.operator);
+                self.out.push(' ');
+                self.out.push_str(&expr.operator);
+                self.out.push(' ');
+                self.operand(&expr.right, prec + 1, &expr.operator);
+            }
+            Node::UnaryExpression(expr) => {
+                self.out.push_str(&expr.operator);
+                // `typeof x`, and `- -x` rather than the decrement `--x`.
+                let operator_end = expr.operator.chars().last();
+                if operator_end.is_some_and(|c| c.is_ascii_alphabetic())
+                    || leading_sign(&expr.argument) == operator_end
+                {
+                    self.out.push(' ');
+                }
+                self.expression(&expr.argument, PREFIX);
+            }
+            Node::UpdateExpression(expr) => {
+                if expr.prefix {
+                    self.out.push_str(&expr.operator);
+                    if leading_sign(&expr.argument).is_some() {
+                        self.out.push(' ');
+                    }
+                    self.expression(&expr.argument, PREFIX);
+                } else {
+                    self.expression(&expr.argument, POSTFIX + 1);
+                    self.out.push_str(&expr.operator);
+                }
+            }
+            Node::ConditionalExpression(expr) => {
+                self.expression(&expr.test, COALESCE);
+                self.out.push_str(" ? ");
+                self.expression(&expr.consequent, ASSIGNMENT);
+                self.out.push_str(" : ");
+                self.expression(&expr.alternate, ASSIGNMENT);
+            }
+            Node::AssignmentExpression(expr) => {
+                self.expression(&expr.left, POSTFIX);
+                self.out.push(' ');
+                self.out.push_str(&expr.operator);
+                self.out.push(' ');
+                self.expression(&expr.right, ASSIGNMENT);
+            }
+            Node::CallExpression(expr) => {
+                self.expression(&expr.callee, MEMBER);
+                self.out.push('(');
+                self.list(&expr.arguments);
+                self.out.push(')');
+            }
+            Node::NewExpression(expr) => {
+                self.out.push_str("new ");
+                // `new f()()` would call the result of `new f()`.
+                if contains_call(&expr.callee) {
+                    self.out.push('(');
+                    self.expression(&expr.callee, 0);
+                    self.out.push(')');
+                } else {
+                    self.expression(&expr.callee, MEMBER);
+                }
+                self.out.push('(');
+                self.list(&expr.arguments);
+                self.out.push(')');
+            }
+            Node::MemberExpression(expr) => {
+                // `1.x` would read the dot as a decimal point.
+                if matches!(*expr.object, Node::Number(_)) && !expr.computed {
+                    self.out.push('(');
+                    self.expression(&expr.object, 0);
+                    self.out.push(')');
+                } else {
+                    self.expression(&expr.object, MEMBER);
+                }
+                match (expr.computed, expr.optional) {
+                    (true, true) => self.out.push_str("?.["),
+                    (true, false) => self.out.push('['),
+                    (false, true) => self.out.push_str("?."),
+                    (false, false) => self.out.push('.'),
+                }
+                self.expression(&expr.property, 0);
+                if expr.computed {
+                    self.out.push(']');
+                }
+            }
+            Node::ArrowFunctionExpression(expr) => {
+                if expr.r#async {
+                    self.out.push_str("async ");
+                }
+                self.params(&expr.params);
+                self.out.push_str(" => ");
+                match &*expr.body {
+                    Node::BlockStatement(_) => self.statement(&expr.body),
+                    // A leading `{` would start a block body.
+                    body if matches!(leftmost(body, ASSIGNMENT), Some(Node::ObjectLiteral(_))) => {
+                        self.out.push('(');
+                        self.expression(body, 0);
+                        self.out.push(')');
+                    }
+                    body => self.expression(body, ASSIGNMENT),
+                }
+            }
+            Node::FunctionExpression(expr) => self.function(
+                expr.r#async,
+                expr.generator,
+                expr.id.as_deref(),
+                &expr.params,
+                &expr.body,
+            ),
+            Node::ClassExpression(expr) => {
+                self.class(expr.id.as_deref(), expr.super_class.as_deref(), &expr.body)
+            }
+            Node::YieldExpression(expr) => {
+                self.out
+                    .push_str(if expr.delegate { "yield*" } else { "yield" });
+                if let Some(argument) = &expr.argument {
+                    self.out.push(' ');
+                    self.expression(argument, ASSIGNMENT);
+                }
+            }
+            Node::AwaitExpression(expr) => {
+                self.out.push_str("await ");
+                self.expression(&expr.argument, PREFIX);
+            }
+            Node::ArrayLiteral(lit) => {
+                self.out.push('[');
+                for (index, element) in lit.elements.iter().enumerate() {
+                    if index > 0 {
+                        self.out.push_str(", ");
+                    }
+                    if let Some(element) = element {
+                        self.expression(element, ASSIGNMENT);
+                    }
+                }
+                // A trailing hole needs its own comma: `[a, ,]` has length 2.
+                if matches!(lit.elements.last(), Some(None)) {
+                    self.out.push(',');
+                }
+                self.out.push(']');
+            }
+            Node::ObjectLiteral(lit) => {
+                if lit.properties.is_empty() {
+                    self.out.push_str("{}");
+                    return;
+                }
+                self.out.push_str("{ ");
+                self.list(&lit.properties);
+                self.out.push_str(" }");
+            }
+            Node::Property(prop) => match prop.kind.as_str() {
+                "get" | "set" => {
+                    let prefix = format!("{} ", prop.kind);
+                    self.method(&prefix, &prop.key, prop.computed, &prop.value);
+                }
+                _ if prop.method => self.method("", &prop.key, prop.computed, &prop.value),
+                _ if prop.shorthand => self.expression(&prop.value, ASSIGNMENT),
+                _ => {
+                    self.property_key(&prop.key, prop.computed);
+                    self.out.push_str(": ");
+                    self.expression(&prop.value, ASSIGNMENT);
+                }
+            },
+            Node::TemplateLiteral(lit) => {
+                self.out.push('`');
+                for (index, quasi) in lit.quasis.iter().enumerate() {
+                    self.out.push_str(&quasi.raw);
+                    if let Some(expression) = lit.expressions.get(index) {
+                        self.out.push_str("${");
+                        self.expression(expression, 0);
+                        self.out.push('}');
+                    }
+                }
+                self.out.push('`');
+            }
+            Node::TaggedTemplateExpression(expr) => {
+                self.expression(&expr.tag, MEMBER);
+                self.expression(&expr.quasi, 0);
+            }
+            Node::SpreadElement(elem) => {
+                self.out.push_str("...");
+                self.expression(&elem.argument, ASSIGNMENT);
+            }
+            Node::RestElement(elem) => {
+                self.out.push_str("...");
+                self.expression(&elem.argument, ASSIGNMENT);
+            }
+            Node::Super(_) => self.out.push_str("super"),
+            Node::MetaProperty(prop) => {
+                self.expression(&prop.meta, 0);
+                self.out.push('.');
+                self.expression(&prop.property, 0);
+            }
+            Node::ImportExpression(expr) => {
+                self.out.push_str("import(");
+                self.expression(&expr.source, ASSIGNMENT);
+                self.out.push(')');
+            }
+            Node::Identifier(id) => self.out.push_str(&id.name),
+            Node::PrivateIdentifier(id) => {
+                self.out.push('#');
+                self.out.push_str(&id.name);
+            }
+            Node::Number(lit) => self.number(lit.value),
+            Node::String(lit) => self.string(&lit.value),
+            Node::Boolean(lit) => self.out.push_str(if lit.value { "true" } else { "false" }),
+            Node::Null(_) => self.out.push_str("null"),
+            Node::Undefined(_) => self.out.push_str("undefined"),
+            Node::This(_) => self.out.push_str("this"),
+            Node::RegExp(regexp) => {
+                self.out.push('/');
+                self.out.push_str(&regexp.pattern);
+                self.out.push('/');
+                self.out.push_str(&regexp.flags);
+            }
+            Node::BigInt(lit) => {
+                self.out.push_str(&lit.value);
+                self.out.push('n');
+            }
+            Node::ClassBody(_) => self.class_body(node),
+            Node::ImportSpecifier(_) | Node::ExportSpecifier(_) => self.named_specifiers(&[node]),
+            Node::ImportDefaultSpecifier(spec) => self.expression(&spec.local, 0),
+            Node::ImportNamespaceSpecifier(spec) => {
+                self.out.push_str("* as ");
+                self.expression(&spec.local, 0);
+            }
+            Node::ExportNamespaceSpecifier(spec) => {
+                self.out.push('*');
+                if let Some(exported) = &spec.exported {
+                    self.out.push_str(" as ");
+                    self.expression(exported, 0);
+                }
+            }
+            #[cfg(feature = "jsx")]
+            Node::JsxElement(elem) => {
+                self.out.push('<');
+                self.expression(&elem.name, 0);
+                for attribute in &elem.attributes {
+                    self.out.push(' ');
+                    self.expression(attribute, 0);
+                }
+                if elem.self_closing {
+                    self.out.push_str(" />");
+                    return;
+                }
+                self.out.push('>');
+                for child in &elem.children {
+                    self.expression(child, 0);
+                }
+                self.out.push_str("</");
+                self.expression(&elem.name, 0);
+                self.out.push('>');
+            }
+            #[cfg(feature = "jsx")]
+            Node::JsxFragment(frag) => {
+                self.out.push_str("<>");
+                for child in &frag.children {
+                    self.expression(child, 0);
+                }
+                self.out.push_str("</>");
+            }
+            #[cfg(feature = "jsx")]
+            Node::JsxAttribute(attr) => {
+                self.expression(&attr.name, 0);
+                match attr.value.as_deref() {
+                    // JSX strings have no escapes, so quotes become entities.
+                    Some(Node::String(lit)) => {
+                        self.out.push_str("=\"");
+                        self.out
+                            .push_str(&lit.value.replace('&', "&amp;").replace('"', "&quot;"));
+                        self.out.push('"');
+                    }
+                    Some(value) => {
+                        self.out.push('=');
+                        self.expression(value, 0);
+                    }
+                    None => {}
+                }
+            }
+            #[cfg(feature = "jsx")]
+            Node::JsxSpreadAttribute(attr) => {
+                self.out.push_str("{...");
+                self.expression(&attr.argument, ASSIGNMENT);
+                self.out.push('}');
+            }
+            #[cfg(feature = "jsx")]
+            Node::JsxExpressionContainer(container) => {
+                self.out.push('{');
+                if let Some(expression) = &container.expression {
+                    self.expression(expression, 0);
+                }
+                self.out.push('}');
+            }
+            #[cfg(feature = "jsx")]
+            Node::JsxText(text) => self.out.push_str(&text.raw),
+            #[cfg(feature = "jsx")]
+            Node::JsxNamespacedName(name) => {
+                self.expression(&name.namespace, 0);
+                self.out.push(':');
+                self.expression(&name.name, 0);
+            }
+            Node::Error(error) => self.out.push_str(&error.skipped.join(" ")),
+            statement => self.statement(statement),
+        }
+    }
+
+    fn binary(&mut self, left: &Node, operator: &str, right: &Node, prec: u8) {
+        // `**` is right associative and its base cannot be a unary expression.
+        if prec == EXPONENT {
+            self.expression(left, POSTFIX);
+            self.out.push_str(" ** ");
+            self.expression(right, EXPONENT);
+            return;
+        }
+        self.expression(left, prec);
+        self.out.push(' ');
+        self.out.push_str(operator);
+        self.out.push(' ');
+        self.expression(right, prec + 1);
+    }
+
+    // `??` cannot be mixed with `&&` or `||` without parentheses.
+    fn operand(&mut self, node: &Node, min: u8, operator: &str) {
+        if mixes_coalesce(operator, node) {
+            self.out.push('(');
+            self.expression(node, 0);
+            self.out.push(')');
+        } else {
+            self.expression(node, min);
+        }
+    }
+
+    fn number(&mut self, value: f64) {
+        if value.is_nan() {
+            self.out.push_str("NaN");
+        } else if value.is_infinite() {
+            self.out
+                .push_str(if value > 0.0 { "Infinity" } else { "-Infinity" });
+        } else if value != 0.0 && !(1e-6..1e21).contains(&value.abs()) {
+            self.out.push_str(&format!("{value:e}"));
+        } else {
+            self.out.push_str(&value.to_string());
+        }
+    }
+
+    fn string(&mut self, value: &str) {
+        let quote = self.options.quote_style.as_char();
+        self.out.push(quote);
+        for c in value.chars() {
+            match c {
+                '\\' => self.out.push_str("\\\\"),
+                '\n' => self.out.push_str("\\n"),
+                '\r' => self.out.push_str("\\r"),
+                '\t' => self.out.push_str("\\t"),
+                '\u{2028}' => self.out.push_str("\\u2028"),
+                '\u{2029}' => self.out.push_str("\\u2029"),
+                c if c == quote => {
+                    self.out.push('\\');
+                    self.out.push(c);
+                }
+                c if c.is_control() => self.out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+                c => self.out.push(c),
+            }
+        }
+        self.out.push(quote);
+    }
+}
+
+fn precedence(node: &Node) -> u8 {
+    match node {
+        Node::AssignmentExpression(_)
+        | Node::ArrowFunctionExpression(_)
+        | Node::YieldExpression(_) => ASSIGNMENT,
+        Node::ConditionalExpression(_) => CONDITIONAL,
+        Node::LogicalExpression(expr) => match expr.operator.as_str() {
+            "??" => COALESCE,
+            "||" => 6,
+            _ => 7,
+        },
+        Node::BinaryExpression(expr) => match expr.operator.as_str() {
+            "|" => 8,
+            "^" => 9,
+            "&" => 10,
+            "==" | "!=" | "===" | "!==" => 11,
+            "<" | ">" | "<=" | ">=" | "in" | "instanceof" => 12,
+            "<<" | ">>" | ">>>" => 13,
+            "+" | "-" => 14,
+            "**" => EXPONENT,
+            _ => 15,
+        },
+        Node::UnaryExpression(_) | Node::AwaitExpression(_) => PREFIX,
+        Node::UpdateExpression(expr) if expr.prefix => PREFIX,
+        Node::UpdateExpression(_) => POSTFIX,
+        Node::CallExpression(_)
+        | Node::NewExpression(_)
+        | Node::MemberExpression(_)
+        | Node::TaggedTemplateExpression(_) => MEMBER,
+        // Numbers from constant folding can be negative.
+        Node::Number(lit) if lit.value.is_sign_negative() && !lit.value.is_nan() => PREFIX,
+        _ => PRIMARY,
+    }
+}
+
+fn is_statement(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::VariableDeclaration(_)
+            | Node::FunctionDeclaration(_)
+            | Node::ClassDeclaration(_)
+            | Node::ImportDeclaration(_)
+            | Node::ExportDeclaration(_)
+            | Node::BlockStatement(_)
+            | Node::IfStatement(_)
+            | Node::ForStatement(_)
+            | Node::ForInStatement(_)
+            | Node::ForOfStatement(_)
+            | Node::WhileStatement(_)
+            | Node::DoWhileStatement(_)
+            | Node::SwitchStatement(_)
+            | Node::TryStatement(_)
+            | Node::CatchClause(_)
+            | Node::ThrowStatement(_)
+            | Node::ReturnStatement(_)
+            | Node::BreakStatement(_)
+            | Node::ContinueStatement(_)
+            | Node::LabeledStatement(_)
+            | Node::WithStatement(_)
+            | Node::DebuggerStatement(_)
+            | Node::EmptyStatement(_)
+            | Node::ExpressionStatement(_)
+            | Node::MethodDefinition(_)
+            | Node::PropertyDefinition(_)
+            | Node::StaticBlock(_)
+    )
+}
+
+// Whether a statement ends in an `if` without an `else`, which would take
+// an `else` printed after it.
+fn ends_with_open_if(node: &Node) -> bool {
+    match node {
+        Node::IfStatement(stmt) => match &stmt.alternate {
+            Some(alternate) => ends_with_open_if(alternate),
+            None => true,
+        },
+        Node::ForStatement(stmt) => ends_with_open_if(&stmt.body),
+        Node::ForInStatement(stmt) => ends_with_open_if(&stmt.body),
+        Node::ForOfStatement(stmt) => ends_with_open_if(&stmt.body),
+        Node::WhileStatement(stmt) => ends_with_open_if(&stmt.body),
+        Node::LabeledStatement(stmt) => ends_with_open_if(&stmt.body),
+        Node::WithStatement(stmt) => ends_with_open_if(&stmt.body),
+        _ => false,
+    }
+}
+
+// Whether `in` appears outside of any brackets, where it would end a `for`
+// initializer.
+fn contains_in(node: &Node) -> bool {
+    match node {
+        Node::BinaryExpression(expr) => {
+            expr.operator == "in" || contains_in(&expr.left) || contains_in(&expr.right)
+        }
+        Node::LogicalExpression(expr) => contains_in(&expr.left) || contains_in(&expr.right),
+        Node::AssignmentExpression(expr) => contains_in(&expr.left) || contains_in(&expr.right),
+        Node::ConditionalExpression(expr) => {
+            contains_in(&expr.test) || contains_in(&expr.consequent) || contains_in(&expr.alternate)
+        }
+        Node::UnaryExpression(expr) => contains_in(&expr.argument),
+        Node::AwaitExpression(expr) => contains_in(&expr.argument),
+        Node::YieldExpression(expr) => expr.argument.as_deref().is_some_and(contains_in),
+        Node::ArrowFunctionExpression(expr) => contains_in(&expr.body),
+        _ => false,
+    }
+}
+
+fn contains_call(node: &Node) -> bool {
+    match node {
+        Node::CallExpression(_) => true,
+        Node::MemberExpression(expr) => contains_call(&expr.object),
+        Node::TaggedTemplateExpression(expr) => contains_call(&expr.tag),
+        _ => false,
+    }
+}
+
+fn mixes_coalesce(operator: &str, operand: &Node) -> bool {
+    matches!(operand, Node::LogicalExpression(inner) if (operator == "??") != (inner.operator == "??"))
+}
+
+// The node whose text comes first when `node` is printed where `min` is
+// required, or `None` when the output starts with a parenthesis.
+fn leftmost(node: &Node, min: u8) -> Option<&Node> {
+    let prec = precedence(node);
+    if prec < min {
+        return None;
+    }
+    match node {
+        Node::BinaryExpression(expr) if prec == EXPONENT => leftmost(&expr.left, POSTFIX),
+        Node::BinaryExpression(expr) => leftmost(&expr.left, prec),
+        Node::LogicalExpression(expr) if mixes_coalesce(&expr.operator, &expr.left) => None,
+        Node::LogicalExpression(expr) => leftmost(&expr.left, prec),
+        Node::ConditionalExpression(expr) => leftmost(&expr.test, COALESCE),
+        Node::AssignmentExpression(expr) => leftmost(&expr.left, POSTFIX),
+        Node::CallExpression(expr) => leftmost(&expr.callee, MEMBER),
+        Node::MemberExpression(expr)
+            if matches!(*expr.object, Node::Number(_)) && !expr.computed =>
+        {
+            None
+        }
+        Node::MemberExpression(expr) => leftmost(&expr.object, MEMBER),
+        Node::TaggedTemplateExpression(expr) => leftmost(&expr.tag, MEMBER),
+        Node::UpdateExpression(expr) if !expr.prefix => leftmost(&expr.argument, POSTFIX + 1),
+        node => Some(node),
+    }
+}
+
+// The `+` or `-` that a prefix expression starts with.
+fn leading_sign(node: &Node) -> Option<char> {
+    let sign = match node {
+        Node::UnaryExpression(expr) => expr.operator.chars().next(),
+        Node::UpdateExpression(expr) if expr.prefix => expr.operator.chars().next(),
+        Node::Number(lit) if lit.value.is_sign_negative() && !lit.value.is_nan() => Some('-'),
+        _ => None,
+    };
+    sign.filter(|c| matches!(c, '+' | '-'))
+}
+
+fn same_name(a: &Node, b: &Node) -> bool {
+    match (a, b) {
+        (Node::Identifier(a), Node::Identifier(b)) => a.name == b.name,
+        _ => false,
+    }
+}
@@ -1,3 +1,5 @@
+use crate::ast::error::AstError;
+use crate::ast::estree::{from_estree, to_estree};
 use crate::ast::node::Node;
 use serde_json;
 
@@ -8,3 +10,15 @@ pub fn serialize_ast(node: &Node) -> Result<String, serde_json::Error> {
 pub fn deserialize_ast(json: &str) -> Result<Node, serde_json::Error> {
     serde_json::from_str(json)
 }
+
+pub fn to_estree_json(node: &Node) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&to_estree(node))
+}
+
+pub fn from_estree_json(json: &str) -> Result<Node, AstError> {
+    let value = serde_json::from_str(json).map_err(|error| AstError::DeserializationError {
+        message: error.to_string(),
+        position: None,
+    })?;
+    from_estree(&value)
+}
@@ -0,0 +1,384 @@
+mod vlq;
+
+use crate::ast::{AstError, Position, Span};
+use indexmap::IndexSet;
+use serde_json::{json, Value};
+
+// A source map from generated code back to the sources it came from,
+// serialized as version 3 JSON. Positions are `Position`s on both sides,
+// 1-based; the JSON uses 0-based lines and columns as the format requires.
+// Generated columns count UTF-16 code units, while original columns are
+// whatever the mapping was given, which for parser spans means chars.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceMap {
+    pub file: Option<String>,
+    pub sources: Vec<String>,
+    pub sources_content: Vec<Option<String>>,
+    pub names: Vec<String>,
+    // Sorted by generated position.
+    pub mappings: Vec<Mapping>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapping {
+    pub generated: Position,
+    // `None` for generated text that has no original, such as a helper the
+    // transform inserted.
+    pub original: Option<OriginalLocation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OriginalLocation {
+    pub source: usize,
+    pub position: Position,
+    pub name: Option<usize>,
+}
+
+// A lookup result with the indices resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OriginalPosition<'a> {
+    pub source: &'a str,
+    pub position: Position,
+    pub name: Option<&'a str>,
+}
+
+impl SourceMap {
+    // The original location of the mapping that covers `generated`, which
+    // is the closest one at or before it on the same line.
+    pub fn original_position(&self, generated: Position) -> Option<OriginalPosition<'_>> {
+        let key = position_key(generated);
+        let index = self
+            .mappings
+            .partition_point(|mapping| position_key(mapping.generated) <= key);
+        let mapping = self.mappings[..index].last()?;
+        if mapping.generated.line != generated.line {
+            return None;
+        }
+        let original = mapping.original?;
+        Some(OriginalPosition {
+            source: self.sources.get(original.source)?,
+            position: original.position,
+            name: original
+                .name
+                .and_then(|name| self.names.get(name))
+                .map(String::as_str),
+        })
+    }
+
+    // Rewrites every `file:line:column` in a stack trace that points into
+    // the generated file so it points at the original source instead.
+    // Lines and columns in stack traces are 1-based, as in `Position`.
+    pub fn translate_stack_trace(&self, trace: &str) -> String {
+        let mut out = String::with_capacity(trace.len());
+        for (index, line) in trace.split('\n').enumerate() {
+            if index > 0 {
+                out.push('\n');
+            }
+            out.push_str(&self.translate_frame(line));
+        }
+        out
+    }
+
+    fn translate_frame(&self, frame: &str) -> String {
+        let location = frame
+            .trim_end()
+            .trim_end_matches(')')
+            .rsplit([' ', '(', '@'])
+            .next()
+            .unwrap_or_default();
+        let mut parts = location.rsplitn(3, ':');
+        let (Some(column), Some(line), Some(file)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return frame.to_string();
+        };
+        let (Ok(line), Ok(column)) = (line.parse(), column.parse()) else {
+            return frame.to_string();
+        };
+        if !self.is_generated_file(file) {
+            return frame.to_string();
+        }
+        match self.original_position(Position::new(line, column)) {
+            Some(original) => frame.replacen(
+                location,
+                &format!("{}:{}", original.source, original.position),
+                1,
+            ),
+            None => frame.to_string(),
+        }
+    }
+
+    // Frames carry a path or URL; an unnamed map accepts any of them.
+    fn is_generated_file(&self, path: &str) -> bool {
+        match &self.file {
+            Some(file) => path == file || path.ends_with(&format!("/{file}")),
+            None => true,
+        }
+    }
+
+    pub fn encode_mappings(&self) -> String {
+        let mut out = String::new();
+        let mut line = 1;
+        let mut column = 0;
+        let mut source = 0;
+        let mut original_line = 0;
+        let mut original_column = 0;
+        let mut name = 0;
+
+        for (index, mapping) in self.mappings.iter().enumerate() {
+            let generated_line = mapping.generated.line.as_usize();
+            if generated_line != line {
+                while line < generated_line {
+                    out.push(';');
+                    line += 1;
+                }
+                column = 0;
+            } else if index > 0 {
+                out.push(',');
+            }
+
+            let generated_column = zero_based(mapping.generated.column.as_usize());
+            vlq::encode(generated_column - column, &mut out);
+            column = generated_column;
+
+            if let Some(original) = mapping.original {
+                let next_line = zero_based(original.position.line.as_usize());
+                let next_column = zero_based(original.position.column.as_usize());
+                vlq::encode(original.source as i64 - source, &mut out);
+                vlq::encode(next_line - original_line, &mut out);
+                vlq::encode(next_column - original_column, &mut out);
+                source = original.source as i64;
+                original_line = next_line;
+                original_column = next_column;
+                if let Some(next_name) = original.name {
+                    vlq::encode(next_name as i64 - name, &mut out);
+                    name = next_name as i64;
+                }
+            }
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut map = json!({
+            "version": 3,
+            "sources": self.sources,
+            "names": self.names,
+            "mappings": self.encode_mappings(),
+        });
+        if let Some(file) = &self.file {
+            map["file"] = json!(file);
+        }
+        if self.sources_content.iter().any(Option::is_some) {
+            map["sourcesContent"] = json!(self.sources_content);
+        }
+        map.to_string()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, AstError> {
+        let invalid = |message: &str| AstError::DeserializationError {
+            message: message.to_string(),
+            position: None,
+        };
+        let value: Value =
+            serde_json::from_str(json).map_err(|error| invalid(&error.to_string()))?;
+        if value["version"] != 3 {
+            return Err(invalid("only version 3 source maps are supported"));
+        }
+        let strings = |field: &str| -> Result<Vec<String>, AstError> {
+            match &value[field] {
+                Value::Null => Ok(Vec::new()),
+                Value::Array(values) => values
+                    .iter()
+                    .map(|value| {
+                        value
+                            .as_str()
+                            .map(str::to_string)
+                            .ok_or_else(|| invalid(&format!("'{field}' must hold strings")))
+                    })
+                    .collect(),
+                _ => Err(invalid(&format!("'{field}' must be an array"))),
+            }
+        };
+
+        let sources = strings("sources")?;
+        let sources_content = match &value["sourcesContent"] {
+            Value::Array(values) => values
+                .iter()
+                .map(|value| value.as_str().map(str::to_string))
+                .collect(),
+            _ => vec![None; sources.len()],
+        };
+        let names = strings("names")?;
+        let mappings = value["mappings"]
+            .as_str()
+            .ok_or_else(|| invalid("'mappings' must be a string"))?;
+
+        Ok(Self {
+            file: value["file"].as_str().map(str::to_string),
+            mappings: decode_mappings(mappings, &sources, &names)
+                .ok_or_else(|| invalid("malformed 'mappings'"))?,
+            sources,
+            sources_content,
+            names,
+        })
+    }
+}
+
+fn decode_mappings(encoded: &str, sources: &[String], names: &[String]) -> Option<Vec<Mapping>> {
+    let mut mappings = Vec::new();
+    let mut source = 0;
+    let mut original_line = 0;
+    let mut original_column = 0;
+    let mut name = 0;
+
+    for (line, segments) in encoded.split(';').enumerate() {
+        let mut column = 0;
+        for segment in segments.split(',').filter(|segment| !segment.is_empty()) {
+            let mut chars = segment.chars().peekable();
+            let mut fields = Vec::with_capacity(5);
+            while chars.peek().is_some() {
+                fields.push(vlq::decode(&mut chars)?);
+            }
+
+            column += fields[0];
+            let original = match fields[..] {
+                [_] => None,
+                [_, source_delta, line_delta, column_delta, ref rest @ ..] if rest.len() <= 1 => {
+                    source += source_delta;
+                    original_line += line_delta;
+                    original_column += column_delta;
+                    let name = rest.first().map(|delta| {
+                        name += delta;
+                        name
+                    });
+                    Some(OriginalLocation {
+                        source: index(source, sources.len())?,
+                        position: one_based(original_line, original_column)?,
+                        name: match name {
+                            Some(name) => Some(index(name, names.len())?),
+                            None => None,
+                        },
+                    })
+                }
+                _ => return None,
+            };
+            mappings.push(Mapping {
+                generated: one_based(line as i64, column)?,
+                original,
+            });
+        }
+    }
+    Some(mappings)
+}
+
+fn index(value: i64, len: usize) -> Option<usize> {
+    usize::try_from(value).ok().filter(|&value| value < len)
+}
+
+fn zero_based(value: usize) -> i64 {
+    value.saturating_sub(1) as i64
+}
+
+fn one_based(line: i64, column: i64) -> Option<Position> {
+    Some(Position::new(
+        usize::try_from(line).ok()? + 1,
+        usize::try_from(column).ok()? + 1,
+    ))
+}
+
+fn position_key(position: Position) -> (usize, usize) {
+    (position.line.as_usize(), position.column.as_usize())
+}
+
+// Collects mappings while code is emitted. Sources and names are stored
+// once each, however many mappings refer to them.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMapBuilder {
+    file: Option<String>,
+    sources: IndexSet<String>,
+    sources_content: Vec<Option<String>>,
+    names: IndexSet<String>,
+    mappings: Vec<Mapping>,
+}
+
+impl SourceMapBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    pub fn add_source(&mut self, source: &str, content: Option<&str>) -> usize {
+        let (index, added) = self.sources.insert_full(source.to_string());
+        if added {
+            self.sources_content.push(content.map(str::to_string));
+        } else if let Some(content) = content {
+            self.sources_content[index] = Some(content.to_string());
+        }
+        index
+    }
+
+    pub fn add_name(&mut self, name: &str) -> usize {
+        self.names.insert_full(name.to_string()).0
+    }
+
+    pub fn add_mapping(
+        &mut self,
+        generated: Position,
+        source: usize,
+        original: Position,
+        name: Option<&str>,
+    ) {
+        let name = name.map(|name| self.add_name(name));
+        self.push(Mapping {
+            generated,
+            original: Some(OriginalLocation {
+                source,
+                position: original,
+                name,
+            }),
+        });
+    }
+
+    // Maps generated text back to where the node `span` covers began.
+    pub fn add_span_mapping(
+        &mut self,
+        generated: Position,
+        source: usize,
+        span: &Span,
+        name: Option<&str>,
+    ) {
+        self.add_mapping(generated, source, span.start, name);
+    }
+
+    pub fn add_unmapped(&mut self, generated: Position) {
+        self.push(Mapping {
+            generated,
+            original: None,
+        });
+    }
+
+    // A later mapping at the same generated position replaces the earlier
+    // one, so the innermost node that starts there wins.
+    fn push(&mut self, mapping: Mapping) {
+        match self.mappings.last_mut() {
+            Some(last) if last.generated == mapping.generated => *last = mapping,
+            _ => self.mappings.push(mapping),
+        }
+    }
+
+    pub fn build(mut self) -> SourceMap {
+        self.mappings
+            .sort_by_key(|mapping| position_key(mapping.generated));
+        SourceMap {
+            file: self.file,
+            sources: self.sources.into_iter().collect(),
+            sources_content: self.sources_content,
+            names: self.names.into_iter().collect(),
+            mappings: self.mappings,
+        }
+    }
+}
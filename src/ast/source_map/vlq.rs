@@ -0,0 +1,42 @@
+// Base64 VLQ, the number encoding used by the `mappings` field. Each digit
+// carries five bits, with the sixth set when more digits follow; the lowest
+// bit of the first digit is the sign.
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn encode(value: i64, out: &mut String) {
+    let mut vlq = if value < 0 {
+        (value.unsigned_abs() << 1) | 1
+    } else {
+        (value as u64) << 1
+    };
+    loop {
+        let mut digit = (vlq & 0b11111) as u8;
+        vlq >>= 5;
+        if vlq > 0 {
+            digit |= 0b100000;
+        }
+        out.push(BASE64[digit as usize] as char);
+        if vlq == 0 {
+            break;
+        }
+    }
+}
+
+pub fn decode(chars: &mut impl Iterator<Item = char>) -> Option<i64> {
+    let mut vlq: u64 = 0;
+    let mut shift = 0;
+    loop {
+        let c = chars.next()?;
+        let digit = BASE64.iter().position(|&b| b as char == c)? as u64;
+        vlq |= (digit & 0b11111) << shift;
+        if digit & 0b100000 == 0 {
+            break;
+        }
+        shift += 5;
+        if shift >= 64 {
+            return None;
+        }
+    }
+    let value = (vlq >> 1) as i64;
+    Some(if vlq & 1 == 1 { -value } else { value })
+}
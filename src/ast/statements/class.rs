@@ -6,5 +6,48 @@ pub struct ClassDeclaration {
     pub id: Option<Box<crate::ast::node::Node>>,
     pub super_class: Option<Box<crate::ast::node::Node>>,
     pub body: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassBody {
+    pub body: Vec<crate::ast::node::Node>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MethodKind {
+    Constructor,
+    #[default]
+    Method,
+    Get,
+    Set,
+}
+
+// `value` is always a FunctionExpression; `key` is an Identifier, a literal,
+// a PrivateIdentifier or, when `computed`, any expression.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MethodDefinition {
+    pub key: Box<crate::ast::node::Node>,
+    pub value: Box<crate::ast::node::Node>,
+    pub kind: MethodKind,
+    pub computed: bool,
+    pub r#static: bool,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyDefinition {
+    pub key: Box<crate::ast::node::Node>,
+    pub value: Option<Box<crate::ast::node::Node>>,
+    pub computed: bool,
+    pub r#static: bool,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StaticBlock {
+    pub body: Vec<crate::ast::node::Node>,
+    pub span: Span,
 }
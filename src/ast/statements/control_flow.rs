@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BlockStatement {
     pub body: Vec<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -12,7 +12,7 @@ pub struct IfStatement {
     pub test: Box<crate::ast::node::Node>,
     pub consequent: Box<crate::ast::node::Node>,
     pub alternate: Option<Box<crate::ast::node::Node>>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -21,35 +21,52 @@ pub struct ForStatement {
     pub test: Option<Box<crate::ast::node::Node>>,
     pub update: Option<Box<crate::ast::node::Node>>,
     pub body: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForInStatement {
+    pub left: Box<crate::ast::node::Node>,
+    pub right: Box<crate::ast::node::Node>,
+    pub body: Box<crate::ast::node::Node>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForOfStatement {
+    pub left: Box<crate::ast::node::Node>,
+    pub right: Box<crate::ast::node::Node>,
+    pub body: Box<crate::ast::node::Node>,
+    pub r#await: bool,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WhileStatement {
     pub test: Box<crate::ast::node::Node>,
     pub body: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DoWhileStatement {
     pub body: Box<crate::ast::node::Node>,
     pub test: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SwitchStatement {
     pub discriminant: Box<crate::ast::node::Node>,
     pub cases: Vec<SwitchCase>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SwitchCase {
     pub test: Option<Box<crate::ast::node::Node>>,
     pub consequent: Vec<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -57,61 +74,69 @@ pub struct TryStatement {
     pub block: Box<crate::ast::node::Node>,
     pub handler: Option<Box<crate::ast::node::Node>>,
     pub finalizer: Option<Box<crate::ast::node::Node>>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CatchClause {
     pub param: Box<crate::ast::node::Node>,
     pub body: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ThrowStatement {
     pub argument: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ReturnStatement {
     pub argument: Option<Box<crate::ast::node::Node>>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BreakStatement {
     pub label: Option<Box<crate::ast::node::Node>>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ContinueStatement {
     pub label: Option<Box<crate::ast::node::Node>>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LabeledStatement {
     pub label: Box<crate::ast::node::Node>,
     pub body: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WithStatement {
     pub object: Box<crate::ast::node::Node>,
     pub body: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DebuggerStatement {
-    pub span: Option<Span>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmptyStatement {
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExpressionStatement {
     pub expression: Box<crate::ast::node::Node>,
-    pub span: Option<Span>,
+    // The raw text inside the quotes when the statement is part of a
+    // directive prologue, e.g. `use strict`.
+    pub directive: Option<String>,
+    pub span: Span,
 }
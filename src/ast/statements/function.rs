@@ -8,5 +8,5 @@ pub struct FunctionDeclaration {
     pub body: Box<crate::ast::node::Node>,
     pub generator: bool,
     pub r#async: bool,
-    pub span: Option<Span>,
+    pub span: Span,
 }
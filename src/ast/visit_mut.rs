@@ -0,0 +1,463 @@
+use crate::ast::{Node, *};
+
+// In-place counterpart of `Visitor`. Unlike `Visitor`, the default methods
+// walk every child node, including non-computed keys and member property
+// names, so a pass decides for itself which of them to touch.
+//
+// Overriding `visit_mut_node` sees every node in the tree and may replace
+// it wholesale through `*node = ...`; calling `visit_mut_children` from the
+// override carries on with the default walk.
+pub trait VisitMut {
+    fn visit_mut_node(&mut self, node: &mut Node) {
+        self.visit_mut_children(node);
+    }
+
+    // Dispatches to the method for the node's variant, each of which visits
+    // the node's children by default.
+    fn visit_mut_children(&mut self, node: &mut Node) {
+        match node {
+            Node::Program(program) => self.visit_mut_program(program),
+            Node::VariableDeclaration(decl) => self.visit_mut_variable_declaration(decl),
+            Node::FunctionDeclaration(decl) => self.visit_mut_function_declaration(decl),
+            Node::ClassDeclaration(decl) => self.visit_mut_class_declaration(decl),
+            Node::ClassBody(body) => self.visit_mut_class_body(body),
+            Node::MethodDefinition(method) => self.visit_mut_method_definition(method),
+            Node::PropertyDefinition(prop) => self.visit_mut_property_definition(prop),
+            Node::StaticBlock(block) => self.visit_mut_static_block(block),
+            Node::ImportDeclaration(decl) => self.visit_mut_import_declaration(decl),
+            Node::ExportDeclaration(decl) => self.visit_mut_export_declaration(decl),
+            Node::ImportSpecifier(spec) => self.visit_mut_import_specifier(spec),
+            Node::ImportDefaultSpecifier(spec) => self.visit_mut_import_default_specifier(spec),
+            Node::ImportNamespaceSpecifier(spec) => self.visit_mut_import_namespace_specifier(spec),
+            Node::ExportSpecifier(spec) => self.visit_mut_export_specifier(spec),
+            Node::ExportNamespaceSpecifier(spec) => self.visit_mut_export_namespace_specifier(spec),
+            Node::BinaryExpression(expr) => self.visit_mut_binary_expression(expr),
+            Node::UnaryExpression(expr) => self.visit_mut_unary_expression(expr),
+            Node::UpdateExpression(expr) => self.visit_mut_update_expression(expr),
+            Node::LogicalExpression(expr) => self.visit_mut_logical_expression(expr),
+            Node::ConditionalExpression(expr) => self.visit_mut_conditional_expression(expr),
+            Node::AssignmentExpression(expr) => self.visit_mut_assignment_expression(expr),
+            Node::CallExpression(expr) => self.visit_mut_call_expression(expr),
+            Node::NewExpression(expr) => self.visit_mut_new_expression(expr),
+            Node::MemberExpression(expr) => self.visit_mut_member_expression(expr),
+            Node::ArrowFunctionExpression(expr) => self.visit_mut_arrow_function_expression(expr),
+            Node::FunctionExpression(expr) => self.visit_mut_function_expression(expr),
+            Node::ClassExpression(expr) => self.visit_mut_class_expression(expr),
+            Node::YieldExpression(expr) => self.visit_mut_yield_expression(expr),
+            Node::AwaitExpression(expr) => self.visit_mut_await_expression(expr),
+            Node::BlockStatement(stmt) => self.visit_mut_block_statement(stmt),
+            Node::IfStatement(stmt) => self.visit_mut_if_statement(stmt),
+            Node::ForStatement(stmt) => self.visit_mut_for_statement(stmt),
+            Node::ForInStatement(stmt) => self.visit_mut_for_in_statement(stmt),
+            Node::ForOfStatement(stmt) => self.visit_mut_for_of_statement(stmt),
+            Node::WhileStatement(stmt) => self.visit_mut_while_statement(stmt),
+            Node::DoWhileStatement(stmt) => self.visit_mut_do_while_statement(stmt),
+            Node::SwitchStatement(stmt) => self.visit_mut_switch_statement(stmt),
+            Node::TryStatement(stmt) => self.visit_mut_try_statement(stmt),
+            Node::CatchClause(clause) => self.visit_mut_catch_clause(clause),
+            Node::ThrowStatement(stmt) => self.visit_mut_throw_statement(stmt),
+            Node::ReturnStatement(stmt) => self.visit_mut_return_statement(stmt),
+            Node::BreakStatement(stmt) => self.visit_mut_break_statement(stmt),
+            Node::ContinueStatement(stmt) => self.visit_mut_continue_statement(stmt),
+            Node::LabeledStatement(stmt) => self.visit_mut_labeled_statement(stmt),
+            Node::WithStatement(stmt) => self.visit_mut_with_statement(stmt),
+            Node::DebuggerStatement(stmt) => self.visit_mut_debugger_statement(stmt),
+            Node::EmptyStatement(stmt) => self.visit_mut_empty_statement(stmt),
+            Node::ExpressionStatement(stmt) => self.visit_mut_expression_statement(stmt),
+            Node::ArrayLiteral(lit) => self.visit_mut_array_literal(lit),
+            Node::ObjectLiteral(lit) => self.visit_mut_object_literal(lit),
+            Node::TemplateLiteral(lit) => self.visit_mut_template_literal(lit),
+            Node::TaggedTemplateExpression(expr) => self.visit_mut_tagged_template_expression(expr),
+            Node::Property(prop) => self.visit_mut_property(prop),
+            Node::SpreadElement(elem) => self.visit_mut_spread_element(elem),
+            Node::RestElement(elem) => self.visit_mut_rest_element(elem),
+            Node::Super(super_expr) => self.visit_mut_super(super_expr),
+            Node::MetaProperty(prop) => self.visit_mut_meta_property(prop),
+            Node::ImportExpression(expr) => self.visit_mut_import_expression(expr),
+            Node::Identifier(id) => self.visit_mut_identifier(id),
+            Node::PrivateIdentifier(id) => self.visit_mut_private_identifier(id),
+            Node::Number(lit) => self.visit_mut_number(lit),
+            Node::String(lit) => self.visit_mut_string(lit),
+            Node::Boolean(lit) => self.visit_mut_boolean(lit),
+            Node::Null(lit) => self.visit_mut_null(lit),
+            Node::Undefined(lit) => self.visit_mut_undefined(lit),
+            Node::This(expr) => self.visit_mut_this(expr),
+            Node::RegExp(regexp) => self.visit_mut_regexp(regexp),
+            Node::BigInt(lit) => self.visit_mut_bigint(lit),
+            #[cfg(feature = "jsx")]
+            Node::JsxElement(elem) => self.visit_mut_jsx_element(elem),
+            #[cfg(feature = "jsx")]
+            Node::JsxFragment(frag) => self.visit_mut_jsx_fragment(frag),
+            #[cfg(feature = "jsx")]
+            Node::JsxAttribute(attr) => self.visit_mut_jsx_attribute(attr),
+            #[cfg(feature = "jsx")]
+            Node::JsxSpreadAttribute(attr) => self.visit_mut_jsx_spread_attribute(attr),
+            #[cfg(feature = "jsx")]
+            Node::JsxExpressionContainer(container) => {
+                self.visit_mut_jsx_expression_container(container)
+            }
+            #[cfg(feature = "jsx")]
+            Node::JsxText(text) => self.visit_mut_jsx_text(text),
+            #[cfg(feature = "jsx")]
+            Node::JsxNamespacedName(name) => self.visit_mut_jsx_namespaced_name(name),
+            Node::Error(error) => self.visit_mut_error(error),
+        }
+    }
+
+    fn visit_mut_nodes(&mut self, nodes: &mut [Node]) {
+        for node in nodes {
+            self.visit_mut_node(node);
+        }
+    }
+
+    fn visit_mut_opt(&mut self, node: &mut Option<Box<Node>>) {
+        if let Some(node) = node {
+            self.visit_mut_node(node);
+        }
+    }
+
+    fn visit_mut_program(&mut self, program: &mut Program) {
+        self.visit_mut_nodes(&mut program.body);
+    }
+
+    fn visit_mut_variable_declaration(&mut self, decl: &mut VariableDeclaration) {
+        for declarator in &mut decl.declarations {
+            self.visit_mut_node(&mut declarator.id);
+            self.visit_mut_opt(&mut declarator.init);
+        }
+    }
+
+    fn visit_mut_function_declaration(&mut self, decl: &mut FunctionDeclaration) {
+        self.visit_mut_opt(&mut decl.id);
+        self.visit_mut_nodes(&mut decl.params);
+        self.visit_mut_node(&mut decl.body);
+    }
+
+    fn visit_mut_class_declaration(&mut self, decl: &mut ClassDeclaration) {
+        self.visit_mut_opt(&mut decl.id);
+        self.visit_mut_opt(&mut decl.super_class);
+        self.visit_mut_node(&mut decl.body);
+    }
+
+    fn visit_mut_class_body(&mut self, body: &mut ClassBody) {
+        self.visit_mut_nodes(&mut body.body);
+    }
+
+    fn visit_mut_method_definition(&mut self, method: &mut MethodDefinition) {
+        self.visit_mut_node(&mut method.key);
+        self.visit_mut_node(&mut method.value);
+    }
+
+    fn visit_mut_property_definition(&mut self, prop: &mut PropertyDefinition) {
+        self.visit_mut_node(&mut prop.key);
+        self.visit_mut_opt(&mut prop.value);
+    }
+
+    fn visit_mut_static_block(&mut self, block: &mut StaticBlock) {
+        self.visit_mut_nodes(&mut block.body);
+    }
+
+    fn visit_mut_import_declaration(&mut self, decl: &mut ImportDeclaration) {
+        self.visit_mut_nodes(&mut decl.specifiers);
+        self.visit_mut_node(&mut decl.source);
+        for attribute in &mut decl.attributes {
+            self.visit_mut_node(&mut attribute.key);
+            self.visit_mut_node(&mut attribute.value);
+        }
+    }
+
+    fn visit_mut_export_declaration(&mut self, decl: &mut ExportDeclaration) {
+        self.visit_mut_opt(&mut decl.declaration);
+        self.visit_mut_nodes(&mut decl.specifiers);
+        self.visit_mut_opt(&mut decl.source);
+        for attribute in &mut decl.attributes {
+            self.visit_mut_node(&mut attribute.key);
+            self.visit_mut_node(&mut attribute.value);
+        }
+    }
+
+    fn visit_mut_import_specifier(&mut self, spec: &mut ImportSpecifier) {
+        self.visit_mut_node(&mut spec.imported);
+        self.visit_mut_node(&mut spec.local);
+    }
+
+    fn visit_mut_import_default_specifier(&mut self, spec: &mut ImportDefaultSpecifier) {
+        self.visit_mut_node(&mut spec.local);
+    }
+
+    fn visit_mut_import_namespace_specifier(&mut self, spec: &mut ImportNamespaceSpecifier) {
+        self.visit_mut_node(&mut spec.local);
+    }
+
+    fn visit_mut_export_specifier(&mut self, spec: &mut ExportSpecifier) {
+        self.visit_mut_node(&mut spec.local);
+        self.visit_mut_node(&mut spec.exported);
+    }
+
+    fn visit_mut_export_namespace_specifier(&mut self, spec: &mut ExportNamespaceSpecifier) {
+        self.visit_mut_opt(&mut spec.exported);
+    }
+
+    fn visit_mut_binary_expression(&mut self, expr: &mut BinaryExpression) {
+        self.visit_mut_node(&mut expr.left);
+        self.visit_mut_node(&mut expr.right);
+    }
+
+    fn visit_mut_unary_expression(&mut self, expr: &mut UnaryExpression) {
+        self.visit_mut_node(&mut expr.argument);
+    }
+
+    fn visit_mut_update_expression(&mut self, expr: &mut UpdateExpression) {
+        self.visit_mut_node(&mut expr.argument);
+    }
+
+    fn visit_mut_logical_expression(&mut self, expr: &mut LogicalExpression) {
+        self.visit_mut_node(&mut expr.left);
+        self.visit_mut_node(&mut expr.right);
+    }
+
+    fn visit_mut_conditional_expression(&mut self, expr: &mut ConditionalExpression) {
+        self.visit_mut_node(&mut expr.test);
+        self.visit_mut_node(&mut expr.consequent);
+        self.visit_mut_node(&mut expr.alternate);
+    }
+
+    fn visit_mut_assignment_expression(&mut self, expr: &mut AssignmentExpression) {
+        self.visit_mut_node(&mut expr.left);
+        self.visit_mut_node(&mut expr.right);
+    }
+
+    fn visit_mut_call_expression(&mut self, expr: &mut CallExpression) {
+        self.visit_mut_node(&mut expr.callee);
+        self.visit_mut_nodes(&mut expr.arguments);
+    }
+
+    fn visit_mut_new_expression(&mut self, expr: &mut NewExpression) {
+        self.visit_mut_node(&mut expr.callee);
+        self.visit_mut_nodes(&mut expr.arguments);
+    }
+
+    fn visit_mut_member_expression(&mut self, expr: &mut MemberExpression) {
+        self.visit_mut_node(&mut expr.object);
+        self.visit_mut_node(&mut expr.property);
+    }
+
+    fn visit_mut_arrow_function_expression(&mut self, expr: &mut ArrowFunctionExpression) {
+        self.visit_mut_nodes(&mut expr.params);
+        self.visit_mut_node(&mut expr.body);
+    }
+
+    fn visit_mut_function_expression(&mut self, expr: &mut FunctionExpression) {
+        self.visit_mut_opt(&mut expr.id);
+        self.visit_mut_nodes(&mut expr.params);
+        self.visit_mut_node(&mut expr.body);
+    }
+
+    fn visit_mut_class_expression(&mut self, expr: &mut ClassExpression) {
+        self.visit_mut_opt(&mut expr.id);
+        self.visit_mut_opt(&mut expr.super_class);
+        self.visit_mut_node(&mut expr.body);
+    }
+
+    fn visit_mut_yield_expression(&mut self, expr: &mut YieldExpression) {
+        self.visit_mut_opt(&mut expr.argument);
+    }
+
+    fn visit_mut_await_expression(&mut self, expr: &mut AwaitExpression) {
+        self.visit_mut_node(&mut expr.argument);
+    }
+
+    fn visit_mut_block_statement(&mut self, stmt: &mut BlockStatement) {
+        self.visit_mut_nodes(&mut stmt.body);
+    }
+
+    fn visit_mut_if_statement(&mut self, stmt: &mut IfStatement) {
+        self.visit_mut_node(&mut stmt.test);
+        self.visit_mut_node(&mut stmt.consequent);
+        self.visit_mut_opt(&mut stmt.alternate);
+    }
+
+    fn visit_mut_for_statement(&mut self, stmt: &mut ForStatement) {
+        self.visit_mut_opt(&mut stmt.init);
+        self.visit_mut_opt(&mut stmt.test);
+        self.visit_mut_opt(&mut stmt.update);
+        self.visit_mut_node(&mut stmt.body);
+    }
+
+    fn visit_mut_for_in_statement(&mut self, stmt: &mut ForInStatement) {
+        self.visit_mut_node(&mut stmt.left);
+        self.visit_mut_node(&mut stmt.right);
+        self.visit_mut_node(&mut stmt.body);
+    }
+
+    fn visit_mut_for_of_statement(&mut self, stmt: &mut ForOfStatement) {
+        self.visit_mut_node(&mut stmt.left);
+        self.visit_mut_node(&mut stmt.right);
+        self.visit_mut_node(&mut stmt.body);
+    }
+
+    fn visit_mut_while_statement(&mut self, stmt: &mut WhileStatement) {
+        self.visit_mut_node(&mut stmt.test);
+        self.visit_mut_node(&mut stmt.body);
+    }
+
+    fn visit_mut_do_while_statement(&mut self, stmt: &mut DoWhileStatement) {
+        self.visit_mut_node(&mut stmt.body);
+        self.visit_mut_node(&mut stmt.test);
+    }
+
+    fn visit_mut_switch_statement(&mut self, stmt: &mut SwitchStatement) {
+        self.visit_mut_node(&mut stmt.discriminant);
+        for case in &mut stmt.cases {
+            self.visit_mut_opt(&mut case.test);
+            self.visit_mut_nodes(&mut case.consequent);
+        }
+    }
+
+    fn visit_mut_try_statement(&mut self, stmt: &mut TryStatement) {
+        self.visit_mut_node(&mut stmt.block);
+        self.visit_mut_opt(&mut stmt.handler);
+        self.visit_mut_opt(&mut stmt.finalizer);
+    }
+
+    fn visit_mut_catch_clause(&mut self, clause: &mut CatchClause) {
+        self.visit_mut_node(&mut clause.param);
+        self.visit_mut_node(&mut clause.body);
+    }
+
+    fn visit_mut_throw_statement(&mut self, stmt: &mut ThrowStatement) {
+        self.visit_mut_node(&mut stmt.argument);
+    }
+
+    fn visit_mut_return_statement(&mut self, stmt: &mut ReturnStatement) {
+        self.visit_mut_opt(&mut stmt.argument);
+    }
+
+    fn visit_mut_break_statement(&mut self, stmt: &mut BreakStatement) {
+        self.visit_mut_opt(&mut stmt.label);
+    }
+
+    fn visit_mut_continue_statement(&mut self, stmt: &mut ContinueStatement) {
+        self.visit_mut_opt(&mut stmt.label);
+    }
+
+    fn visit_mut_labeled_statement(&mut self, stmt: &mut LabeledStatement) {
+        self.visit_mut_node(&mut stmt.label);
+        self.visit_mut_node(&mut stmt.body);
+    }
+
+    fn visit_mut_with_statement(&mut self, stmt: &mut WithStatement) {
+        self.visit_mut_node(&mut stmt.object);
+        self.visit_mut_node(&mut stmt.body);
+    }
+
+    fn visit_mut_debugger_statement(&mut self, _stmt: &mut DebuggerStatement) {}
+
+    fn visit_mut_empty_statement(&mut self, _stmt: &mut EmptyStatement) {}
+
+    fn visit_mut_expression_statement(&mut self, stmt: &mut ExpressionStatement) {
+        self.visit_mut_node(&mut stmt.expression);
+    }
+
+    fn visit_mut_array_literal(&mut self, lit: &mut ArrayLiteral) {
+        for element in lit.elements.iter_mut().flatten() {
+            self.visit_mut_node(element);
+        }
+    }
+
+    fn visit_mut_object_literal(&mut self, lit: &mut ObjectLiteral) {
+        self.visit_mut_nodes(&mut lit.properties);
+    }
+
+    fn visit_mut_template_literal(&mut self, lit: &mut TemplateLiteral) {
+        self.visit_mut_nodes(&mut lit.expressions);
+    }
+
+    fn visit_mut_tagged_template_expression(&mut self, expr: &mut TaggedTemplateExpression) {
+        self.visit_mut_node(&mut expr.tag);
+        self.visit_mut_node(&mut expr.quasi);
+    }
+
+    fn visit_mut_property(&mut self, prop: &mut Property) {
+        self.visit_mut_node(&mut prop.key);
+        self.visit_mut_node(&mut prop.value);
+    }
+
+    fn visit_mut_spread_element(&mut self, elem: &mut SpreadElement) {
+        self.visit_mut_node(&mut elem.argument);
+    }
+
+    fn visit_mut_rest_element(&mut self, elem: &mut RestElement) {
+        self.visit_mut_node(&mut elem.argument);
+    }
+
+    fn visit_mut_super(&mut self, _super_expr: &mut Super) {}
+
+    fn visit_mut_meta_property(&mut self, prop: &mut MetaProperty) {
+        self.visit_mut_node(&mut prop.meta);
+        self.visit_mut_node(&mut prop.property);
+    }
+
+    fn visit_mut_import_expression(&mut self, expr: &mut ImportExpression) {
+        self.visit_mut_node(&mut expr.source);
+    }
+
+    fn visit_mut_identifier(&mut self, _id: &mut Identifier) {}
+
+    fn visit_mut_private_identifier(&mut self, _id: &mut PrivateIdentifier) {}
+
+    fn visit_mut_number(&mut self, _lit: &mut NumberLiteral) {}
+
+    fn visit_mut_string(&mut self, _lit: &mut StringLiteral) {}
+
+    fn visit_mut_boolean(&mut self, _lit: &mut BooleanLiteral) {}
+
+    fn visit_mut_null(&mut self, _lit: &mut NullLiteral) {}
+
+    fn visit_mut_undefined(&mut self, _lit: &mut UndefinedLiteral) {}
+
+    fn visit_mut_this(&mut self, _expr: &mut ThisExpression) {}
+
+    fn visit_mut_regexp(&mut self, _regexp: &mut RegExp) {}
+
+    fn visit_mut_bigint(&mut self, _lit: &mut BigIntLiteral) {}
+
+    #[cfg(feature = "jsx")]
+    fn visit_mut_jsx_element(&mut self, elem: &mut JsxElement) {
+        self.visit_mut_node(&mut elem.name);
+        self.visit_mut_nodes(&mut elem.attributes);
+        self.visit_mut_nodes(&mut elem.children);
+    }
+
+    #[cfg(feature = "jsx")]
+    fn visit_mut_jsx_fragment(&mut self, frag: &mut JsxFragment) {
+        self.visit_mut_nodes(&mut frag.children);
+    }
+
+    #[cfg(feature = "jsx")]
+    fn visit_mut_jsx_attribute(&mut self, attr: &mut JsxAttribute) {
+        self.visit_mut_node(&mut attr.name);
+        self.visit_mut_opt(&mut attr.value);
+    }
+
+    #[cfg(feature = "jsx")]
+    fn visit_mut_jsx_spread_attribute(&mut self, attr: &mut JsxSpreadAttribute) {
+        self.visit_mut_node(&mut attr.argument);
+    }
+
+    #[cfg(feature = "jsx")]
+    fn visit_mut_jsx_expression_container(&mut self, container: &mut JsxExpressionContainer) {
+        self.visit_mut_opt(&mut container.expression);
+    }
+
+    #[cfg(feature = "jsx")]
+    fn visit_mut_jsx_text(&mut self, _text: &mut JsxText) {}
+
+    #[cfg(feature = "jsx")]
+    fn visit_mut_jsx_namespaced_name(&mut self, name: &mut JsxNamespacedName) {
+        self.visit_mut_node(&mut name.namespace);
+        self.visit_mut_node(&mut name.name);
+    }
+
+    fn visit_mut_error(&mut self, _error: &mut ErrorNode) {}
+}
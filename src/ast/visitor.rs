@@ -10,6 +10,10 @@ pub trait Visitor {
             Node::VariableDeclaration(decl) => self.visit_variable_declaration(decl),
             Node::FunctionDeclaration(decl) => self.visit_function_declaration(decl),
             Node::ClassDeclaration(decl) => self.visit_class_declaration(decl),
+            Node::ClassBody(body) => self.visit_class_body(body),
+            Node::MethodDefinition(method) => self.visit_method_definition(method),
+            Node::PropertyDefinition(prop) => self.visit_property_definition(prop),
+            Node::StaticBlock(block) => self.visit_static_block(block),
             Node::BinaryExpression(expr) => self.visit_binary_expression(expr),
             Node::UnaryExpression(expr) => self.visit_unary_expression(expr),
             Node::CallExpression(expr) => self.visit_call_expression(expr),
@@ -22,6 +26,8 @@ pub trait Visitor {
             Node::BlockStatement(stmt) => self.visit_block_statement(stmt),
             Node::IfStatement(stmt) => self.visit_if_statement(stmt),
             Node::ForStatement(stmt) => self.visit_for_statement(stmt),
+            Node::ForInStatement(stmt) => self.visit_for_in_statement(stmt),
+            Node::ForOfStatement(stmt) => self.visit_for_of_statement(stmt),
             Node::WhileStatement(stmt) => self.visit_while_statement(stmt),
             Node::DoWhileStatement(stmt) => self.visit_do_while_statement(stmt),
             Node::SwitchStatement(stmt) => self.visit_switch_statement(stmt),
@@ -35,13 +41,14 @@ pub trait Visitor {
             Node::ArrayLiteral(lit) => self.visit_array_literal(lit),
             Node::ObjectLiteral(lit) => self.visit_object_literal(lit),
             Node::Property(prop) => self.visit_property(prop),
-            Node::Identifier(id) => self.visit_identifier(id),
-            Node::Number(num) => self.visit_number(*num),
-            Node::String(s) => self.visit_string(s),
-            Node::Boolean(b) => self.visit_boolean(*b),
-            Node::Null => self.visit_null(),
-            Node::Undefined => self.visit_undefined(),
-            Node::This => self.visit_this(),
+            Node::Identifier(id) => self.visit_identifier(&id.name),
+            Node::PrivateIdentifier(id) => self.visit_private_identifier(&id.name),
+            Node::Number(num) => self.visit_number(num.value),
+            Node::String(s) => self.visit_string(&s.value),
+            Node::Boolean(b) => self.visit_boolean(b.value),
+            Node::Null(_) => self.visit_null(),
+            Node::Undefined(_) => self.visit_undefined(),
+            Node::This(_) => self.visit_this(),
             Node::ArrowFunctionExpression(expr) => self.visit_arrow_function_expression(expr),
             Node::FunctionExpression(expr) => self.visit_function_expression(expr),
             Node::ClassExpression(expr) => self.visit_class_expression(expr),
@@ -49,17 +56,41 @@ pub trait Visitor {
             Node::AwaitExpression(expr) => self.visit_await_expression(expr),
             Node::Super(super_expr) => self.visit_super(super_expr),
             Node::MetaProperty(prop) => self.visit_meta_property(prop),
+            Node::ImportExpression(expr) => self.visit_import_expression(expr),
             Node::SpreadElement(elem) => self.visit_spread_element(elem),
             Node::RestElement(elem) => self.visit_rest_element(elem),
             Node::TemplateLiteral(lit) => self.visit_template_literal(lit),
             Node::TaggedTemplateExpression(expr) => self.visit_tagged_template_expression(expr),
             Node::ImportDeclaration(decl) => self.visit_import_declaration(decl),
             Node::ExportDeclaration(decl) => self.visit_export_declaration(decl),
+            Node::ImportSpecifier(spec) => self.visit_import_specifier(spec),
+            Node::ImportDefaultSpecifier(spec) => self.visit_import_default_specifier(spec),
+            Node::ImportNamespaceSpecifier(spec) => self.visit_import_namespace_specifier(spec),
+            Node::ExportSpecifier(spec) => self.visit_export_specifier(spec),
+            Node::ExportNamespaceSpecifier(spec) => self.visit_export_namespace_specifier(spec),
             Node::LabeledStatement(stmt) => self.visit_labeled_statement(stmt),
             Node::WithStatement(stmt) => self.visit_with_statement(stmt),
             Node::DebuggerStatement(stmt) => self.visit_debugger_statement(stmt),
-            Node::BigInt(bigint) => self.visit_bigint(bigint),
+            Node::EmptyStatement(stmt) => self.visit_empty_statement(stmt),
+            Node::BigInt(bigint) => self.visit_bigint(&bigint.value),
             Node::RegExp(regexp) => self.visit_regexp(regexp),
+            #[cfg(feature = "jsx")]
+            Node::JsxElement(elem) => self.visit_jsx_element(elem),
+            #[cfg(feature = "jsx")]
+            Node::JsxFragment(frag) => self.visit_jsx_fragment(frag),
+            #[cfg(feature = "jsx")]
+            Node::JsxAttribute(attr) => self.visit_jsx_attribute(attr),
+            #[cfg(feature = "jsx")]
+            Node::JsxSpreadAttribute(attr) => self.visit_jsx_spread_attribute(attr),
+            #[cfg(feature = "jsx")]
+            Node::JsxExpressionContainer(container) => {
+                self.visit_jsx_expression_container(container)
+            }
+            #[cfg(feature = "jsx")]
+            Node::JsxText(text) => self.visit_jsx_text(text),
+            #[cfg(feature = "jsx")]
+            Node::JsxNamespacedName(name) => self.visit_jsx_namespaced_name(name),
+            Node::Error(error) => self.visit_error(error),
         }
     }
 
@@ -108,6 +139,38 @@ pub trait Visitor {
         self.default_output()
     }
 
+    fn visit_class_body(&mut self, body: &ClassBody) -> Self::Output {
+        for element in &body.body {
+            self.visit_node(element);
+        }
+        self.default_output()
+    }
+
+    fn visit_method_definition(&mut self, method: &MethodDefinition) -> Self::Output {
+        if method.computed {
+            self.visit_node(&method.key);
+        }
+        self.visit_node(&method.value);
+        self.default_output()
+    }
+
+    fn visit_property_definition(&mut self, prop: &PropertyDefinition) -> Self::Output {
+        if prop.computed {
+            self.visit_node(&prop.key);
+        }
+        if let Some(value) = &prop.value {
+            self.visit_node(value);
+        }
+        self.default_output()
+    }
+
+    fn visit_static_block(&mut self, block: &StaticBlock) -> Self::Output {
+        for statement in &block.body {
+            self.visit_node(statement);
+        }
+        self.default_output()
+    }
+
     fn visit_binary_expression(&mut self, expr: &crate::ast::BinaryExpression) -> Self::Output {
         self.visit_node(&expr.left);
         self.visit_node(&expr.right);
@@ -200,6 +263,20 @@ pub trait Visitor {
         self.default_output()
     }
 
+    fn visit_for_in_statement(&mut self, stmt: &ForInStatement) -> Self::Output {
+        self.visit_node(&stmt.left);
+        self.visit_node(&stmt.right);
+        self.visit_node(&stmt.body);
+        self.default_output()
+    }
+
+    fn visit_for_of_statement(&mut self, stmt: &ForOfStatement) -> Self::Output {
+        self.visit_node(&stmt.left);
+        self.visit_node(&stmt.right);
+        self.visit_node(&stmt.body);
+        self.default_output()
+    }
+
     fn visit_while_statement(&mut self, stmt: &WhileStatement) -> Self::Output {
         self.visit_node(&stmt.test);
         self.visit_node(&stmt.body);
@@ -293,6 +370,10 @@ pub trait Visitor {
         self.default_output()
     }
 
+    fn visit_private_identifier(&mut self, _name: &str) -> Self::Output {
+        self.default_output()
+    }
+
     fn visit_number(&mut self, _num: f64) -> Self::Output {
         self.default_output()
     }
@@ -367,6 +448,11 @@ pub trait Visitor {
         self.default_output()
     }
 
+    fn visit_import_expression(&mut self, expr: &ImportExpression) -> Self::Output {
+        self.visit_node(&expr.source);
+        self.default_output()
+    }
+
     fn visit_spread_element(&mut self, elem: &SpreadElement) -> Self::Output {
         self.visit_node(&elem.argument);
         self.default_output()
@@ -413,6 +499,41 @@ pub trait Visitor {
         self.default_output()
     }
 
+    fn visit_import_specifier(&mut self, spec: &ImportSpecifier) -> Self::Output {
+        self.visit_node(&spec.imported);
+        self.visit_node(&spec.local);
+        self.default_output()
+    }
+
+    fn visit_import_default_specifier(&mut self, spec: &ImportDefaultSpecifier) -> Self::Output {
+        self.visit_node(&spec.local);
+        self.default_output()
+    }
+
+    fn visit_import_namespace_specifier(
+        &mut self,
+        spec: &ImportNamespaceSpecifier,
+    ) -> Self::Output {
+        self.visit_node(&spec.local);
+        self.default_output()
+    }
+
+    fn visit_export_specifier(&mut self, spec: &ExportSpecifier) -> Self::Output {
+        self.visit_node(&spec.local);
+        self.visit_node(&spec.exported);
+        self.default_output()
+    }
+
+    fn visit_export_namespace_specifier(
+        &mut self,
+        spec: &ExportNamespaceSpecifier,
+    ) -> Self::Output {
+        if let Some(exported) = &spec.exported {
+            self.visit_node(exported);
+        }
+        self.default_output()
+    }
+
     fn visit_labeled_statement(&mut self, stmt: &LabeledStatement) -> Self::Output {
         self.visit_node(&stmt.label);
         self.visit_node(&stmt.body);
@@ -429,6 +550,10 @@ pub trait Visitor {
         self.default_output()
     }
 
+    fn visit_empty_statement(&mut self, _stmt: &EmptyStatement) -> Self::Output {
+        self.default_output()
+    }
+
     fn visit_bigint(&mut self, _bigint: &str) -> Self::Output {
         self.default_output()
     }
@@ -437,6 +562,68 @@ pub trait Visitor {
         self.default_output()
     }
 
+    #[cfg(feature = "jsx")]
+    fn visit_jsx_element(&mut self, elem: &JsxElement) -> Self::Output {
+        self.visit_node(&elem.name);
+        for attribute in &elem.attributes {
+            self.visit_node(attribute);
+        }
+        for child in &elem.children {
+            self.visit_node(child);
+        }
+        self.default_output()
+    }
+
+    #[cfg(feature = "jsx")]
+    fn visit_jsx_fragment(&mut self, frag: &JsxFragment) -> Self::Output {
+        for child in &frag.children {
+            self.visit_node(child);
+        }
+        self.default_output()
+    }
+
+    #[cfg(feature = "jsx")]
+    fn visit_jsx_attribute(&mut self, attr: &JsxAttribute) -> Self::Output {
+        self.visit_node(&attr.name);
+        if let Some(value) = &attr.value {
+            self.visit_node(value);
+        }
+        self.default_output()
+    }
+
+    #[cfg(feature = "jsx")]
+    fn visit_jsx_spread_attribute(&mut self, attr: &JsxSpreadAttribute) -> Self::Output {
+        self.visit_node(&attr.argument);
+        self.default_output()
+    }
+
+    #[cfg(feature = "jsx")]
+    fn visit_jsx_expression_container(
+        &mut self,
+        container: &JsxExpressionContainer,
+    ) -> Self::Output {
+        if let Some(expression) = &container.expression {
+            self.visit_node(expression);
+        }
+        self.default_output()
+    }
+
+    #[cfg(feature = "jsx")]
+    fn visit_jsx_text(&mut self, _text: &JsxText) -> Self::Output {
+        self.default_output()
+    }
+
+    #[cfg(feature = "jsx")]
+    fn visit_jsx_namespaced_name(&mut self, name: &JsxNamespacedName) -> Self::Output {
+        self.visit_node(&name.namespace);
+        self.visit_node(&name.name);
+        self.default_output()
+    }
+
+    fn visit_error(&mut self, _error: &ErrorNode) -> Self::Output {
+        self.default_output()
+    }
+
     fn visit_unknown(&mut self, _node: &Node) -> Self::Output {
         self.default_output()
     }
@@ -616,9 +803,9 @@ impl Visitor for AstPrinter {
             Node::Number(_num) => {}
             Node::String(_s) => {}
             Node::Boolean(_b) => {}
-            Node::Null => {}
-            Node::Undefined => {}
-            Node::This => {}
+            Node::Null(_) => {}
+            Node::Undefined(_) => {}
+            Node::This(_) => {}
             _ => {}
         }
 
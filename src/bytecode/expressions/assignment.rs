@@ -1,6 +1,8 @@
-use crate::ast::Node;
+use crate::ast::{MemberExpression, Node};
+use crate::bytecode::expressions::PatternGenerator;
+use crate::bytecode::scope::{Binding, ConstantManager, ScopeManager};
 use crate::vm::instructions::Instruction;
-use crate::vm::types::{CodeAddress, LocalIndex};
+use crate::vm::types::{ArgIndex, CodeAddress, FunctionIndex, LocalIndex};
 
 pub trait AssignmentGenerator {
     fn generate_assignment_expression(&mut self, node: &Node);
@@ -17,14 +19,57 @@ pub trait AssignmentCore {
 
 impl<T> AssignmentGenerator for T
 where
-    T: AssignmentCore,
+    T: AssignmentCore + ScopeManager + ConstantManager + PatternGenerator,
 {
     fn generate_assignment_expression(&mut self, node: &Node) {
         if let Node::AssignmentExpression(expr) = node {
-            self.visit_node(&expr.right);
-            self.visit_node(&expr.left);
-            self.instructions()
-                .push(Instruction::StoreLocal(LocalIndex::new(0)));
+            let compound = compound_assignment_instruction(&expr.operator);
+            let simple_target = match &*expr.left {
+                Node::Identifier(id) if expr.operator == "=" || compound.is_some() => {
+                    Some(&id.name)
+                }
+                _ => None,
+            };
+
+            if let Node::MemberExpression(member) = &*expr.left {
+                if expr.operator == "=" || compound.is_some() {
+                    generate_member_assignment(self, member, &expr.right, compound);
+                    return;
+                }
+            }
+
+            if let Node::ArrayLiteral(_) | Node::ObjectLiteral(_) = &*expr.left {
+                if expr.operator == "=" {
+                    // The assignment evaluates to the value taken apart.
+                    self.visit_node(&expr.right);
+                    self.instructions().push(Instruction::Dup);
+                    self.generate_pattern_store(&expr.left);
+                    return;
+                }
+            }
+
+            if let Some(name) = simple_target {
+                if let Some(instruction) = compound {
+                    self.visit_node(&expr.left);
+                    self.visit_node(&expr.right);
+                    self.instructions().push(instruction);
+                } else {
+                    self.visit_node(&expr.right);
+                }
+
+                // The assignment itself evaluates to the stored value.
+                let binding = match self.resolve(name) {
+                    Some(binding) => binding,
+                    None => Binding::Local(self.get_or_create_local(name)),
+                };
+                self.instructions().push(Instruction::Dup);
+                self.instructions().push(binding.store());
+            } else {
+                self.visit_node(&expr.right);
+                self.visit_node(&expr.left);
+                self.instructions()
+                    .push(Instruction::StoreLocal(LocalIndex::new(0)));
+            }
         }
     }
 
@@ -53,34 +98,112 @@ where
         }
     }
 
+    // A method call passes the object it was read from as `this`; any other
+    // call passes undefined.
     fn generate_call_expression(&mut self, node: &Node) {
         if let Node::CallExpression(expr) = node {
+            if let Node::MemberExpression(member) = &*expr.callee {
+                self.visit_node(&member.object);
+                self.instructions().push(Instruction::Dup);
+                generate_member_key(self, member);
+                self.instructions().push(Instruction::GetProperty);
+            } else {
+                self.instructions().push(Instruction::PushUndefined);
+                self.visit_node(&expr.callee);
+            }
             for arg in &expr.arguments {
                 self.visit_node(arg);
             }
-            self.visit_node(&expr.callee);
             self.instructions()
-                .push(Instruction::Call(crate::vm::types::FunctionIndex::new(
-                    expr.arguments.len(),
-                )));
+                .push(Instruction::Call(FunctionIndex::new(expr.arguments.len())));
         }
     }
 
     fn generate_new_expression(&mut self, node: &Node) {
         if let Node::NewExpression(expr) = node {
+            self.visit_node(&expr.callee);
             for arg in &expr.arguments {
                 self.visit_node(arg);
             }
-            self.visit_node(&expr.callee);
-            self.instructions().push(Instruction::New);
+            self.instructions()
+                .push(Instruction::New(ArgIndex::new(expr.arguments.len())));
         }
     }
 
     fn generate_member_expression(&mut self, node: &Node) {
         if let Node::MemberExpression(expr) = node {
             self.visit_node(&expr.object);
-            self.visit_node(&expr.property);
+            generate_member_key(self, expr);
             self.instructions().push(Instruction::GetProperty);
         }
     }
 }
+
+// `o.name` reads the property called `name`, not the variable.
+fn generate_member_key<T>(generator: &mut T, member: &MemberExpression)
+where
+    T: AssignmentCore + ConstantManager,
+{
+    match &*member.property {
+        Node::Identifier(id) if !member.computed => {
+            let constant = generator.add_constant(id.name.clone());
+            generator
+                .instructions()
+                .push(Instruction::PushConst(constant));
+        }
+        property => generator.visit_node(property),
+    }
+}
+
+// `SetProperty` leaves nothing behind, so the stored value is kept in a
+// hidden local to be the result of the assignment. A compound assignment
+// also keeps the object and key, to read the old value.
+fn generate_member_assignment<T>(
+    generator: &mut T,
+    member: &MemberExpression,
+    right: &Node,
+    compound: Option<Instruction>,
+) where
+    T: AssignmentCore + ScopeManager + ConstantManager,
+{
+    let value = generator.get_or_create_local("%value");
+    generator.visit_node(&member.object);
+    generate_member_key(generator, member);
+
+    if let Some(instruction) = compound {
+        let object = generator.get_or_create_local("%object");
+        let key = generator.get_or_create_local("%key");
+        generator.instructions().extend([
+            Instruction::StoreLocal(key),
+            Instruction::StoreLocal(object),
+            Instruction::LoadLocal(object),
+            Instruction::LoadLocal(key),
+            Instruction::LoadLocal(object),
+            Instruction::LoadLocal(key),
+            Instruction::GetProperty,
+        ]);
+        generator.visit_node(right);
+        generator.instructions().push(instruction);
+    } else {
+        generator.visit_node(right);
+    }
+
+    generator.instructions().extend([
+        Instruction::Dup,
+        Instruction::StoreLocal(value),
+        Instruction::SetProperty,
+        Instruction::LoadLocal(value),
+    ]);
+}
+
+fn compound_assignment_instruction(operator: &str) -> Option<Instruction> {
+    match operator {
+        "+=" => Some(Instruction::Add),
+        "-=" => Some(Instruction::Sub),
+        "*=" => Some(Instruction::Mul),
+        "/=" => Some(Instruction::Div),
+        "%=" => Some(Instruction::Mod),
+        "**=" => Some(Instruction::Exp),
+        _ => None,
+    }
+}
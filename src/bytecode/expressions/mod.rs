@@ -2,10 +2,12 @@ pub mod arithmetic;
 pub mod assignment;
 pub mod comparison;
 pub mod logical;
+pub mod pattern;
 pub mod unary;
 
 pub use arithmetic::*;
 pub use assignment::*;
 pub use comparison::*;
 pub use logical::*;
+pub use pattern::*;
 pub use unary::*;
@@ -0,0 +1,142 @@
+use crate::ast::Node;
+use crate::bytecode::error::BytecodeError;
+use crate::bytecode::scope::{ConstantManager, ScopeManager};
+use crate::vm::instructions::Instruction;
+use crate::vm::types::CodeAddress;
+
+pub trait PatternGenerator {
+    // Stores the value on top of the stack into `target`: a name, a member
+    // or an array or object pattern, written like the literal it resembles.
+    fn generate_pattern_store(&mut self, target: &Node);
+}
+
+pub trait PatternCore {
+    fn instructions(&mut self) -> &mut Vec<Instruction>;
+    fn visit_node(&mut self, node: &Node);
+    fn report_error(&mut self, error: BytecodeError);
+}
+
+impl<T> PatternGenerator for T
+where
+    T: PatternCore + ScopeManager + ConstantManager,
+{
+    fn generate_pattern_store(&mut self, target: &Node) {
+        store(self, target, 0);
+    }
+}
+
+// Patterns keep the value they take apart in a hidden local, one per
+// nesting level.
+fn store<T>(generator: &mut T, target: &Node, depth: usize)
+where
+    T: PatternCore + ScopeManager + ConstantManager,
+{
+    match target {
+        Node::Identifier(id) => {
+            let local = generator.get_or_create_local(&id.name);
+            generator
+                .instructions()
+                .push(Instruction::StoreLocal(local));
+        }
+        Node::MemberExpression(member) => {
+            let value = generator.get_or_create_local(&format!("%pattern{depth}"));
+            generator
+                .instructions()
+                .push(Instruction::StoreLocal(value));
+            generator.visit_node(&member.object);
+            match &*member.property {
+                Node::Identifier(id) if !member.computed => push_key(generator, &id.name),
+                property => generator.visit_node(property),
+            }
+            generator.instructions().push(Instruction::LoadLocal(value));
+            generator.instructions().push(Instruction::SetProperty);
+        }
+        Node::ArrayLiteral(array) => {
+            let value = generator.get_or_create_local(&format!("%pattern{depth}"));
+            generator
+                .instructions()
+                .push(Instruction::StoreLocal(value));
+            for (index, element) in array.elements.iter().enumerate() {
+                let Some(element) = element else {
+                    continue;
+                };
+                if let Node::SpreadElement(_) | Node::RestElement(_) = element {
+                    unsupported(generator, "RestElement", element, "not supported yet");
+                    continue;
+                }
+                generator.instructions().push(Instruction::LoadLocal(value));
+                push_key(generator, &index.to_string());
+                generator.instructions().push(Instruction::GetProperty);
+                store(generator, element, depth + 1);
+            }
+        }
+        Node::ObjectLiteral(object) => {
+            let value = generator.get_or_create_local(&format!("%pattern{depth}"));
+            generator
+                .instructions()
+                .push(Instruction::StoreLocal(value));
+            for property in &object.properties {
+                let Node::Property(property) = property else {
+                    unsupported(generator, "RestElement", property, "not supported yet");
+                    continue;
+                };
+                generator.instructions().push(Instruction::LoadLocal(value));
+                match &*property.key {
+                    Node::Identifier(id) if !property.computed => push_key(generator, &id.name),
+                    key => generator.visit_node(key),
+                }
+                generator.instructions().push(Instruction::GetProperty);
+                store(generator, &property.value, depth + 1);
+            }
+        }
+        // `target = default` uses the default when the value is undefined.
+        Node::AssignmentExpression(assign) if assign.operator == "=" => {
+            generator.instructions().extend([
+                Instruction::Dup,
+                Instruction::PushUndefined,
+                Instruction::Eq,
+            ]);
+            let skip_default = generator.instructions().len();
+            generator
+                .instructions()
+                .push(Instruction::JumpIfFalse(CodeAddress::new(0)));
+            generator.instructions().push(Instruction::Pop);
+            generator.visit_node(&assign.right);
+            let end = generator.instructions().len();
+            generator.instructions()[skip_default] =
+                Instruction::JumpIfFalse(CodeAddress::new(end));
+            store(generator, &assign.left, depth);
+        }
+        other => {
+            unsupported(
+                generator,
+                "AssignmentTarget",
+                other,
+                "invalid assignment target",
+            );
+            generator.instructions().push(Instruction::Pop);
+        }
+    }
+}
+
+fn push_key<T>(generator: &mut T, key: &str)
+where
+    T: PatternCore + ConstantManager,
+{
+    let constant = generator.add_constant(key.to_string());
+    generator
+        .instructions()
+        .push(Instruction::PushConst(constant));
+}
+
+// Reports a target that cannot be stored to; compiling fails with it.
+fn unsupported<T>(generator: &mut T, node_type: &str, target: &Node, message: &str)
+where
+    T: PatternCore,
+{
+    generator.report_error(BytecodeError::UnsupportedNode {
+        node_type: node_type.to_string(),
+        message: message.to_string(),
+        position: Some(target.span().start),
+    });
+}
@@ -1,7 +1,10 @@
 use crate::ast::node::Node;
+#[cfg(feature = "jsx")]
+use crate::ast::{lower_jsx, JsxOptions};
+use crate::bytecode::error::BytecodeError;
 use crate::bytecode::expressions::{
     ArithmeticCore, ArithmeticGenerator, AssignmentCore, AssignmentGenerator, ComparisonCore,
-    LogicalCore, LogicalGenerator, UnaryCore, UnaryGenerator,
+    LogicalCore, LogicalGenerator, PatternCore, UnaryCore, UnaryGenerator,
 };
 use crate::bytecode::literals::{
     ArrayCore, ArrayGenerator, FunctionLiteralCore, ObjectCore, ObjectGenerator,
@@ -9,10 +12,10 @@ use crate::bytecode::literals::{
 use crate::bytecode::scope::{ConstantCore, ConstantManager, ScopeCore, ScopeManager};
 use crate::bytecode::statements::{
     ClassCore, ClassGenerator, ControlFlowCore, ControlFlowGenerator, FunctionCore,
-    FunctionGenerator, VariableCore, VariableGenerator,
+    FunctionGenerator, LoopContext, PrivateScope, VariableCore, VariableGenerator,
 };
 use crate::vm::instructions::Instruction;
-use crate::vm::types::{CodeAddress, ConstantIndex, LocalIndex};
+use crate::vm::types::{ArgIndex, CodeAddress, ConstantIndex, LocalIndex};
 use std::collections::HashMap;
 
 pub struct BytecodeGenerator {
@@ -20,7 +23,11 @@ pub struct BytecodeGenerator {
     constant_map: HashMap<String, ConstantIndex>,
     instructions: Vec<Instruction>,
     local_vars: HashMap<String, LocalIndex>,
+    enclosing_scopes: Vec<HashMap<String, LocalIndex>>,
     next_local: usize,
+    loops: Vec<LoopContext>,
+    private_scopes: Vec<PrivateScope>,
+    errors: Vec<BytecodeError>,
 }
 
 impl BytecodeGenerator {
@@ -30,7 +37,11 @@ impl BytecodeGenerator {
             constant_map: HashMap::new(),
             instructions: Vec::new(),
             local_vars: HashMap::new(),
+            enclosing_scopes: Vec::new(),
             next_local: 0,
+            loops: Vec::new(),
+            private_scopes: Vec::new(),
+            errors: Vec::new(),
         }
     }
 }
@@ -51,6 +62,45 @@ impl BytecodeGenerator {
         <Self as ConstantManager>::get_constants(self)
     }
 
+    // Code that could not be compiled; the instructions are not usable when
+    // there is any.
+    pub fn errors(&self) -> &[BytecodeError] {
+        &self.errors
+    }
+
+    // Compiles a function body on its own, with locals of its own, for
+    // `MakeFunction`. The locals of the bodies around it stay reachable
+    // through `LoadOuter` and `StoreOuter`. Parameters are copied into locals
+    // up front; the body returns undefined unless it returns earlier.
+    fn compile_function_body(
+        &mut self,
+        params: &[Node],
+        emit_body: impl FnOnce(&mut Self),
+    ) -> Vec<Instruction> {
+        let instructions = std::mem::take(&mut self.instructions);
+        let local_vars = std::mem::take(&mut self.local_vars);
+        self.enclosing_scopes.push(local_vars);
+        let next_local = std::mem::replace(&mut self.next_local, 0);
+        let loops = std::mem::take(&mut self.loops);
+
+        for (index, param) in params.iter().enumerate() {
+            if let Node::Identifier(id) = param {
+                let local = <Self as ScopeManager>::get_or_create_local(self, &id.name);
+                self.instructions
+                    .push(Instruction::LoadArg(ArgIndex::new(index)));
+                self.instructions.push(Instruction::StoreLocal(local));
+            }
+        }
+        emit_body(self);
+        self.instructions.push(Instruction::PushUndefined);
+        self.instructions.push(Instruction::Return);
+
+        self.local_vars = self.enclosing_scopes.pop().unwrap_or_default();
+        self.next_local = next_local;
+        self.loops = loops;
+        std::mem::replace(&mut self.instructions, instructions)
+    }
+
     fn visit_node(&mut self, node: &Node) {
         match node {
             Node::Program(program) => {
@@ -67,7 +117,21 @@ impl BytecodeGenerator {
             Node::ClassDeclaration(_decl) => {
                 <Self as ClassGenerator>::generate_class_declaration(self, node);
             }
-            Node::ImportDeclaration(_) | Node::ExportDeclaration(_) => {}
+            Node::ClassBody(body) => {
+                for element in &body.body {
+                    <Self as ClassGenerator>::generate_class_element(self, element);
+                }
+            }
+            Node::MethodDefinition(_) | Node::PropertyDefinition(_) | Node::StaticBlock(_) => {
+                <Self as ClassGenerator>::generate_class_element(self, node);
+            }
+            Node::ImportDeclaration(_)
+            | Node::ExportDeclaration(_)
+            | Node::ImportSpecifier(_)
+            | Node::ImportDefaultSpecifier(_)
+            | Node::ImportNamespaceSpecifier(_)
+            | Node::ExportSpecifier(_)
+            | Node::ExportNamespaceSpecifier(_) => {}
             Node::ClassExpression(_expr) => {
                 <Self as ClassGenerator>::generate_class_expression(self, node);
             }
@@ -120,18 +184,40 @@ impl BytecodeGenerator {
             Node::ContinueStatement(_) => {
                 <Self as ControlFlowGenerator>::generate_continue_statement(self, node);
             }
-            Node::LabeledStatement(stmt) => {
-                self.visit_node(&stmt.label);
-                self.visit_node(&stmt.body);
+            Node::LabeledStatement(_) => {
+                <Self as ControlFlowGenerator>::generate_labeled_statement(self, node);
             }
             Node::WithStatement(stmt) => {
                 self.visit_node(&stmt.object);
                 self.visit_node(&stmt.body);
             }
-            Node::DebuggerStatement(_) => {}
+            Node::DebuggerStatement(_) | Node::EmptyStatement(_) | Node::Error(_) => {}
+            #[cfg(feature = "jsx")]
+            Node::JsxElement(_) | Node::JsxFragment(_) => {
+                let lowered = lower_jsx(node, &JsxOptions::default());
+                self.visit_node(&lowered);
+            }
+            #[cfg(feature = "jsx")]
+            Node::JsxAttribute(_)
+            | Node::JsxSpreadAttribute(_)
+            | Node::JsxExpressionContainer(_)
+            | Node::JsxText(_)
+            | Node::JsxNamespacedName(_) => {}
             Node::TemplateLiteral(lit) => {
-                for expr in &lit.expressions {
-                    self.visit_node(expr);
+                // Start from the first quasi, which is always a string, so
+                // each `Add` concatenates rather than adding numbers.
+                for (index, quasi) in lit.quasis.iter().enumerate() {
+                    let cooked = quasi.cooked.clone().unwrap_or_default();
+                    let constant_id = <Self as ConstantManager>::add_constant(self, cooked);
+                    self.instructions.push(Instruction::PushConst(constant_id));
+                    if index > 0 {
+                        self.instructions.push(Instruction::Add);
+                    }
+
+                    if let Some(expr) = lit.expressions.get(index) {
+                        self.visit_node(expr);
+                        self.instructions.push(Instruction::Add);
+                    }
                 }
             }
             Node::TaggedTemplateExpression(expr) => {
@@ -148,6 +234,18 @@ impl BytecodeGenerator {
 
                 self.instructions.push(Instruction::LoadThisFunction);
             }
+            Node::ImportExpression(expr) => {
+                // There is no module loader, so the import fails once its
+                // specifier is evaluated.
+                self.visit_node(&expr.source);
+                self.instructions.push(Instruction::Pop);
+                let message = <Self as ConstantManager>::add_constant(
+                    self,
+                    "Dynamic import() is not supported".to_string(),
+                );
+                self.instructions.push(Instruction::PushConst(message));
+                self.instructions.push(Instruction::Throw);
+            }
             Node::SpreadElement(elem) => {
                 self.visit_node(&elem.argument);
                 self.instructions.push(Instruction::Spread);
@@ -157,7 +255,7 @@ impl BytecodeGenerator {
                 self.instructions.push(Instruction::PushConst(constant_id));
             }
             Node::BigInt(val) => {
-                let constant_id = <Self as ConstantManager>::add_constant(self, val.clone());
+                let constant_id = <Self as ConstantManager>::add_constant(self, val.value.clone());
                 self.instructions.push(Instruction::PushBigInt(constant_id));
             }
             Node::BinaryExpression(_expr) => {
@@ -213,6 +311,12 @@ impl BytecodeGenerator {
             Node::ForStatement(_stmt) => {
                 <Self as ControlFlowGenerator>::generate_for_statement(self, node);
             }
+            Node::ForInStatement(_stmt) => {
+                <Self as ControlFlowGenerator>::generate_for_in_statement(self, node);
+            }
+            Node::ForOfStatement(_stmt) => {
+                <Self as ControlFlowGenerator>::generate_for_of_statement(self, node);
+            }
             Node::WhileStatement(_stmt) => {
                 <Self as ControlFlowGenerator>::generate_while_statement(self, node);
             }
@@ -235,36 +339,60 @@ impl BytecodeGenerator {
             Node::RestElement(elem) => {
                 self.visit_node(&elem.argument);
             }
-            Node::Identifier(name) => {
-                if let Some(&local_idx) = <Self as ScopeManager>::get_local(self, name) {
-                    self.instructions.push(Instruction::LoadLocal(local_idx));
+            Node::Identifier(id) => {
+                if let Some(binding) = <Self as ScopeManager>::resolve(self, &id.name) {
+                    self.instructions.push(binding.load());
                 } else {
-                    let constant_id = <Self as ConstantManager>::add_constant(self, name.clone());
+                    let constant_id =
+                        <Self as ConstantManager>::add_constant(self, id.name.clone());
                     self.instructions.push(Instruction::PushConst(constant_id));
                 }
             }
+            // The name belongs to the innermost class body declaring it.
+            Node::PrivateIdentifier(id) => {
+                let class = self
+                    .private_scopes
+                    .iter()
+                    .rev()
+                    .find(|scope| scope.names.contains(&id.name))
+                    .and_then(|scope| <Self as ScopeManager>::resolve(self, &scope.class));
+                match class {
+                    Some(class) => {
+                        self.instructions.push(class.load());
+                        let constant_id =
+                            <Self as ConstantManager>::add_constant(self, id.name.clone());
+                        self.instructions
+                            .push(Instruction::PushPrivateName(constant_id));
+                    }
+                    None => self.errors.push(BytecodeError::GenerationError {
+                        message: format!("Private name #{} is not declared", id.name),
+                        position: Some(id.span.start),
+                    }),
+                }
+            }
             Node::Number(n) => {
-                let constant_id = <Self as ConstantManager>::add_constant(self, n.to_string());
+                let constant_id =
+                    <Self as ConstantManager>::add_constant(self, n.value.to_string());
                 self.instructions.push(Instruction::PushConst(constant_id));
             }
             Node::String(s) => {
-                let constant_id = <Self as ConstantManager>::add_constant(self, s.clone());
+                let constant_id = <Self as ConstantManager>::add_constant(self, s.value.clone());
                 self.instructions.push(Instruction::PushConst(constant_id));
             }
             Node::Boolean(b) => {
-                if *b {
+                if b.value {
                     self.instructions.push(Instruction::PushTrue);
                 } else {
                     self.instructions.push(Instruction::PushFalse);
                 }
             }
-            Node::Null => {
+            Node::Null(_) => {
                 self.instructions.push(Instruction::PushNull);
             }
-            Node::Undefined => {
+            Node::Undefined(_) => {
                 self.instructions.push(Instruction::PushUndefined);
             }
-            Node::This => {
+            Node::This(_) => {
                 self.instructions.push(Instruction::LoadThis);
             }
         }
@@ -305,6 +433,10 @@ impl ScopeCore for BytecodeGenerator {
     fn set_next_local(&mut self, next: usize) {
         self.next_local = next;
     }
+
+    fn enclosing_scopes(&self) -> &[HashMap<String, LocalIndex>] {
+        &self.enclosing_scopes
+    }
 }
 
 impl VariableCore for BytecodeGenerator {
@@ -335,6 +467,25 @@ impl ClassCore for BytecodeGenerator {
     fn visit_node(&mut self, node: &Node) {
         self.visit_node(node)
     }
+
+    fn compile_function(&mut self, params: &[Node], body: &[Node]) -> Vec<Instruction> {
+        self.compile_function_body(params, |generator| {
+            for statement in body {
+                generator.visit_node(statement);
+            }
+        })
+    }
+
+    fn compile_thunk(&mut self, expression: &Node) -> Vec<Instruction> {
+        self.compile_function_body(&[], |generator| {
+            generator.visit_node(expression);
+            generator.instructions.push(Instruction::Return);
+        })
+    }
+
+    fn private_scopes(&mut self) -> &mut Vec<PrivateScope> {
+        &mut self.private_scopes
+    }
 }
 
 impl ControlFlowCore for BytecodeGenerator {
@@ -345,6 +496,24 @@ impl ControlFlowCore for BytecodeGenerator {
     fn visit_node(&mut self, node: &Node) {
         self.visit_node(node)
     }
+
+    fn loops(&mut self) -> &mut Vec<LoopContext> {
+        &mut self.loops
+    }
+}
+
+impl PatternCore for BytecodeGenerator {
+    fn instructions(&mut self) -> &mut Vec<Instruction> {
+        &mut self.instructions
+    }
+
+    fn visit_node(&mut self, node: &Node) {
+        self.visit_node(node)
+    }
+
+    fn report_error(&mut self, error: BytecodeError) {
+        self.errors.push(error);
+    }
 }
 
 impl ArithmeticCore for BytecodeGenerator {
@@ -17,8 +17,11 @@ where
 {
     fn generate_array_literal(&mut self, node: &Node) {
         if let Node::ArrayLiteral(lit) = node {
-            for e in lit.elements.iter().flatten() {
-                self.visit_node(e);
+            for element in &lit.elements {
+                match element {
+                    Some(element) => self.visit_node(element),
+                    None => self.instructions().push(Instruction::PushUndefined),
+                }
             }
             self.instructions()
                 .push(Instruction::NewArray(ArraySize::new(lit.elements.len())));
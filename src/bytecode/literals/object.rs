@@ -1,4 +1,5 @@
 use crate::ast::Node;
+use crate::bytecode::scope::ConstantManager;
 use crate::vm::instructions::Instruction;
 
 pub trait ObjectGenerator {
@@ -13,14 +14,22 @@ pub trait ObjectCore {
 
 impl<T> ObjectGenerator for T
 where
-    T: ObjectCore,
+    T: ObjectCore + ConstantManager,
 {
     fn generate_object_literal(&mut self, node: &Node) {
         if let Node::ObjectLiteral(lit) = node {
             self.instructions().push(Instruction::NewObject);
             for prop in &lit.properties {
                 if let Node::Property(property) = prop {
-                    self.visit_node(&property.key);
+                    self.instructions().push(Instruction::Dup);
+                    // `{ a: 1 }` names the key `a` rather than reading `a`.
+                    match &*property.key {
+                        Node::Identifier(id) if !property.computed => {
+                            let constant = self.add_constant(id.name.clone());
+                            self.instructions().push(Instruction::PushConst(constant));
+                        }
+                        key => self.visit_node(key),
+                    }
                     self.visit_node(&property.value);
                     self.instructions().push(Instruction::SetProperty);
                 }
@@ -1,9 +1,38 @@
-use crate::vm::types::LocalIndex;
+use crate::vm::instructions::Instruction;
+use crate::vm::types::{LocalIndex, ScopeDepth};
 use std::collections::HashMap;
 
+// Where a name is bound: in the body being compiled, or in one enclosing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+    Local(LocalIndex),
+    Outer(ScopeDepth, LocalIndex),
+}
+
+impl Binding {
+    pub fn load(self) -> Instruction {
+        match self {
+            Binding::Local(index) => Instruction::LoadLocal(index),
+            Binding::Outer(depth, index) => Instruction::LoadOuter(depth, index),
+        }
+    }
+
+    pub fn store(self) -> Instruction {
+        match self {
+            Binding::Local(index) => Instruction::StoreLocal(index),
+            Binding::Outer(depth, index) => Instruction::StoreOuter(depth, index),
+        }
+    }
+}
+
 pub trait ScopeManager {
     fn get_or_create_local(&mut self, name: &str) -> LocalIndex;
     fn get_local(&self, name: &str) -> Option<&LocalIndex>;
+    fn resolve(&self, name: &str) -> Option<Binding>;
+    // Binds `name` to a new local until `restore_local` puts back what it
+    // was bound to before.
+    fn shadow_local(&mut self, name: &str) -> (LocalIndex, Option<LocalIndex>);
+    fn restore_local(&mut self, name: &str, previous: Option<LocalIndex>);
 }
 
 pub trait ScopeCore {
@@ -11,6 +40,9 @@ pub trait ScopeCore {
     fn local_vars_mut(&mut self) -> &mut HashMap<String, LocalIndex>;
     fn next_local(&self) -> usize;
     fn set_next_local(&mut self, next: usize);
+    // The locals of the bodies being compiled around this one, innermost
+    // last.
+    fn enclosing_scopes(&self) -> &[HashMap<String, LocalIndex>];
 }
 
 impl<T> ScopeManager for T
@@ -31,4 +63,32 @@ where
     fn get_local(&self, name: &str) -> Option<&LocalIndex> {
         self.local_vars().get(name)
     }
+
+    fn resolve(&self, name: &str) -> Option<Binding> {
+        if let Some(&index) = self.get_local(name) {
+            return Some(Binding::Local(index));
+        }
+        self.enclosing_scopes()
+            .iter()
+            .rev()
+            .enumerate()
+            .find_map(|(distance, scope)| {
+                let index = *scope.get(name)?;
+                Some(Binding::Outer(ScopeDepth::new(distance + 1), index))
+            })
+    }
+
+    fn shadow_local(&mut self, name: &str) -> (LocalIndex, Option<LocalIndex>) {
+        let idx = LocalIndex::new(self.next_local());
+        self.set_next_local(self.next_local() + 1);
+        let previous = self.local_vars_mut().insert(name.to_string(), idx);
+        (idx, previous)
+    }
+
+    fn restore_local(&mut self, name: &str, previous: Option<LocalIndex>) {
+        match previous {
+            Some(previous) => self.local_vars_mut().insert(name.to_string(), previous),
+            None => self.local_vars_mut().remove(name),
+        };
+    }
 }
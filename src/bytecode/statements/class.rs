@@ -1,43 +1,227 @@
-use crate::ast::Node;
+use crate::ast::{ClassBody, MethodKind, Node};
+use crate::bytecode::scope::{ConstantManager, ScopeManager};
 use crate::vm::instructions::Instruction;
+use crate::vm::types::{ArgIndex, FunctionIndex, LocalIndex};
+use std::collections::HashSet;
 
 pub trait ClassGenerator {
     fn generate_class_declaration(&mut self, node: &Node);
     fn generate_class_expression(&mut self, node: &Node);
+    fn generate_class_element(&mut self, node: &Node);
 }
 
 pub trait ClassCore {
     fn instructions(&mut self) -> &mut Vec<Instruction>;
     fn visit_node(&mut self, node: &Node);
+    // Compile a body separately, for `MakeFunction`.
+    fn compile_function(&mut self, params: &[Node], body: &[Node]) -> Vec<Instruction>;
+    fn compile_thunk(&mut self, expression: &Node) -> Vec<Instruction>;
+    // The class bodies being compiled, innermost last.
+    fn private_scopes(&mut self) -> &mut Vec<PrivateScope>;
+}
+
+// The private names a class body declares, and the hidden local holding the
+// class, which `PushPrivateName` makes each `#name` of the body's own.
+pub struct PrivateScope {
+    pub names: HashSet<String>,
+    pub class: String,
 }
 
 impl<T> ClassGenerator for T
 where
-    T: ClassCore,
+    T: ClassCore + ConstantManager + ScopeManager,
 {
     fn generate_class_declaration(&mut self, node: &Node) {
         if let Node::ClassDeclaration(decl) = node {
-            if let Some(id) = &decl.id {
-                self.visit_node(id);
-            }
-            if let Some(super_class) = &decl.super_class {
-                self.visit_node(super_class);
-            }
-            self.visit_node(&decl.body);
-            self.instructions().push(Instruction::NewClass);
+            let binding = match decl.id.as_deref() {
+                Some(Node::Identifier(id)) => Some(self.get_or_create_local(&id.name)),
+                _ => None,
+            };
+            generate_class(self, binding, decl.super_class.as_deref(), &decl.body);
+            self.instructions().push(Instruction::Pop);
         }
     }
 
+    // A class expression's name is bound only inside its own body.
     fn generate_class_expression(&mut self, node: &Node) {
         if let Node::ClassExpression(expr) = node {
-            if let Some(id) = &expr.id {
-                self.visit_node(id);
+            match expr.id.as_deref() {
+                Some(Node::Identifier(id)) => {
+                    let (binding, previous) = self.shadow_local(&id.name);
+                    generate_class(self, Some(binding), expr.super_class.as_deref(), &expr.body);
+                    self.restore_local(&id.name, previous);
+                }
+                _ => generate_class(self, None, expr.super_class.as_deref(), &expr.body),
+            }
+        }
+    }
+
+    // Each element starts and ends with the class on top of the stack.
+    // Prototype members are defined on `GetPrototype` of a copy of it. The
+    // constructor is passed to `NewClass` instead.
+    fn generate_class_element(&mut self, node: &Node) {
+        match node {
+            Node::MethodDefinition(method) if method.kind != MethodKind::Constructor => {
+                self.instructions().push(Instruction::Dup);
+                if !method.r#static {
+                    self.instructions().push(Instruction::GetPrototype);
+                }
+                generate_key(self, &method.key, method.computed);
+                generate_method(self, &method.value);
+                self.instructions().push(match method.kind {
+                    MethodKind::Get => Instruction::DefineGetter,
+                    MethodKind::Set => Instruction::DefineSetter,
+                    MethodKind::Constructor | MethodKind::Method => Instruction::SetProperty,
+                });
             }
-            if let Some(super_class) = &expr.super_class {
-                self.visit_node(super_class);
+            // Static fields are evaluated once, here; instance fields get an
+            // initializer run for every instance.
+            Node::PropertyDefinition(prop) => {
+                self.instructions().push(Instruction::Dup);
+                generate_key(self, &prop.key, prop.computed);
+                match &prop.value {
+                    Some(value) if prop.r#static => self.visit_node(value),
+                    Some(value) => {
+                        let body = self.compile_thunk(value);
+                        self.instructions()
+                            .push(Instruction::MakeFunction(body, ArgIndex::new(0)));
+                    }
+                    None => self.instructions().push(Instruction::PushUndefined),
+                }
+                self.instructions().push(if prop.r#static {
+                    Instruction::SetProperty
+                } else {
+                    Instruction::DefineField
+                });
             }
-            self.visit_node(&expr.body);
-            self.instructions().push(Instruction::NewClass);
+            // Static blocks run as methods of the class, so `this` is the
+            // class and their declarations stay local.
+            Node::StaticBlock(block) => {
+                let body = self.compile_function(&[], &block.body);
+                self.instructions().push(Instruction::Dup);
+                self.instructions()
+                    .push(Instruction::MakeFunction(body, ArgIndex::new(0)));
+                self.instructions()
+                    .push(Instruction::Call(FunctionIndex::new(0)));
+                self.instructions().push(Instruction::Pop);
+            }
+            _ => {}
+        }
+    }
+}
+
+// Leaves the new class on the stack. The class is stored in `binding`
+// before any element is evaluated, so methods, static blocks and field
+// initializers can refer to it by name.
+fn generate_class<T>(
+    generator: &mut T,
+    binding: Option<LocalIndex>,
+    super_class: Option<&Node>,
+    body: &Node,
+) where
+    T: ClassGenerator + ClassCore + ScopeManager,
+{
+    let elements = match body {
+        Node::ClassBody(ClassBody { body, .. }) => body.as_slice(),
+        _ => &[],
+    };
+
+    match super_class {
+        Some(super_class) => generator.visit_node(super_class),
+        None => generator.instructions().push(Instruction::PushUndefined),
+    }
+
+    let names: HashSet<String> = elements
+        .iter()
+        .filter_map(|element| match element {
+            Node::MethodDefinition(method) => Some(&*method.key),
+            Node::PropertyDefinition(prop) => Some(&*prop.key),
+            _ => None,
+        })
+        .filter_map(|key| match key {
+            Node::PrivateIdentifier(id) => Some(id.name.clone()),
+            _ => None,
+        })
+        .collect();
+    // Bound before the constructor is compiled, since it may use the names
+    // too.
+    let private = if names.is_empty() {
+        None
+    } else {
+        let class = format!("%class{}", generator.private_scopes().len());
+        let (local, previous) = generator.shadow_local(&class);
+        generator.private_scopes().push(PrivateScope {
+            names,
+            class: class.clone(),
+        });
+        Some((class, local, previous))
+    };
+
+    let constructor = elements.iter().find_map(|element| match element {
+        Node::MethodDefinition(method) if method.kind == MethodKind::Constructor => {
+            Some(&method.value)
         }
+        _ => None,
+    });
+    match constructor {
+        Some(constructor) => generate_method(generator, constructor),
+        None => generator.instructions().push(Instruction::PushUndefined),
+    }
+    generator.instructions().push(Instruction::NewClass);
+    let private_local = private.as_ref().map(|(_, local, _)| *local);
+    for local in binding.into_iter().chain(private_local) {
+        generator.instructions().push(Instruction::Dup);
+        generator
+            .instructions()
+            .push(Instruction::StoreLocal(local));
+    }
+
+    for element in elements {
+        generator.generate_class_element(element);
+    }
+
+    if let Some((class, _, previous)) = private {
+        generator.private_scopes().pop();
+        generator.restore_local(&class, previous);
+    }
+}
+
+// Pushes a method's function.
+fn generate_method<T>(generator: &mut T, value: &Node)
+where
+    T: ClassCore,
+{
+    if let Node::FunctionExpression(function) = value {
+        let body = match &*function.body {
+            Node::BlockStatement(block) => {
+                generator.compile_function(&function.params, &block.body)
+            }
+            body => generator.compile_function(&function.params, std::slice::from_ref(body)),
+        };
+        generator.instructions().push(Instruction::MakeFunction(
+            body,
+            ArgIndex::new(function.params.len()),
+        ));
+    } else {
+        generator.visit_node(value);
     }
 }
+
+// Non-computed keys are names, not variable lookups. Private names push
+// their `PrivateName`.
+fn generate_key<T>(generator: &mut T, key: &Node, computed: bool)
+where
+    T: ClassCore + ConstantManager,
+{
+    let name = match key {
+        Node::Identifier(id) if !computed => id.name.clone(),
+        _ => {
+            generator.visit_node(key);
+            return;
+        }
+    };
+    let constant = generator.add_constant(name);
+    generator
+        .instructions()
+        .push(Instruction::PushConst(constant));
+}
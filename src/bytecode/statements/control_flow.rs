@@ -1,14 +1,19 @@
 use crate::ast::Node;
+use crate::bytecode::expressions::PatternGenerator;
+use crate::bytecode::scope::ScopeManager;
 use crate::vm::instructions::Instruction;
 use crate::vm::types::CodeAddress;
 
 pub trait ControlFlowGenerator {
     fn generate_if_statement(&mut self, node: &Node);
     fn generate_for_statement(&mut self, node: &Node);
+    fn generate_for_in_statement(&mut self, node: &Node);
+    fn generate_for_of_statement(&mut self, node: &Node);
     fn generate_while_statement(&mut self, node: &Node);
     fn generate_do_while_statement(&mut self, node: &Node);
     fn generate_break_statement(&mut self, node: &Node);
     fn generate_continue_statement(&mut self, node: &Node);
+    fn generate_labeled_statement(&mut self, node: &Node);
     fn generate_return_statement(&mut self, node: &Node);
     fn generate_throw_statement(&mut self, node: &Node);
 }
@@ -16,59 +21,117 @@ pub trait ControlFlowGenerator {
 pub trait ControlFlowCore {
     fn instructions(&mut self) -> &mut Vec<Instruction>;
     fn visit_node(&mut self, node: &Node);
+    fn loops(&mut self) -> &mut Vec<LoopContext>;
+}
+
+// A statement `break` and `continue` may jump out of, with the jumps to
+// patch once its end and its next iteration are known.
+#[derive(Debug, Default)]
+pub struct LoopContext {
+    labels: Vec<String>,
+    // Only loops can be continued; a labeled block can only be left.
+    is_loop: bool,
+    // for-in and for-of loops keep an iterator active while they run.
+    is_iteration: bool,
+    breaks: Vec<usize>,
+    continues: Vec<usize>,
 }
 
 impl<T> ControlFlowGenerator for T
 where
-    T: ControlFlowCore,
+    T: ControlFlowCore + ScopeManager + PatternGenerator,
 {
     fn generate_if_statement(&mut self, node: &Node) {
         if let Node::IfStatement(stmt) = node {
             self.visit_node(&stmt.test);
+            let jump_to_alternate = self.instructions().len();
+            self.instructions()
+                .push(Instruction::JumpIfFalse(CodeAddress::new(0)));
             self.visit_node(&stmt.consequent);
+
             if let Some(alt) = &stmt.alternate {
+                let jump_to_end = self.instructions().len();
+                self.instructions()
+                    .push(Instruction::Jump(CodeAddress::new(0)));
+                patch_jump(self, jump_to_alternate);
                 self.visit_node(alt);
+                patch_jump(self, jump_to_end);
+            } else {
+                patch_jump(self, jump_to_alternate);
             }
         }
     }
 
     fn generate_for_statement(&mut self, node: &Node) {
-        if let Node::ForStatement(stmt) = node {
-            if let Some(init) = &stmt.init {
-                self.visit_node(init);
-            }
-            if let Some(test) = &stmt.test {
-                self.visit_node(test);
-            }
-            if let Some(update) = &stmt.update {
-                self.visit_node(update);
-            }
-            self.visit_node(&stmt.body);
-        }
+        generate_loop(self, node, Vec::new());
+    }
+
+    fn generate_for_in_statement(&mut self, node: &Node) {
+        generate_loop(self, node, Vec::new());
+    }
+
+    fn generate_for_of_statement(&mut self, node: &Node) {
+        generate_loop(self, node, Vec::new());
     }
 
     fn generate_while_statement(&mut self, node: &Node) {
-        if let Node::WhileStatement(stmt) = node {
-            self.visit_node(&stmt.test);
-            self.visit_node(&stmt.body);
-        }
+        generate_loop(self, node, Vec::new());
     }
 
     fn generate_do_while_statement(&mut self, node: &Node) {
-        if let Node::DoWhileStatement(stmt) = node {
-            self.visit_node(&stmt.body);
-            self.visit_node(&stmt.test);
+        generate_loop(self, node, Vec::new());
+    }
+
+    fn generate_break_statement(&mut self, node: &Node) {
+        if let Node::BreakStatement(stmt) = node {
+            let label = label_name(stmt.label.as_deref());
+            let target = self.loops().iter().rposition(|context| match label {
+                Some(label) => context.labels.iter().any(|name| name == label),
+                None => context.is_loop,
+            });
+            if let Some(target) = target {
+                let jump = generate_exit_jump(self, target);
+                self.loops()[target].breaks.push(jump);
+            }
         }
     }
 
-    fn generate_break_statement(&mut self, _node: &Node) {
-        self.instructions()
-            .push(Instruction::Jump(CodeAddress::new(0)));
+    fn generate_continue_statement(&mut self, node: &Node) {
+        if let Node::ContinueStatement(stmt) = node {
+            let label = label_name(stmt.label.as_deref());
+            let target = self.loops().iter().rposition(|context| {
+                context.is_loop
+                    && label.is_none_or(|label| context.labels.iter().any(|name| name == label))
+            });
+            if let Some(target) = target {
+                let jump = generate_exit_jump(self, target + 1);
+                self.loops()[target].continues.push(jump);
+            }
+        }
     }
 
-    fn generate_continue_statement(&mut self, _node: &Node) {
-        self.instructions()
-            .push(Instruction::Jump(CodeAddress::new(0)));
+    // Labels on a loop belong to the loop, so `continue label` goes to its
+    // next iteration; any other labeled statement can only be left.
+    fn generate_labeled_statement(&mut self, node: &Node) {
+        let mut labels = Vec::new();
+        let mut body = node;
+        while let Node::LabeledStatement(stmt) = body {
+            labels.extend(label_name(Some(&stmt.label)).map(str::to_string));
+            body = &stmt.body;
+        }
+
+        if is_loop(body) {
+            generate_loop(self, body, labels);
+        } else {
+            self.loops().push(LoopContext {
+                labels,
+                ..LoopContext::default()
+            });
+            self.visit_node(body);
+            let context = self.loops().pop().unwrap_or_default();
+            let end = self.instructions().len();
+            patch_jumps(self, &context.breaks, end);
+        }
     }
 
     fn generate_return_statement(&mut self, node: &Node) {
@@ -87,3 +150,192 @@ where
         }
     }
 }
+
+fn is_loop(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::ForStatement(_)
+            | Node::ForInStatement(_)
+            | Node::ForOfStatement(_)
+            | Node::WhileStatement(_)
+            | Node::DoWhileStatement(_)
+    )
+}
+
+// Closes the iterators of the loops from `innermost_left` in, which the
+// jump leaves, and emits the jump, returning where it is to patch.
+fn generate_exit_jump<T>(generator: &mut T, innermost_left: usize) -> usize
+where
+    T: ControlFlowCore,
+{
+    let left = generator.loops()[innermost_left..]
+        .iter()
+        .filter(|context| context.is_iteration)
+        .count();
+    for _ in 0..left {
+        generator.instructions().push(Instruction::IteratorClose);
+    }
+    let jump = generator.instructions().len();
+    generator
+        .instructions()
+        .push(Instruction::Jump(CodeAddress::new(0)));
+    jump
+}
+
+fn label_name(label: Option<&Node>) -> Option<&str> {
+    match label {
+        Some(Node::Identifier(id)) => Some(&id.name),
+        _ => None,
+    }
+}
+
+// Emits any loop statement. Each loop records where `continue` goes and
+// where it ends, and patches the `break` and `continue` jumps in its body to
+// those once it is done.
+fn generate_loop<T>(generator: &mut T, node: &Node, labels: Vec<String>)
+where
+    T: ControlFlowCore + ScopeManager + PatternGenerator,
+{
+    generator.loops().push(LoopContext {
+        labels,
+        is_loop: true,
+        is_iteration: matches!(node, Node::ForInStatement(_) | Node::ForOfStatement(_)),
+        ..LoopContext::default()
+    });
+
+    let (next, end) = match node {
+        Node::WhileStatement(stmt) => {
+            let loop_start = generator.instructions().len();
+            generator.visit_node(&stmt.test);
+            let exit = generator.instructions().len();
+            generator
+                .instructions()
+                .push(Instruction::JumpIfFalse(CodeAddress::new(0)));
+            generator.visit_node(&stmt.body);
+            generator
+                .instructions()
+                .push(Instruction::Jump(CodeAddress::new(loop_start)));
+            patch_jump(generator, exit);
+            (loop_start, generator.instructions().len())
+        }
+        Node::DoWhileStatement(stmt) => {
+            let loop_start = generator.instructions().len();
+            generator.visit_node(&stmt.body);
+            let next = generator.instructions().len();
+            generator.visit_node(&stmt.test);
+            generator
+                .instructions()
+                .push(Instruction::JumpIfTrue(CodeAddress::new(loop_start)));
+            (next, generator.instructions().len())
+        }
+        Node::ForStatement(stmt) => {
+            if let Some(init) = &stmt.init {
+                generator.visit_node(init);
+            }
+            let loop_start = generator.instructions().len();
+            let exit = stmt.test.as_ref().map(|test| {
+                generator.visit_node(test);
+                let exit = generator.instructions().len();
+                generator
+                    .instructions()
+                    .push(Instruction::JumpIfFalse(CodeAddress::new(0)));
+                exit
+            });
+            generator.visit_node(&stmt.body);
+            let next = generator.instructions().len();
+            if let Some(update) = &stmt.update {
+                generator.visit_node(update);
+                generator.instructions().push(Instruction::Pop);
+            }
+            generator
+                .instructions()
+                .push(Instruction::Jump(CodeAddress::new(loop_start)));
+            if let Some(exit) = exit {
+                patch_jump(generator, exit);
+            }
+            (next, generator.instructions().len())
+        }
+        Node::ForInStatement(stmt) => {
+            generator.visit_node(&stmt.right);
+            generator
+                .instructions()
+                .push(Instruction::GetPropertyIterator);
+            generate_iteration(generator, &stmt.left, &stmt.body, false)
+        }
+        Node::ForOfStatement(stmt) => {
+            generator.visit_node(&stmt.right);
+            generator.instructions().push(Instruction::GetIterator);
+            generate_iteration(generator, &stmt.left, &stmt.body, stmt.r#await)
+        }
+        _ => {
+            let here = generator.instructions().len();
+            (here, here)
+        }
+    };
+
+    let context = generator.loops().pop().unwrap_or_default();
+    patch_jumps(generator, &context.continues, next);
+    patch_jumps(generator, &context.breaks, end);
+}
+
+// Emits the loop over the iterator that `GetIterator` or
+// `GetPropertyIterator` left active, binding each value to `left`. Returns
+// where the next iteration starts and where the loop ends.
+fn generate_iteration<T>(
+    generator: &mut T,
+    left: &Node,
+    body: &Node,
+    is_await: bool,
+) -> (usize, usize)
+where
+    T: ControlFlowCore + ScopeManager + PatternGenerator,
+{
+    let loop_start = generator.instructions().len();
+    generator
+        .instructions()
+        .push(Instruction::IteratorNext(CodeAddress::new(0)));
+    if is_await {
+        generator.instructions().push(Instruction::Await);
+    }
+
+    match left {
+        Node::VariableDeclaration(decl) => match decl.declarations.first() {
+            Some(var) => generator.generate_pattern_store(&var.id),
+            None => generator.instructions().push(Instruction::Pop),
+        },
+        target => generator.generate_pattern_store(target),
+    }
+
+    generator.visit_node(body);
+    generator
+        .instructions()
+        .push(Instruction::Jump(CodeAddress::new(loop_start)));
+
+    let exit = generator.instructions().len();
+    generator.instructions()[loop_start] = Instruction::IteratorNext(CodeAddress::new(exit));
+    (loop_start, exit)
+}
+
+// Points the jump at `at` to the current end of the code.
+fn patch_jump<T>(generator: &mut T, at: usize)
+where
+    T: ControlFlowCore,
+{
+    let end = generator.instructions().len();
+    patch_jumps(generator, &[at], end);
+}
+
+fn patch_jumps<T>(generator: &mut T, jumps: &[usize], target: usize)
+where
+    T: ControlFlowCore,
+{
+    for &jump in jumps {
+        let target = CodeAddress::new(target);
+        match &mut generator.instructions()[jump] {
+            Instruction::Jump(address)
+            | Instruction::JumpIfFalse(address)
+            | Instruction::JumpIfTrue(address) => *address = target,
+            _ => {}
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use crate::ast::Node;
+use crate::ast::{Identifier, Node};
+use crate::bytecode::expressions::PatternGenerator;
 use crate::bytecode::scope::ScopeManager;
 use crate::vm::instructions::Instruction;
 
@@ -13,12 +14,12 @@ pub trait VariableCore {
 
 impl<T> VariableGenerator for T
 where
-    T: VariableCore + ScopeManager,
+    T: VariableCore + ScopeManager + PatternGenerator,
 {
     fn generate_variable_declaration(&mut self, node: &Node) {
         if let Node::VariableDeclaration(decl) = node {
             for var in &decl.declarations {
-                if let Node::Identifier(name) = &*var.id {
+                if let Node::Identifier(Identifier { name, .. }) = &*var.id {
                     if let Some(init) = &var.init {
                         self.visit_node(init);
                         let local_idx = self.get_or_create_local(name);
@@ -28,6 +29,12 @@ where
                         self.instructions().push(Instruction::PushUndefined);
                         self.instructions().push(Instruction::StoreLocal(local_idx));
                     }
+                } else {
+                    match &var.init {
+                        Some(init) => self.visit_node(init),
+                        None => self.instructions().push(Instruction::PushUndefined),
+                    }
+                    self.generate_pattern_store(&var.id);
                 }
             }
         }
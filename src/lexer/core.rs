@@ -1,111 +1,404 @@
-use crate::lexer::scanners::LexerCore;
+use crate::ast::common::Position;
+use crate::lexer::scanners::{is_id_start, regex_allowed_after, LexerCore, TriviaReader};
+#[cfg(feature = "jsx")]
+use crate::lexer::scanners::{JsxMode, JsxReader};
+use crate::lexer::tokens::Keyword;
 use crate::lexer::utils::PositionManager;
-use crate::lexer::{LexerError, Token, TokenKind};
+use crate::lexer::{LexerError, Token, TokenKind, Trivia};
 use crate::vm::types::{ColumnNumber, LineNumber};
 
-#[derive(Debug)]
-pub struct Lexer {
-    source: Vec<char>,
+// Scans tokens on demand straight out of the borrowed source; `pos` is a byte
+// offset and token payloads borrow from `source` wherever possible.
+#[derive(Debug, Clone)]
+pub struct Lexer<'a> {
+    source: &'a str,
     pos: usize,
     line: LineNumber,
     column: ColumnNumber,
+    regex_allowed: bool,
+    // One entry per open `(`, set when it starts the head of an `if`,
+    // `while`, `for` or `with`, after whose `)` a `/` starts a regular
+    // expression rather than a division.
+    paren_heads: Vec<bool>,
+    after_head_keyword: bool,
+    strict_mode: bool,
+    // Module code has no Annex B HTML-like comments.
+    module_goal: bool,
+    // Annex B syntax, on by default as in web browsers.
+    annex_b: bool,
+    // When set, whitespace and comments are attached to tokens as trivia
+    // instead of being skipped or emitted as comment tokens.
+    trivia: bool,
+    // When set, errors come back as `Error` tokens and scanning resumes after
+    // the offending text instead of stopping.
+    recovery: bool,
+    // One entry per open template substitution, counting the `{` braces
+    // opened inside it so the matching `}` resumes the template.
+    template_braces: Vec<usize>,
+    // Line on which the last non-comment token ended, used to flag tokens
+    // that follow a line break for automatic semicolon insertion.
+    last_token_line: LineNumber,
+    seen_token: bool,
+    error_offset: Option<usize>,
+    error_position: Option<Position>,
+    peeked: Option<Result<Token<'a>, LexerError>>,
+    finished: bool,
+    // Set by the parser while it is inside JSX.
+    #[cfg(feature = "jsx")]
+    jsx_mode: Option<JsxMode>,
 }
 
-impl Lexer {
-    pub fn new(source: &str) -> Self {
+impl<'a> Lexer<'a> {
+    pub fn new(source: &'a str) -> Self {
         Self {
-            source: source.chars().collect(),
+            source,
             pos: 0,
             line: LineNumber::new(1),
             column: ColumnNumber::new(1),
+            regex_allowed: true,
+            paren_heads: Vec::new(),
+            after_head_keyword: false,
+            strict_mode: false,
+            module_goal: false,
+            annex_b: true,
+            trivia: false,
+            recovery: false,
+            template_braces: Vec::new(),
+            last_token_line: LineNumber::new(1),
+            seen_token: false,
+            error_offset: None,
+            error_position: None,
+            peeked: None,
+            finished: false,
+            #[cfg(feature = "jsx")]
+            jsx_mode: None,
         }
     }
 
-    pub fn tokenize(&mut self) -> Result<Vec<Token>, LexerError> {
-        let mut tokens = Vec::new();
+    pub fn with_strict_mode(mut self, strict_mode: bool) -> Self {
+        self.strict_mode = strict_mode;
+        self
+    }
 
-        while self.pos < self.source.len() {
-            let start_line = self.line;
-            let start_col = self.column;
+    pub fn with_module_goal(mut self, module_goal: bool) -> Self {
+        self.module_goal = module_goal;
+        self
+    }
 
-            let token = self.next_token()?;
+    pub fn with_annex_b(mut self, annex_b: bool) -> Self {
+        self.annex_b = annex_b;
+        self
+    }
 
-            if matches!(token.kind, TokenKind::Eof) {
-                tokens.push(token);
-                break;
-            }
+    pub fn with_trivia(mut self, trivia: bool) -> Self {
+        self.trivia = trivia;
+        self
+    }
 
-            tokens.push(token);
+    pub fn with_recovery(mut self, recovery: bool) -> Self {
+        self.recovery = recovery;
+        self
+    }
+
+    // Resumes scanning at a byte offset whose line and column are already
+    // known, e.g. the start of a statement being re-lexed after an edit.
+    pub fn starting_at(mut self, offset: usize, position: Position) -> Self {
+        self.pos = offset;
+        self.line = position.line;
+        self.column = position.column;
+        self.last_token_line = position.line;
+        self.seen_token = offset > 0;
+        self
+    }
+
+    pub fn tokenize(&mut self) -> Result<Vec<Token<'a>>, LexerError> {
+        self.by_ref().collect()
+    }
 
-            <Self as PositionManager>::update_position(self, start_line, start_col);
+    pub fn next_token(&mut self) -> Result<Token<'a>, LexerError> {
+        match self.peeked.take() {
+            Some(token) => token,
+            None => self.scan_next_token(),
         }
+    }
 
-        if tokens.is_empty() || !matches!(tokens.last().unwrap().kind, TokenKind::Eof) {
-            tokens.push(Token::with_positions(
-                TokenKind::Eof,
-                self.line.as_usize(),
-                self.column.as_usize(),
-                self.line.as_usize(),
-                self.column.as_usize(),
-            ));
+    pub fn peek_token(&mut self) -> Result<&Token<'a>, LexerError> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.scan_next_token());
         }
 
-        Ok(tokens)
+        match self.peeked.as_ref() {
+            Some(Ok(token)) => Ok(token),
+            Some(Err(error)) => Err(error.clone()),
+            None => Err(LexerError::InternalError(
+                "peeked token missing".to_string(),
+            )),
+        }
     }
 
-    pub fn next_token(&mut self) -> Result<Token, LexerError> {
-        <Self as PositionManager>::skip_whitespace(self);
+    // Applies to tokens scanned from now on. A token peeked under the old
+    // mode is dropped and scanned again.
+    #[cfg(feature = "jsx")]
+    pub fn set_jsx_mode(&mut self, mode: Option<JsxMode>) {
+        self.jsx_mode = mode;
+        if let Some(Ok(token)) = self.peeked.take() {
+            self.pos = token.range.start;
+            self.line = token.start().line;
+            self.column = token.start().column;
+        }
+    }
 
-        if self.pos >= self.source.len() {
+    // Position of the most recent error returned by `next_token`, pointing at
+    // the offending character rather than the start of the token.
+    pub fn error_position(&self) -> Option<Position> {
+        self.error_position
+    }
+
+    fn scan_next_token(&mut self) -> Result<Token<'a>, LexerError> {
+        self.error_offset = None;
+        // Whitespace between JSX tags is part of the text.
+        #[cfg(feature = "jsx")]
+        if self.jsx_mode == Some(JsxMode::Children) {
+            let (start, line, column) = (self.pos, self.line, self.column);
+            if let Some(text) = <Self as JsxReader>::read_jsx_text(self) {
+                return Ok(Token::with_positions(
+                    text,
+                    line.as_usize(),
+                    column.as_usize(),
+                    self.line.as_usize(),
+                    self.column.as_usize(),
+                )
+                .with_range(start..self.pos));
+            }
+        }
+
+        let leading_trivia = if self.trivia {
+            match self.read_trivia(false) {
+                Ok(trivia) => trivia,
+                Err(error) => {
+                    let start = self.error_offset.unwrap_or(self.pos);
+                    return self.lex_error(error, start, self.position_at(start));
+                }
+            }
+        } else {
+            <Self as PositionManager>::skip_whitespace(self);
+            Vec::new()
+        };
+
+        let start = self.pos;
+        let start_line = self.line;
+        let start_col = self.column;
+
+        let Some(c) = <Self as PositionManager>::peek_char(self, 0) else {
             return Ok(Token::with_positions(
                 TokenKind::Eof,
                 self.line.as_usize(),
                 self.column.as_usize(),
                 self.line.as_usize(),
                 self.column.as_usize(),
-            ));
+            )
+            .with_range(start..start)
+            .with_newline_before(start_line.as_usize() > self.last_token_line.as_usize())
+            .with_trivia(leading_trivia, Vec::new()));
+        };
+
+        let token_kind = match self.scan_token(c) {
+            Ok(token_kind) => token_kind,
+            Err(error) => {
+                let start_position = Position::new(start_line.as_usize(), start_col.as_usize());
+                return self.lex_error(error, start, start_position);
+            }
+        };
+
+        let newline_before = start_line.as_usize() > self.last_token_line.as_usize();
+        if !matches!(token_kind, TokenKind::Comment(_)) {
+            self.regex_allowed = match token_kind {
+                TokenKind::LeftParen => {
+                    self.paren_heads.push(self.after_head_keyword);
+                    true
+                }
+                TokenKind::RightParen => self.paren_heads.pop().unwrap_or(false),
+                _ => regex_allowed_after(Some(&token_kind)),
+            };
+            self.after_head_keyword = matches!(
+                token_kind,
+                TokenKind::Keyword(Keyword::If | Keyword::While | Keyword::For | Keyword::With)
+            );
+            self.last_token_line = self.line;
+            self.seen_token = true;
         }
 
-        let start_line = self.line;
-        let start_col = self.column;
-        let c = self.source[self.pos];
+        match token_kind {
+            TokenKind::TemplateHead { .. } | TokenKind::TemplateMiddle { .. } => {
+                self.template_braces.push(0);
+            }
+            TokenKind::LeftBrace => {
+                if let Some(depth) = self.template_braces.last_mut() {
+                    *depth += 1;
+                }
+            }
+            TokenKind::RightBrace => {
+                if let Some(depth) = self.template_braces.last_mut() {
+                    *depth -= 1;
+                }
+            }
+            _ => {}
+        }
+
+        let token = Token::with_positions(
+            token_kind,
+            start_line.as_usize(),
+            start_col.as_usize(),
+            self.line.as_usize(),
+            self.column.as_usize(),
+        )
+        .with_range(start..self.pos)
+        .with_newline_before(newline_before);
+
+        if !self.trivia {
+            return Ok(token);
+        }
+
+        // A bad comment after the token is left for the next call to report,
+        // so the token itself is never lost.
+        let (pos, line, column) = (self.pos, self.line, self.column);
+        match self.read_trivia(true) {
+            Ok(trailing_trivia) => Ok(token.with_trivia(leading_trivia, trailing_trivia)),
+            Err(_) => {
+                self.pos = pos;
+                self.line = line;
+                self.column = column;
+                Ok(token.with_trivia(leading_trivia, Vec::new()))
+            }
+        }
+    }
+
+    fn read_trivia(&mut self, trailing: bool) -> Result<Vec<Trivia<'a>>, LexerError> {
+        if trailing {
+            <Self as TriviaReader>::read_trailing_trivia(self)
+        } else {
+            <Self as TriviaReader>::read_leading_trivia(self)
+        }
+    }
+
+    // Records where the error happened and, in recovery mode, turns it into an
+    // `Error` token covering the skipped text so scanning can carry on.
+    fn lex_error(
+        &mut self,
+        error: LexerError,
+        start: usize,
+        start_position: Position,
+    ) -> Result<Token<'a>, LexerError> {
+        let offset = self.error_offset.take().unwrap_or(self.pos);
+        self.error_position = Some(self.position_at(offset));
+
+        if !self.recovery {
+            return Err(error);
+        }
 
-        let token_kind = if c.is_ascii_alphabetic() || c == '_' || c == '$' || !c.is_ascii() {
-            <Self as crate::lexer::scanners::IdentifierReader>::read_identifier_or_keyword(self)?
-        } else if c.is_ascii_digit() {
-            <Self as crate::lexer::scanners::NumberReader>::read_number(self)?
+        if self.pos == start {
+            <Self as PositionManager>::advance(self);
+        }
+
+        let newline_before = start_position.line.as_usize() > self.last_token_line.as_usize();
+        Ok(Token::with_positions(
+            TokenKind::Error(error),
+            start_position.line.as_usize(),
+            start_position.column.as_usize(),
+            self.line.as_usize(),
+            self.column.as_usize(),
+        )
+        .with_range(start..self.pos)
+        .with_newline_before(newline_before))
+    }
+
+    fn scan_token(&mut self, c: char) -> Result<TokenKind<'a>, LexerError> {
+        #[cfg(feature = "jsx")]
+        if self.jsx_mode == Some(JsxMode::Tag) {
+            if let Some(token) = <Self as JsxReader>::read_jsx_tag_token(self, c) {
+                return token;
+            }
+        }
+
+        if c == '\\' || is_id_start(c) {
+            <Self as crate::lexer::scanners::IdentifierReader>::read_identifier_or_keyword(self)
+        } else if c == '#' {
+            if self.pos == 0 && <Self as PositionManager>::peek_char(self, 1) == Some('!') {
+                <Self as crate::lexer::scanners::CommentReader>::read_hashbang_comment(self)
+            } else {
+                <Self as crate::lexer::scanners::IdentifierReader>::read_private_name(self)
+            }
+        } else if self.is_html_comment_start(c) {
+            <Self as crate::lexer::scanners::CommentReader>::read_html_comment(self)
+        } else if c.is_ascii_digit()
+            || c == '.'
+                && <Self as PositionManager>::peek_char(self, 1).is_some_and(|c| c.is_ascii_digit())
+        {
+            <Self as crate::lexer::scanners::NumberReader>::read_number(self)
         } else if c == '"' || c == '\'' {
-            <Self as crate::lexer::scanners::StringReader>::read_string(self)?
+            <Self as crate::lexer::scanners::StringReader>::read_string(self)
         } else if c == '`' {
-            <Self as crate::lexer::scanners::StringReader>::read_template_string(self)?
+            <Self as crate::lexer::scanners::StringReader>::read_template(self)
+        } else if c == '}' && self.template_braces.last() == Some(&0) {
+            self.template_braces.pop();
+            <Self as crate::lexer::scanners::StringReader>::read_template(self)
         } else if c == '/' {
             if <Self as PositionManager>::peek_char(self, 1) == Some('/') {
-                <Self as crate::lexer::scanners::CommentReader>::read_line_comment(self)?
+                <Self as crate::lexer::scanners::CommentReader>::read_line_comment(self)
             } else if <Self as PositionManager>::peek_char(self, 1) == Some('*') {
-                <Self as crate::lexer::scanners::CommentReader>::read_block_comment(self)?
+                <Self as crate::lexer::scanners::CommentReader>::read_block_comment(self)
+            } else if self.regex_allowed {
+                <Self as crate::lexer::scanners::RegexReader>::read_regex(self)
             } else {
-                <Self as crate::lexer::scanners::OperatorReader>::read_operator(self)?
+                <Self as crate::lexer::scanners::OperatorReader>::read_operator(self)
             }
         } else {
-            <Self as crate::lexer::scanners::OperatorReader>::read_operator(self)?
-        };
+            <Self as crate::lexer::scanners::OperatorReader>::read_operator(self)
+        }
+    }
 
-        let end_line = self.line;
-        let end_col = self.column;
+    fn is_html_comment_start(&self, c: char) -> bool {
+        let rest = &self.source[self.pos..];
+        match c {
+            '<' => self.html_comments_allowed() && rest.starts_with("<!--"),
+            '-' => self.html_comments_allowed() && self.at_line_start() && rest.starts_with("-->"),
+            _ => false,
+        }
+    }
 
-        Ok(Token::with_positions(
-            token_kind,
-            start_line.as_usize(),
-            start_col.as_usize(),
-            end_line.as_usize(),
-            end_col.as_usize(),
-        ))
+    fn position_at(&self, offset: usize) -> Position {
+        let mut line = 1;
+        let mut column = 1;
+        for c in self.source[..offset].chars() {
+            if c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        Position::new(line, column)
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Result<Token<'a>, LexerError>;
+
+    // Yields every token up to and including `Eof`, or up to the first error.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let result = self.next_token();
+        self.finished = !matches!(result, Ok(ref token) if !token.is_eof());
+        Some(result)
     }
 }
 
-impl LexerCore for Lexer {
-    fn source(&self) -> &[char] {
-        &self.source
+impl<'a> LexerCore<'a> for Lexer<'a> {
+    fn source(&self) -> &'a str {
+        self.source
     }
 
     fn pos(&self) -> usize {
@@ -115,11 +408,27 @@ impl LexerCore for Lexer {
     fn advance_pos(&mut self) {
         self.advance();
     }
+
+    fn report_error_at(&mut self, pos: usize) {
+        self.error_offset = Some(pos);
+    }
+
+    fn strict_mode(&self) -> bool {
+        self.strict_mode
+    }
+
+    fn html_comments_allowed(&self) -> bool {
+        self.annex_b && !self.module_goal
+    }
+
+    fn at_line_start(&self) -> bool {
+        !self.seen_token || self.line.as_usize() > self.last_token_line.as_usize()
+    }
 }
 
-impl crate::lexer::utils::PositionCore for Lexer {
-    fn source(&self) -> &[char] {
-        &self.source
+impl crate::lexer::utils::PositionCore for Lexer<'_> {
+    fn source(&self) -> &str {
+        self.source
     }
 
     fn pos(&self) -> usize {
@@ -150,6 +459,8 @@ impl crate::lexer::utils::PositionCore for Lexer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lexer::TriviaKind;
+    use std::borrow::Cow;
 
     #[test]
     fn test_tokenize_numbers() {
@@ -192,15 +503,17 @@ mod tests {
 
     #[test]
     fn test_tokenize_operators() {
-        let mut lexer = Lexer::new("+-*/%");
+        // `/` is only division after an operand; here it follows `)`.
+        let mut lexer = Lexer::new("+-*)/%");
         let tokens = lexer.tokenize().unwrap();
-        assert_eq!(tokens.len(), 6);
+        assert_eq!(tokens.len(), 7);
         assert!(matches!(tokens[0].kind, TokenKind::Plus));
         assert!(matches!(tokens[1].kind, TokenKind::Minus));
         assert!(matches!(tokens[2].kind, TokenKind::Star));
-        assert!(matches!(tokens[3].kind, TokenKind::Slash));
-        assert!(matches!(tokens[4].kind, TokenKind::Percent));
-        assert!(matches!(tokens[5].kind, TokenKind::Eof));
+        assert!(matches!(tokens[3].kind, TokenKind::RightParen));
+        assert!(matches!(tokens[4].kind, TokenKind::Slash));
+        assert!(matches!(tokens[5].kind, TokenKind::Percent));
+        assert!(matches!(tokens[6].kind, TokenKind::Eof));
     }
 
     #[test]
@@ -252,4 +565,610 @@ mod tests {
             matches!(result.unwrap_err(), LexerError::InvalidNumber(ref s) if s == "123.456.789")
         );
     }
+
+    #[test]
+    fn test_tokenize_regex() {
+        let mut lexer = Lexer::new("x = /ab+c/gi;");
+        let tokens = lexer.tokenize().unwrap();
+        assert_eq!(tokens.len(), 5);
+        assert!(matches!(
+            tokens[2].kind,
+            TokenKind::RegExp { pattern, flags } if pattern == "ab+c" && flags == "gi"
+        ));
+    }
+
+    #[test]
+    fn test_regex_character_class_with_slash() {
+        let mut lexer = Lexer::new("/[/]\\//");
+        let tokens = lexer.tokenize().unwrap();
+        assert!(matches!(
+            tokens[0].kind,
+            TokenKind::RegExp { pattern, .. } if pattern == "[/]\\/"
+        ));
+    }
+
+    #[test]
+    fn test_slash_after_operand_is_division() {
+        let mut lexer = Lexer::new("a / b / c");
+        let tokens = lexer.tokenize().unwrap();
+        assert!(matches!(tokens[1].kind, TokenKind::Slash));
+        assert!(matches!(tokens[3].kind, TokenKind::Slash));
+
+        let mut lexer = Lexer::new("(1) / 2");
+        let tokens = lexer.tokenize().unwrap();
+        assert!(matches!(tokens[3].kind, TokenKind::Slash));
+    }
+
+    #[test]
+    fn test_invalid_regex_flags() {
+        let mut lexer = Lexer::new("/a/gg");
+        assert!(matches!(
+            lexer.tokenize().unwrap_err(),
+            LexerError::InvalidRegexFlags(_)
+        ));
+
+        let mut lexer = Lexer::new("/a/x");
+        assert!(matches!(
+            lexer.tokenize().unwrap_err(),
+            LexerError::InvalidRegexFlags(_)
+        ));
+
+        let mut lexer = Lexer::new("/a/uv");
+        assert!(matches!(
+            lexer.tokenize().unwrap_err(),
+            LexerError::InvalidRegexFlags(_)
+        ));
+    }
+
+    #[test]
+    fn test_unterminated_regex_is_an_error() {
+        let mut lexer = Lexer::new("x = /abc\ny");
+        assert!(matches!(
+            lexer.tokenize().unwrap_err(),
+            LexerError::InvalidRegexLiteral(_)
+        ));
+
+        let tokens = Lexer::new("x = /abc\ny")
+            .with_recovery(true)
+            .tokenize()
+            .unwrap();
+        assert!(matches!(
+            tokens[2].kind,
+            TokenKind::Error(LexerError::InvalidRegexLiteral(_))
+        ));
+        assert_eq!(tokens[2].range, 4..8);
+        assert!(matches!(tokens[3].kind, TokenKind::Identifier(ref s) if s == "y"));
+    }
+
+    #[test]
+    fn test_regex_after_statement_head() {
+        let kinds = |source| {
+            Lexer::new(source)
+                .tokenize()
+                .unwrap()
+                .into_iter()
+                .map(|token| token.kind)
+                .collect::<Vec<_>>()
+        };
+
+        // The `)` of an `if`, `while`, `for` or `with` head ends no
+        // expression, so a `/` after it starts a regular expression.
+        for source in [
+            "if (x) /re/.test(y)",
+            "while (f(x)) /re/g.exec(y)",
+            "for (;;) /re/",
+            "with (o) /re/",
+        ] {
+            assert!(
+                kinds(source)
+                    .iter()
+                    .any(|kind| matches!(kind, TokenKind::RegExp { pattern, .. } if pattern.starts_with("re"))),
+                "{source}"
+            );
+        }
+
+        // Any other `)` ends an expression and is followed by division.
+        let tokens = kinds("(a) / b / c");
+        assert!(matches!(tokens[3], TokenKind::Slash));
+        let tokens = kinds("if (f(x) / 2) y");
+        assert!(matches!(tokens[6], TokenKind::Slash));
+    }
+
+    #[test]
+    fn test_regex_after_contextual_keyword() {
+        for (source, index) in [
+            ("for (x of /re/g) {}", 4),
+            ("export { a as /re/ }", 4),
+            ("import a from /re/", 3),
+        ] {
+            let tokens = Lexer::new(source).tokenize().unwrap();
+            assert!(
+                matches!(tokens[index].kind, TokenKind::RegExp { pattern, .. } if pattern == "re"),
+                "{source}"
+            );
+        }
+
+        let tokens = Lexer::new("this / 2").tokenize().unwrap();
+        assert!(matches!(tokens[1].kind, TokenKind::Slash));
+    }
+
+    #[test]
+    fn test_unicode_identifiers() {
+        let mut lexer = Lexer::new("let café = ünïcödé + π + 变量 + a\u{200D}b");
+        let tokens = lexer.tokenize().unwrap();
+        assert!(matches!(tokens[1].kind, TokenKind::Identifier(ref s) if s == "café"));
+        assert!(matches!(tokens[3].kind, TokenKind::Identifier(ref s) if s == "ünïcödé"));
+        assert!(matches!(tokens[5].kind, TokenKind::Identifier(ref s) if s == "π"));
+        assert!(matches!(tokens[7].kind, TokenKind::Identifier(ref s) if s == "变量"));
+        assert!(matches!(tokens[9].kind, TokenKind::Identifier(ref s) if s == "a\u{200D}b"));
+    }
+
+    #[test]
+    fn test_identifier_escapes_are_decoded() {
+        let mut lexer = Lexer::new("\\u0061bc \\u{1d49c}x \\u{6F}f");
+        let tokens = lexer.tokenize().unwrap();
+        assert!(matches!(tokens[0].kind, TokenKind::Identifier(ref s) if s == "abc"));
+        assert!(matches!(tokens[1].kind, TokenKind::Identifier(ref s) if s == "\u{1d49c}x"));
+        // Contextual keywords may be spelled with escapes.
+        assert!(matches!(tokens[2].kind, TokenKind::Identifier(ref s) if s == "of"));
+    }
+
+    #[test]
+    fn test_escaped_reserved_word_is_rejected() {
+        let mut lexer = Lexer::new("v\\u0061r x");
+        assert!(matches!(
+            lexer.tokenize().unwrap_err(),
+            LexerError::InvalidKeyword(_)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_identifier_escapes() {
+        for source in ["\\u00", "\\u{}", "\\u{110000}", "\\x41", "a\\u{1F600}"] {
+            let mut lexer = Lexer::new(source);
+            assert!(lexer.tokenize().is_err(), "{source} should not lex");
+        }
+
+        let mut lexer = Lexer::new("\\u0031abc");
+        assert!(matches!(
+            lexer.tokenize().unwrap_err(),
+            LexerError::InvalidIdentifier(_)
+        ));
+    }
+
+    #[test]
+    fn test_error_position_points_at_escape() {
+        let mut lexer = Lexer::new("let a = 1;\nlet bc\\u00zz = 2;");
+        assert!(lexer.tokenize().is_err());
+        assert_eq!(lexer.error_position(), Some(Position::new(2, 7)));
+    }
+
+    #[test]
+    fn test_payloads_borrow_from_source() {
+        let source = String::from("name 'text' 'esc\\n'; /re/g");
+        let tokens = Lexer::new(&source).tokenize().unwrap();
+        assert!(matches!(
+            tokens[0].kind,
+            TokenKind::Identifier(Cow::Borrowed("name"))
+        ));
+        assert!(matches!(
+            tokens[1].kind,
+            TokenKind::String(Cow::Borrowed("text"))
+        ));
+        assert!(matches!(tokens[2].kind, TokenKind::String(Cow::Owned(ref s)) if s == "esc\n"));
+        assert!(matches!(
+            tokens[4].kind,
+            TokenKind::RegExp {
+                pattern: "re",
+                flags: "g"
+            }
+        ));
+    }
+
+    #[test]
+    fn test_token_ranges_are_byte_offsets() {
+        let source = "é + ü";
+        let tokens = Lexer::new(source).tokenize().unwrap();
+        assert_eq!(tokens[0].range, 0..2);
+        assert_eq!(tokens[1].range, 3..4);
+        assert_eq!(tokens[2].range, 5..7);
+        assert_eq!(&source[tokens[2].range.clone()], "ü");
+        assert_eq!(tokens[3].range, 7..7);
+    }
+
+    #[test]
+    fn test_lexer_streams_tokens_lazily() {
+        let mut lexer = Lexer::new("a b \"unterminated");
+        assert!(
+            matches!(lexer.peek_token().unwrap().kind, TokenKind::Identifier(ref s) if s == "a")
+        );
+        assert!(
+            matches!(lexer.next().unwrap().unwrap().kind, TokenKind::Identifier(ref s) if s == "a")
+        );
+        assert!(
+            matches!(lexer.next().unwrap().unwrap().kind, TokenKind::Identifier(ref s) if s == "b")
+        );
+        assert!(matches!(
+            lexer.next(),
+            Some(Err(LexerError::UnterminatedString))
+        ));
+        assert!(lexer.next().is_none());
+    }
+
+    fn first_token_kind(source: &str) -> Result<TokenKind<'_>, LexerError> {
+        Lexer::new(source).next_token().map(|token| token.kind)
+    }
+
+    #[test]
+    fn test_prefixed_numbers() {
+        assert_eq!(first_token_kind("0xFF").unwrap(), TokenKind::Number(255.0));
+        assert_eq!(first_token_kind("0o17").unwrap(), TokenKind::Number(15.0));
+        assert_eq!(first_token_kind("0B101").unwrap(), TokenKind::Number(5.0));
+        assert_eq!(
+            first_token_kind("0x1_0000_0000_0000_0000").unwrap(),
+            TokenKind::Number(18446744073709551616.0)
+        );
+        assert!(matches!(
+            first_token_kind("0b2"),
+            Err(LexerError::InvalidBinaryLiteral(_))
+        ));
+        assert!(matches!(
+            first_token_kind("0b12"),
+            Err(LexerError::InvalidBinaryLiteral(_))
+        ));
+        assert!(matches!(
+            first_token_kind("0o8"),
+            Err(LexerError::InvalidOctalLiteral(_))
+        ));
+        assert!(matches!(
+            first_token_kind("0x"),
+            Err(LexerError::InvalidHexLiteral(_))
+        ));
+        assert!(matches!(
+            first_token_kind("0xG"),
+            Err(LexerError::InvalidHexLiteral(_))
+        ));
+    }
+
+    #[test]
+    fn test_decimal_numbers_and_exponents() {
+        assert_eq!(first_token_kind(".5").unwrap(), TokenKind::Number(0.5));
+        assert_eq!(first_token_kind("5.").unwrap(), TokenKind::Number(5.0));
+        assert_eq!(first_token_kind("1.e3").unwrap(), TokenKind::Number(1000.0));
+        assert_eq!(first_token_kind("2E-2").unwrap(), TokenKind::Number(0.02));
+        assert_eq!(first_token_kind("1e+2").unwrap(), TokenKind::Number(100.0));
+        assert!(matches!(
+            first_token_kind("1e"),
+            Err(LexerError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            first_token_kind("1e+"),
+            Err(LexerError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            first_token_kind("3in"),
+            Err(LexerError::InvalidNumber(_))
+        ));
+    }
+
+    #[test]
+    fn test_numeric_separators() {
+        assert_eq!(
+            first_token_kind("1_000_000").unwrap(),
+            TokenKind::Number(1e6)
+        );
+        assert_eq!(
+            first_token_kind("1_0.0_1e1_0").unwrap(),
+            TokenKind::Number(10.01e10)
+        );
+        assert_eq!(first_token_kind("0xF_F").unwrap(), TokenKind::Number(255.0));
+        for source in ["1__0", "1_", "1_.5", "1._5", "1e_1", "0_1", "0x_1", "08_1"] {
+            assert!(first_token_kind(source).is_err(), "{source} should not lex");
+        }
+    }
+
+    #[test]
+    fn test_legacy_octal_numbers() {
+        assert_eq!(first_token_kind("017").unwrap(), TokenKind::Number(15.0));
+        assert_eq!(first_token_kind("089").unwrap(), TokenKind::Number(89.0));
+        assert_eq!(first_token_kind("08.5").unwrap(), TokenKind::Number(8.5));
+
+        let strict = |source| Lexer::new(source).with_strict_mode(true).next_token();
+        assert!(matches!(
+            strict("017"),
+            Err(LexerError::InvalidOctalLiteral(_))
+        ));
+        assert!(matches!(strict("089"), Err(LexerError::InvalidNumber(_))));
+        assert!(strict("0").is_ok());
+        assert!(strict("0.5").is_ok());
+    }
+
+    #[test]
+    fn test_bigint_literals() {
+        assert_eq!(
+            first_token_kind("123n").unwrap(),
+            TokenKind::BigInt("123".into())
+        );
+        assert_eq!(
+            first_token_kind("0n").unwrap(),
+            TokenKind::BigInt("0".into())
+        );
+        assert_eq!(
+            first_token_kind("0xFFn").unwrap(),
+            TokenKind::BigInt("0xFF".into())
+        );
+        assert_eq!(
+            first_token_kind("1_000n").unwrap(),
+            TokenKind::BigInt("1000".into())
+        );
+        for source in ["1.5n", "1e3n", "017n", "1nn"] {
+            assert!(first_token_kind(source).is_err(), "{source} should not lex");
+        }
+    }
+
+    #[test]
+    fn test_nested_template_tokens() {
+        let tokens = Lexer::new("`a ${ `b ${c}` } d${ {x: 1}.x }`")
+            .tokenize()
+            .unwrap();
+        let kinds: Vec<_> = tokens.iter().map(|token| &token.kind).collect();
+        assert!(matches!(kinds[0], TokenKind::TemplateHead { raw, .. } if raw == "a "));
+        assert!(matches!(kinds[1], TokenKind::TemplateHead { raw, .. } if raw == "b "));
+        assert!(matches!(kinds[2], TokenKind::Identifier(name) if name == "c"));
+        assert!(matches!(kinds[3], TokenKind::TemplateTail { raw, .. } if raw.is_empty()));
+        assert!(matches!(kinds[4], TokenKind::TemplateMiddle { raw, .. } if raw == " d"));
+        assert!(matches!(kinds[5], TokenKind::LeftBrace));
+        assert!(matches!(kinds[9], TokenKind::RightBrace));
+        assert!(matches!(kinds[12], TokenKind::TemplateTail { raw, .. } if raw.is_empty()));
+        assert!(matches!(kinds[13], TokenKind::Eof));
+    }
+
+    #[test]
+    fn test_template_cooked_and_raw_values() {
+        match first_token_kind("`a\\n\\u{41}\r\nb`").unwrap() {
+            TokenKind::NoSubstitutionTemplate { cooked, raw } => {
+                assert_eq!(cooked.as_deref(), Some("a\nA\nb"));
+                assert_eq!(raw, "a\\n\\u{41}\nb");
+            }
+            other => panic!("expected template, got {other:?}"),
+        }
+
+        match first_token_kind("`\\unicode and \\1`").unwrap() {
+            TokenKind::NoSubstitutionTemplate { cooked, raw } => {
+                assert_eq!(cooked, None);
+                assert_eq!(raw, "\\unicode and \\1");
+            }
+            other => panic!("expected template, got {other:?}"),
+        }
+
+        assert!(matches!(
+            Lexer::new("`abc ${x} def").tokenize(),
+            Err(LexerError::UnterminatedTemplateString)
+        ));
+    }
+
+    #[test]
+    fn test_tokens_record_preceding_line_breaks() {
+        let tokens = Lexer::new("a b\nc /* x\n */ d /* */ e // f\ng")
+            .tokenize()
+            .unwrap();
+        let flags: Vec<_> = tokens
+            .iter()
+            .filter(|token| !token.is_comment() && !token.is_eof())
+            .map(|token| token.newline_before)
+            .collect();
+        assert_eq!(flags, [false, false, true, true, false, true]);
+    }
+
+    #[test]
+    fn test_multi_character_punctuators() {
+        let tokens = Lexer::new("a === b !== c => d >>>= e ?. f ?? g ... h **= i")
+            .tokenize()
+            .unwrap();
+        let operators: Vec<_> = tokens
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .filter(|token| !token.is_eof())
+            .map(|token| token.kind.clone())
+            .collect();
+        assert_eq!(
+            operators,
+            [
+                TokenKind::StrictEqual,
+                TokenKind::StrictNotEqual,
+                TokenKind::Arrow,
+                TokenKind::UnsignedRightShiftAssign,
+                TokenKind::OptionalChaining,
+                TokenKind::NullishCoalescing,
+                TokenKind::Spread,
+                TokenKind::StarStarAssign,
+            ]
+        );
+
+        let tokens = Lexer::new("a?.5:b").tokenize().unwrap();
+        assert!(matches!(tokens[1].kind, TokenKind::Question));
+    }
+
+    #[test]
+    fn test_trivia_mode() {
+        let source = "// header\nlet a = 1; /* note */\n\n  a\r\n/* end */";
+        let tokens = Lexer::new(source).with_trivia(true).tokenize().unwrap();
+
+        assert!(tokens.iter().all(|token| !token.is_comment()));
+
+        let leading: Vec<_> = tokens[0]
+            .leading_trivia
+            .iter()
+            .map(|trivia| (trivia.kind, trivia.text))
+            .collect();
+        assert_eq!(
+            leading,
+            [
+                (TriviaKind::LineComment, "// header"),
+                (TriviaKind::Newline, "\n")
+            ]
+        );
+
+        let semicolon = &tokens[4];
+        assert_eq!(semicolon.kind, TokenKind::Semicolon);
+        let trailing: Vec<_> = semicolon
+            .trailing_trivia
+            .iter()
+            .map(|trivia| (trivia.kind, trivia.text))
+            .collect();
+        assert_eq!(
+            trailing,
+            [
+                (TriviaKind::Whitespace, " "),
+                (TriviaKind::BlockComment, "/* note */"),
+                (TriviaKind::Newline, "\n"),
+            ]
+        );
+        assert_eq!(tokens[5].leading_trivia.len(), 2);
+        assert!(tokens[5].newline_before);
+        assert_eq!(tokens[5].trailing_trivia[0].text, "\r\n");
+        assert!(tokens[6].is_eof());
+        assert!(tokens[6].leading_trivia[0].is_comment());
+
+        let mut rebuilt = String::new();
+        for token in &tokens {
+            for trivia in &token.leading_trivia {
+                rebuilt.push_str(trivia.text);
+            }
+            rebuilt.push_str(token.raw(source));
+            for trivia in &token.trailing_trivia {
+                rebuilt.push_str(trivia.text);
+            }
+        }
+        assert_eq!(rebuilt, source);
+    }
+
+    #[test]
+    fn test_trivia_mode_is_opt_in() {
+        let tokens = Lexer::new("a // note\nb").tokenize().unwrap();
+        assert!(tokens[1].is_comment());
+        assert!(tokens
+            .iter()
+            .all(|token| token.leading_trivia.is_empty() && token.trailing_trivia.is_empty()));
+
+        assert_eq!(
+            Lexer::new("a /* open").with_trivia(true).tokenize(),
+            Err(LexerError::UnterminatedComment)
+        );
+    }
+
+    #[test]
+    fn test_hashbang_and_html_comments() {
+        let kinds = |source| -> Vec<TokenKind> {
+            Lexer::new(source)
+                .tokenize()
+                .unwrap()
+                .into_iter()
+                .map(|token| token.kind)
+                .collect()
+        };
+
+        assert_eq!(
+            kinds("#!/usr/bin/env node\nx"),
+            [
+                TokenKind::Comment("/usr/bin/env node"),
+                TokenKind::Identifier(Cow::Borrowed("x")),
+                TokenKind::Eof,
+            ]
+        );
+        assert_eq!(
+            Lexer::new("x\n#!/bin/sh").tokenize(),
+            Err(LexerError::UnexpectedCharacter('#'))
+        );
+
+        assert_eq!(
+            kinds("x <!-- hidden\n  --> also hidden\ny-->z"),
+            [
+                TokenKind::Identifier(Cow::Borrowed("x")),
+                TokenKind::Comment(" hidden"),
+                TokenKind::Comment(" also hidden"),
+                TokenKind::Identifier(Cow::Borrowed("y")),
+                TokenKind::Decrement,
+                TokenKind::GreaterThan,
+                TokenKind::Identifier(Cow::Borrowed("z")),
+                TokenKind::Eof,
+            ]
+        );
+
+        let module_tokens = Lexer::new("a <!--b")
+            .with_module_goal(true)
+            .tokenize()
+            .unwrap();
+        assert!(module_tokens.iter().all(|token| !token.is_comment()));
+
+        let tokens = Lexer::new("#!node\n<!-- a\nb")
+            .with_trivia(true)
+            .tokenize()
+            .unwrap();
+        let kinds: Vec<_> = tokens[0]
+            .leading_trivia
+            .iter()
+            .map(|trivia| trivia.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                TriviaKind::Hashbang,
+                TriviaKind::Newline,
+                TriviaKind::HtmlComment,
+                TriviaKind::Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_private_names() {
+        assert_eq!(
+            first_token_kind("#count").unwrap(),
+            TokenKind::PrivateName(Cow::Borrowed("count"))
+        );
+        assert_eq!(
+            first_token_kind("#if").unwrap(),
+            TokenKind::PrivateName(Cow::Borrowed("if"))
+        );
+        assert_eq!(
+            first_token_kind("#\\u0061b").unwrap(),
+            TokenKind::PrivateName(Cow::Owned("ab".to_string()))
+        );
+        assert_eq!(
+            Lexer::new("# x").tokenize(),
+            Err(LexerError::UnexpectedCharacter('#'))
+        );
+
+        let tokens = Lexer::new("this.#x / 2").tokenize().unwrap();
+        assert_eq!(tokens[3].kind, TokenKind::Slash);
+    }
+
+    #[test]
+    fn test_recovery_mode_emits_error_tokens() {
+        let source = "let a = 0b12;\nlet b = 'open\nlet c = @ 1;";
+        let tokens = Lexer::new(source).with_recovery(true).tokenize().unwrap();
+
+        let errors: Vec<_> = tokens
+            .iter()
+            .filter(|token| token.is_error())
+            .map(|token| token.raw(source))
+            .collect();
+        assert_eq!(errors, ["0b12", "'open", "@"]);
+        assert!(matches!(
+            tokens[3].kind,
+            TokenKind::Error(LexerError::InvalidBinaryLiteral(_))
+        ));
+        assert!(tokens.last().unwrap().is_eof());
+        assert_eq!(
+            tokens
+                .iter()
+                .filter(|token| token.kind == TokenKind::Keyword(crate::lexer::Keyword::Let))
+                .count(),
+            3
+        );
+
+        assert!(Lexer::new("@").tokenize().is_err());
+    }
 }
@@ -7,15 +7,22 @@ pub mod utils;
 
 pub use core::Lexer;
 pub use error::LexerError;
-pub use token::{Token, TokenKind};
+#[cfg(feature = "jsx")]
+pub use scanners::JsxMode;
+pub use token::{Token, TokenKind, Trivia, TriviaKind};
 pub use tokens::{Keyword, Literal, Operator, Punctuation};
 
-pub fn tokenize(source: &str) -> Result<Vec<Token>, LexerError> {
+pub fn tokenize(source: &str) -> Result<Vec<Token<'_>>, LexerError> {
     let mut lexer = Lexer::new(source);
     lexer.tokenize()
 }
 
-pub fn tokenize_fallback(source: &str) -> Vec<Token> {
+pub fn tokenize_with_trivia(source: &str) -> Result<Vec<Token<'_>>, LexerError> {
+    let mut lexer = Lexer::new(source).with_trivia(true);
+    lexer.tokenize()
+}
+
+pub fn tokenize_fallback(source: &str) -> Vec<Token<'_>> {
     match tokenize(source) {
         Ok(tokens) => tokens,
         Err(_) => vec![Token::with_positions(TokenKind::Eof, 1, 1, 1, 1)],
@@ -1,59 +1,69 @@
 use crate::lexer::{LexerError, TokenKind};
 
-pub trait CommentReader {
-    fn read_line_comment(&mut self) -> Result<TokenKind, LexerError>;
-    fn read_block_comment(&mut self) -> Result<TokenKind, LexerError>;
+pub trait CommentReader<'a> {
+    fn read_line_comment(&mut self) -> Result<TokenKind<'a>, LexerError>;
+    fn read_block_comment(&mut self) -> Result<TokenKind<'a>, LexerError>;
+    fn read_hashbang_comment(&mut self) -> Result<TokenKind<'a>, LexerError>;
+    fn read_html_comment(&mut self) -> Result<TokenKind<'a>, LexerError>;
 }
 
-impl<T> CommentReader for T
+impl<'a, T> CommentReader<'a> for T
 where
-    T: LexerCore + LexerCoreExt,
+    T: LexerCore<'a> + LexerCoreExt<'a>,
 {
-    fn read_line_comment(&mut self) -> Result<TokenKind, LexerError> {
-        self.advance_pos();
-        self.advance_pos();
-
-        let mut comment = String::new();
-
-        while self.pos() < self.source().len() {
-            let c = self.source()[self.pos()];
-            if c == '\n' {
-                break;
-            }
-            comment.push(c);
-            self.advance_pos();
-        }
-
-        Ok(TokenKind::Comment(comment))
+    fn read_line_comment(&mut self) -> Result<TokenKind<'a>, LexerError> {
+        read_single_line_comment(self, 2)
     }
 
-    fn read_block_comment(&mut self) -> Result<TokenKind, LexerError> {
+    fn read_block_comment(&mut self) -> Result<TokenKind<'a>, LexerError> {
         self.advance_pos();
         self.advance_pos();
 
-        let mut comment = String::new();
-        let mut found_closing_comment = false;
-
-        while self.pos() < self.source().len() {
-            let c = self.source()[self.pos()];
-
+        let start = self.pos();
+        while let Some(c) = self.peek_char(0) {
             if c == '*' && self.peek_char(1) == Some('/') {
+                let comment = self.slice_from(start);
                 self.advance_pos();
                 self.advance_pos();
-                found_closing_comment = true;
-                break;
+                return Ok(TokenKind::Comment(comment));
             }
-
-            comment.push(c);
             self.advance_pos();
         }
 
-        if !found_closing_comment {
-            return Err(LexerError::UnterminatedComment);
-        }
+        Err(LexerError::UnterminatedComment)
+    }
+
+    fn read_hashbang_comment(&mut self) -> Result<TokenKind<'a>, LexerError> {
+        read_single_line_comment(self, 2)
+    }
+
+    // `<!--` and `-->` both comment out the rest of the line.
+    fn read_html_comment(&mut self) -> Result<TokenKind<'a>, LexerError> {
+        let delimiter_len = if self.peek_char(0) == Some('<') { 4 } else { 3 };
+        read_single_line_comment(self, delimiter_len)
+    }
+}
 
-        Ok(TokenKind::Comment(comment))
+fn read_single_line_comment<'a, T>(
+    lexer: &mut T,
+    delimiter_len: usize,
+) -> Result<TokenKind<'a>, LexerError>
+where
+    T: LexerCore<'a> + LexerCoreExt<'a>,
+{
+    for _ in 0..delimiter_len {
+        lexer.advance_pos();
+    }
+
+    let start = lexer.pos();
+    while let Some(c) = lexer.peek_char(0) {
+        if matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}') {
+            break;
+        }
+        lexer.advance_pos();
     }
+
+    Ok(TokenKind::Comment(lexer.slice_from(start)))
 }
 
 use crate::lexer::scanners::{LexerCore, LexerCoreExt};
@@ -1,28 +1,34 @@
+use crate::lexer::scanners::LexerCoreExt;
 use crate::lexer::tokens::Keyword;
 use crate::lexer::{LexerError, TokenKind};
+use std::borrow::Cow;
 
-pub trait IdentifierReader {
-    fn read_identifier_or_keyword(&mut self) -> Result<TokenKind, LexerError>;
+pub trait IdentifierReader<'a> {
+    fn read_identifier_or_keyword(&mut self) -> Result<TokenKind<'a>, LexerError>;
+    fn read_private_name(&mut self) -> Result<TokenKind<'a>, LexerError>;
 }
 
-impl<T> IdentifierReader for T
+impl<'a, T> IdentifierReader<'a> for T
 where
-    T: LexerCore,
+    T: LexerCore<'a>,
 {
-    fn read_identifier_or_keyword(&mut self) -> Result<TokenKind, LexerError> {
-        let mut identifier = String::new();
-
-        while self.pos() < self.source().len() {
-            let c = self.source()[self.pos()];
-            if c.is_alphanumeric() || c == '_' || c == '$' || c.is_alphabetic() || !c.is_ascii() {
-                identifier.push(c);
-                self.advance_pos();
-            } else {
-                break;
+    fn read_identifier_or_keyword(&mut self) -> Result<TokenKind<'a>, LexerError> {
+        let start = self.pos();
+
+        let identifier = match read_identifier_name(self)? {
+            Cow::Owned(identifier) => {
+                if is_reserved_word(&identifier) {
+                    self.report_error_at(start);
+                    return Err(LexerError::InvalidKeyword(format!(
+                        "keyword '{identifier}' must not contain escape sequences"
+                    )));
+                }
+                return Ok(TokenKind::Identifier(Cow::Owned(identifier)));
             }
-        }
+            Cow::Borrowed(identifier) => identifier,
+        };
 
-        match identifier.as_str() {
+        match identifier {
             "true" => Ok(TokenKind::Boolean(true)),
             "false" => Ok(TokenKind::Boolean(false)),
             "null" => Ok(TokenKind::Null),
@@ -30,18 +36,217 @@ where
             "this" => Ok(TokenKind::Keyword(Keyword::This)),
             "super" => Ok(TokenKind::Keyword(Keyword::Super)),
             _ => {
-                if let Some(keyword) = Keyword::from_string(&identifier) {
+                if let Some(keyword) = Keyword::from_string(identifier) {
                     Ok(TokenKind::Keyword(keyword))
                 } else {
-                    Ok(TokenKind::Identifier(identifier))
+                    Ok(TokenKind::Identifier(Cow::Borrowed(identifier)))
+                }
+            }
+        }
+    }
+
+    // `#name` is an IdentifierName after the `#`, so reserved words such as
+    // `#if` are allowed, and the payload is the name without the `#`.
+    fn read_private_name(&mut self) -> Result<TokenKind<'a>, LexerError> {
+        let start = self.pos();
+        self.advance_pos();
+
+        match self.peek_char(0) {
+            Some(c) if c == '\\' || is_id_start(c) => {
+                Ok(TokenKind::PrivateName(read_identifier_name(self)?))
+            }
+            _ => {
+                self.report_error_at(start);
+                Err(LexerError::UnexpectedCharacter('#'))
+            }
+        }
+    }
+}
+
+// Reads an IdentifierName, borrowing from the source unless an escape
+// sequence had to be decoded.
+fn read_identifier_name<'a, T: LexerCore<'a>>(lexer: &mut T) -> Result<Cow<'a, str>, LexerError> {
+    let start = lexer.pos();
+    // Only allocated once an escape sequence is seen; plain identifiers
+    // borrow straight from the source.
+    let mut decoded: Option<String> = None;
+
+    while let Some(c) = lexer.peek_char(0) {
+        let at_start = lexer.pos() == start;
+
+        if c == '\\' {
+            let escape_start = lexer.pos();
+            let escaped = read_identifier_escape(lexer)?;
+            if !(at_start && is_id_start(escaped) || !at_start && is_id_continue(escaped)) {
+                lexer.report_error_at(escape_start);
+                return Err(LexerError::InvalidIdentifier(format!(
+                    "escape sequence decodes to '{}' which is not allowed in an identifier",
+                    escaped.escape_unicode()
+                )));
+            }
+            decoded
+                .get_or_insert_with(|| lexer.source()[start..escape_start].to_string())
+                .push(escaped);
+        } else if at_start && is_id_start(c) || !at_start && is_id_continue(c) {
+            lexer.advance_pos();
+            if let Some(decoded) = decoded.as_mut() {
+                decoded.push(c);
+            }
+        } else {
+            break;
+        }
+    }
+
+    Ok(match decoded {
+        Some(identifier) => Cow::Owned(identifier),
+        None => Cow::Borrowed(lexer.slice_from(start)),
+    })
+}
+
+pub fn is_id_start(c: char) -> bool {
+    c == '$' || c == '_' || unicode_id_start::is_id_start(c)
+}
+
+pub fn is_id_continue(c: char) -> bool {
+    c == '$' || c == '\u{200C}' || c == '\u{200D}' || unicode_id_start::is_id_continue(c)
+}
+
+// Reads `\uXXXX` or `\u{X...}` starting at the backslash. On failure the
+// error position points at the backslash.
+fn read_identifier_escape<'a, T: LexerCore<'a>>(lexer: &mut T) -> Result<char, LexerError> {
+    let start = lexer.pos();
+    lexer.advance_pos();
+
+    if lexer.peek_char(0) != Some('u') {
+        lexer.report_error_at(start);
+        return Err(LexerError::InvalidUnicodeEscape(
+            "expected 'u' after '\\' in identifier".to_string(),
+        ));
+    }
+    lexer.advance_pos();
+
+    let code_point = match read_unicode_escape_digits(lexer) {
+        Ok(code_point) => code_point,
+        Err(error) => {
+            lexer.report_error_at(start);
+            return Err(error);
+        }
+    };
+
+    char::from_u32(code_point).ok_or_else(|| {
+        lexer.report_error_at(start);
+        LexerError::InvalidUnicodeEscape(format!("\\u{{{code_point:X}}} is not a valid code point"))
+    })
+}
+
+// Reads the digits of a Unicode escape after the `\u` prefix, either four hex
+// digits or a braced code point up to U+10FFFF.
+pub fn read_unicode_escape_digits<'a, T: LexerCore<'a>>(lexer: &mut T) -> Result<u32, LexerError> {
+    let mut digits = String::new();
+
+    if lexer.peek_char(0) == Some('{') {
+        lexer.advance_pos();
+        while let Some(c) = lexer.peek_char(0) {
+            if c == '}' {
+                break;
+            }
+            if !c.is_ascii_hexdigit() {
+                return Err(LexerError::InvalidUnicodeEscape(format!(
+                    "\\u{{{digits}{c}"
+                )));
+            }
+            digits.push(c);
+            lexer.advance_pos();
+        }
+
+        if lexer.peek_char(0) != Some('}') || digits.is_empty() {
+            return Err(LexerError::InvalidUnicodeEscape(format!("\\u{{{digits}")));
+        }
+        lexer.advance_pos();
+
+        match u32::from_str_radix(&digits, 16) {
+            Ok(code_point) if code_point <= 0x10FFFF => Ok(code_point),
+            _ => Err(LexerError::InvalidUnicodeEscape(format!(
+                "\\u{{{digits}}} is out of range"
+            ))),
+        }
+    } else {
+        for _ in 0..4 {
+            match lexer.peek_char(0) {
+                Some(c) if c.is_ascii_hexdigit() => {
+                    digits.push(c);
+                    lexer.advance_pos();
                 }
+                _ => return Err(LexerError::InvalidUnicodeEscape(format!("\\u{digits}"))),
             }
         }
+
+        Ok(u32::from_str_radix(&digits, 16).unwrap_or_default())
     }
 }
 
-pub trait LexerCore {
-    fn source(&self) -> &[char];
+fn is_reserved_word(name: &str) -> bool {
+    matches!(
+        name,
+        "await"
+            | "break"
+            | "case"
+            | "catch"
+            | "class"
+            | "const"
+            | "continue"
+            | "debugger"
+            | "default"
+            | "delete"
+            | "do"
+            | "else"
+            | "enum"
+            | "export"
+            | "extends"
+            | "false"
+            | "finally"
+            | "for"
+            | "function"
+            | "if"
+            | "import"
+            | "in"
+            | "instanceof"
+            | "new"
+            | "null"
+            | "return"
+            | "super"
+            | "switch"
+            | "this"
+            | "throw"
+            | "true"
+            | "try"
+            | "typeof"
+            | "var"
+            | "void"
+            | "while"
+            | "with"
+            | "yield"
+    )
+}
+
+pub trait LexerCore<'a> {
+    fn source(&self) -> &'a str;
     fn pos(&self) -> usize;
     fn advance_pos(&mut self);
+
+    fn report_error_at(&mut self, _pos: usize) {}
+
+    fn strict_mode(&self) -> bool {
+        false
+    }
+
+    fn html_comments_allowed(&self) -> bool {
+        false
+    }
+
+    // True when only whitespace and comments precede the current position on
+    // its line, which is where an Annex B `-->` comment may start.
+    fn at_line_start(&self) -> bool {
+        false
+    }
 }
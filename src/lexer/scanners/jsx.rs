@@ -0,0 +1,76 @@
+use crate::lexer::scanners::{is_id_continue, is_id_start, LexerCore, LexerCoreExt};
+use crate::lexer::{LexerError, TokenKind};
+
+// Where the parser is inside JSX, which the lexer needs to know because the
+// same characters form different tokens there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsxMode {
+    // Inside `<...>`: names may contain `-`, strings have no escapes and
+    // `>` and `/` always stand alone.
+    Tag,
+    // Between tags, where everything up to `{` or `<` is text.
+    Children,
+}
+
+pub trait JsxReader<'a> {
+    fn read_jsx_text(&mut self) -> Option<TokenKind<'a>>;
+    fn read_jsx_tag_token(&mut self, c: char) -> Option<Result<TokenKind<'a>, LexerError>>;
+}
+
+impl<'a, T> JsxReader<'a> for T
+where
+    T: LexerCore<'a>,
+{
+    // `None` when the text is empty, leaving the `{` or `<` to the regular
+    // scanner.
+    fn read_jsx_text(&mut self) -> Option<TokenKind<'a>> {
+        let start = self.pos();
+        while let Some(c) = self.peek_char(0) {
+            if c == '{' || c == '<' {
+                break;
+            }
+            self.advance_pos();
+        }
+        (self.pos() > start).then(|| TokenKind::JsxText(self.slice_from(start)))
+    }
+
+    // `None` for characters that lex the same as outside JSX.
+    fn read_jsx_tag_token(&mut self, c: char) -> Option<Result<TokenKind<'a>, LexerError>> {
+        match c {
+            c if is_id_start(c) => {
+                let start = self.pos();
+                self.advance_pos();
+                while let Some(c) = self.peek_char(0) {
+                    if !is_id_continue(c) && c != '-' {
+                        break;
+                    }
+                    self.advance_pos();
+                }
+                Some(Ok(TokenKind::JsxName(self.slice_from(start))))
+            }
+            '"' | '\'' => {
+                self.advance_pos();
+                let start = self.pos();
+                loop {
+                    match self.peek_char(0) {
+                        None => return Some(Err(LexerError::UnterminatedString)),
+                        Some(quote) if quote == c => break,
+                        Some(_) => self.advance_pos(),
+                    }
+                }
+                let value = self.slice_from(start);
+                self.advance_pos();
+                Some(Ok(TokenKind::JsxString(value)))
+            }
+            '>' => {
+                self.advance_pos();
+                Some(Ok(TokenKind::GreaterThan))
+            }
+            '/' => {
+                self.advance_pos();
+                Some(Ok(TokenKind::Slash))
+            }
+            _ => None,
+        }
+    }
+}
@@ -1,11 +1,19 @@
 pub mod comment;
 pub mod identifier;
+#[cfg(feature = "jsx")]
+pub mod jsx;
 pub mod number;
 pub mod operator;
+pub mod regex;
 pub mod string;
+pub mod trivia;
 
 pub use comment::*;
 pub use identifier::*;
+#[cfg(feature = "jsx")]
+pub use jsx::*;
 pub use number::*;
 pub use operator::*;
+pub use regex::*;
 pub use string::*;
+pub use trivia::*;
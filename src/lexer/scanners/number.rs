@@ -1,107 +1,303 @@
+use crate::lexer::scanners::is_id_start;
 use crate::lexer::{LexerError, TokenKind};
+use std::borrow::Cow;
 
-pub trait NumberReader {
-    fn read_number(&mut self) -> Result<TokenKind, LexerError>;
+pub trait NumberReader<'a> {
+    fn read_number(&mut self) -> Result<TokenKind<'a>, LexerError>;
 }
 
-impl<T> NumberReader for T
+impl<'a, T> NumberReader<'a> for T
 where
-    T: LexerCore,
+    T: LexerCore<'a>,
 {
-    fn read_number(&mut self) -> Result<TokenKind, LexerError> {
-        let mut number = String::new();
-        let mut is_hex = false;
-        let mut is_binary = false;
-        let mut is_octal = false;
-
-        if self.source()[self.pos()] == '0' && self.pos() + 1 < self.source().len() {
-            match self.source()[self.pos() + 1] {
-                'x' | 'X' => {
-                    is_hex = true;
-                    number.push('0');
-                    number.push(self.source()[self.pos() + 1]);
-                    self.advance_pos();
-                    self.advance_pos();
-                }
-                'b' | 'B' => {
-                    is_binary = true;
-                    number.push('0');
-                    number.push(self.source()[self.pos() + 1]);
-                    self.advance_pos();
-                    self.advance_pos();
-                }
-                'o' | 'O' => {
-                    is_octal = true;
-                    number.push('0');
-                    number.push(self.source()[self.pos() + 1]);
-                    self.advance_pos();
+    fn read_number(&mut self) -> Result<TokenKind<'a>, LexerError> {
+        let start = self.pos();
+
+        if self.peek_char(0) == Some('0') {
+            match self.peek_char(1) {
+                Some('x' | 'X') => return read_prefixed_number(self, start, 16),
+                Some('o' | 'O') => return read_prefixed_number(self, start, 8),
+                Some('b' | 'B') => return read_prefixed_number(self, start, 2),
+                Some(c) if c.is_ascii_digit() => return read_legacy_number(self, start),
+                Some('_') => {
                     self.advance_pos();
+                    return Err(LexerError::InvalidNumber(
+                        "numeric separator is not allowed after a leading 0".to_string(),
+                    ));
                 }
                 _ => {}
             }
         }
 
-        while self.pos() < self.source().len() {
-            let c = self.source()[self.pos()];
+        if self.peek_char(0) != Some('.') {
+            read_digits(self, 10)?;
+        }
 
-            if is_hex {
-                if c.is_ascii_hexdigit() {
-                    number.push(c);
-                    self.advance_pos();
-                } else {
-                    break;
-                }
-            } else if is_binary {
-                if c == '0' || c == '1' {
-                    number.push(c);
-                    self.advance_pos();
-                } else {
-                    break;
-                }
-            } else if is_octal {
-                if ('0'..='7').contains(&c) {
-                    number.push(c);
-                    self.advance_pos();
-                } else {
-                    break;
-                }
-            } else if c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || c == '+' || c == '-'
-            {
-                number.push(c);
-                self.advance_pos();
-            } else {
-                break;
+        let mut is_integer = true;
+
+        if self.peek_char(0) == Some('.') {
+            self.advance_pos();
+            is_integer = false;
+            if self.peek_char(0).is_some_and(|c| c.is_ascii_digit()) {
+                read_digits(self, 10)?;
+            } else if self.peek_char(0) == Some('_') {
+                return Err(LexerError::InvalidNumber(format!(
+                    "numeric separator is not allowed after '.' in {}_",
+                    self.slice_from(start)
+                )));
             }
         }
 
-        if self.pos() < self.source().len() && self.source()[self.pos()] == 'n' {
-            number.push('n');
+        if matches!(self.peek_char(0), Some('e' | 'E')) {
             self.advance_pos();
-            return Ok(TokenKind::BigInt(number));
+            is_integer = false;
+            if matches!(self.peek_char(0), Some('+' | '-')) {
+                self.advance_pos();
+            }
+            if !self.peek_char(0).is_some_and(|c| c.is_ascii_digit()) {
+                return Err(LexerError::InvalidNumber(format!(
+                    "missing exponent digits in {}",
+                    self.slice_from(start)
+                )));
+            }
+            read_digits(self, 10)?;
         }
 
-        if is_hex {
-            match u64::from_str_radix(&number[2..], 16) {
-                Ok(n) => Ok(TokenKind::Number(n as f64)),
-                Err(_) => Err(LexerError::InvalidNumber(number)),
+        if self.peek_char(0) == Some('n') {
+            if !is_integer {
+                self.advance_pos();
+                return Err(LexerError::InvalidBigIntLiteral(format!(
+                    "{} must be an integer",
+                    self.slice_from(start)
+                )));
             }
-        } else if is_binary {
-            match u64::from_str_radix(&number[2..], 2) {
-                Ok(n) => Ok(TokenKind::Number(n as f64)),
-                Err(_) => Err(LexerError::InvalidNumber(number)),
+            return read_bigint_suffix(self, start);
+        }
+
+        // `1.2.3` can never be valid, so report the whole run rather than
+        // splitting it into two adjacent numbers.
+        if !is_integer
+            && self.peek_char(0) == Some('.')
+            && self.peek_char(1).is_some_and(|c| c.is_ascii_digit())
+        {
+            while self
+                .peek_char(0)
+                .is_some_and(|c| c.is_ascii_digit() || c == '.')
+            {
+                self.advance_pos();
             }
-        } else if is_octal {
-            match u64::from_str_radix(&number[2..], 8) {
-                Ok(n) => Ok(TokenKind::Number(n as f64)),
-                Err(_) => Err(LexerError::InvalidNumber(number)),
+            return Err(LexerError::InvalidNumber(
+                self.slice_from(start).to_string(),
+            ));
+        }
+
+        check_literal_end(self, start, 10)?;
+
+        let text = strip_separators(self.slice_from(start));
+        text.parse::<f64>()
+            .map(TokenKind::Number)
+            .map_err(|_| LexerError::InvalidNumber(text.into_owned()))
+    }
+}
+
+fn read_prefixed_number<'a, T: LexerCore<'a>>(
+    lexer: &mut T,
+    start: usize,
+    radix: u32,
+) -> Result<TokenKind<'a>, LexerError> {
+    lexer.advance_pos();
+    lexer.advance_pos();
+
+    if !lexer.peek_char(0).is_some_and(|c| c.is_digit(radix)) {
+        return Err(invalid_literal(
+            radix,
+            format!("missing digits after {}", lexer.slice_from(start)),
+        ));
+    }
+    read_digits(lexer, radix)?;
+
+    if lexer.peek_char(0) == Some('n') {
+        return read_bigint_suffix(lexer, start);
+    }
+
+    check_literal_end(lexer, start, radix)?;
+
+    let digits = strip_separators(&lexer.slice_from(start)[2..]);
+    Ok(TokenKind::Number(digits_to_f64(&digits, radix)))
+}
+
+// Literals such as `017` (legacy octal) and `089` (decimal with a leading
+// zero) are only allowed in sloppy mode and never take separators or `n`.
+fn read_legacy_number<'a, T: LexerCore<'a>>(
+    lexer: &mut T,
+    start: usize,
+) -> Result<TokenKind<'a>, LexerError> {
+    let mut is_octal = true;
+    while let Some(c) = lexer.peek_char(0) {
+        if !c.is_ascii_digit() {
+            break;
+        }
+        is_octal &= c < '8';
+        lexer.advance_pos();
+    }
+
+    if lexer.strict_mode() {
+        lexer.report_error_at(start);
+        return Err(if is_octal {
+            LexerError::InvalidOctalLiteral(format!(
+                "legacy octal literal {} is not allowed in strict mode",
+                lexer.slice_from(start)
+            ))
+        } else {
+            LexerError::InvalidNumber(format!(
+                "decimal literal {} with a leading zero is not allowed in strict mode",
+                lexer.slice_from(start)
+            ))
+        });
+    }
+
+    match lexer.peek_char(0) {
+        Some('_') => {
+            return Err(LexerError::InvalidNumber(format!(
+                "numeric separator is not allowed in {}",
+                lexer.slice_from(start)
+            )))
+        }
+        Some('n') => {
+            lexer.advance_pos();
+            return Err(LexerError::InvalidBigIntLiteral(format!(
+                "{} cannot have a leading zero",
+                lexer.slice_from(start)
+            )));
+        }
+        _ => {}
+    }
+
+    if is_octal {
+        check_literal_end(lexer, start, 8)?;
+        return Ok(TokenKind::Number(digits_to_f64(
+            &lexer.slice_from(start)[1..],
+            8,
+        )));
+    }
+
+    if lexer.peek_char(0) == Some('.') {
+        lexer.advance_pos();
+        while lexer.peek_char(0).is_some_and(|c| c.is_ascii_digit()) {
+            lexer.advance_pos();
+        }
+    }
+    if matches!(lexer.peek_char(0), Some('e' | 'E')) {
+        lexer.advance_pos();
+        if matches!(lexer.peek_char(0), Some('+' | '-')) {
+            lexer.advance_pos();
+        }
+        if !lexer.peek_char(0).is_some_and(|c| c.is_ascii_digit()) {
+            return Err(LexerError::InvalidNumber(format!(
+                "missing exponent digits in {}",
+                lexer.slice_from(start)
+            )));
+        }
+        while lexer.peek_char(0).is_some_and(|c| c.is_ascii_digit()) {
+            lexer.advance_pos();
+        }
+    }
+
+    check_literal_end(lexer, start, 10)?;
+
+    let text = lexer.slice_from(start);
+    text.parse::<f64>()
+        .map(TokenKind::Number)
+        .map_err(|_| LexerError::InvalidNumber(text.to_string()))
+}
+
+// Reads one or more digits, allowing single `_` separators between them.
+fn read_digits<'a, T: LexerCore<'a>>(lexer: &mut T, radix: u32) -> Result<(), LexerError> {
+    let start = lexer.pos();
+
+    while let Some(c) = lexer.peek_char(0) {
+        if c.is_digit(radix) {
+            lexer.advance_pos();
+        } else if c == '_' {
+            let follows_digit = lexer.pos() > start;
+            let precedes_digit = lexer.peek_char(1).is_some_and(|next| next.is_digit(radix));
+            if !follows_digit || !precedes_digit {
+                lexer.advance_pos();
+                return Err(invalid_literal(
+                    radix,
+                    format!(
+                        "numeric separator must be between digits in {}",
+                        lexer.slice_from(start)
+                    ),
+                ));
             }
+            lexer.advance_pos();
         } else {
-            match number.parse::<f64>() {
-                Ok(n) => Ok(TokenKind::Number(n)),
-                Err(_) => Err(LexerError::InvalidNumber(number)),
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn read_bigint_suffix<'a, T: LexerCore<'a>>(
+    lexer: &mut T,
+    start: usize,
+) -> Result<TokenKind<'a>, LexerError> {
+    let digits_end = lexer.pos();
+    lexer.advance_pos();
+    check_literal_end(lexer, start, 10)?;
+
+    Ok(TokenKind::BigInt(strip_separators(
+        &lexer.source()[start..digits_end],
+    )))
+}
+
+// A numeric literal must not run straight into an identifier or another
+// digit, so `3in` and `0b12` are errors rather than two tokens.
+fn check_literal_end<'a, T: LexerCore<'a>>(
+    lexer: &mut T,
+    start: usize,
+    radix: u32,
+) -> Result<(), LexerError> {
+    match lexer.peek_char(0) {
+        Some(c) if c.is_ascii_digit() || c == '\\' || is_id_start(c) => {
+            while lexer
+                .peek_char(0)
+                .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+            {
+                lexer.advance_pos();
             }
+            Err(invalid_literal(radix, lexer.slice_from(start).to_string()))
         }
+        _ => Ok(()),
+    }
+}
+
+fn strip_separators(text: &str) -> Cow<'_, str> {
+    if text.contains('_') {
+        Cow::Owned(text.replace('_', ""))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+fn digits_to_f64(digits: &str, radix: u32) -> f64 {
+    match u128::from_str_radix(digits, radix) {
+        Ok(value) => value as f64,
+        Err(_) => digits.chars().fold(0.0, |value, c| {
+            value * radix as f64 + c.to_digit(radix).unwrap_or(0) as f64
+        }),
+    }
+}
+
+fn invalid_literal(radix: u32, message: String) -> LexerError {
+    match radix {
+        16 => LexerError::InvalidHexLiteral(message),
+        8 => LexerError::InvalidOctalLiteral(message),
+        2 => LexerError::InvalidBinaryLiteral(message),
+        _ => LexerError::InvalidNumber(message),
     }
 }
 
-use crate::lexer::scanners::LexerCore;
+use crate::lexer::scanners::{LexerCore, LexerCoreExt};
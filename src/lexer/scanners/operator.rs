@@ -1,16 +1,18 @@
-use crate::lexer::scanners::LexerCore;
+use crate::lexer::scanners::{LexerCore, LexerCoreExt};
 use crate::lexer::{LexerError, TokenKind};
 
-pub trait OperatorReader {
-    fn read_operator(&mut self) -> Result<TokenKind, LexerError>;
+pub trait OperatorReader<'a> {
+    fn read_operator(&mut self) -> Result<TokenKind<'a>, LexerError>;
 }
 
-impl<T> OperatorReader for T
+impl<'a, T> OperatorReader<'a> for T
 where
-    T: LexerCore,
+    T: LexerCore<'a>,
 {
-    fn read_operator(&mut self) -> Result<TokenKind, LexerError> {
-        let c = self.source()[self.pos()];
+    fn read_operator(&mut self) -> Result<TokenKind<'a>, LexerError> {
+        let Some(c) = self.peek_char(0) else {
+            return Err(LexerError::UnexpectedEndOfInput);
+        };
 
         match c {
             '(' => {
@@ -37,10 +39,11 @@ where
                 self.advance_pos();
                 Ok(TokenKind::RightBracket)
             }
-            '.' => {
-                self.advance_pos();
-                Ok(TokenKind::Dot)
-            }
+            '.' => Ok(read_longest(
+                self,
+                TokenKind::Dot,
+                &[("..", TokenKind::Spread)],
+            )),
             ';' => {
                 self.advance_pos();
                 Ok(TokenKind::Semicolon)
@@ -53,63 +56,140 @@ where
                 self.advance_pos();
                 Ok(TokenKind::Colon)
             }
-            '?' => {
+            // `a?.5:b` is a conditional, not optional chaining.
+            '?' if self.peek_char(1) == Some('.')
+                && self.peek_char(2).is_some_and(|c| c.is_ascii_digit()) =>
+            {
                 self.advance_pos();
                 Ok(TokenKind::Question)
             }
-            '!' => {
-                self.advance_pos();
-                Ok(TokenKind::Exclamation)
-            }
+            '?' => Ok(read_longest(
+                self,
+                TokenKind::Question,
+                &[
+                    ("?", TokenKind::NullishCoalescing),
+                    (".", TokenKind::OptionalChaining),
+                ],
+            )),
+            '!' => Ok(read_longest(
+                self,
+                TokenKind::Exclamation,
+                &[
+                    ("==", TokenKind::StrictNotEqual),
+                    ("=", TokenKind::NotEqual),
+                ],
+            )),
             '~' => {
                 self.advance_pos();
                 Ok(TokenKind::Tilde)
             }
-            '=' => {
-                self.advance_pos();
-                Ok(TokenKind::Assign)
-            }
-            '+' => {
-                self.advance_pos();
-                Ok(TokenKind::Plus)
-            }
-            '-' => {
-                self.advance_pos();
-                Ok(TokenKind::Minus)
-            }
-            '*' => {
-                self.advance_pos();
-                Ok(TokenKind::Star)
-            }
-            '/' => {
-                self.advance_pos();
-                Ok(TokenKind::Slash)
-            }
-            '%' => {
-                self.advance_pos();
-                Ok(TokenKind::Percent)
-            }
-            '<' => {
-                self.advance_pos();
-                Ok(TokenKind::LessThan)
-            }
-            '>' => {
-                self.advance_pos();
-                Ok(TokenKind::GreaterThan)
-            }
-            '&' => {
-                self.advance_pos();
-                Ok(TokenKind::BitwiseAnd)
-            }
-            '|' => {
-                self.advance_pos();
-                Ok(TokenKind::BitwiseOr)
-            }
-            '^' => {
-                self.advance_pos();
-                Ok(TokenKind::BitwiseXor)
-            }
+            '=' => Ok(read_longest(
+                self,
+                TokenKind::Assign,
+                &[
+                    ("==", TokenKind::StrictEqual),
+                    ("=", TokenKind::Equal),
+                    (">", TokenKind::Arrow),
+                ],
+            )),
+            '+' => Ok(read_longest(
+                self,
+                TokenKind::Plus,
+                &[("+", TokenKind::Increment), ("=", TokenKind::PlusAssign)],
+            )),
+            '-' => Ok(read_longest(
+                self,
+                TokenKind::Minus,
+                &[("-", TokenKind::Decrement), ("=", TokenKind::MinusAssign)],
+            )),
+            '*' => Ok(read_longest(
+                self,
+                TokenKind::Star,
+                &[
+                    ("*=", TokenKind::StarStarAssign),
+                    ("*", TokenKind::StarStar),
+                    ("=", TokenKind::StarAssign),
+                ],
+            )),
+            '/' => Ok(read_longest(
+                self,
+                TokenKind::Slash,
+                &[("=", TokenKind::SlashAssign)],
+            )),
+            '%' => Ok(read_longest(
+                self,
+                TokenKind::Percent,
+                &[("=", TokenKind::PercentAssign)],
+            )),
+            '<' => Ok(read_longest(
+                self,
+                TokenKind::LessThan,
+                &[
+                    ("<=", TokenKind::LeftShiftAssign),
+                    ("<", TokenKind::LeftShift),
+                    ("=", TokenKind::LessThanEqual),
+                ],
+            )),
+            '>' => Ok(read_longest(
+                self,
+                TokenKind::GreaterThan,
+                &[
+                    (">>=", TokenKind::UnsignedRightShiftAssign),
+                    (">>", TokenKind::UnsignedRightShift),
+                    (">=", TokenKind::RightShiftAssign),
+                    (">", TokenKind::RightShift),
+                    ("=", TokenKind::GreaterThanEqual),
+                ],
+            )),
+            '&' => Ok(read_longest(
+                self,
+                TokenKind::BitwiseAnd,
+                &[
+                    ("&", TokenKind::LogicalAnd),
+                    ("=", TokenKind::BitwiseAndAssign),
+                ],
+            )),
+            '|' => Ok(read_longest(
+                self,
+                TokenKind::BitwiseOr,
+                &[
+                    ("|", TokenKind::LogicalOr),
+                    ("=", TokenKind::BitwiseOrAssign),
+                ],
+            )),
+            '^' => Ok(read_longest(
+                self,
+                TokenKind::BitwiseXor,
+                &[("=", TokenKind::BitwiseXorAssign)],
+            )),
             _ => Err(LexerError::UnexpectedCharacter(c)),
         }
     }
 }
+
+// Consumes the operator's first character, then the longest of `longer` that
+// continues it, so `>>>=` is one token rather than four. `longer` lists the
+// continuations longest first.
+fn read_longest<'a, T>(
+    lexer: &mut T,
+    single: TokenKind<'a>,
+    longer: &[(&str, TokenKind<'a>)],
+) -> TokenKind<'a>
+where
+    T: LexerCore<'a>,
+{
+    lexer.advance_pos();
+    for (rest, kind) in longer {
+        if rest
+            .chars()
+            .enumerate()
+            .all(|(offset, c)| lexer.peek_char(offset) == Some(c))
+        {
+            for _ in rest.chars() {
+                lexer.advance_pos();
+            }
+            return kind.clone();
+        }
+    }
+    single
+}
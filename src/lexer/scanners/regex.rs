@@ -0,0 +1,133 @@
+use crate::lexer::scanners::{LexerCore, LexerCoreExt};
+use crate::lexer::tokens::Keyword;
+use crate::lexer::{LexerError, TokenKind};
+
+pub trait RegexReader<'a> {
+    fn read_regex(&mut self) -> Result<TokenKind<'a>, LexerError>;
+}
+
+impl<'a, T> RegexReader<'a> for T
+where
+    T: LexerCore<'a>,
+{
+    fn read_regex(&mut self) -> Result<TokenKind<'a>, LexerError> {
+        self.advance_pos();
+
+        let start = self.pos();
+        let mut in_class = false;
+
+        loop {
+            let c = match self.peek_char(0) {
+                Some(c) if !is_line_terminator(c) => c,
+                _ => {
+                    return Err(LexerError::InvalidRegexLiteral(format!(
+                        "unterminated regular expression /{}",
+                        self.slice_from(start)
+                    )))
+                }
+            };
+
+            if c == '/' && !in_class {
+                break;
+            }
+
+            match c {
+                '\\' => {
+                    self.advance_pos();
+                    match self.peek_char(0) {
+                        Some(escaped) if !is_line_terminator(escaped) => self.advance_pos(),
+                        _ => {
+                            return Err(LexerError::InvalidRegexLiteral(format!(
+                                "unterminated regular expression /{}",
+                                self.slice_from(start)
+                            )))
+                        }
+                    }
+                    continue;
+                }
+                '[' => in_class = true,
+                ']' => in_class = false,
+                _ => {}
+            }
+
+            self.advance_pos();
+        }
+
+        let pattern = self.slice_from(start);
+        self.advance_pos();
+
+        let flags_start = self.pos();
+        while let Some(c) = self.peek_char(0) {
+            if c.is_alphanumeric() || c == '_' || c == '$' || c == '\\' {
+                self.advance_pos();
+            } else {
+                break;
+            }
+        }
+        let flags = self.slice_from(flags_start);
+
+        validate_flags(flags)?;
+
+        Ok(TokenKind::RegExp { pattern, flags })
+    }
+}
+
+// A `/` after a token that can end an expression is division; anywhere
+// else (including after `}`) it starts a regular expression literal.
+// Contextual keywords such as `of`, `as` and `from` are taken as keywords
+// here, so `for (x of /re/g)` reads a regular expression.
+pub fn regex_allowed_after(previous: Option<&TokenKind>) -> bool {
+    match previous {
+        None => true,
+        Some(kind) => !matches!(
+            kind,
+            TokenKind::Identifier(_)
+                | TokenKind::PrivateName(_)
+                | TokenKind::Number(_)
+                | TokenKind::String(_)
+                | TokenKind::BigInt(_)
+                | TokenKind::Boolean(_)
+                | TokenKind::Null
+                | TokenKind::Undefined
+                | TokenKind::RegExp { .. }
+                | TokenKind::NoSubstitutionTemplate { .. }
+                | TokenKind::TemplateTail { .. }
+                | TokenKind::Literal(_)
+                | TokenKind::RightParen
+                | TokenKind::RightBracket
+                | TokenKind::Increment
+                | TokenKind::Decrement
+                | TokenKind::Keyword(Keyword::This | Keyword::Super)
+        ),
+    }
+}
+
+fn validate_flags(flags: &str) -> Result<(), LexerError> {
+    let mut seen = Vec::new();
+
+    for flag in flags.chars() {
+        if !matches!(flag, 'd' | 'g' | 'i' | 'm' | 's' | 'u' | 'v' | 'y') {
+            return Err(LexerError::InvalidRegexFlags(format!(
+                "unknown flag '{flag}' in '{flags}'"
+            )));
+        }
+        if seen.contains(&flag) {
+            return Err(LexerError::InvalidRegexFlags(format!(
+                "duplicate flag '{flag}' in '{flags}'"
+            )));
+        }
+        seen.push(flag);
+    }
+
+    if seen.contains(&'u') && seen.contains(&'v') {
+        return Err(LexerError::InvalidRegexFlags(format!(
+            "flags 'u' and 'v' cannot be combined in '{flags}'"
+        )));
+    }
+
+    Ok(())
+}
+
+fn is_line_terminator(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
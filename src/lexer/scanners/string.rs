@@ -1,112 +1,364 @@
+use crate::lexer::scanners::read_unicode_escape_digits;
 use crate::lexer::{LexerError, TokenKind};
+use std::borrow::Cow;
 
-pub trait StringReader {
-    fn read_string(&mut self) -> Result<TokenKind, LexerError>;
-    fn read_template_string(&mut self) -> Result<TokenKind, LexerError>;
+pub trait StringReader<'a> {
+    fn read_string(&mut self) -> Result<TokenKind<'a>, LexerError>;
+    fn read_template(&mut self) -> Result<TokenKind<'a>, LexerError>;
 }
 
-impl<T> StringReader for T
+impl<'a, T> StringReader<'a> for T
 where
-    T: LexerCore,
+    T: LexerCore<'a>,
 {
-    fn read_string(&mut self) -> Result<TokenKind, LexerError> {
-        let quote = self.source()[self.pos()];
+    fn read_string(&mut self) -> Result<TokenKind<'a>, LexerError> {
+        let quote = self.peek_char(0).unwrap_or_default();
         self.advance_pos();
 
-        let mut string = String::new();
-        let mut found_closing_quote = false;
+        let start = self.pos();
+        let mut decoded: Option<String> = None;
 
-        while self.pos() < self.source().len() {
-            let c = self.source()[self.pos()];
+        loop {
+            let c = match self.peek_char(0) {
+                Some('\n' | '\r') | None => return Err(LexerError::UnterminatedString),
+                Some(c) => c,
+            };
 
             if c == quote {
+                let value = match decoded {
+                    Some(value) => Cow::Owned(value),
+                    None => Cow::Borrowed(self.slice_from(start)),
+                };
                 self.advance_pos();
-                found_closing_quote = true;
-                break;
-            } else if c == '\\' {
+                return Ok(TokenKind::String(value));
+            }
+
+            if c == '\\' {
+                let value = decoded.get_or_insert_with(|| self.slice_from(start).to_string());
+                read_escape_sequence(self, value, EscapeContext::String)?;
+            } else {
+                if let Some(value) = decoded.as_mut() {
+                    value.push(c);
+                }
                 self.advance_pos();
-                if self.pos() < self.source().len() {
-                    let escaped = self.source()[self.pos()];
-                    match escaped {
-                        'n' => string.push('\n'),
-                        't' => string.push('\t'),
-                        'r' => string.push('\r'),
-                        '\\' => string.push('\\'),
-                        '"' => string.push('"'),
-                        '\'' => string.push('\''),
-                        _ => string.push(escaped),
+            }
+        }
+    }
+
+    // Reads one template piece, starting either at the opening backtick or at
+    // the `}` that closes a substitution.
+    fn read_template(&mut self) -> Result<TokenKind<'a>, LexerError> {
+        let template_start = self.pos();
+        let is_head = self.peek_char(0) == Some('`');
+        self.advance_pos();
+
+        let start = self.pos();
+        let mut decoded: Option<String> = None;
+        let mut cooked_valid = true;
+
+        loop {
+            let Some(c) = self.peek_char(0) else {
+                self.report_error_at(template_start);
+                return Err(LexerError::UnterminatedTemplateString);
+            };
+
+            let ends_with_substitution = c == '$' && self.peek_char(1) == Some('{');
+            if c == '`' || ends_with_substitution {
+                let raw = &self.source()[start..self.pos()];
+                self.advance_pos();
+                if ends_with_substitution {
+                    self.advance_pos();
+                }
+
+                let cooked = cooked_valid.then_some(match decoded {
+                    Some(value) => Cow::Owned(value),
+                    None => Cow::Borrowed(raw),
+                });
+                let raw = normalize_line_endings(raw);
+
+                return Ok(match (is_head, ends_with_substitution) {
+                    (true, false) => TokenKind::NoSubstitutionTemplate { cooked, raw },
+                    (true, true) => TokenKind::TemplateHead { cooked, raw },
+                    (false, true) => TokenKind::TemplateMiddle { cooked, raw },
+                    (false, false) => TokenKind::TemplateTail { cooked, raw },
+                });
+            }
+
+            match c {
+                '\\' => {
+                    let value = decoded.get_or_insert_with(|| self.slice_from(start).to_string());
+                    // Tagged templates may contain malformed escapes; the
+                    // parser rejects them when the template is untagged.
+                    if read_escape_sequence(self, value, EscapeContext::Template).is_err() {
+                        cooked_valid = false;
+                    }
+                }
+                '\r' => {
+                    decoded
+                        .get_or_insert_with(|| self.slice_from(start).to_string())
+                        .push('\n');
+                    self.advance_pos();
+                    if self.peek_char(0) == Some('\n') {
+                        self.advance_pos();
+                    }
+                }
+                _ => {
+                    if let Some(value) = decoded.as_mut() {
+                        value.push(c);
                     }
                     self.advance_pos();
                 }
-            } else {
-                string.push(c);
-                self.advance_pos();
             }
         }
+    }
+}
 
-        if !found_closing_quote {
-            return Err(LexerError::UnterminatedString);
+fn normalize_line_endings(raw: &str) -> Cow<'_, str> {
+    if raw.contains('\r') {
+        Cow::Owned(raw.replace("\r\n", "\n").replace('\r', "\n"))
+    } else {
+        Cow::Borrowed(raw)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EscapeContext {
+    String,
+    Template,
+}
+
+// Decodes one escape sequence starting at the backslash and appends it to
+// `out`. Legacy octal escapes are only accepted in sloppy-mode strings.
+pub fn read_escape_sequence<'a, T: LexerCore<'a>>(
+    lexer: &mut T,
+    out: &mut String,
+    context: EscapeContext,
+) -> Result<(), LexerError> {
+    let start = lexer.pos();
+    lexer.advance_pos();
+
+    let Some(c) = lexer.peek_char(0) else {
+        return Err(match context {
+            EscapeContext::String => LexerError::UnterminatedString,
+            EscapeContext::Template => LexerError::UnterminatedTemplateString,
+        });
+    };
+    lexer.advance_pos();
+
+    match c {
+        'n' => out.push('\n'),
+        't' => out.push('\t'),
+        'r' => out.push('\r'),
+        'b' => out.push('\u{8}'),
+        'f' => out.push('\u{C}'),
+        'v' => out.push('\u{B}'),
+        '\r' => {
+            if lexer.peek_char(0) == Some('\n') {
+                lexer.advance_pos();
+            }
         }
+        '\n' | '\u{2028}' | '\u{2029}' => {}
+        '0' if !lexer.peek_char(0).is_some_and(|c| c.is_ascii_digit()) => out.push('\0'),
+        '0'..='7' => {
+            if context == EscapeContext::Template || lexer.strict_mode() {
+                lexer.report_error_at(start);
+                return Err(LexerError::InvalidOctalEscape(format!(
+                    "\\{c} is not allowed in {}",
+                    context.description(lexer.strict_mode())
+                )));
+            }
 
-        Ok(TokenKind::String(string))
+            // At most three digits, and only while the value fits in a byte.
+            let mut value = c.to_digit(8).unwrap_or_default();
+            let max_digits = if c <= '3' { 3 } else { 2 };
+            for _ in 1..max_digits {
+                match lexer.peek_char(0).and_then(|c| c.to_digit(8)) {
+                    Some(digit) => {
+                        value = value * 8 + digit;
+                        lexer.advance_pos();
+                    }
+                    None => break,
+                }
+            }
+            out.push(char::from_u32(value).unwrap_or_default());
+        }
+        '8' | '9' => {
+            if context == EscapeContext::Template || lexer.strict_mode() {
+                lexer.report_error_at(start);
+                return Err(LexerError::InvalidEscapeSequence(format!(
+                    "\\{c} is not allowed in {}",
+                    context.description(lexer.strict_mode())
+                )));
+            }
+            out.push(c);
+        }
+        'x' => {
+            let mut value = 0;
+            for _ in 0..2 {
+                match lexer.peek_char(0).and_then(|c| c.to_digit(16)) {
+                    Some(digit) => {
+                        value = value * 16 + digit;
+                        lexer.advance_pos();
+                    }
+                    None => {
+                        lexer.report_error_at(start);
+                        return Err(LexerError::InvalidHexEscape(
+                            lexer.source()[start..lexer.pos()].to_string(),
+                        ));
+                    }
+                }
+            }
+            out.push(char::from_u32(value).unwrap_or_default());
+        }
+        'u' => {
+            let code_point = read_unicode_escape_digits(lexer).inspect_err(|_| {
+                lexer.report_error_at(start);
+            })?;
+            push_code_unit(lexer, out, code_point);
+        }
+        _ => out.push(c),
     }
 
-    fn read_template_string(&mut self) -> Result<TokenKind, LexerError> {
-        self.advance_pos();
+    Ok(())
+}
 
-        let mut template = String::new();
+// Combines a `\uD83D\uDE00` style surrogate pair into one character. Lone
+// surrogates cannot be represented in a Rust string and become U+FFFD.
+fn push_code_unit<'a, T: LexerCore<'a>>(lexer: &mut T, out: &mut String, code_point: u32) {
+    if (0xD800..0xDC00).contains(&code_point) {
+        if let Some((low @ 0xDC00..=0xDFFF, len)) = peek_unicode_escape(lexer) {
+            for _ in 0..len {
+                lexer.advance_pos();
+            }
+            let combined = 0x10000 + ((code_point - 0xD800) << 10) + (low - 0xDC00);
+            out.push(char::from_u32(combined).unwrap_or(char::REPLACEMENT_CHARACTER));
+            return;
+        }
+    }
 
-        while self.pos() < self.source().len() {
-            let c = self.source()[self.pos()];
+    out.push(char::from_u32(code_point).unwrap_or(char::REPLACEMENT_CHARACTER));
+}
 
-            if c == '`' {
-                self.advance_pos();
-                break;
-            } else if c == '$' && self.peek_char(1) == Some('{') {
-                template.push_str("${");
-                self.advance_pos();
-                self.advance_pos();
-            } else if c == '\\' {
-                self.advance_pos();
-                if self.pos() < self.source().len() {
-                    let escaped = self.source()[self.pos()];
-                    match escaped {
-                        'n' => template.push('\n'),
-                        't' => template.push('\t'),
-                        'r' => template.push('\r'),
-                        '\\' => template.push('\\'),
-                        '`' => template.push('`'),
-                        '$' => template.push('$'),
-                        _ => template.push(escaped),
-                    }
-                    self.advance_pos();
+// Looks ahead for a well-formed `\uXXXX` or `\u{X...}` without consuming it,
+// returning the code point and the escape's length in characters.
+fn peek_unicode_escape<'a, T: LexerCore<'a>>(lexer: &T) -> Option<(u32, usize)> {
+    if lexer.peek_char(0) != Some('\\') || lexer.peek_char(1) != Some('u') {
+        return None;
+    }
+
+    let braced = lexer.peek_char(2) == Some('{');
+    let first = if braced { 3 } else { 2 };
+    let mut value = 0u32;
+    let mut len = first;
+
+    loop {
+        match lexer.peek_char(len) {
+            Some('}') if braced && len > first => return Some((value, len + 1)),
+            Some(c) if c.is_ascii_hexdigit() && len - first < 6 => {
+                value = value * 16 + c.to_digit(16).unwrap_or_default();
+                len += 1;
+                if !braced && len - first == 4 {
+                    return Some((value, len));
                 }
-            } else {
-                template.push(c);
-                self.advance_pos();
             }
+            _ => return None,
         }
+    }
+}
 
-        Ok(TokenKind::TemplateString(template))
+impl EscapeContext {
+    fn description(self, strict_mode: bool) -> &'static str {
+        match self {
+            EscapeContext::Template => "template literals",
+            EscapeContext::String if strict_mode => "strict mode",
+            EscapeContext::String => "string literals",
+        }
     }
 }
 
 use crate::lexer::scanners::LexerCore;
 
-pub trait LexerCoreExt {
+pub trait LexerCoreExt<'a> {
     fn peek_char(&self, offset: usize) -> Option<char>;
+    fn slice_from(&self, start: usize) -> &'a str;
 }
 
-impl<T> LexerCoreExt for T
+impl<'a, T> LexerCoreExt<'a> for T
 where
-    T: LexerCore,
+    T: LexerCore<'a>,
 {
     fn peek_char(&self, offset: usize) -> Option<char> {
-        let pos = self.pos() + offset;
-        if pos < self.source().len() {
-            Some(self.source()[pos])
-        } else {
-            None
-        }
+        self.source()[self.pos()..].chars().nth(offset)
+    }
+
+    fn slice_from(&self, start: usize) -> &'a str {
+        &self.source()[start..self.pos()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::{Lexer, LexerError, TokenKind};
+
+    fn lex_string(source: &str) -> Result<TokenKind<'_>, LexerError> {
+        Lexer::new(source).next_token().map(|token| token.kind)
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let decoded = |source| match lex_string(source) {
+            Ok(TokenKind::String(value)) => value.into_owned(),
+            other => panic!("expected string for {source}, got {other:?}"),
+        };
+        assert_eq!(decoded(r"'\x41\u0042\u{43}'"), "ABC");
+        assert_eq!(decoded(r"'\b\f\v\0'"), "\u{8}\u{C}\u{B}\0");
+        assert_eq!(decoded(r"'\uD83D\uDE00 \u{1F600}'"), "😀 😀");
+        assert_eq!(decoded(r"'\uD83D!'"), "\u{FFFD}!");
+        assert_eq!(decoded("'a\\\nb\\\r\nc'"), "abc");
+        assert_eq!(decoded(r"'\q\'\\'"), "q'\\");
+        assert_eq!(decoded(r"'\101\0101\400\8'"), "A\u{8}1 08");
+    }
+
+    #[test]
+    fn test_string_raw_text_is_kept() {
+        let source = r#""\x41""#;
+        let token = Lexer::new(source).next_token().unwrap();
+        assert!(matches!(token.kind, TokenKind::String(ref s) if s == "A"));
+        assert_eq!(token.raw(source), source);
+    }
+
+    #[test]
+    fn test_invalid_string_escapes() {
+        assert!(matches!(
+            lex_string(r"'\x4'"),
+            Err(LexerError::InvalidHexEscape(_))
+        ));
+        assert!(matches!(
+            lex_string(r"'\u12'"),
+            Err(LexerError::InvalidUnicodeEscape(_))
+        ));
+        assert!(matches!(
+            lex_string("'a\nb'"),
+            Err(LexerError::UnterminatedString)
+        ));
+        assert!(matches!(
+            lex_string("`\\1`"),
+            Ok(TokenKind::NoSubstitutionTemplate { cooked: None, .. })
+        ));
+        assert!(matches!(
+            lex_string("`\\8`"),
+            Ok(TokenKind::NoSubstitutionTemplate { cooked: None, .. })
+        ));
+
+        let strict = |source| Lexer::new(source).with_strict_mode(true).next_token();
+        assert!(matches!(
+            strict(r"'\012'"),
+            Err(LexerError::InvalidOctalEscape(_))
+        ));
+        assert!(matches!(
+            strict(r"'\9'"),
+            Err(LexerError::InvalidEscapeSequence(_))
+        ));
+        assert!(strict(r"'\0'").is_ok());
     }
 }
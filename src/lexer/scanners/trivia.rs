@@ -0,0 +1,123 @@
+use crate::lexer::scanners::{LexerCore, LexerCoreExt};
+use crate::lexer::token::{Trivia, TriviaKind};
+use crate::lexer::LexerError;
+
+pub trait TriviaReader<'a> {
+    fn read_leading_trivia(&mut self) -> Result<Vec<Trivia<'a>>, LexerError>;
+    fn read_trailing_trivia(&mut self) -> Result<Vec<Trivia<'a>>, LexerError>;
+}
+
+impl<'a, T> TriviaReader<'a> for T
+where
+    T: LexerCore<'a> + LexerCoreExt<'a>,
+{
+    fn read_leading_trivia(&mut self) -> Result<Vec<Trivia<'a>>, LexerError> {
+        let mut trivia = Vec::new();
+        while let Some(piece) = read_trivia_piece(self)? {
+            trivia.push(piece);
+        }
+        Ok(trivia)
+    }
+
+    // Trailing trivia stops after the first line break, so everything on the
+    // following lines becomes leading trivia of the next token.
+    fn read_trailing_trivia(&mut self) -> Result<Vec<Trivia<'a>>, LexerError> {
+        let mut trivia = Vec::new();
+        while let Some(piece) = read_trivia_piece(self)? {
+            let ends_line = piece.kind == TriviaKind::Newline;
+            trivia.push(piece);
+            if ends_line {
+                break;
+            }
+        }
+        Ok(trivia)
+    }
+}
+
+fn read_trivia_piece<'a, T>(lexer: &mut T) -> Result<Option<Trivia<'a>>, LexerError>
+where
+    T: LexerCore<'a> + LexerCoreExt<'a>,
+{
+    let start = lexer.pos();
+    let kind = match (lexer.peek_char(0), lexer.peek_char(1)) {
+        (Some('\r'), Some('\n')) => {
+            lexer.advance_pos();
+            lexer.advance_pos();
+            TriviaKind::Newline
+        }
+        (Some(c), _) if is_line_terminator(c) => {
+            lexer.advance_pos();
+            TriviaKind::Newline
+        }
+        (Some('#'), Some('!')) if start == 0 => {
+            skip_to_line_end(lexer);
+            TriviaKind::Hashbang
+        }
+        (Some('<'), Some('!'))
+            if lexer.html_comments_allowed()
+                && lexer.peek_char(2) == Some('-')
+                && lexer.peek_char(3) == Some('-') =>
+        {
+            skip_to_line_end(lexer);
+            TriviaKind::HtmlComment
+        }
+        (Some('-'), Some('-'))
+            if lexer.html_comments_allowed()
+                && lexer.at_line_start()
+                && lexer.peek_char(2) == Some('>') =>
+        {
+            skip_to_line_end(lexer);
+            TriviaKind::HtmlComment
+        }
+        (Some(c), _) if is_trivia_whitespace(c) => {
+            while lexer.peek_char(0).is_some_and(is_trivia_whitespace) {
+                lexer.advance_pos();
+            }
+            TriviaKind::Whitespace
+        }
+        (Some('/'), Some('/')) => {
+            skip_to_line_end(lexer);
+            TriviaKind::LineComment
+        }
+        (Some('/'), Some('*')) => {
+            lexer.advance_pos();
+            lexer.advance_pos();
+            loop {
+                match lexer.peek_char(0) {
+                    Some('*') if lexer.peek_char(1) == Some('/') => {
+                        lexer.advance_pos();
+                        lexer.advance_pos();
+                        break;
+                    }
+                    Some(_) => lexer.advance_pos(),
+                    None => {
+                        lexer.report_error_at(start);
+                        return Err(LexerError::UnterminatedComment);
+                    }
+                }
+            }
+            TriviaKind::BlockComment
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(Trivia {
+        kind,
+        text: lexer.slice_from(start),
+        range: start..lexer.pos(),
+    }))
+}
+
+fn skip_to_line_end<'a, T: LexerCore<'a>>(lexer: &mut T) {
+    while lexer.peek_char(0).is_some_and(|c| !is_line_terminator(c)) {
+        lexer.advance_pos();
+    }
+}
+
+fn is_trivia_whitespace(c: char) -> bool {
+    (c.is_whitespace() || c == '\u{FEFF}') && !is_line_terminator(c)
+}
+
+fn is_line_terminator(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
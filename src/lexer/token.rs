@@ -1,26 +1,39 @@
 use crate::ast::common::{Position, Span};
 use crate::lexer::tokens::{Keyword, Literal, Operator, Punctuation};
+use crate::lexer::LexerError;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::ops::Range;
 
+// Payloads borrow from the source text and are only owned when the lexer had
+// to decode escape sequences or strip numeric separators.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub enum TokenKind {
+pub enum TokenKind<'a> {
     // Direct variants for backward compatibility
-    Identifier(String),
+    Identifier(Cow<'a, str>),
+    // `#name` in a class body or member access; the payload omits the `#`.
+    PrivateName(Cow<'a, str>),
     Keyword(Keyword),
     Operator(Operator),
     Literal(Literal),
     Punctuation(Punctuation),
-    Comment(String),
+    Comment(&'a str),
     Whitespace,
     Eof,
+    // Produced instead of failing when the lexer runs with `with_recovery(true)`.
+    Error(LexerError),
 
     // Literal variants
     Number(f64),
-    String(String),
+    String(Cow<'a, str>),
     Boolean(bool),
     Null,
     Undefined,
-    BigInt(String),
+    BigInt(Cow<'a, str>),
+    RegExp {
+        pattern: &'a str,
+        flags: &'a str,
+    },
 
     // Direct token variants for lexer and parser compatibility
     // Assignment operators
@@ -99,22 +112,112 @@ pub enum TokenKind {
     TemplateStart,
     TemplateEnd,
     TemplateExpr,
-    TemplateString(String),
+
+    // Template literal pieces. `cooked` is `None` when the quasi contains an
+    // escape that is only legal in tagged templates.
+    NoSubstitutionTemplate {
+        cooked: Option<Cow<'a, str>>,
+        raw: Cow<'a, str>,
+    },
+    TemplateHead {
+        cooked: Option<Cow<'a, str>>,
+        raw: Cow<'a, str>,
+    },
+    TemplateMiddle {
+        cooked: Option<Cow<'a, str>>,
+        raw: Cow<'a, str>,
+    },
+    TemplateTail {
+        cooked: Option<Cow<'a, str>>,
+        raw: Cow<'a, str>,
+    },
+
+    // Only produced while the parser has the lexer in a JSX mode. Names may
+    // contain `-`, and neither text nor strings have escapes.
+    #[cfg(feature = "jsx")]
+    JsxName(&'a str),
+    #[cfg(feature = "jsx")]
+    JsxString(&'a str),
+    #[cfg(feature = "jsx")]
+    JsxText(&'a str),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Token {
-    pub kind: TokenKind,
+pub struct Token<'a> {
+    #[serde(borrow)]
+    pub kind: TokenKind<'a>,
     pub span: Span,
+    pub range: Range<usize>,
+    pub newline_before: bool,
+    // Only filled in when the lexer runs with `with_trivia(true)`.
+    #[serde(borrow, default)]
+    pub leading_trivia: Vec<Trivia<'a>>,
+    #[serde(borrow, default)]
+    pub trailing_trivia: Vec<Trivia<'a>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TriviaKind {
+    Whitespace,
+    Newline,
+    LineComment,
+    BlockComment,
+    // Annex B `<!--` and `-->` single-line comments, script goal only.
+    HtmlComment,
+    Hashbang,
+}
+
+// A run of source text between tokens. `text` includes comment delimiters, so
+// concatenating every token's trivia and raw text reproduces the source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trivia<'a> {
+    pub kind: TriviaKind,
+    pub text: &'a str,
+    pub range: Range<usize>,
 }
 
-impl Token {
-    pub fn new(kind: TokenKind, span: Span) -> Self {
-        Self { kind, span }
+impl Trivia<'_> {
+    pub fn is_comment(&self) -> bool {
+        matches!(
+            self.kind,
+            TriviaKind::LineComment
+                | TriviaKind::BlockComment
+                | TriviaKind::HtmlComment
+                | TriviaKind::Hashbang
+        )
+    }
+}
+
+impl<'a> Token<'a> {
+    pub fn new(kind: TokenKind<'a>, span: Span) -> Self {
+        Self {
+            kind,
+            span,
+            range: 0..0,
+            newline_before: false,
+            leading_trivia: Vec::new(),
+            trailing_trivia: Vec::new(),
+        }
+    }
+
+    pub fn with_range(mut self, range: Range<usize>) -> Self {
+        self.range = range;
+        self
+    }
+
+    pub fn with_newline_before(mut self, newline_before: bool) -> Self {
+        self.newline_before = newline_before;
+        self
+    }
+
+    pub fn with_trivia(mut self, leading: Vec<Trivia<'a>>, trailing: Vec<Trivia<'a>>) -> Self {
+        self.leading_trivia = leading;
+        self.trailing_trivia = trailing;
+        self
     }
 
     pub fn with_positions(
-        kind: TokenKind,
+        kind: TokenKind<'a>,
         start_line: usize,
         start_col: usize,
         end_line: usize,
@@ -132,6 +235,12 @@ impl Token {
         self.span.end
     }
 
+    // The token's text as written, e.g. a string literal with its quotes and
+    // undecoded escapes.
+    pub fn raw<'s>(&self, source: &'s str) -> &'s str {
+        &source[self.range.clone()]
+    }
+
     pub fn is_keyword(&self) -> bool {
         matches!(self.kind, TokenKind::Keyword(_))
     }
@@ -215,7 +324,16 @@ impl Token {
                 | TokenKind::TemplateStart
                 | TokenKind::TemplateEnd
                 | TokenKind::TemplateExpr
-                | TokenKind::TemplateString(_)
+        )
+    }
+
+    pub fn is_template(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::NoSubstitutionTemplate { .. }
+                | TokenKind::TemplateHead { .. }
+                | TokenKind::TemplateMiddle { .. }
+                | TokenKind::TemplateTail { .. }
         )
     }
 
@@ -223,6 +341,10 @@ impl Token {
         matches!(self.kind, TokenKind::Comment(_))
     }
 
+    pub fn is_error(&self) -> bool {
+        matches!(self.kind, TokenKind::Error(_))
+    }
+
     pub fn is_whitespace(&self) -> bool {
         matches!(self.kind, TokenKind::Whitespace)
     }
@@ -8,7 +8,7 @@ pub trait PositionManager {
 }
 
 pub trait PositionCore {
-    fn source(&self) -> &[char];
+    fn source(&self) -> &str;
     fn pos(&self) -> usize;
     fn line(&self) -> LineNumber;
     fn column(&self) -> ColumnNumber;
@@ -22,19 +22,8 @@ where
     T: PositionCore,
 {
     fn skip_whitespace(&mut self) {
-        while self.pos() < self.source().len() {
-            let c = self.source()[self.pos()];
-            if c.is_whitespace() {
-                if c == '\n' {
-                    let mut new_line = self.line();
-                    new_line += 1;
-                    self.set_line(new_line);
-                    self.set_column(ColumnNumber::new(1));
-                } else {
-                    let mut new_column = self.column();
-                    new_column += 1;
-                    self.set_column(new_column);
-                }
+        while let Some(c) = self.peek_char(0) {
+            if c.is_whitespace() || c == '\u{FEFF}' {
                 self.advance();
             } else {
                 break;
@@ -42,9 +31,27 @@ where
         }
     }
 
+    // Positions are byte offsets into the source; lines and columns are kept
+    // in step so tokens can still report human-readable locations.
     fn advance(&mut self) {
-        if self.pos() < self.source().len() {
-            self.set_pos(self.pos() + 1);
+        let Some(c) = self.peek_char(0) else {
+            return;
+        };
+
+        self.set_pos(self.pos() + c.len_utf8());
+
+        let ends_line = match c {
+            '\r' => self.peek_char(0) != Some('\n'),
+            '\n' | '\u{2028}' | '\u{2029}' => true,
+            _ => false,
+        };
+
+        if ends_line {
+            let mut new_line = self.line();
+            new_line += 1;
+            self.set_line(new_line);
+            self.set_column(ColumnNumber::new(1));
+        } else {
             let mut new_column = self.column();
             new_column += 1;
             self.set_column(new_column);
@@ -52,11 +59,7 @@ where
     }
 
     fn peek_char(&self, offset: usize) -> Option<char> {
-        if self.pos() + offset < self.source().len() {
-            Some(self.source()[self.pos() + offset])
-        } else {
-            None
-        }
+        self.source()[self.pos()..].chars().nth(offset)
     }
 
     fn update_position(&mut self, _start_line: LineNumber, _start_col: ColumnNumber) {}
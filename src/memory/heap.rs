@@ -157,6 +157,7 @@ impl MemoryHeap {
             Value::Boolean(_) => ObjectSize::new(1),
             Value::Undefined | Value::Null => ObjectSize::new(0),
             Value::Object(_) | Value::Array(_) | Value::Function(_) => ObjectSize::new(8),
+            Value::PrivateName(private) => ObjectSize::new(8 + private.name.len()),
         }
     }
 }
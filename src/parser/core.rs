@@ -1,111 +1,281 @@
 use crate::ast::{
-    ArrayLiteral, ArrowFunctionExpression, BinaryExpression, BlockStatement, DebuggerStatement,
-    DoWhileStatement, ExportDeclaration, ExpressionStatement, ForStatement, IfStatement,
-    ImportDeclaration, Node, ObjectLiteral, Position, Program, Property, ReturnStatement, Span,
-    SpreadElement, SwitchCase, SwitchStatement, TemplateElement, TemplateLiteral, WhileStatement,
-    WithStatement,
+    ArrayLiteral, ArrowFunctionExpression, AssignmentExpression, BigIntLiteral, BinaryExpression,
+    BlockStatement, BooleanLiteral, DebuggerStatement, DoWhileStatement, EmptyStatement, ErrorNode,
+    ExpressionStatement, ForInStatement, ForOfStatement, ForStatement, Identifier, IfStatement,
+    Node, NullLiteral, NumberLiteral, ObjectLiteral, Position, PrivateIdentifier, Program,
+    Property, RegExp, ReturnStatement, Span, SpreadElement, StringLiteral, SwitchCase,
+    SwitchStatement, ThisExpression, UndefinedLiteral, WhileStatement, WithStatement,
 };
 use crate::lexer::tokens::Keyword;
 use crate::lexer::{Lexer, Token, TokenKind};
 use crate::parser::error::{ParseResult, ParserError};
+use crate::parser::incremental::LocatedStatement;
+use crate::parser::options::{EcmaVersion, ParserOptions};
 use crate::parser::recovery::{ErrorRecovery, ParsingContext, RecoveryContext, RecoveryStrategy};
 
-pub struct Parser {
-    #[allow(dead_code)]
-    source: String,
+pub struct Parser<'a> {
+    source: &'a str,
 
-    lexer: Lexer,
+    pub(crate) lexer: Lexer<'a>,
 
-    pub current: Option<Token>,
+    pub current: Option<Token<'a>>,
 
-    previous: Option<Token>,
+    previous: Option<Token<'a>>,
 
     error_recovery: ErrorRecovery,
 
     context: ParsingContext,
 
-    #[allow(dead_code)]
+    // Strict from the options, a "use strict" directive or an enclosing
+    // class body. The lexer only knows about the first.
     strict_mode: bool,
+
+    options: ParserOptions,
+
+    // Whether `await` starts an expression here, which is inside async
+    // functions and at the top level of a module.
+    await_allowed: bool,
+
+    // Cleared in the head of a `for` loop, where `in` ends the left-hand
+    // side instead of being an operator.
+    in_allowed: bool,
+
+    // Set at the start of a program or function body until the first
+    // statement that is not a string literal directive.
+    directive_prologue: bool,
+
+    // An octal escape in an earlier directive of the prologue, which a later
+    // "use strict" makes an error.
+    prologue_octal_escape: Option<ParserError>,
+
+    lexer_error: Option<ParserError>,
 }
 
-impl Parser {
-    pub fn new(source: &str) -> Self {
-        let mut lexer = Lexer::new(source);
-        let current = lexer.next_token().ok();
+impl<'a> Parser<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self::with_options(source, ParserOptions::default())
+    }
 
-        Self {
-            source: source.to_string(),
+    pub fn with_options(source: &'a str, options: ParserOptions) -> Self {
+        Self::with_lexer(source, Lexer::new(source), options)
+    }
+
+    // Parses `source` from `offset` onwards, which must be the start of a
+    // statement at `position`.
+    pub(crate) fn new_at(
+        source: &'a str,
+        offset: usize,
+        position: Position,
+        options: ParserOptions,
+    ) -> Self {
+        let mut parser = Self::with_lexer(
+            source,
+            Lexer::new(source).starting_at(offset, position),
+            options,
+        );
+        parser.directive_prologue = offset == 0;
+        parser
+    }
+
+    fn with_lexer(source: &'a str, lexer: Lexer<'a>, options: ParserOptions) -> Self {
+        let lexer = lexer
+            .with_recovery(true)
+            .with_strict_mode(options.is_strict())
+            .with_module_goal(options.is_module())
+            .with_annex_b(options.annex_b);
+
+        let mut parser = Self {
+            source,
             lexer,
-            current,
+            current: None,
             previous: None,
             error_recovery: ErrorRecovery::default(),
             context: ParsingContext::TopLevel,
-            strict_mode: false,
-        }
+            strict_mode: options.is_strict(),
+            options,
+            await_allowed: options.allows_top_level_await(),
+            in_allowed: true,
+            directive_prologue: true,
+            prologue_octal_escape: None,
+            lexer_error: None,
+        };
+        parser.current = parser.next_token();
+        parser
     }
 
     pub fn parse(&mut self) -> ParseResult<Node> {
-        self.parse_program()
+        let program = self.parse_program();
+
+        // Any parse error that follows a lexer error is most likely only a
+        // symptom of it.
+        if let Some(error) = self.lexer_error.take() {
+            return Err(error);
+        }
+        // Later errors are often caused by the first one.
+        match self.error_recovery.errors().first() {
+            Some(error) => Err(error.clone()),
+            None => Ok(Node::Program(program)),
+        }
     }
 
-    pub fn parse_with_recovery(&mut self) -> (Option<Node>, Vec<ParserError>) {
-        match self.parse() {
-            Ok(ast) => (Some(ast), self.error_recovery.errors().to_vec()),
-            Err(error) => {
-                self.error_recovery.add_error(error);
-                (None, self.error_recovery.errors().to_vec())
+    // Always returns a complete `Program`; statements that fail to parse are
+    // kept in it as `Node::Error`.
+    pub fn parse_with_recovery(&mut self) -> (Program, Vec<ParserError>) {
+        // Lexer errors are already in the recovery list, collected as the
+        // error tokens were skipped.
+        let program = self.parse_program();
+        (program, self.error_recovery.errors().to_vec())
+    }
+
+    // The program covers the whole source, including leading and trailing
+    // comments.
+    fn parse_program(&mut self) -> Program {
+        let mut body = Vec::new();
+
+        while !self.is_eof() {
+            let start = self.start_span();
+            match self.parse_statement_list_item() {
+                Ok(Some(stmt)) => body.push(stmt),
+                Ok(None) => {}
+                Err(error) => body.push(self.recover_statement(error, start)),
             }
         }
+
+        let end = self.start_span();
+        let span = Span::new(Position::default(), end.end).with_range(0..end.range.end);
+
+        Program {
+            body,
+            source_type: self.options.source_type,
+            span,
+        }
     }
 
-    fn parse_program(&mut self) -> ParseResult<Node> {
-        let mut body = Vec::new();
-        let start_pos = self.current_position();
+    // Skips the rest of a statement that failed to parse from `start`, and
+    // returns a `Node::Error` covering it. Once too many errors have been
+    // reported, the error node covers the rest of the input instead.
+    fn recover_statement(&mut self, error: ParserError, start: Span) -> Node {
+        let message = error.to_string();
+        if !self.try_recover_from_error(error.clone()) {
+            self.error_recovery.add_error(error);
+            while !self.is_eof() {
+                self.advance();
+            }
+        }
 
-        if self.is_eof() {
-            let end_pos = self.previous_position();
-            let span = self.create_span(start_pos, end_pos);
-            return Ok(Node::Program(Program {
-                body,
-                source_type: "script".to_string(),
-                span: Some(span),
-            }));
+        let made_progress = self.start_span().range.start != start.range.start;
+        if self.check(TokenKind::Semicolon) || !made_progress && !self.is_eof() {
+            self.advance();
         }
 
-        while !self.is_eof() {
-            match self.parse_statement() {
-                Ok(stmt) => body.push(stmt),
-                Err(error) => {
-                    if !self.try_recover_from_error(error.clone()) {
-                        return Err(error);
-                    }
+        let span = self.span_from(&start);
+        Node::Error(ErrorNode {
+            message,
+            skipped: self.raw_tokens(span.range.clone()),
+            span,
+        })
+    }
 
-                    if self.is_eof() {
-                        break;
-                    }
-                }
+    fn raw_tokens(&self, range: std::ops::Range<usize>) -> Vec<String> {
+        let source = &self.source[range];
+        let mut lexer = Lexer::new(source).with_recovery(true);
+        let mut tokens = Vec::new();
+        while let Ok(token) = lexer.next_token() {
+            if token.is_eof() {
+                break;
             }
+            if !token.is_comment() {
+                tokens.push(token.raw(source).to_string());
+            }
+        }
+        tokens
+    }
+
+    // Parses the statements that start before byte offset `end`, failing on
+    // the first error, recording the source each one covers.
+    pub(crate) fn parse_located_statements(
+        &mut self,
+        end: usize,
+    ) -> ParseResult<Vec<LocatedStatement>> {
+        let mut statements = Vec::new();
+
+        while let Some(first) = self
+            .current
+            .as_ref()
+            .filter(|token| !token.is_eof() && token.range.start < end)
+        {
+            let (offset, start) = (first.range.start, first.start());
+            let Some(node) = self.parse_statement_list_item()? else {
+                continue;
+            };
+            let last = self
+                .previous
+                .as_ref()
+                .filter(|token| token.range.end > offset)
+                .ok_or_else(|| ParserError::internal_error("statement consumed no tokens"))?;
+
+            statements.push(LocatedStatement {
+                node,
+                range: offset..last.range.end,
+                span: Span::new(start, last.end()).with_range(offset..last.range.end),
+            });
         }
 
-        let end_pos = self.previous_position();
-        let span = self.create_span(start_pos, end_pos);
+        // Blocks still recover from errors in their statements; those are
+        // errors all the same.
+        if let Some(error) = self.lexer_error.take() {
+            return Err(error);
+        }
+        match self.error_recovery.errors().first() {
+            Some(error) => Err(error.clone()),
+            None => Ok(statements),
+        }
+    }
 
-        Ok(Node::Program(Program {
-            body,
-            source_type: "script".to_string(),
-            span: Some(span),
-        }))
+    // A statement in a list of them, or `None` for a TypeScript declaration
+    // that only exists at the type level and leaves nothing behind.
+    pub(crate) fn parse_statement_list_item(&mut self) -> ParseResult<Option<Node>> {
+        #[cfg(feature = "typescript")]
+        if self.skip_type_declaration()? {
+            return Ok(None);
+        }
+        self.parse_statement().map(Some)
     }
 
-    fn parse_statement(&mut self) -> ParseResult<Node> {
+    pub(crate) fn parse_statement(&mut self) -> ParseResult<Node> {
         let old_context = self.context.clone();
+        let module_item_allowed =
+            old_context == ParsingContext::TopLevel || self.is_import_expression_ahead();
         self.context = ParsingContext::Statement;
 
+        #[cfg(feature = "typescript")]
+        self.skip_abstract_modifier();
+        let labeled = self.is_labeled_statement_ahead();
+        let in_prologue = std::mem::take(&mut self.directive_prologue);
+        let directive = match &self.current {
+            Some(token) if in_prologue && matches!(token.kind, TokenKind::String(_)) => {
+                Some(token.raw(self.source))
+            }
+            _ => None,
+        };
+
         let result = if let Some(token) = &self.current {
             match &token.kind {
                 TokenKind::Keyword(kw) => match kw.as_str() {
+                    "import" | "export" if !module_item_allowed => {
+                        Err(ParserError::invalid_module(
+                            &format!(
+                                "'{}' declarations may only appear at the top level",
+                                kw.as_str()
+                            ),
+                            self.current_position().unwrap_or_default(),
+                        ))
+                    }
                     "let" | "const" | "var" => self.parse_declaration(),
                     "function" => self.parse_function_declaration(),
+                    "async" if self.is_async_function_ahead() => {
+                        self.parse_async_function_declaration()
+                    }
                     "class" => self.parse_class_declaration(),
                     "if" => self.parse_if_statement(),
                     "while" => self.parse_while_statement(),
@@ -129,6 +299,7 @@ impl Parser {
                     self.parse_block_statement()
                 }
                 TokenKind::Semicolon => self.parse_empty_statement(),
+                TokenKind::Identifier(_) if labeled => self.parse_labeled_statement(),
                 _ => self.parse_expression_statement(),
             }
         } else {
@@ -136,15 +307,34 @@ impl Parser {
         };
 
         self.context = old_context;
-        result
+        match (result, directive) {
+            (Ok(Node::ExpressionStatement(mut stmt)), Some(raw))
+                if matches!(*stmt.expression, Node::String(_)) =>
+            {
+                let directive = &raw[1..raw.len() - 1];
+                if directive == "use strict" {
+                    if let Some(error) = self.prologue_octal_escape.take() {
+                        return Err(error);
+                    }
+                    self.strict_mode = true;
+                } else if self.prologue_octal_escape.is_none() {
+                    self.prologue_octal_escape = strict_literal_error(raw, stmt.span.start);
+                }
+                stmt.directive = Some(directive.to_string());
+                self.directive_prologue = true;
+                Ok(Node::ExpressionStatement(stmt))
+            }
+            (result, _) => result,
+        }
     }
 
-    fn parse_declaration(&mut self) -> ParseResult<Node> {
+    pub fn parse_declaration(&mut self) -> ParseResult<Node> {
         if let Some(token) = &self.current {
             match &token.kind {
                 TokenKind::Keyword(kw) => match kw.as_str() {
                     "let" | "const" | "var" => self.parse_variable_declaration(),
                     "function" => self.parse_function_declaration(),
+                    "async" => self.parse_async_function_declaration(),
                     "class" => self.parse_class_declaration(),
                     _ => Err(ParserError::invalid_declaration(
                         "Expected declaration",
@@ -162,6 +352,7 @@ impl Parser {
     }
 
     fn parse_if_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
         self.expect(TokenKind::LeftParen)?;
@@ -185,16 +376,17 @@ impl Parser {
             None
         };
 
-        let span = self.create_span_from_tokens();
+        let span = self.span_from(&start);
         Ok(Node::IfStatement(IfStatement {
             test,
             consequent,
             alternate,
-            span: Some(span),
+            span,
         }))
     }
 
     fn parse_while_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
         self.expect(TokenKind::LeftParen)?;
@@ -203,29 +395,62 @@ impl Parser {
 
         let body = Box::new(self.parse_statement()?);
 
-        let span = self.create_span_from_tokens();
-        Ok(Node::WhileStatement(WhileStatement {
-            test,
-            body,
-            span: Some(span),
-        }))
+        let span = self.span_from(&start);
+        Ok(Node::WhileStatement(WhileStatement { test, body, span }))
     }
 
     fn parse_for_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
+        let is_await = self.check_keyword("await");
+        if is_await {
+            if !self.await_allowed {
+                return Err(ParserError::invalid_statement(
+                    "'for await' is only valid in async functions and modules",
+                    self.current_position().unwrap_or_default(),
+                ));
+            }
+            self.advance();
+        }
+
         self.expect(TokenKind::LeftParen)?;
 
         let init = if !self.check(TokenKind::Semicolon) {
-            Some(Box::new(if self.is_declaration() {
-                self.parse_declaration()?
-            } else {
-                self.parse_expression()?
-            }))
+            Some(Box::new(self.parse_with_in(false, |parser| {
+                if parser.is_variable_declaration() {
+                    parser.parse_variable_declaration_list()
+                } else {
+                    parser.parse_expression()
+                }
+            })?))
         } else {
             None
         };
 
+        if let Some(left) = init {
+            if self.check_keyword("of") || (self.check_keyword("in") && !is_await) {
+                return self.parse_for_in_of_rest(start, left, is_await);
+            }
+            return self.parse_for_rest(start, Some(left), is_await);
+        }
+        self.parse_for_rest(start, None, is_await)
+    }
+
+    // The rest of a loop whose `for` keyword is at `start`.
+    fn parse_for_rest(
+        &mut self,
+        start: Span,
+        init: Option<Box<Node>>,
+        is_await: bool,
+    ) -> ParseResult<Node> {
+        if is_await {
+            return Err(ParserError::invalid_statement(
+                "'for await' requires an 'of' loop",
+                self.current_position().unwrap_or_default(),
+            ));
+        }
+
         self.expect(TokenKind::Semicolon)?;
 
         let test = if !self.check(TokenKind::Semicolon) {
@@ -246,33 +471,114 @@ impl Parser {
 
         let body = Box::new(self.parse_statement()?);
 
-        let span = self.create_span_from_tokens();
+        let span = self.span_from(&start);
         Ok(Node::ForStatement(ForStatement {
             init,
             test,
             update,
             body,
-            span: Some(span),
+            span,
         }))
     }
 
+    fn parse_for_in_of_rest(
+        &mut self,
+        start: Span,
+        left: Box<Node>,
+        is_await: bool,
+    ) -> ParseResult<Node> {
+        let is_of = self.check_keyword("of");
+        let position = self.current_position().unwrap_or_default();
+        self.check_for_in_of_left(&left, is_of, position)?;
+        self.advance();
+
+        // for-of takes an AssignmentExpression, for-in any Expression.
+        let right = Box::new(if is_of {
+            self.parse_assignment_expression()?
+        } else {
+            self.parse_expression()?
+        });
+        self.expect(TokenKind::RightParen)?;
+
+        let body = Box::new(self.parse_statement()?);
+
+        let span = self.span_from(&start);
+        if is_of {
+            Ok(Node::ForOfStatement(ForOfStatement {
+                left,
+                right,
+                body,
+                r#await: is_await,
+                span,
+            }))
+        } else {
+            Ok(Node::ForInStatement(ForInStatement {
+                left,
+                right,
+                body,
+                span,
+            }))
+        }
+    }
+
+    fn check_for_in_of_left(
+        &self,
+        left: &Node,
+        is_of: bool,
+        position: Position,
+    ) -> ParseResult<()> {
+        let loop_kind = if is_of { "for-of" } else { "for-in" };
+
+        match left {
+            Node::VariableDeclaration(decl) => {
+                if decl.declarations.len() != 1 {
+                    return Err(ParserError::invalid_declaration(
+                        &format!("Only a single binding is allowed in a {loop_kind} loop"),
+                        position,
+                    ));
+                }
+                // Annex B keeps `for (var x = 1 in o)` working in sloppy
+                // scripts.
+                let legacy_initializer = !is_of
+                    && decl.kind == "var"
+                    && self.options.annex_b
+                    && !self.options.is_strict();
+                if decl.declarations[0].init.is_some() && !legacy_initializer {
+                    return Err(ParserError::invalid_declaration(
+                        &format!("A {loop_kind} loop variable cannot have an initializer"),
+                        position,
+                    ));
+                }
+                Ok(())
+            }
+            Node::Identifier(_)
+            | Node::MemberExpression(_)
+            | Node::ArrayLiteral(_)
+            | Node::ObjectLiteral(_) => Ok(()),
+            _ => Err(ParserError::invalid_syntax(
+                &format!("Invalid left-hand side in {loop_kind} loop"),
+                position,
+            )),
+        }
+    }
+
     fn parse_return_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
-        let argument = if !self.check(TokenKind::Semicolon) && !self.is_eof() {
+        let argument = if !self.check(TokenKind::Semicolon) && !self.can_insert_semicolon() {
             Some(Box::new(self.parse_expression()?))
         } else {
             None
         };
+        self.consume_semicolon()?;
 
-        let span = self.create_span_from_tokens();
-        Ok(Node::ReturnStatement(ReturnStatement {
-            argument,
-            span: Some(span),
-        }))
+        let span = self.span_from(&start);
+        Ok(Node::ReturnStatement(ReturnStatement { argument, span }))
     }
 
     fn parse_switch_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
         self.expect(TokenKind::LeftParen)?;
@@ -288,15 +594,16 @@ impl Parser {
 
         self.expect(TokenKind::RightBrace)?;
 
-        let span = self.create_span_from_tokens();
+        let span = self.span_from(&start);
         Ok(Node::SwitchStatement(SwitchStatement {
             discriminant,
             cases,
-            span: Some(span),
+            span,
         }))
     }
 
     fn parse_switch_case(&mut self) -> ParseResult<SwitchCase> {
+        let start = self.start_span();
         let test = if let Some(token) = &self.current {
             if let TokenKind::Keyword(kw) = &token.kind {
                 if kw == "default" {
@@ -317,23 +624,24 @@ impl Parser {
         self.expect(TokenKind::Colon)?;
 
         let mut consequent = Vec::new();
-        while !self.check(TokenKind::Keyword(Keyword::Case))
-            && !self.check(TokenKind::Keyword(Keyword::Default))
+        while !self.check_keyword("case")
+            && !self.check_keyword("default")
             && !self.check(TokenKind::RightBrace)
             && !self.is_eof()
         {
-            consequent.push(self.parse_statement()?);
+            consequent.extend(self.parse_statement_list_item()?);
         }
 
-        let span = self.create_span_from_tokens();
+        let span = self.span_from(&start);
         Ok(SwitchCase {
             test,
             consequent,
-            span: Some(span),
+            span,
         })
     }
 
     fn parse_do_while_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
         let body = Box::new(self.parse_statement()?);
@@ -346,11 +654,16 @@ impl Parser {
                     let test = Box::new(self.parse_expression()?);
                     self.expect(TokenKind::RightParen)?;
 
-                    let span = self.create_span_from_tokens();
+                    // A `;` is always optional after `do ... while (...)`.
+                    if self.check(TokenKind::Semicolon) {
+                        self.advance();
+                    }
+
+                    let span = self.span_from(&start);
                     return Ok(Node::DoWhileStatement(DoWhileStatement {
                         body,
                         test,
-                        span: Some(span),
+                        span,
                     }));
                 }
             }
@@ -363,6 +676,7 @@ impl Parser {
     }
 
     fn parse_with_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
         self.expect(TokenKind::LeftParen)?;
@@ -371,24 +685,21 @@ impl Parser {
 
         let body = Box::new(self.parse_statement()?);
 
-        let span = self.create_span_from_tokens();
-        Ok(Node::WithStatement(WithStatement {
-            object,
-            body,
-            span: Some(span),
-        }))
+        let span = self.span_from(&start);
+        Ok(Node::WithStatement(WithStatement { object, body, span }))
     }
 
     fn parse_debugger_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
+        self.consume_semicolon()?;
 
-        let span = self.create_span_from_tokens();
-        Ok(Node::DebuggerStatement(DebuggerStatement {
-            span: Some(span),
-        }))
+        let span = self.span_from(&start);
+        Ok(Node::DebuggerStatement(DebuggerStatement { span }))
     }
 
     pub fn parse_block_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
         let old_context = self.context.clone();
@@ -396,13 +707,16 @@ impl Parser {
 
         let mut body = Vec::new();
         while !self.check(TokenKind::RightBrace) && !self.is_eof() {
-            match self.parse_statement() {
-                Ok(stmt) => body.push(stmt),
+            let statement_start = self.start_span();
+            match self.parse_statement_list_item() {
+                Ok(Some(stmt)) => body.push(stmt),
+                Ok(None) => {}
+                Err(error) if self.error_recovery.can_recover() => {
+                    body.push(self.recover_statement(error, statement_start));
+                }
                 Err(error) => {
-                    if !self.try_recover_from_error(error.clone()) {
-                        self.context = old_context;
-                        return Err(error);
-                    }
+                    self.context = old_context;
+                    return Err(error);
                 }
             }
         }
@@ -411,59 +725,29 @@ impl Parser {
 
         self.context = old_context;
 
-        let span = self.create_span_from_tokens();
-        Ok(Node::BlockStatement(BlockStatement {
-            body,
-            span: Some(span),
-        }))
+        let span = self.span_from(&start);
+        Ok(Node::BlockStatement(BlockStatement { body, span }))
     }
 
     fn parse_empty_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
-        let span = self.create_span_from_tokens();
+        let span = self.span_from(&start);
 
-        Ok(Node::ExpressionStatement(ExpressionStatement {
-            expression: Box::new(Node::Null),
-            span: Some(span),
-        }))
+        Ok(Node::EmptyStatement(EmptyStatement { span }))
     }
 
-    fn parse_expression_statement(&mut self) -> ParseResult<Node> {
+    pub fn parse_expression_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         let expression = Box::new(self.parse_expression()?);
+        self.consume_semicolon()?;
 
-        if self.check(TokenKind::Semicolon) {
-            self.advance();
-        }
-
-        let span = self.create_span_from_tokens();
+        let span = self.span_from(&start);
         Ok(Node::ExpressionStatement(ExpressionStatement {
             expression,
-            span: Some(span),
-        }))
-    }
-
-    fn parse_import_declaration(&mut self) -> ParseResult<Node> {
-        self.advance();
-
-        let span = self.create_span_from_tokens();
-        Ok(Node::ImportDeclaration(ImportDeclaration {
-            specifiers: Vec::new(),
-            source: Box::new(Node::String("".to_string())),
-            span: Some(span),
-        }))
-    }
-
-    fn parse_export_declaration(&mut self) -> ParseResult<Node> {
-        self.advance();
-
-        let span = self.create_span_from_tokens();
-        Ok(Node::ExportDeclaration(ExportDeclaration {
-            declaration: None,
-            specifiers: Vec::new(),
-            source: None,
-            default: false,
-            span: Some(span),
+            directive: None,
+            span,
         }))
     }
 
@@ -472,6 +756,7 @@ impl Parser {
     }
 
     pub fn parse_equality_expression(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         let mut left = self.parse_relational_expression()?;
 
         while self.is_equality_operator() {
@@ -479,12 +764,12 @@ impl Parser {
             self.advance();
             let right = Box::new(self.parse_relational_expression()?);
 
-            let span = self.create_span_from_tokens();
+            let span = self.span_from(&start);
             left = Node::BinaryExpression(BinaryExpression {
                 left: Box::new(left),
                 operator,
                 right,
-                span: Some(span),
+                span,
             });
         }
 
@@ -492,63 +777,115 @@ impl Parser {
     }
 
     pub fn parse_primary_expression(&mut self) -> ParseResult<Node> {
+        // Also the whole span of the single-token expressions below.
+        let start = self.start_span();
         if let Some(token) = &self.current {
             match &token.kind {
                 TokenKind::Number(n) => {
                     let value = *n;
+                    self.check_strict_literal()?;
+                    self.check_numeric_separators()?;
                     self.advance();
-                    Ok(Node::Number(value))
+                    Ok(Node::Number(NumberLiteral { value, span: start }))
+                }
+                TokenKind::BigInt(digits) => {
+                    let value = digits.to_string();
+                    self.check_numeric_separators()?;
+                    self.advance();
+                    Ok(Node::BigInt(BigIntLiteral { value, span: start }))
                 }
                 TokenKind::String(s) => {
-                    let value = s.clone();
+                    let value = s.to_string();
+                    self.check_strict_literal()?;
                     self.advance();
-                    Ok(Node::String(value))
+                    Ok(Node::String(StringLiteral { value, span: start }))
+                }
+                TokenKind::NoSubstitutionTemplate { .. } | TokenKind::TemplateHead { .. } => {
+                    self.parse_template_literal(false)
                 }
-                TokenKind::TemplateString(s) => {
-                    let value = s.clone();
+                TokenKind::RegExp { pattern, flags } => {
+                    let pattern = pattern.to_string();
+                    let flags = flags.to_string();
                     self.advance();
 
-                    self.parse_template_literal(value)
+                    let span = self.span_from(&start);
+                    Ok(Node::RegExp(RegExp {
+                        pattern,
+                        flags,
+                        span,
+                    }))
                 }
                 TokenKind::Boolean(b) => {
                     let value = *b;
                     self.advance();
-                    Ok(Node::Boolean(value))
+                    Ok(Node::Boolean(BooleanLiteral { value, span: start }))
                 }
                 TokenKind::Null => {
                     self.advance();
-                    Ok(Node::Null)
+                    Ok(Node::Null(NullLiteral { span: start }))
                 }
                 TokenKind::Undefined => {
                     self.advance();
-                    Ok(Node::Undefined)
+                    Ok(Node::Undefined(UndefinedLiteral { span: start }))
                 }
                 TokenKind::Keyword(kw) if kw == "this" => {
                     self.advance();
-                    Ok(Node::This)
+                    Ok(Node::This(ThisExpression { span: start }))
                 }
-                TokenKind::LeftParen => {
+                // A bare `#x` is only valid as the left side of `#x in obj`.
+                TokenKind::PrivateName(name) => {
+                    let name = name.to_string();
+                    let position = self.current_position().unwrap_or_default();
                     self.advance();
-                    let expr = self.parse_expression()?;
-                    self.expect(TokenKind::RightParen)?;
-                    Ok(expr)
+                    if self.in_allowed && self.check_keyword("in") {
+                        Ok(Node::PrivateIdentifier(PrivateIdentifier {
+                            name,
+                            span: start,
+                        }))
+                    } else {
+                        Err(ParserError::invalid_expression(
+                            &format!("Unexpected private name '#{name}'"),
+                            position,
+                        ))
+                    }
                 }
-                TokenKind::LeftBracket => self.parse_array_literal(),
-                TokenKind::LeftBrace => self.parse_object_literal(),
+                #[cfg(feature = "typescript")]
+                TokenKind::LessThan | TokenKind::LeftParen
+                    if self.typescript() && self.is_typed_arrow_function_ahead() =>
+                {
+                    self.parse_arrow_function_expression(start, false)
+                }
+                TokenKind::LeftParen => self.parse_parenthesized_or_arrow(start),
+                TokenKind::LeftBracket => self.parse_with_in(true, Self::parse_array_literal),
+                TokenKind::LeftBrace => self.parse_with_in(true, Self::parse_object_literal),
+                #[cfg(feature = "jsx")]
+                TokenKind::LessThan => self.parse_jsx_element(),
                 TokenKind::Keyword(kw) if kw == "function" => self.parse_function_expression(),
                 TokenKind::Keyword(kw) if kw == "class" => self.parse_class_expression(),
                 TokenKind::Keyword(kw) if kw == "new" => self.parse_new_expression(),
+                TokenKind::Keyword(kw) if kw == "import" => self.parse_import_expression(),
                 TokenKind::Keyword(kw) if kw == "async" => {
                     self.advance();
-                    if self.check(TokenKind::Keyword(Keyword::Function)) {
-                        self.parse_function_expression()
+                    // After a line break `async` is just a name.
+                    if self.has_newline_before() || self.is_eof() {
+                        Ok(Node::Identifier(Identifier {
+                            name: "async".to_string(),
+                            span: start,
+                        }))
+                    } else if self.check(TokenKind::Keyword(Keyword::Function)) {
+                        self.parse_function_expression_inner(start, true)
                     } else {
-                        self.parse_arrow_function_expression(true)
+                        self.parse_arrow_function_expression(start, true)
                     }
                 }
                 _ => {
                     if self.check_identifier() {
-                        self.parse_identifier()
+                        let id = self.parse_identifier()?;
+                        if self.check(TokenKind::Arrow) {
+                            self.parse_arrow_function_body(start, vec![id], false)
+                        } else {
+                            Ok(id)
+                        }
                     } else {
                         Err(ParserError::invalid_expression(
                             "Unexpected token in expression",
@@ -566,7 +903,13 @@ impl Parser {
         let mut params = Vec::new();
 
         while !self.check(TokenKind::RightParen) && !self.is_eof() {
+            #[cfg(feature = "typescript")]
+            if self.skip_type_only_parameter()? {
+                continue;
+            }
             params.push(self.parse_identifier()?);
+            #[cfg(feature = "typescript")]
+            self.skip_binding_type()?;
 
             if self.check(TokenKind::Comma) {
                 self.advance();
@@ -591,32 +934,81 @@ impl Parser {
     }
 
     pub fn parse_function_body(&mut self) -> ParseResult<Node> {
-        self.parse_block_statement()
+        let outer_strict = self.strict_mode;
+        self.directive_prologue = true;
+        self.prologue_octal_escape = None;
+        let body = self.parse_block_statement();
+        self.directive_prologue = false;
+        self.strict_mode = outer_strict;
+        body
     }
 
-    pub fn parse_class_body(&mut self) -> ParseResult<Node> {
-        self.expect(TokenKind::LeftBrace)?;
+    // The lexer accepts `1_000` whatever the version; the parser gates it.
+    fn check_numeric_separators(&self) -> ParseResult<()> {
+        match &self.current {
+            Some(token) if token.raw(self.source).contains('_') => {
+                self.require_ecma_version(EcmaVersion::Es2021, "numeric separators")
+            }
+            _ => Ok(()),
+        }
+    }
 
-        let mut body = Vec::new();
-        while !self.check(TokenKind::RightBrace) && !self.is_eof() {
-            body.push(self.parse_statement()?);
+    // Code the lexer was not told is strict has its literals checked here.
+    fn check_strict_literal(&self) -> ParseResult<()> {
+        match &self.current {
+            Some(token) if self.strict_mode && !self.options.is_strict() => {
+                match strict_literal_error(token.raw(self.source), token.start()) {
+                    Some(error) => Err(error),
+                    None => Ok(()),
+                }
+            }
+            _ => Ok(()),
         }
+    }
 
-        self.expect(TokenKind::RightBrace)?;
+    // Runs `parse` with `in` treated as an operator or not.
+    pub fn parse_with_in<T>(
+        &mut self,
+        in_allowed: bool,
+        parse: impl FnOnce(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<T> {
+        let outer = std::mem::replace(&mut self.in_allowed, in_allowed);
+        let result = parse(self);
+        self.in_allowed = outer;
+        result
+    }
 
-        let span = self.create_span_from_tokens();
-        Ok(Node::BlockStatement(BlockStatement {
-            body,
-            span: Some(span),
-        }))
+    // Runs `parse` as strict code, which every part of a class is.
+    pub fn parse_strict<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<T> {
+        let outer = std::mem::replace(&mut self.strict_mode, true);
+        let result = parse(self);
+        self.strict_mode = outer;
+        result
+    }
+
+    // Runs `parse` with `await` treated as an operator or not, as it is for
+    // the body of an async or ordinary function.
+    pub fn parse_with_await<T>(
+        &mut self,
+        await_allowed: bool,
+        parse: impl FnOnce(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<T> {
+        let outer = std::mem::replace(&mut self.await_allowed, await_allowed);
+        let result = parse(self);
+        self.await_allowed = outer;
+        result
     }
 
     pub fn parse_identifier(&mut self) -> ParseResult<Node> {
         if let Some(token) = &self.current {
             if let TokenKind::Identifier(ident) = &token.kind {
-                let name = ident.clone();
+                let name = ident.to_string();
+                let span = self.start_span();
                 self.advance();
-                Ok(Node::Identifier(name))
+                Ok(Node::Identifier(Identifier { name, span }))
             } else {
                 Err(ParserError::invalid_syntax(
                     "Expected identifier",
@@ -628,7 +1020,7 @@ impl Parser {
         }
     }
 
-    fn current_token(&self) -> Option<&Token> {
+    fn current_token(&self) -> Option<&Token<'a>> {
         self.current.as_ref()
     }
 
@@ -689,8 +1081,8 @@ impl Parser {
                 TokenKind::Spread => "...".to_string(),
                 TokenKind::NullishCoalescing => "??".to_string(),
                 TokenKind::OptionalChaining => "?.".to_string(),
-                TokenKind::Identifier(id) => id.clone(),
-                TokenKind::String(s) => s.clone(),
+                TokenKind::Identifier(id) => id.to_string(),
+                TokenKind::String(s) => s.to_string(),
                 TokenKind::Number(n) => n.to_string(),
                 TokenKind::Keyword(kw) => kw.as_str().to_string(),
                 TokenKind::Boolean(b) => b.to_string(),
@@ -710,6 +1102,38 @@ impl Parser {
         }
     }
 
+    pub fn options(&self) -> &ParserOptions {
+        &self.options
+    }
+
+    // Rejects the syntax at the current token when the target version
+    // predates it.
+    pub(crate) fn require_ecma_version(
+        &self,
+        version: EcmaVersion,
+        syntax: &str,
+    ) -> ParseResult<()> {
+        if self.options.ecma_version >= version {
+            return Ok(());
+        }
+        Err(ParserError::invalid_syntax(
+            &format!("{version} is required for {syntax}"),
+            self.current_position().unwrap_or_default(),
+        ))
+    }
+
+    pub fn await_allowed(&self) -> bool {
+        self.await_allowed
+    }
+
+    pub fn check_keyword(&self, keyword: &str) -> bool {
+        matches!(self.current_kind(), Some(TokenKind::Keyword(kw)) if kw == keyword)
+    }
+
+    pub fn current_kind(&self) -> Option<&TokenKind<'a>> {
+        self.current.as_ref().map(|token| &token.kind)
+    }
+
     pub fn check_identifier(&self) -> bool {
         self.current_token()
             .map(|t| t.is_identifier())
@@ -733,9 +1157,72 @@ impl Parser {
         }
     }
 
+    // A statement may end without `;` when the next token is `}`, the end of
+    // input, or the first token on a new line.
+    pub fn can_insert_semicolon(&self) -> bool {
+        match &self.current {
+            Some(token) => {
+                token.newline_before || matches!(token.kind, TokenKind::RightBrace | TokenKind::Eof)
+            }
+            None => true,
+        }
+    }
+
+    pub fn consume_semicolon(&mut self) -> ParseResult<()> {
+        if self.check(TokenKind::Semicolon) {
+            self.advance();
+            return Ok(());
+        }
+
+        match self.current_token() {
+            Some(token) if !self.can_insert_semicolon() => {
+                Err(ParserError::unexpected_token(token, Some(";")))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn has_newline_before(&self) -> bool {
+        self.current
+            .as_ref()
+            .is_some_and(|token| token.newline_before)
+    }
+
     pub fn advance(&mut self) {
         self.previous = self.current.take();
-        self.current = self.lexer.next_token().ok();
+        self.current = self.next_token();
+    }
+
+    pub fn peek(&mut self) -> Option<&Token<'a>> {
+        self.lexer.peek_token().ok()
+    }
+
+    // Error tokens are recorded and skipped, so parsing carries on past bad
+    // characters and every lexical error ends up in the recovery list.
+    fn next_token(&mut self) -> Option<Token<'a>> {
+        match self.lexer.next_token() {
+            Ok(token) if token.is_comment() => self.next_token(),
+            Ok(Token {
+                kind: TokenKind::Error(error),
+                ..
+            }) => {
+                let position = self.lexer.error_position().unwrap_or_default();
+                let error = ParserError::lexer_error(&error.to_string(), position);
+                if self.lexer_error.is_none() {
+                    self.lexer_error = Some(error.clone());
+                }
+                self.error_recovery.add_error(error);
+                self.next_token()
+            }
+            Ok(token) => Some(token),
+            Err(error) => {
+                if self.lexer_error.is_none() {
+                    let position = self.lexer.error_position().unwrap_or_default();
+                    self.lexer_error = Some(ParserError::lexer_error(&error.to_string(), position));
+                }
+                None
+            }
+        }
     }
 
     pub fn is_eof(&self) -> bool {
@@ -750,25 +1237,58 @@ impl Parser {
         })
     }
 
-    fn previous_position(&self) -> Option<Position> {
-        self.previous.as_ref().map(|t| Position {
-            line: t.end().line,
-            column: t.end().column,
-        })
+    // The span of the current token. A node starting here gets its full span
+    // from `span_from` once its last token has been consumed.
+    pub fn start_span(&self) -> Span {
+        self.current
+            .as_ref()
+            .or(self.previous.as_ref())
+            .map(token_span)
+            .unwrap_or_default()
     }
 
-    pub fn create_span_from_tokens(&self) -> Span {
-        let start = self.previous_position().unwrap_or_default();
-        let end = self.current_position().unwrap_or_default();
-        Span::new(start, end)
+    // From the start of `start` to the end of the last consumed token, or an
+    // empty span at `start` if nothing has been consumed since.
+    pub fn span_from(&self, start: &Span) -> Span {
+        match &self.previous {
+            Some(token) if token.range.end > start.range.start => start.to(&token_span(token)),
+            _ => {
+                Span::new(start.start, start.start).with_range(start.range.start..start.range.start)
+            }
+        }
     }
 
-    fn create_span(&self, start: Option<Position>, end: Option<Position>) -> Span {
-        let start = start.unwrap_or_default();
-        let end = end.unwrap_or_default();
-        Span::new(start, end)
+    // A throwaway copy of the parser at the current token, for trying out a
+    // parse without consuming anything.
+    #[cfg(feature = "typescript")]
+    pub(crate) fn lookahead(&self) -> Parser<'a> {
+        Self {
+            source: self.source,
+            lexer: self.lexer.clone(),
+            current: self.current.clone(),
+            previous: self.previous.clone(),
+            error_recovery: ErrorRecovery::default(),
+            context: self.context.clone(),
+            strict_mode: self.strict_mode,
+            options: self.options,
+            await_allowed: self.await_allowed,
+            in_allowed: self.in_allowed,
+            directive_prologue: false,
+            prologue_octal_escape: None,
+            lexer_error: None,
+        }
+    }
+
+    fn is_variable_declaration(&self) -> bool {
+        matches!(
+            self.current.as_ref().map(|t| &t.kind),
+            Some(TokenKind::Keyword(
+                Keyword::Let | Keyword::Const | Keyword::Var
+            ))
+        )
     }
 
+    #[allow(dead_code)]
     fn is_declaration(&self) -> bool {
         if let Some(token) = &self.current {
             matches!(token.kind,
@@ -810,7 +1330,7 @@ impl Parser {
                     | TokenKind::GreaterThan
                     | TokenKind::LessThanEqual
                     | TokenKind::GreaterThanEqual
-            ) || matches!(token.kind, TokenKind::Keyword(ref kw) if kw == "instanceof" || kw == "in")
+            ) || matches!(token.kind, TokenKind::Keyword(ref kw) if kw == "instanceof" || kw == "in" && self.in_allowed)
         } else {
             false
         }
@@ -884,10 +1404,8 @@ impl Parser {
             RecoveryStrategy::SkipUntil(tokens) => {
                 while !self.is_eof() {
                     if let Some(token) = self.current_token() {
-                        if tokens
-                            .iter()
-                            .any(|t| format!("{:?}", token.kind).contains(t))
-                        {
+                        let raw = token.raw(self.source);
+                        if tokens.iter().any(|t| t == raw) {
                             break;
                         }
                     }
@@ -910,146 +1428,113 @@ impl Parser {
                 true
             }
 
-            RecoveryStrategy::SkipUntilBlock => {
-                while !self.is_eof() {
-                    if let Some(token) = self.current_token() {
-                        if matches!(token.kind, TokenKind::RightBrace) {
-                            break;
-                        }
-                    }
-                    self.advance();
-                }
-                true
-            }
-
-            RecoveryStrategy::SkipUntilFunction => {
-                while !self.is_eof() {
-                    if let Some(token) = self.current_token() {
-                        match token.kind {
-                            TokenKind::RightBrace | TokenKind::Semicolon => break,
-                            _ => self.advance(),
-                        }
-                    } else {
-                        break;
-                    }
-                }
-                true
-            }
-
-            RecoveryStrategy::SkipUntilClass => {
-                while !self.is_eof() {
-                    if let Some(token) = self.current_token() {
-                        if matches!(token.kind, TokenKind::RightBrace) {
-                            break;
-                        }
-                    }
-                    self.advance();
-                }
-                true
-            }
-
-            RecoveryStrategy::SkipUntilModule => {
-                while !self.is_eof() {
-                    let should_break = if let Some(token) = &self.current {
-                        matches!(token.kind, TokenKind::RightBrace)
-                            || matches!(token.kind, TokenKind::Keyword(ref kw) if kw == "import" || kw == "export")
-                    } else {
-                        false
-                    };
-
-                    if should_break {
-                        break;
-                    }
-                    self.advance();
-                }
-                true
-            }
-
-            RecoveryStrategy::InsertToken(_) => {
-                self.advance();
-                true
-            }
-
-            RecoveryStrategy::ReplaceToken(_) => {
-                self.advance();
-                true
-            }
-
-            RecoveryStrategy::DeleteToken => {
-                self.advance();
-                true
-            }
-
             RecoveryStrategy::NoRecovery => false,
         }
     }
 
-    fn parse_arrow_function_expression(&mut self, is_async: bool) -> ParseResult<Node> {
+    // Parses from the parameters, after any `async` at `start` has been
+    // consumed.
+    pub fn parse_arrow_function_expression(
+        &mut self,
+        start: Span,
+        is_async: bool,
+    ) -> ParseResult<Node> {
         let mut params = Vec::new();
 
+        #[cfg(feature = "typescript")]
+        self.skip_type_parameters()?;
         if self.check(TokenKind::LeftParen) {
             self.advance();
             if !self.check(TokenKind::RightParen) {
                 params = self.parse_parameters()?;
             }
             self.expect(TokenKind::RightParen)?;
+            #[cfg(feature = "typescript")]
+            self.skip_return_type()?;
         } else {
             params.push(self.parse_identifier()?);
         }
 
+        self.parse_arrow_function_body(start, params, is_async)
+    }
+
+    // Parses from the `=>`, once the parameters are known.
+    pub fn parse_arrow_function_body(
+        &mut self,
+        start: Span,
+        params: Vec<Node>,
+        is_async: bool,
+    ) -> ParseResult<Node> {
+        self.require_ecma_version(EcmaVersion::Es2015, "arrow functions")?;
+        if self.check(TokenKind::Arrow) && self.has_newline_before() {
+            return Err(ParserError::invalid_syntax(
+                "Line break is not allowed before '=>'",
+                self.current_position().unwrap_or_default(),
+            ));
+        }
         self.expect(TokenKind::Arrow)?;
 
-        let body = if self.check(TokenKind::LeftBrace) {
-            Box::new(self.parse_function_body()?)
+        let expression = !self.check(TokenKind::LeftBrace);
+        let body = if expression {
+            Box::new(self.parse_with_await(is_async, Self::parse_expression)?)
         } else {
-            Box::new(self.parse_expression()?)
+            Box::new(self.parse_with_await(is_async, Self::parse_function_body)?)
         };
 
-        let span = self.create_span_from_tokens();
+        let span = self.span_from(&start);
         Ok(Node::ArrowFunctionExpression(ArrowFunctionExpression {
             params,
             body,
-            expression: !self.check(TokenKind::LeftBrace),
+            expression,
             r#async: is_async,
-            span: Some(span),
+            span,
         }))
     }
 
-    #[allow(dead_code)]
-    fn parse_destructuring_pattern(&mut self) -> ParseResult<Node> {
+    // A binding identifier, or an array or object pattern, which is kept as
+    // the literal it is written like.
+    pub fn parse_destructuring_pattern(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         if self.check(TokenKind::LeftBrace) {
             self.advance();
             let mut properties = Vec::new();
 
             while !self.check(TokenKind::RightBrace) && !self.is_eof() {
+                let property_start = self.start_span();
                 if self.check_identifier() {
                     let key = self.parse_identifier()?;
                     let value = if self.check(TokenKind::Colon) {
                         self.advance();
-                        Some(Box::new(self.parse_expression()?))
+                        Some(self.parse_binding_element()?)
                     } else {
                         None
                     };
 
-                    let span = self.create_span_from_tokens();
                     let is_shorthand = value.is_none();
+                    let value = match value {
+                        Some(value) => value,
+                        None => self.parse_default_value(key.clone(), &property_start)?,
+                    };
+                    let span = self.span_from(&property_start);
                     properties.push(Node::Property(Property {
                         key: Box::new(key),
-                        value: value.unwrap_or_else(|| Box::new(Node::Identifier("".to_string()))),
+                        value: Box::new(value),
                         kind: "init".to_string(),
                         computed: false,
                         method: false,
                         shorthand: is_shorthand,
-                        span: Some(span),
+                        span,
                     }));
                 } else if self.check(TokenKind::Spread) {
                     self.advance();
-                    let argument = Box::new(self.parse_expression()?);
-                    let span = self.create_span_from_tokens();
-                    properties.push(Node::SpreadElement(SpreadElement {
-                        argument,
-                        span: Some(span),
-                    }));
+                    let argument = Box::new(self.parse_identifier()?);
+                    let span = self.span_from(&property_start);
+                    properties.push(Node::SpreadElement(SpreadElement { argument, span }));
+                } else {
+                    return Err(ParserError::invalid_syntax(
+                        "Expected identifier in object pattern",
+                        self.current_position().unwrap_or_default(),
+                    ));
                 }
 
                 if self.check(TokenKind::Comma) {
@@ -1061,29 +1546,25 @@ impl Parser {
 
             self.expect(TokenKind::RightBrace)?;
 
-            let span = self.create_span_from_tokens();
-            Ok(Node::ObjectLiteral(ObjectLiteral {
-                properties,
-                span: Some(span),
-            }))
+            let span = self.span_from(&start);
+            Ok(Node::ObjectLiteral(ObjectLiteral { properties, span }))
         } else if self.check(TokenKind::LeftBracket) {
             self.advance();
             let mut elements = Vec::new();
 
             while !self.check(TokenKind::RightBracket) && !self.is_eof() {
+                let element_start = self.start_span();
                 if self.check(TokenKind::Comma) {
                     elements.push(None);
                     self.advance();
+                    continue;
                 } else if self.check(TokenKind::Spread) {
                     self.advance();
-                    let argument = Box::new(self.parse_expression()?);
-                    let span = self.create_span_from_tokens();
-                    elements.push(Some(Node::SpreadElement(SpreadElement {
-                        argument,
-                        span: Some(span),
-                    })));
+                    let argument = Box::new(self.parse_destructuring_pattern()?);
+                    let span = self.span_from(&element_start);
+                    elements.push(Some(Node::SpreadElement(SpreadElement { argument, span })));
                 } else {
-                    elements.push(Some(self.parse_expression()?));
+                    elements.push(Some(self.parse_binding_element()?));
                 }
 
                 if self.check(TokenKind::Comma) {
@@ -1095,66 +1576,46 @@ impl Parser {
 
             self.expect(TokenKind::RightBracket)?;
 
-            let span = self.create_span_from_tokens();
-            Ok(Node::ArrayLiteral(ArrayLiteral {
-                elements,
-                span: Some(span),
-            }))
+            let span = self.span_from(&start);
+            Ok(Node::ArrayLiteral(ArrayLiteral { elements, span }))
         } else {
             self.parse_identifier()
         }
     }
 
-    fn parse_template_literal(&mut self, initial_value: String) -> ParseResult<Node> {
-        let mut quasis = Vec::new();
-        let mut expressions = Vec::new();
-
-        quasis.push(TemplateElement {
-            value: initial_value,
-            tail: false,
-            span: None,
-        });
-
-        while !self.check(TokenKind::TemplateEnd) && !self.is_eof() {
-            if self.check(TokenKind::TemplateExpr) {
-                self.advance();
-                let expr = self.parse_expression()?;
-                expressions.push(expr);
-
-                if self.check(TokenKind::RightBrace) {
-                    self.advance();
-                } else {
-                    return Err(ParserError::unexpected_token(
-                        self.current_token().unwrap(),
-                        Some("Expected '}' in template expression"),
-                    ));
-                }
-            } else if let TokenKind::TemplateString(value) = &self.current_token().unwrap().kind {
-                let value = value.clone();
-                self.advance();
-                quasis.push(TemplateElement {
-                    value,
-                    tail: false,
-                    span: None,
-                });
-            } else {
-                break;
-            }
-        }
-
-        if self.check(TokenKind::TemplateEnd) {
-            self.advance();
+    // A pattern inside another pattern, which may have a default value.
+    fn parse_binding_element(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        let target = self.parse_destructuring_pattern()?;
+        self.parse_default_value(target, &start)
+    }
 
-            if let Some(last_quasi) = quasis.last_mut() {
-                last_quasi.tail = true;
-            }
+    // `target = value` when a default follows, otherwise `target` itself.
+    fn parse_default_value(&mut self, target: Node, start: &Span) -> ParseResult<Node> {
+        if !self.check(TokenKind::Assign) {
+            return Ok(target);
         }
-
-        let span = self.create_span_from_tokens();
-        Ok(Node::TemplateLiteral(TemplateLiteral {
-            quasis,
-            expressions,
-            span: Some(span),
+        self.advance();
+        let right = Box::new(self.parse_assignment_expression()?);
+        Ok(Node::AssignmentExpression(AssignmentExpression {
+            left: Box::new(target),
+            operator: "=".to_string(),
+            right,
+            span: self.span_from(start),
         }))
     }
 }
+
+fn token_span(token: &Token) -> Span {
+    Span::new(token.start(), token.end()).with_range(token.range.clone())
+}
+
+// The error a number or string literal's source text is in strict code: a
+// legacy octal literal or an octal escape.
+fn strict_literal_error(raw: &str, position: Position) -> Option<ParserError> {
+    Lexer::new(raw)
+        .with_strict_mode(true)
+        .next_token()
+        .err()
+        .map(|error| ParserError::lexer_error(&error.to_string(), position))
+}
@@ -1,9 +1,11 @@
 use crate::ast::{BinaryExpression, Node};
-use crate::parser::error::ParseResult;
+use crate::parser::error::{ParseResult, ParserError};
+use crate::parser::options::EcmaVersion;
 use crate::parser::Parser;
 
-impl Parser {
+impl Parser<'_> {
     pub fn parse_additive_expression(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         let mut left = self.parse_multiplicative_expression()?;
 
         while self.is_additive_operator() {
@@ -11,12 +13,12 @@ impl Parser {
             self.advance();
             let right = Box::new(self.parse_multiplicative_expression()?);
 
-            let span = self.create_span_from_tokens();
+            let span = self.span_from(&start);
             left = Node::BinaryExpression(BinaryExpression {
                 left: Box::new(left),
                 operator,
                 right,
-                span: Some(span),
+                span,
             });
         }
 
@@ -24,6 +26,7 @@ impl Parser {
     }
 
     pub fn parse_multiplicative_expression(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         let mut left = self.parse_exponentiation_expression()?;
 
         while self.is_multiplicative_operator() {
@@ -31,12 +34,12 @@ impl Parser {
             self.advance();
             let right = Box::new(self.parse_exponentiation_expression()?);
 
-            let span = self.create_span_from_tokens();
+            let span = self.span_from(&start);
             left = Node::BinaryExpression(BinaryExpression {
                 left: Box::new(left),
                 operator,
                 right,
-                span: Some(span),
+                span,
             });
         }
 
@@ -44,19 +47,30 @@ impl Parser {
     }
 
     pub fn parse_exponentiation_expression(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        // `-x ** 2` is ambiguous, so the base of `**` may only be a unary
+        // expression when it is parenthesized.
+        let unary = self.is_unary_operator() || self.await_allowed() && self.check_keyword("await");
         let mut left = self.parse_unary_expression()?;
 
         while self.is_exponentiation_operator() {
+            self.require_ecma_version(EcmaVersion::Es2016, "the '**' operator")?;
+            if unary {
+                return Err(ParserError::invalid_expression(
+                    "A unary expression cannot be the base of '**'; parenthesize it",
+                    self.current_position().unwrap_or_default(),
+                ));
+            }
             let operator = self.current_token_string();
             self.advance();
             let right = Box::new(self.parse_exponentiation_expression()?);
 
-            let span = self.create_span_from_tokens();
+            let span = self.span_from(&start);
             left = Node::BinaryExpression(BinaryExpression {
                 left: Box::new(left),
                 operator,
                 right,
-                span: Some(span),
+                span,
             });
         }
 
@@ -1,22 +1,28 @@
 use crate::ast::{AssignmentExpression, Node};
+use crate::lexer::TokenKind;
 use crate::parser::error::ParseResult;
+use crate::parser::options::EcmaVersion;
 use crate::parser::Parser;
 
-impl Parser {
+impl Parser<'_> {
     pub fn parse_assignment_expression(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         let mut left = self.parse_logical_or_expression()?;
 
         if self.is_assignment_operator() {
+            if self.check(TokenKind::StarStarAssign) {
+                self.require_ecma_version(EcmaVersion::Es2016, "the '**=' operator")?;
+            }
             let operator = self.current_token_string();
             self.advance();
             let right = Box::new(self.parse_assignment_expression()?);
 
-            let span = self.create_span_from_tokens();
+            let span = self.span_from(&start);
             left = Node::AssignmentExpression(AssignmentExpression {
                 left: Box::new(left),
                 operator,
                 right,
-                span: Some(span),
+                span,
             });
         }
 
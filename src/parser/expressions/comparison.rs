@@ -2,21 +2,26 @@ use crate::ast::{BinaryExpression, Node};
 use crate::parser::error::ParseResult;
 use crate::parser::Parser;
 
-impl Parser {
+impl Parser<'_> {
     pub fn parse_relational_expression(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         let mut left = self.parse_shift_expression()?;
+        #[cfg(feature = "typescript")]
+        self.skip_type_assertions()?;
 
         while self.is_relational_operator() {
             let operator = self.current_token_string();
             self.advance();
             let right = Box::new(self.parse_shift_expression()?);
+            #[cfg(feature = "typescript")]
+            self.skip_type_assertions()?;
 
-            let span = self.create_span_from_tokens();
+            let span = self.span_from(&start);
             left = Node::BinaryExpression(BinaryExpression {
                 left: Box::new(left),
                 operator,
                 right,
-                span: Some(span),
+                span,
             });
         }
 
@@ -24,6 +29,7 @@ impl Parser {
     }
 
     pub fn parse_shift_expression(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         let mut left = self.parse_additive_expression()?;
 
         while self.is_shift_operator() {
@@ -31,12 +37,12 @@ impl Parser {
             self.advance();
             let right = Box::new(self.parse_additive_expression()?);
 
-            let span = self.create_span_from_tokens();
+            let span = self.span_from(&start);
             left = Node::BinaryExpression(BinaryExpression {
                 left: Box::new(left),
                 operator,
                 right,
-                span: Some(span),
+                span,
             });
         }
 
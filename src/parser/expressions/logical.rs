@@ -1,23 +1,28 @@
 use crate::ast::{LogicalExpression, Node};
 use crate::lexer::TokenKind;
 use crate::parser::error::ParseResult;
+use crate::parser::options::EcmaVersion;
 use crate::parser::Parser;
 
-impl Parser {
+impl Parser<'_> {
     pub fn parse_logical_or_expression(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         let mut left = self.parse_logical_and_expression()?;
 
         while self.check(TokenKind::LogicalOr) || self.check(TokenKind::NullishCoalescing) {
+            if self.check(TokenKind::NullishCoalescing) {
+                self.require_ecma_version(EcmaVersion::Es2020, "the '??' operator")?;
+            }
             let operator = self.current_token_string();
             self.advance();
             let right = Box::new(self.parse_logical_and_expression()?);
 
-            let span = self.create_span_from_tokens();
+            let span = self.span_from(&start);
             left = Node::LogicalExpression(LogicalExpression {
                 left: Box::new(left),
                 operator,
                 right,
-                span: Some(span),
+                span,
             });
         }
 
@@ -25,6 +30,7 @@ impl Parser {
     }
 
     pub fn parse_logical_and_expression(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         let mut left = self.parse_equality_expression()?;
 
         while self.check(TokenKind::LogicalAnd) {
@@ -32,12 +38,12 @@ impl Parser {
             self.advance();
             let right = Box::new(self.parse_equality_expression()?);
 
-            let span = self.create_span_from_tokens();
+            let span = self.span_from(&start);
             left = Node::LogicalExpression(LogicalExpression {
                 left: Box::new(left),
                 operator,
                 right,
-                span: Some(span),
+                span,
             });
         }
 
@@ -1,86 +1,144 @@
-use crate::ast::{CallExpression, MemberExpression, Node, UnaryExpression, UpdateExpression};
+use crate::ast::{
+    AwaitExpression, CallExpression, MemberExpression, Node, PrivateIdentifier,
+    TaggedTemplateExpression, UnaryExpression, UpdateExpression,
+};
 use crate::lexer::TokenKind;
 use crate::parser::error::ParseResult;
 use crate::parser::Parser;
 
-impl Parser {
+impl Parser<'_> {
     pub fn parse_unary_expression(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         if self.is_unary_operator() {
             let operator = self.current_token_string();
             let prefix = true;
             self.advance();
             let argument = Box::new(self.parse_unary_expression()?);
 
-            let span = self.create_span_from_tokens();
+            let span = self.span_from(&start);
             return Ok(Node::UnaryExpression(UnaryExpression {
                 operator,
                 argument,
                 prefix,
-                span: Some(span),
+                span,
             }));
         }
 
+        if self.check(TokenKind::Increment) || self.check(TokenKind::Decrement) {
+            let operator = self.current_token_string();
+            self.advance();
+            let argument = Box::new(self.parse_unary_expression()?);
+
+            let span = self.span_from(&start);
+            return Ok(Node::UpdateExpression(UpdateExpression {
+                operator,
+                argument,
+                prefix: true,
+                span,
+            }));
+        }
+
+        if self.await_allowed() && self.check_keyword("await") {
+            self.advance();
+            let argument = Box::new(self.parse_unary_expression()?);
+
+            let span = self.span_from(&start);
+            return Ok(Node::AwaitExpression(AwaitExpression { argument, span }));
+        }
+
         self.parse_postfix_expression()
     }
 
     pub fn parse_postfix_expression(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         let mut expr = self.parse_primary_expression()?;
 
         while let Some(token) = &self.current {
             match &token.kind {
                 TokenKind::LeftBracket => {
                     self.advance();
-                    let property = Box::new(self.parse_expression()?);
+                    let property = Box::new(self.parse_with_in(true, Self::parse_expression)?);
                     self.expect(TokenKind::RightBracket)?;
 
-                    let span = self.create_span_from_tokens();
+                    let span = self.span_from(&start);
                     expr = Node::MemberExpression(MemberExpression {
                         object: Box::new(expr),
                         property,
                         computed: true,
                         optional: false,
-                        span: Some(span),
+                        span,
                     });
                 }
 
                 TokenKind::Dot => {
                     self.advance();
-                    let property = Box::new(self.parse_identifier()?);
+                    let property = match self.current_kind() {
+                        Some(TokenKind::PrivateName(name)) => {
+                            let name = name.to_string();
+                            let span = self.start_span();
+                            self.advance();
+                            Box::new(Node::PrivateIdentifier(PrivateIdentifier { name, span }))
+                        }
+                        _ => Box::new(self.parse_identifier()?),
+                    };
 
-                    let span = self.create_span_from_tokens();
+                    let span = self.span_from(&start);
                     expr = Node::MemberExpression(MemberExpression {
                         object: Box::new(expr),
                         property,
                         computed: false,
                         optional: false,
-                        span: Some(span),
+                        span,
                     });
                 }
 
                 TokenKind::LeftParen => {
                     self.advance();
-                    let arguments = self.parse_arguments()?;
+                    let arguments = self.parse_with_in(true, Self::parse_arguments)?;
                     self.expect(TokenKind::RightParen)?;
 
-                    let span = self.create_span_from_tokens();
+                    let span = self.span_from(&start);
                     expr = Node::CallExpression(CallExpression {
                         callee: Box::new(expr),
                         arguments,
-                        span: Some(span),
+                        span,
                     });
                 }
 
-                TokenKind::Increment | TokenKind::Decrement => {
+                TokenKind::NoSubstitutionTemplate { .. } | TokenKind::TemplateHead { .. } => {
+                    let quasi = Box::new(self.parse_template_literal(true)?);
+
+                    let span = self.span_from(&start);
+                    expr = Node::TaggedTemplateExpression(TaggedTemplateExpression {
+                        tag: Box::new(expr),
+                        quasi,
+                        span,
+                    });
+                }
+
+                // `f<T>(x)` and the non-null assertion `x!` in TypeScript.
+                #[cfg(feature = "typescript")]
+                TokenKind::LessThan if self.typescript() && self.is_type_arguments_ahead() => {
+                    self.skip_type_arguments()?;
+                }
+                #[cfg(feature = "typescript")]
+                TokenKind::Exclamation if self.typescript() && !token.newline_before => {
+                    self.advance();
+                }
+
+                // No line break is allowed before a postfix `++`/`--`; the
+                // operator then belongs to the next statement instead.
+                TokenKind::Increment | TokenKind::Decrement if !token.newline_before => {
                     let operator = self.current_token_string();
                     let prefix = false;
                     self.advance();
 
-                    let span = self.create_span_from_tokens();
+                    let span = self.span_from(&start);
                     expr = Node::UpdateExpression(UpdateExpression {
                         operator,
                         argument: Box::new(expr),
                         prefix,
-                        span: Some(span),
+                        span,
                     });
                 }
 
@@ -0,0 +1,619 @@
+use crate::ast::{Node, Position, Program, Span};
+use crate::parser::error::{ParseResult, ParserError};
+use crate::parser::options::ParserOptions;
+use crate::parser::Parser;
+use crate::vm::types::LineNumber;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range<usize>, text: impl Into<String>) -> Self {
+        Self {
+            range,
+            text: text.into(),
+        }
+    }
+}
+
+// A top-level statement with the byte range and positions of its first and
+// last token.
+#[derive(Debug, Clone)]
+pub(crate) struct LocatedStatement {
+    pub(crate) node: Node,
+    pub(crate) range: Range<usize>,
+    pub(crate) span: Span,
+}
+
+// Keeps a parsed program in step with an edited source. An edit re-lexes and
+// re-parses only the top-level statements it touches plus one neighbour on
+// either side, so automatic semicolon insertion can still join or split them
+// the way a full parse would. If that region fails to parse on its own, the
+// whole source is parsed again.
+#[derive(Debug, Clone)]
+pub struct IncrementalParser {
+    source: String,
+    options: ParserOptions,
+    program: Option<Program>,
+    locations: Vec<(Range<usize>, Span)>,
+}
+
+impl IncrementalParser {
+    pub fn new(source: impl Into<String>) -> ParseResult<Self> {
+        Self::with_options(source, ParserOptions::default())
+    }
+
+    pub fn with_options(source: impl Into<String>, options: ParserOptions) -> ParseResult<Self> {
+        let mut parser = Self {
+            source: source.into(),
+            options,
+            program: None,
+            locations: Vec::new(),
+        };
+        parser.reparse_all()?;
+        Ok(parser)
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    // `None` while the edited source does not parse.
+    pub fn program(&self) -> Option<&Program> {
+        self.program.as_ref()
+    }
+
+    // Returns the spans of the statements the edit changed. Neighbours that
+    // were only re-parsed for context, or only moved, are left out.
+    pub fn apply_edit(&mut self, edit: &TextEdit) -> ParseResult<Vec<Span>> {
+        let Range { start, end } = edit.range;
+        if start > end
+            || end > self.source.len()
+            || !self.source.is_char_boundary(start)
+            || !self.source.is_char_boundary(end)
+        {
+            return Err(ParserError::internal_error(&format!(
+                "edit range {start}..{end} is not valid for a source of {} bytes",
+                self.source.len()
+            )));
+        }
+
+        let old_len = self.source.len();
+        self.source.replace_range(start..end, &edit.text);
+
+        if self.program.is_none() || self.locations.is_empty() {
+            return self.reparse_all();
+        }
+
+        match self.reparse_region(edit, old_len) {
+            Ok(changed) => Ok(changed),
+            Err(_) => self.reparse_all(),
+        }
+    }
+
+    fn reparse_all(&mut self) -> ParseResult<Vec<Span>> {
+        let statements = match Parser::with_options(&self.source, self.options)
+            .parse_located_statements(usize::MAX)
+        {
+            Ok(statements) => statements,
+            Err(error) => {
+                self.program = None;
+                self.locations.clear();
+                return Err(error);
+            }
+        };
+
+        let (body, locations): (Vec<_>, Vec<_>) = statements
+            .into_iter()
+            .map(|statement| (statement.node, (statement.range, statement.span)))
+            .unzip();
+        self.program = Some(Program {
+            body,
+            source_type: self.options.source_type,
+            span: Span::default(),
+        });
+        self.locations = locations;
+        self.update_program_span();
+
+        Ok(self
+            .locations
+            .iter()
+            .map(|(_, span)| span.clone())
+            .collect())
+    }
+
+    fn reparse_region(&mut self, edit: &TextEdit, old_len: usize) -> ParseResult<Vec<Span>> {
+        let count = self.locations.len();
+        let touched_first = self
+            .locations
+            .iter()
+            .position(|(range, _)| range.end >= edit.range.start)
+            .unwrap_or(count);
+        let touched_last = self
+            .locations
+            .iter()
+            .rposition(|(range, _)| range.start <= edit.range.end);
+
+        let first = touched_first.saturating_sub(1).min(count - 1);
+        let mut last = touched_last.map_or(0, |index| index + 1).min(count - 1);
+        // Statements sharing a line with the end of the region are re-parsed
+        // too, so everything after it only moves by whole lines.
+        while last + 1 < count
+            && self.locations[last + 1].1.start.line == self.locations[last].1.end.line
+        {
+            last += 1;
+        }
+
+        let (region_start, start_position) = if first == 0 {
+            (0, Position::default())
+        } else {
+            let (range, span) = &self.locations[first];
+            (range.start, span.start)
+        };
+        let old_region_end = if last + 1 == count {
+            old_len
+        } else {
+            self.locations[last].0.end
+        };
+        let region_end = old_region_end + edit.text.len() - edit.range.len();
+
+        // A region after the prologue doesn't see its "use strict", so it
+        // starts out strict instead.
+        let options = if first > 0 && self.program.as_ref().is_some_and(has_use_strict) {
+            self.options.with_strict_mode(true)
+        } else {
+            self.options
+        };
+        // The parser sees the rest of the source so spans and lookahead match a
+        // full parse, but stops before the first statement after the region.
+        let statements = Parser::new_at(&self.source, region_start, start_position, options)
+            .parse_located_statements(region_end)?;
+        if statements
+            .last()
+            .is_some_and(|statement| statement.range.end > region_end)
+        {
+            return Err(ParserError::internal_error(
+                "edited statement runs past the re-parsed region",
+            ));
+        }
+
+        let byte_delta = edit.text.len() as isize - edit.range.len() as isize;
+        let line_delta = match statements.last() {
+            Some(statement) if last + 1 < count => {
+                statement.span.end.line.as_usize() as isize
+                    - self.locations[last].1.end.line.as_usize() as isize
+            }
+            _ => 0,
+        };
+
+        let Some(program) = self.program.as_mut() else {
+            return Err(ParserError::internal_error("no program to update"));
+        };
+        for node in &mut program.body[last + 1..] {
+            shift_node(node, line_delta, byte_delta);
+        }
+        for (range, span) in &mut self.locations[last + 1..] {
+            *range = shift_range(range, byte_delta);
+            shift_span(span, line_delta, byte_delta);
+        }
+
+        // A neighbour comes back over the same source as before, moved along
+        // if it follows the edit.
+        let unchanged: Vec<Range<usize>> = self.locations[first..=last]
+            .iter()
+            .filter_map(|(range, _)| {
+                if range.end <= edit.range.start {
+                    Some(range.clone())
+                } else if range.start >= edit.range.end {
+                    Some(shift_range(range, byte_delta))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let changed: Vec<Span> = statements
+            .iter()
+            .filter(|statement| !unchanged.contains(&statement.range))
+            .map(|statement| statement.span.clone())
+            .collect();
+        let (body, locations): (Vec<_>, Vec<_>) = statements
+            .into_iter()
+            .map(|statement| (statement.node, (statement.range, statement.span)))
+            .unzip();
+        program.body.splice(first..=last, body);
+        self.locations.splice(first..=last, locations);
+        self.update_program_span();
+
+        Ok(changed)
+    }
+
+    fn update_program_span(&mut self) {
+        if let Some(program) = self.program.as_mut() {
+            program.span = Span::new(Position::default(), end_position(&self.source))
+                .with_range(0..self.source.len());
+        }
+    }
+}
+
+// Whether the program's directive prologue holds "use strict".
+fn has_use_strict(program: &Program) -> bool {
+    program
+        .body
+        .iter()
+        .map_while(|statement| match statement {
+            Node::ExpressionStatement(stmt) => stmt.directive.as_deref(),
+            _ => None,
+        })
+        .any(|directive| directive == "use strict")
+}
+
+// Where the EOF token of `source` ends, which is also where a full parse
+// ends the program span.
+fn end_position(source: &str) -> Position {
+    let (line, column) = source.chars().fold((1, 1), |(line, column), c| {
+        if c == '\n' {
+            (line + 1, 1)
+        } else {
+            (line, column + 1)
+        }
+    });
+    Position::new(line, column)
+}
+
+fn shift_offset(offset: usize, delta: isize) -> usize {
+    (offset as isize + delta) as usize
+}
+
+fn shift_range(range: &Range<usize>, delta: isize) -> Range<usize> {
+    shift_offset(range.start, delta)..shift_offset(range.end, delta)
+}
+
+fn shift_span(span: &mut Span, lines: isize, bytes: isize) {
+    for position in [&mut span.start, &mut span.end] {
+        position.line = LineNumber::new((position.line.as_usize() as isize + lines) as usize);
+    }
+    span.range = shift_range(&span.range, bytes);
+}
+
+// Moves every span in `node` down by `lines` and along by `bytes`. Columns
+// stay put because the node starts on a line the edit did not touch.
+fn shift_node(node: &mut Node, lines: isize, bytes: isize) {
+    if lines == 0 && bytes == 0 {
+        return;
+    }
+
+    let shift = |span: &mut Span| shift_span(span, lines, bytes);
+    let shift_all = |nodes: &mut [Node]| {
+        nodes
+            .iter_mut()
+            .for_each(|node| shift_node(node, lines, bytes))
+    };
+    let shift_opt = |node: &mut Option<Box<Node>>| {
+        if let Some(node) = node {
+            shift_node(node, lines, bytes);
+        }
+    };
+
+    match node {
+        Node::Program(program) => {
+            shift(&mut program.span);
+            shift_all(&mut program.body);
+        }
+        Node::VariableDeclaration(decl) => {
+            shift(&mut decl.span);
+            for declarator in &mut decl.declarations {
+                shift(&mut declarator.span);
+                shift_node(&mut declarator.id, lines, bytes);
+                shift_opt(&mut declarator.init);
+            }
+        }
+        Node::FunctionDeclaration(decl) => {
+            shift(&mut decl.span);
+            shift_opt(&mut decl.id);
+            shift_all(&mut decl.params);
+            shift_node(&mut decl.body, lines, bytes);
+        }
+        Node::ClassDeclaration(decl) => {
+            shift(&mut decl.span);
+            shift_opt(&mut decl.id);
+            shift_opt(&mut decl.super_class);
+            shift_node(&mut decl.body, lines, bytes);
+        }
+        Node::ClassBody(body) => {
+            shift(&mut body.span);
+            shift_all(&mut body.body);
+        }
+        Node::MethodDefinition(method) => {
+            shift(&mut method.span);
+            shift_node(&mut method.key, lines, bytes);
+            shift_node(&mut method.value, lines, bytes);
+        }
+        Node::PropertyDefinition(prop) => {
+            shift(&mut prop.span);
+            shift_node(&mut prop.key, lines, bytes);
+            shift_opt(&mut prop.value);
+        }
+        Node::StaticBlock(block) => {
+            shift(&mut block.span);
+            shift_all(&mut block.body);
+        }
+        Node::ImportDeclaration(decl) => {
+            shift(&mut decl.span);
+            shift_all(&mut decl.specifiers);
+            shift_node(&mut decl.source, lines, bytes);
+            for attribute in &mut decl.attributes {
+                shift(&mut attribute.span);
+            }
+        }
+        Node::ExportDeclaration(decl) => {
+            shift(&mut decl.span);
+            shift_opt(&mut decl.declaration);
+            shift_all(&mut decl.specifiers);
+            shift_opt(&mut decl.source);
+            for attribute in &mut decl.attributes {
+                shift(&mut attribute.span);
+            }
+        }
+        Node::ImportSpecifier(spec) => shift(&mut spec.span),
+        Node::ImportDefaultSpecifier(spec) => shift(&mut spec.span),
+        Node::ImportNamespaceSpecifier(spec) => shift(&mut spec.span),
+        Node::ExportSpecifier(spec) => shift(&mut spec.span),
+        Node::ExportNamespaceSpecifier(spec) => shift(&mut spec.span),
+        Node::BinaryExpression(expr) => {
+            shift(&mut expr.span);
+            shift_node(&mut expr.left, lines, bytes);
+            shift_node(&mut expr.right, lines, bytes);
+        }
+        Node::LogicalExpression(expr) => {
+            shift(&mut expr.span);
+            shift_node(&mut expr.left, lines, bytes);
+            shift_node(&mut expr.right, lines, bytes);
+        }
+        Node::AssignmentExpression(expr) => {
+            shift(&mut expr.span);
+            shift_node(&mut expr.left, lines, bytes);
+            shift_node(&mut expr.right, lines, bytes);
+        }
+        Node::UnaryExpression(expr) => {
+            shift(&mut expr.span);
+            shift_node(&mut expr.argument, lines, bytes);
+        }
+        Node::UpdateExpression(expr) => {
+            shift(&mut expr.span);
+            shift_node(&mut expr.argument, lines, bytes);
+        }
+        Node::ConditionalExpression(expr) => {
+            shift(&mut expr.span);
+            shift_node(&mut expr.test, lines, bytes);
+            shift_node(&mut expr.consequent, lines, bytes);
+            shift_node(&mut expr.alternate, lines, bytes);
+        }
+        Node::CallExpression(expr) => {
+            shift(&mut expr.span);
+            shift_node(&mut expr.callee, lines, bytes);
+            shift_all(&mut expr.arguments);
+        }
+        Node::NewExpression(expr) => {
+            shift(&mut expr.span);
+            shift_node(&mut expr.callee, lines, bytes);
+            shift_all(&mut expr.arguments);
+        }
+        Node::MemberExpression(expr) => {
+            shift(&mut expr.span);
+            shift_node(&mut expr.object, lines, bytes);
+            shift_node(&mut expr.property, lines, bytes);
+        }
+        Node::ArrowFunctionExpression(expr) => {
+            shift(&mut expr.span);
+            shift_all(&mut expr.params);
+            shift_node(&mut expr.body, lines, bytes);
+        }
+        Node::FunctionExpression(expr) => {
+            shift(&mut expr.span);
+            shift_opt(&mut expr.id);
+            shift_all(&mut expr.params);
+            shift_node(&mut expr.body, lines, bytes);
+        }
+        Node::ClassExpression(expr) => {
+            shift(&mut expr.span);
+            shift_opt(&mut expr.id);
+            shift_opt(&mut expr.super_class);
+            shift_node(&mut expr.body, lines, bytes);
+        }
+        Node::YieldExpression(expr) => {
+            shift(&mut expr.span);
+            shift_opt(&mut expr.argument);
+        }
+        Node::AwaitExpression(expr) => {
+            shift(&mut expr.span);
+            shift_node(&mut expr.argument, lines, bytes);
+        }
+        Node::BlockStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_all(&mut stmt.body);
+        }
+        Node::IfStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_node(&mut stmt.test, lines, bytes);
+            shift_node(&mut stmt.consequent, lines, bytes);
+            shift_opt(&mut stmt.alternate);
+        }
+        Node::ForStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_opt(&mut stmt.init);
+            shift_opt(&mut stmt.test);
+            shift_opt(&mut stmt.update);
+            shift_node(&mut stmt.body, lines, bytes);
+        }
+        Node::ForInStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_node(&mut stmt.left, lines, bytes);
+            shift_node(&mut stmt.right, lines, bytes);
+            shift_node(&mut stmt.body, lines, bytes);
+        }
+        Node::ForOfStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_node(&mut stmt.left, lines, bytes);
+            shift_node(&mut stmt.right, lines, bytes);
+            shift_node(&mut stmt.body, lines, bytes);
+        }
+        Node::WhileStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_node(&mut stmt.test, lines, bytes);
+            shift_node(&mut stmt.body, lines, bytes);
+        }
+        Node::DoWhileStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_node(&mut stmt.body, lines, bytes);
+            shift_node(&mut stmt.test, lines, bytes);
+        }
+        Node::SwitchStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_node(&mut stmt.discriminant, lines, bytes);
+            for case in &mut stmt.cases {
+                shift(&mut case.span);
+                shift_opt(&mut case.test);
+                shift_all(&mut case.consequent);
+            }
+        }
+        Node::TryStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_node(&mut stmt.block, lines, bytes);
+            shift_opt(&mut stmt.handler);
+            shift_opt(&mut stmt.finalizer);
+        }
+        Node::CatchClause(clause) => {
+            shift(&mut clause.span);
+            shift_node(&mut clause.param, lines, bytes);
+            shift_node(&mut clause.body, lines, bytes);
+        }
+        Node::ThrowStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_node(&mut stmt.argument, lines, bytes);
+        }
+        Node::ReturnStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_opt(&mut stmt.argument);
+        }
+        Node::BreakStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_opt(&mut stmt.label);
+        }
+        Node::ContinueStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_opt(&mut stmt.label);
+        }
+        Node::LabeledStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_node(&mut stmt.label, lines, bytes);
+            shift_node(&mut stmt.body, lines, bytes);
+        }
+        Node::WithStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_node(&mut stmt.object, lines, bytes);
+            shift_node(&mut stmt.body, lines, bytes);
+        }
+        Node::DebuggerStatement(stmt) => shift(&mut stmt.span),
+        Node::EmptyStatement(stmt) => shift(&mut stmt.span),
+        Node::ExpressionStatement(stmt) => {
+            shift(&mut stmt.span);
+            shift_node(&mut stmt.expression, lines, bytes);
+        }
+        Node::ArrayLiteral(lit) => {
+            shift(&mut lit.span);
+            for element in lit.elements.iter_mut().flatten() {
+                shift_node(element, lines, bytes);
+            }
+        }
+        Node::ObjectLiteral(lit) => {
+            shift(&mut lit.span);
+            shift_all(&mut lit.properties);
+        }
+        Node::TemplateLiteral(lit) => {
+            shift(&mut lit.span);
+            for quasi in &mut lit.quasis {
+                shift(&mut quasi.span);
+            }
+            shift_all(&mut lit.expressions);
+        }
+        Node::TaggedTemplateExpression(expr) => {
+            shift(&mut expr.span);
+            shift_node(&mut expr.tag, lines, bytes);
+            shift_node(&mut expr.quasi, lines, bytes);
+        }
+        Node::Property(prop) => {
+            shift(&mut prop.span);
+            shift_node(&mut prop.key, lines, bytes);
+            shift_node(&mut prop.value, lines, bytes);
+        }
+        Node::SpreadElement(elem) => {
+            shift(&mut elem.span);
+            shift_node(&mut elem.argument, lines, bytes);
+        }
+        Node::RestElement(elem) => {
+            shift(&mut elem.span);
+            shift_node(&mut elem.argument, lines, bytes);
+        }
+        Node::Super(super_expr) => shift(&mut super_expr.span),
+        Node::MetaProperty(prop) => {
+            shift(&mut prop.span);
+            shift_node(&mut prop.meta, lines, bytes);
+            shift_node(&mut prop.property, lines, bytes);
+        }
+        Node::ImportExpression(expr) => {
+            shift(&mut expr.span);
+            shift_node(&mut expr.source, lines, bytes);
+        }
+        Node::RegExp(regexp) => shift(&mut regexp.span),
+        Node::Error(error) => shift(&mut error.span),
+        Node::Identifier(id) => shift(&mut id.span),
+        Node::PrivateIdentifier(id) => shift(&mut id.span),
+        Node::Number(lit) => shift(&mut lit.span),
+        Node::String(lit) => shift(&mut lit.span),
+        Node::Boolean(lit) => shift(&mut lit.span),
+        Node::Null(lit) => shift(&mut lit.span),
+        Node::Undefined(lit) => shift(&mut lit.span),
+        Node::This(expr) => shift(&mut expr.span),
+        Node::BigInt(lit) => shift(&mut lit.span),
+        #[cfg(feature = "jsx")]
+        Node::JsxElement(elem) => {
+            shift(&mut elem.span);
+            shift_node(&mut elem.name, lines, bytes);
+            shift_all(&mut elem.attributes);
+            shift_all(&mut elem.children);
+        }
+        #[cfg(feature = "jsx")]
+        Node::JsxFragment(frag) => {
+            shift(&mut frag.span);
+            shift_all(&mut frag.children);
+        }
+        #[cfg(feature = "jsx")]
+        Node::JsxAttribute(attr) => {
+            shift(&mut attr.span);
+            shift_node(&mut attr.name, lines, bytes);
+            shift_opt(&mut attr.value);
+        }
+        #[cfg(feature = "jsx")]
+        Node::JsxSpreadAttribute(attr) => {
+            shift(&mut attr.span);
+            shift_node(&mut attr.argument, lines, bytes);
+        }
+        #[cfg(feature = "jsx")]
+        Node::JsxExpressionContainer(container) => {
+            shift(&mut container.span);
+            shift_opt(&mut container.expression);
+        }
+        #[cfg(feature = "jsx")]
+        Node::JsxText(text) => shift(&mut text.span),
+        #[cfg(feature = "jsx")]
+        Node::JsxNamespacedName(name) => {
+            shift(&mut name.span);
+            shift_node(&mut name.namespace, lines, bytes);
+            shift_node(&mut name.name, lines, bytes);
+        }
+    }
+}
@@ -3,8 +3,9 @@ use crate::lexer::TokenKind;
 use crate::parser::error::ParseResult;
 use crate::parser::Parser;
 
-impl Parser {
+impl Parser<'_> {
     pub fn parse_array_literal(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
         let mut elements = Vec::new();
@@ -24,10 +25,7 @@ impl Parser {
 
         self.expect(TokenKind::RightBracket)?;
 
-        let span = self.create_span_from_tokens();
-        Ok(Node::ArrayLiteral(ArrayLiteral {
-            elements,
-            span: Some(span),
-        }))
+        let span = self.span_from(&start);
+        Ok(Node::ArrayLiteral(ArrayLiteral { elements, span }))
     }
 }
@@ -1,10 +1,13 @@
 use crate::ast::{ClassExpression, Node};
 use crate::lexer::TokenKind;
 use crate::parser::error::ParseResult;
+use crate::parser::options::EcmaVersion;
 use crate::parser::Parser;
 
-impl Parser {
+impl Parser<'_> {
     pub fn parse_class_expression(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        self.require_ecma_version(EcmaVersion::Es2015, "classes")?;
         self.advance();
 
         let id = if self.check_identifier() {
@@ -12,12 +15,14 @@ impl Parser {
         } else {
             None
         };
+        #[cfg(feature = "typescript")]
+        self.skip_type_parameters()?;
 
         let super_class = if let Some(token) = &self.current {
             if let TokenKind::Keyword(kw) = &token.kind {
                 if kw == "extends" {
                     self.advance();
-                    Some(Box::new(self.parse_expression()?))
+                    Some(Box::new(self.parse_strict(Self::parse_super_class)?))
                 } else {
                     None
                 }
@@ -28,14 +33,16 @@ impl Parser {
             None
         };
 
-        let body = Box::new(self.parse_class_body()?);
+        #[cfg(feature = "typescript")]
+        self.skip_implements_clause()?;
+        let body = Box::new(self.parse_strict(Self::parse_class_body)?);
 
-        let span = self.create_span_from_tokens();
+        let span = self.span_from(&start);
         Ok(Node::ClassExpression(ClassExpression {
             id,
             super_class,
             body,
-            span: Some(span),
+            span,
         }))
     }
 }
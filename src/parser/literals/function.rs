@@ -1,10 +1,21 @@
-use crate::ast::{FunctionExpression, Node};
+use crate::ast::{FunctionExpression, Node, Span};
 use crate::lexer::TokenKind;
 use crate::parser::error::ParseResult;
 use crate::parser::Parser;
 
-impl Parser {
+impl Parser<'_> {
     pub fn parse_function_expression(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        self.parse_function_expression_inner(start, false)
+    }
+
+    // Parses from the `function` keyword, after any `async` at `start` has
+    // been consumed.
+    pub fn parse_function_expression_inner(
+        &mut self,
+        start: Span,
+        is_async: bool,
+    ) -> ParseResult<Node> {
         self.advance();
 
         let id = if self.check_identifier() {
@@ -13,20 +24,24 @@ impl Parser {
             None
         };
 
+        #[cfg(feature = "typescript")]
+        self.skip_type_parameters()?;
         self.expect(TokenKind::LeftParen)?;
         let params = self.parse_parameters()?;
         self.expect(TokenKind::RightParen)?;
+        #[cfg(feature = "typescript")]
+        self.skip_return_type()?;
 
-        let body = Box::new(self.parse_function_body()?);
+        let body = Box::new(self.parse_with_await(is_async, Self::parse_function_body)?);
 
-        let span = self.create_span_from_tokens();
+        let span = self.span_from(&start);
         Ok(Node::FunctionExpression(FunctionExpression {
             id,
             params,
             body,
             generator: false,
-            r#async: false,
-            span: Some(span),
+            r#async: is_async,
+            span,
         }))
     }
 }
@@ -0,0 +1,314 @@
+use crate::ast::{
+    Identifier, JsxAttribute, JsxElement, JsxExpressionContainer, JsxFragment, JsxNamespacedName,
+    JsxSpreadAttribute, JsxText, MemberExpression, Node, StringLiteral,
+};
+use crate::lexer::{JsxMode, TokenKind};
+use crate::parser::error::{ParseResult, ParserError};
+use crate::parser::Parser;
+
+impl Parser<'_> {
+    // Parses an element or fragment starting at the current `<`. The lexer
+    // is switched into the matching JSX mode before each token is read, so
+    // the mode always has to be set before `advance`, never after.
+    pub fn parse_jsx_element(&mut self) -> ParseResult<Node> {
+        let result = self.parse_jsx_element_then(None);
+        if result.is_err() {
+            self.lexer.set_jsx_mode(None);
+        }
+        result
+    }
+
+    // `after` is the mode for the token that follows the closing `>`:
+    // children text inside a parent element, the rest of the tag for an
+    // attribute value, and plain JavaScript otherwise.
+    fn parse_jsx_element_then(&mut self, after: Option<JsxMode>) -> ParseResult<Node> {
+        let start = self.start_span();
+        self.advance_jsx(Some(JsxMode::Tag));
+
+        if self.check(TokenKind::GreaterThan) {
+            self.advance_jsx(Some(JsxMode::Children));
+            let children = self.parse_jsx_children()?;
+            self.expect_jsx(TokenKind::GreaterThan, after)?;
+            return Ok(Node::JsxFragment(JsxFragment {
+                children,
+                span: self.span_from(&start),
+            }));
+        }
+
+        let name = self.parse_jsx_element_name()?;
+        let mut attributes = Vec::new();
+        while !self.check(TokenKind::GreaterThan) {
+            if self.check(TokenKind::Slash) {
+                self.advance();
+                self.expect_jsx(TokenKind::GreaterThan, after)?;
+                return Ok(Node::JsxElement(JsxElement {
+                    name: Box::new(name),
+                    attributes,
+                    children: Vec::new(),
+                    self_closing: true,
+                    span: self.span_from(&start),
+                }));
+            }
+            attributes.push(self.parse_jsx_attribute()?);
+        }
+        self.advance_jsx(Some(JsxMode::Children));
+
+        let children = self.parse_jsx_children()?;
+        let position = self.current_position().unwrap_or_default();
+        let closing = self.parse_jsx_element_name()?;
+        if jsx_name_text(&closing) != jsx_name_text(&name) {
+            return Err(ParserError::invalid_syntax(
+                &format!(
+                    "Expected corresponding JSX closing tag for '{}'",
+                    jsx_name_text(&name)
+                ),
+                position,
+            ));
+        }
+        self.expect_jsx(TokenKind::GreaterThan, after)?;
+
+        Ok(Node::JsxElement(JsxElement {
+            name: Box::new(name),
+            attributes,
+            children,
+            self_closing: false,
+            span: self.span_from(&start),
+        }))
+    }
+
+    // Reads children up to and including the `</` of the closing tag.
+    fn parse_jsx_children(&mut self) -> ParseResult<Vec<Node>> {
+        let mut children = Vec::new();
+        loop {
+            let start = self.start_span();
+            match self.current_kind() {
+                Some(TokenKind::JsxText(raw)) => {
+                    let raw = raw.to_string();
+                    self.advance();
+                    children.push(Node::JsxText(JsxText {
+                        value: decode_entities(&raw),
+                        raw,
+                        span: start,
+                    }));
+                }
+                Some(TokenKind::LeftBrace) => {
+                    self.advance_jsx(None);
+                    let expression = if self.check(TokenKind::RightBrace) {
+                        None
+                    } else {
+                        Some(Box::new(self.parse_with_in(true, Self::parse_expression)?))
+                    };
+                    self.expect_jsx(TokenKind::RightBrace, Some(JsxMode::Children))?;
+                    children.push(Node::JsxExpressionContainer(JsxExpressionContainer {
+                        expression,
+                        span: self.span_from(&start),
+                    }));
+                }
+                Some(TokenKind::LessThan) => {
+                    self.lexer.set_jsx_mode(Some(JsxMode::Tag));
+                    if matches!(self.peek().map(|token| &token.kind), Some(TokenKind::Slash)) {
+                        self.advance();
+                        self.advance();
+                        return Ok(children);
+                    }
+                    children.push(self.parse_jsx_element_then(Some(JsxMode::Children))?);
+                }
+                _ => {
+                    return Err(ParserError::invalid_syntax(
+                        "Unterminated JSX contents",
+                        self.current_position().unwrap_or_default(),
+                    ))
+                }
+            }
+        }
+    }
+
+    // `name`, `a.b.c` or `ns:name`.
+    fn parse_jsx_element_name(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        let name = self.parse_jsx_identifier()?;
+        if self.check(TokenKind::Colon) {
+            return self.parse_jsx_namespaced_name(start, name);
+        }
+
+        let mut name = name;
+        while self.check(TokenKind::Dot) {
+            self.advance();
+            let property = self.parse_jsx_identifier()?;
+            name = Node::MemberExpression(MemberExpression {
+                object: Box::new(name),
+                property: Box::new(property),
+                computed: false,
+                optional: false,
+                span: self.span_from(&start),
+            });
+        }
+        Ok(name)
+    }
+
+    fn parse_jsx_attribute(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        if self.check(TokenKind::LeftBrace) {
+            self.advance_jsx(None);
+            self.expect(TokenKind::Spread)?;
+            let argument = self.parse_assignment_expression()?;
+            self.expect_jsx(TokenKind::RightBrace, Some(JsxMode::Tag))?;
+            return Ok(Node::JsxSpreadAttribute(JsxSpreadAttribute {
+                argument: Box::new(argument),
+                span: self.span_from(&start),
+            }));
+        }
+
+        let name = self.parse_jsx_identifier()?;
+        let name = if self.check(TokenKind::Colon) {
+            self.parse_jsx_namespaced_name(start.clone(), name)?
+        } else {
+            name
+        };
+        let value = if self.check(TokenKind::Assign) {
+            self.advance();
+            Some(Box::new(self.parse_jsx_attribute_value()?))
+        } else {
+            None
+        };
+
+        Ok(Node::JsxAttribute(JsxAttribute {
+            name: Box::new(name),
+            value,
+            span: self.span_from(&start),
+        }))
+    }
+
+    fn parse_jsx_attribute_value(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        match self.current_kind() {
+            Some(TokenKind::JsxString(raw)) => {
+                let value = decode_entities(raw);
+                self.advance();
+                Ok(Node::String(StringLiteral { value, span: start }))
+            }
+            Some(TokenKind::LeftBrace) => {
+                self.advance_jsx(None);
+                if self.check(TokenKind::RightBrace) {
+                    return Err(ParserError::invalid_syntax(
+                        "JSX attributes must only be assigned a non-empty expression",
+                        self.current_position().unwrap_or_default(),
+                    ));
+                }
+                let expression = self.parse_assignment_expression()?;
+                self.expect_jsx(TokenKind::RightBrace, Some(JsxMode::Tag))?;
+                Ok(Node::JsxExpressionContainer(JsxExpressionContainer {
+                    expression: Some(Box::new(expression)),
+                    span: self.span_from(&start),
+                }))
+            }
+            Some(TokenKind::LessThan) => self.parse_jsx_element_then(Some(JsxMode::Tag)),
+            _ => Err(ParserError::invalid_syntax(
+                "JSX value should be either an expression or a quoted JSX text",
+                self.current_position().unwrap_or_default(),
+            )),
+        }
+    }
+
+    fn parse_jsx_namespaced_name(
+        &mut self,
+        start: crate::ast::Span,
+        namespace: Node,
+    ) -> ParseResult<Node> {
+        self.advance();
+        let name = self.parse_jsx_identifier()?;
+        Ok(Node::JsxNamespacedName(JsxNamespacedName {
+            namespace: Box::new(namespace),
+            name: Box::new(name),
+            span: self.span_from(&start),
+        }))
+    }
+
+    fn parse_jsx_identifier(&mut self) -> ParseResult<Node> {
+        match self.current_kind() {
+            Some(TokenKind::JsxName(name)) => {
+                let name = name.to_string();
+                let span = self.start_span();
+                self.advance();
+                Ok(Node::Identifier(Identifier { name, span }))
+            }
+            Some(_) => Err(ParserError::invalid_syntax(
+                "Expected a JSX name",
+                self.current_position().unwrap_or_default(),
+            )),
+            None => Err(ParserError::unexpected_end_of_input(Some("JSX name"))),
+        }
+    }
+
+    fn advance_jsx(&mut self, mode: Option<JsxMode>) {
+        self.lexer.set_jsx_mode(mode);
+        self.advance();
+    }
+
+    fn expect_jsx(&mut self, token_kind: TokenKind, mode: Option<JsxMode>) -> ParseResult<()> {
+        self.lexer.set_jsx_mode(mode);
+        self.expect(token_kind)
+    }
+}
+
+// The text of an element name, for matching closing tags to opening ones.
+fn jsx_name_text(name: &Node) -> String {
+    match name {
+        Node::Identifier(id) => id.name.clone(),
+        Node::MemberExpression(expr) => {
+            format!(
+                "{}.{}",
+                jsx_name_text(&expr.object),
+                jsx_name_text(&expr.property)
+            )
+        }
+        Node::JsxNamespacedName(name) => format!(
+            "{}:{}",
+            jsx_name_text(&name.namespace),
+            jsx_name_text(&name.name)
+        ),
+        _ => String::new(),
+    }
+}
+
+// JSX text and attribute strings have no escapes, only HTML entities. An
+// unknown entity is kept as written.
+fn decode_entities(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(index) = rest.find('&') {
+        out.push_str(&rest[..index]);
+        rest = &rest[index..];
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => match entity.strip_prefix('#') {
+                    Some(hex) if hex.starts_with(['x', 'X']) => u32::from_str_radix(&hex[1..], 16)
+                        .ok()
+                        .and_then(char::from_u32),
+                    Some(decimal) => decimal.parse().ok().and_then(char::from_u32),
+                    None => None,
+                },
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -1,5 +1,7 @@
 pub mod array;
 pub mod class;
 pub mod function;
+#[cfg(feature = "jsx")]
+pub mod jsx;
 pub mod new;
 pub mod object;
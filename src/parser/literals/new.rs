@@ -3,11 +3,16 @@ use crate::lexer::TokenKind;
 use crate::parser::error::ParseResult;
 use crate::parser::Parser;
 
-impl Parser {
+impl Parser<'_> {
     pub fn parse_new_expression(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
         let callee = Box::new(self.parse_primary_expression()?);
+        #[cfg(feature = "typescript")]
+        if self.is_type_arguments_ahead() {
+            self.skip_type_arguments()?;
+        }
 
         let arguments = if self.check(TokenKind::LeftParen) {
             self.advance();
@@ -18,11 +23,11 @@ impl Parser {
             Vec::new()
         };
 
-        let span = self.create_span_from_tokens();
+        let span = self.span_from(&start);
         Ok(Node::NewExpression(NewExpression {
             callee,
             arguments,
-            span: Some(span),
+            span,
         }))
     }
 }
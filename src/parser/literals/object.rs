@@ -3,8 +3,9 @@ use crate::lexer::TokenKind;
 use crate::parser::error::{ParseResult, ParserError};
 use crate::parser::Parser;
 
-impl Parser {
+impl Parser<'_> {
     pub fn parse_object_literal(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
         let mut properties = Vec::new();
@@ -19,14 +20,12 @@ impl Parser {
 
         self.expect(TokenKind::RightBrace)?;
 
-        let span = self.create_span_from_tokens();
-        Ok(Node::ObjectLiteral(ObjectLiteral {
-            properties,
-            span: Some(span),
-        }))
+        let span = self.span_from(&start);
+        Ok(Node::ObjectLiteral(ObjectLiteral { properties, span }))
     }
 
     pub fn parse_property(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         let key = if self.check_identifier() {
             Box::new(self.parse_identifier()?)
         } else if let Some(token) = &self.current {
@@ -42,18 +41,25 @@ impl Parser {
             return Err(ParserError::unexpected_end_of_input(None));
         };
 
-        self.expect(TokenKind::Colon)?;
-        let value = Box::new(self.parse_expression()?);
+        // `{ a }` is short for `{ a: a }`.
+        let shorthand = matches!(*key, Node::Identifier(_))
+            && (self.check(TokenKind::Comma) || self.check(TokenKind::RightBrace));
+        let value = if shorthand {
+            key.clone()
+        } else {
+            self.expect(TokenKind::Colon)?;
+            Box::new(self.parse_expression()?)
+        };
 
-        let span = self.create_span_from_tokens();
+        let span = self.span_from(&start);
         Ok(Node::Property(Property {
             key,
             value,
             kind: "init".to_string(),
             computed: false,
             method: false,
-            shorthand: false,
-            span: Some(span),
+            shorthand,
+            span,
         }))
     }
 }
@@ -1,20 +1,26 @@
 pub mod core;
 pub mod error;
 pub mod expressions;
+pub mod incremental;
 pub mod literals;
+pub mod options;
 pub mod recovery;
 pub mod statements;
+#[cfg(feature = "typescript")]
+pub mod typescript;
 pub mod utils;
 
 pub use core::Parser;
 pub use error::ParserError;
+pub use incremental::{IncrementalParser, TextEdit};
+pub use options::{EcmaVersion, ParserOptions, SourceType};
 
 pub fn parse(source: &str) -> Result<crate::ast::Node, ParserError> {
     let mut parser = Parser::new(source);
     parser.parse()
 }
 
-pub fn parse_with_recovery(source: &str) -> (Option<crate::ast::Node>, Vec<ParserError>) {
+pub fn parse_with_recovery(source: &str) -> (crate::ast::Program, Vec<ParserError>) {
     let mut parser = Parser::new(source);
     parser.parse_with_recovery()
 }
@@ -0,0 +1,123 @@
+pub use crate::ast::SourceType;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EcmaVersion {
+    Es5,
+    Es2015,
+    Es2016,
+    Es2017,
+    Es2018,
+    Es2019,
+    Es2020,
+    Es2021,
+    Es2022,
+    Es2023,
+    Es2024,
+    #[default]
+    Latest,
+}
+
+impl std::fmt::Display for EcmaVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            EcmaVersion::Es5 => "ES5",
+            EcmaVersion::Es2015 => "ES2015",
+            EcmaVersion::Es2016 => "ES2016",
+            EcmaVersion::Es2017 => "ES2017",
+            EcmaVersion::Es2018 => "ES2018",
+            EcmaVersion::Es2019 => "ES2019",
+            EcmaVersion::Es2020 => "ES2020",
+            EcmaVersion::Es2021 => "ES2021",
+            EcmaVersion::Es2022 => "ES2022",
+            EcmaVersion::Es2023 => "ES2023",
+            EcmaVersion::Es2024 => "ES2024",
+            EcmaVersion::Latest => "the latest edition",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParserOptions {
+    pub source_type: SourceType,
+    pub strict_mode: bool,
+    // Rejects arrow functions, classes, `let`, `const`, templates and
+    // modules before ES2015, `**` and `**=` before ES2016, `??` and dynamic
+    // `import()` before ES2020, numeric separators before ES2021, and class
+    // fields, private members, static blocks and top-level await before
+    // ES2022. Other syntax is accepted whatever the version.
+    pub ecma_version: EcmaVersion,
+    // Annex B web compatibility syntax such as HTML-like comments.
+    pub annex_b: bool,
+    // TypeScript syntax, with every type annotation and type-only
+    // declaration dropped from the tree.
+    #[cfg(feature = "typescript")]
+    pub typescript: bool,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self {
+            source_type: SourceType::Script,
+            strict_mode: false,
+            ecma_version: EcmaVersion::Latest,
+            annex_b: true,
+            #[cfg(feature = "typescript")]
+            typescript: false,
+        }
+    }
+}
+
+impl ParserOptions {
+    pub fn script() -> Self {
+        Self::default()
+    }
+
+    pub fn module() -> Self {
+        Self::default().with_source_type(SourceType::Module)
+    }
+
+    pub fn with_source_type(mut self, source_type: SourceType) -> Self {
+        self.source_type = source_type;
+        self
+    }
+
+    pub fn with_strict_mode(mut self, strict_mode: bool) -> Self {
+        self.strict_mode = strict_mode;
+        self
+    }
+
+    pub fn with_ecma_version(mut self, ecma_version: EcmaVersion) -> Self {
+        self.ecma_version = ecma_version;
+        self
+    }
+
+    pub fn with_annex_b(mut self, annex_b: bool) -> Self {
+        self.annex_b = annex_b;
+        self
+    }
+
+    #[cfg(feature = "typescript")]
+    pub fn with_typescript(mut self, typescript: bool) -> Self {
+        self.typescript = typescript;
+        self
+    }
+
+    pub fn is_module(&self) -> bool {
+        self.source_type == SourceType::Module
+    }
+
+    // Module code is always strict.
+    pub fn is_strict(&self) -> bool {
+        self.strict_mode || self.is_module()
+    }
+
+    pub fn allows_top_level_await(&self) -> bool {
+        self.is_module() && self.ecma_version >= EcmaVersion::Es2022
+    }
+
+    // HTML-like comments are Annex B syntax that only script code accepts.
+    pub fn allows_html_comments(&self) -> bool {
+        self.annex_b && !self.is_module()
+    }
+}
@@ -6,20 +6,13 @@ use crate::vm::types::ErrorCount;
 pub enum RecoveryStrategy {
     SkipUntil(Vec<String>),
     SkipUntilStatement,
-    SkipUntilBlock,
-    SkipUntilFunction,
-    SkipUntilClass,
-    SkipUntilModule,
-    InsertToken(String),
-    ReplaceToken(String),
-    DeleteToken,
     NoRecovery,
 }
 
 #[derive(Debug, Clone)]
-pub struct RecoveryContext {
-    pub current_token: Option<Token>,
-    pub previous_token: Option<Token>,
+pub struct RecoveryContext<'a> {
+    pub current_token: Option<Token<'a>>,
+    pub previous_token: Option<Token<'a>>,
     pub recovery_tokens: Vec<String>,
     pub context: ParsingContext,
 }
@@ -36,10 +29,10 @@ pub enum ParsingContext {
     Declaration,
 }
 
-impl RecoveryContext {
+impl<'a> RecoveryContext<'a> {
     pub fn new(
-        current_token: Option<Token>,
-        previous_token: Option<Token>,
+        current_token: Option<Token<'a>>,
+        previous_token: Option<Token<'a>>,
         context: ParsingContext,
     ) -> Self {
         Self {
@@ -74,7 +67,9 @@ impl RecoveryContext {
                 RecoveryStrategy::SkipUntil(vec![";".to_string(), "}".to_string(), ")".to_string()])
             }
 
-            ParsingContext::Block => RecoveryStrategy::SkipUntil(vec!["}".to_string()]),
+            ParsingContext::Block => {
+                RecoveryStrategy::SkipUntil(vec![";".to_string(), "}".to_string()])
+            }
 
             ParsingContext::Function => {
                 RecoveryStrategy::SkipUntil(vec!["}".to_string(), ";".to_string()])
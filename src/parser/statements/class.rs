@@ -1,10 +1,16 @@
-use crate::ast::{ClassDeclaration, Node};
+use crate::ast::{
+    ClassBody, ClassDeclaration, FunctionExpression, MethodDefinition, MethodKind, Node,
+    PrivateIdentifier, PropertyDefinition, Span, StaticBlock,
+};
 use crate::lexer::TokenKind;
-use crate::parser::error::ParseResult;
+use crate::parser::error::{ParseResult, ParserError};
+use crate::parser::options::EcmaVersion;
 use crate::parser::Parser;
 
-impl Parser {
+impl Parser<'_> {
     pub fn parse_class_declaration(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        self.require_ecma_version(EcmaVersion::Es2015, "classes")?;
         self.advance();
 
         let id = if self.check_identifier() {
@@ -12,12 +18,14 @@ impl Parser {
         } else {
             None
         };
+        #[cfg(feature = "typescript")]
+        self.skip_type_parameters()?;
 
         let super_class = if let Some(token) = &self.current {
             if let TokenKind::Keyword(kw) = &token.kind {
                 if kw == "extends" {
                     self.advance();
-                    Some(Box::new(self.parse_expression()?))
+                    Some(Box::new(self.parse_strict(Self::parse_super_class)?))
                 } else {
                     None
                 }
@@ -28,14 +36,294 @@ impl Parser {
             None
         };
 
-        let body = Box::new(self.parse_class_body()?);
+        #[cfg(feature = "typescript")]
+        self.skip_implements_clause()?;
+        let body = Box::new(self.parse_strict(Self::parse_class_body)?);
 
-        let span = self.create_span_from_tokens();
+        let span = self.span_from(&start);
         Ok(Node::ClassDeclaration(ClassDeclaration {
             id,
             super_class,
             body,
-            span: Some(span),
+            span,
         }))
     }
+
+    // In TypeScript the superclass may be followed by type arguments, so it
+    // ends at the left-hand side expression that they apply to.
+    pub fn parse_super_class(&mut self) -> ParseResult<Node> {
+        #[cfg(feature = "typescript")]
+        if self.typescript() {
+            let super_class = self.parse_postfix_expression()?;
+            self.skip_type_arguments()?;
+            return Ok(super_class);
+        }
+        self.parse_expression()
+    }
+
+    pub fn parse_class_body(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        self.expect(TokenKind::LeftBrace)?;
+
+        let mut body = Vec::new();
+        let mut has_constructor = false;
+        while !self.check(TokenKind::RightBrace) && !self.is_eof() {
+            if self.check(TokenKind::Semicolon) {
+                self.advance();
+                continue;
+            }
+            #[cfg(feature = "typescript")]
+            if self.skip_type_only_member()? {
+                continue;
+            }
+
+            let position = self.current_position().unwrap_or_default();
+            let element = self.parse_class_element()?;
+            if let Node::MethodDefinition(MethodDefinition {
+                kind: MethodKind::Constructor,
+                ..
+            }) = &element
+            {
+                if has_constructor {
+                    return Err(ParserError::invalid_class(
+                        "A class may only have one constructor",
+                        position,
+                    ));
+                }
+                has_constructor = true;
+            }
+            body.push(element);
+        }
+
+        self.expect(TokenKind::RightBrace)?;
+
+        let span = self.span_from(&start);
+        Ok(Node::ClassBody(ClassBody { body, span }))
+    }
+
+    fn parse_class_element(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        let position = self.current_position().unwrap_or_default();
+
+        #[cfg(feature = "typescript")]
+        self.skip_member_modifiers();
+        let is_static = self.check_keyword("static") && self.is_class_modifier_ahead();
+        if is_static {
+            self.advance();
+            if self.check(TokenKind::LeftBrace) {
+                self.require_ecma_version(EcmaVersion::Es2022, "class static blocks")?;
+                return self.parse_static_block(start);
+            }
+            #[cfg(feature = "typescript")]
+            self.skip_member_modifiers();
+        }
+
+        let is_async = self.check_keyword("async")
+            && self.is_class_modifier_ahead()
+            && !self.peek().is_some_and(|token| token.newline_before);
+        if is_async {
+            self.advance();
+        }
+
+        let is_generator = self.check(TokenKind::Star);
+        if is_generator {
+            self.advance();
+        }
+
+        let mut kind = MethodKind::Method;
+        if !is_async && !is_generator && self.is_class_modifier_ahead() {
+            if self.check_keyword("get") {
+                kind = MethodKind::Get;
+                self.advance();
+            } else if self.check_keyword("set") {
+                kind = MethodKind::Set;
+                self.advance();
+            }
+        }
+
+        let (key, computed) = self.parse_class_element_key()?;
+        #[cfg(feature = "typescript")]
+        self.skip_member_suffix()?;
+        if matches!(&key, Node::PrivateIdentifier(id) if id.name == "constructor") {
+            return Err(ParserError::invalid_class(
+                "Classes may not have a private element named '#constructor'",
+                position,
+            ));
+        }
+        let named = |name: &str| {
+            !computed
+                && match &key {
+                    Node::Identifier(id) => id.name == name,
+                    Node::String(string) => string.value == name,
+                    _ => false,
+                }
+        };
+
+        if !self.check(TokenKind::LeftParen) {
+            if is_async || is_generator || kind != MethodKind::Method {
+                return Err(ParserError::invalid_class(
+                    "Expected '(' after class method name",
+                    self.current_position().unwrap_or_default(),
+                ));
+            }
+            if named("constructor") || is_static && named("prototype") {
+                return Err(ParserError::invalid_class(
+                    "Classes may not have a field named 'constructor' or a static field named 'prototype'",
+                    position,
+                ));
+            }
+            self.require_ecma_version(EcmaVersion::Es2022, "class fields")?;
+            return self.parse_class_field(start, key, computed, is_static);
+        }
+
+        if !is_static && named("constructor") {
+            if is_async || is_generator || kind != MethodKind::Method {
+                return Err(ParserError::invalid_class(
+                    "Class constructor may not be an accessor, generator or async method",
+                    position,
+                ));
+            }
+            kind = MethodKind::Constructor;
+        } else if is_static && named("prototype") {
+            return Err(ParserError::invalid_class(
+                "Classes may not have a static method named 'prototype'",
+                position,
+            ));
+        }
+
+        let value = self.parse_method_function(is_async, is_generator)?;
+        if let Node::FunctionExpression(function) = &value {
+            let arity_error = match kind {
+                MethodKind::Get if !function.params.is_empty() => {
+                    Some("Getter must not have any formal parameters")
+                }
+                MethodKind::Set if function.params.len() != 1 => {
+                    Some("Setter must have exactly one formal parameter")
+                }
+                _ => None,
+            };
+            if let Some(message) = arity_error {
+                return Err(ParserError::invalid_class(message, position));
+            }
+        }
+
+        let span = self.span_from(&start);
+        Ok(Node::MethodDefinition(MethodDefinition {
+            key: Box::new(key),
+            value: Box::new(value),
+            kind,
+            computed,
+            r#static: is_static,
+            span,
+        }))
+    }
+
+    // `static`, `async`, `get` and `set` are only modifiers when a member
+    // name follows; otherwise they name the member themselves.
+    pub(crate) fn is_class_modifier_ahead(&mut self) -> bool {
+        !matches!(
+            self.peek().map(|token| &token.kind),
+            None | Some(
+                TokenKind::LeftParen
+                    | TokenKind::Assign
+                    | TokenKind::Semicolon
+                    | TokenKind::RightBrace
+                    | TokenKind::Eof
+            )
+        )
+    }
+
+    fn parse_class_element_key(&mut self) -> ParseResult<(Node, bool)> {
+        match self.current_kind() {
+            Some(TokenKind::LeftBracket) => {
+                self.advance();
+                let key = self.parse_with_in(true, Self::parse_assignment_expression)?;
+                self.expect(TokenKind::RightBracket)?;
+                Ok((key, true))
+            }
+            Some(TokenKind::PrivateName(name)) => {
+                let name = name.to_string();
+                self.require_ecma_version(EcmaVersion::Es2022, "private class members")?;
+                let span = self.start_span();
+                self.advance();
+                Ok((
+                    Node::PrivateIdentifier(PrivateIdentifier { name, span }),
+                    false,
+                ))
+            }
+            Some(TokenKind::String(_) | TokenKind::Number(_) | TokenKind::BigInt(_)) => {
+                Ok((self.parse_primary_expression()?, false))
+            }
+            _ => Ok((self.parse_identifier_name()?, false)),
+        }
+    }
+
+    fn parse_class_field(
+        &mut self,
+        start: Span,
+        key: Node,
+        computed: bool,
+        is_static: bool,
+    ) -> ParseResult<Node> {
+        #[cfg(feature = "typescript")]
+        self.skip_type_annotation()?;
+        let value = if self.check(TokenKind::Assign) {
+            self.advance();
+            let value = self.parse_with_await(false, |parser| {
+                parser.parse_with_in(true, Self::parse_assignment_expression)
+            })?;
+            Some(Box::new(value))
+        } else {
+            None
+        };
+        self.consume_semicolon()?;
+
+        let span = self.span_from(&start);
+        Ok(Node::PropertyDefinition(PropertyDefinition {
+            key: Box::new(key),
+            value,
+            computed,
+            r#static: is_static,
+            span,
+        }))
+    }
+
+    fn parse_method_function(&mut self, is_async: bool, is_generator: bool) -> ParseResult<Node> {
+        let start = self.start_span();
+        self.expect(TokenKind::LeftParen)?;
+        let params = self.parse_parameters()?;
+        self.expect(TokenKind::RightParen)?;
+        #[cfg(feature = "typescript")]
+        self.skip_return_type()?;
+
+        if !self.check(TokenKind::LeftBrace) {
+            return Err(ParserError::invalid_class(
+                "Expected '{' before class method body",
+                self.current_position().unwrap_or_default(),
+            ));
+        }
+        let body = Box::new(self.parse_with_await(is_async, Self::parse_function_body)?);
+
+        let span = self.span_from(&start);
+        Ok(Node::FunctionExpression(FunctionExpression {
+            id: None,
+            params,
+            body,
+            generator: is_generator,
+            r#async: is_async,
+            span,
+        }))
+    }
+
+    // Parses from the `{` after `static` at `start`.
+    fn parse_static_block(&mut self, start: Span) -> ParseResult<Node> {
+        let block = self.parse_with_await(false, Self::parse_block_statement)?;
+        let body = match block {
+            Node::BlockStatement(block) => block.body,
+            _ => Vec::new(),
+        };
+
+        let span = self.span_from(&start);
+        Ok(Node::StaticBlock(StaticBlock { body, span }))
+    }
 }
@@ -1,56 +1,81 @@
 use crate::ast::{
-    BreakStatement, CatchClause, ContinueStatement, Node, ThrowStatement, TryStatement,
+    BreakStatement, CatchClause, ContinueStatement, LabeledStatement, Node, ThrowStatement,
+    TryStatement,
 };
 use crate::lexer::TokenKind;
-use crate::parser::error::ParseResult;
+use crate::parser::error::{ParseResult, ParserError};
 use crate::parser::Parser;
 
-impl Parser {
+impl Parser<'_> {
     pub fn parse_break_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
-        let label = if self.check_identifier() {
+        let label = if self.check_identifier() && !self.has_newline_before() {
             Some(Box::new(self.parse_identifier()?))
         } else {
             None
         };
+        self.consume_semicolon()?;
 
-        let span = self.create_span_from_tokens();
-        Ok(Node::BreakStatement(BreakStatement {
-            label,
-            span: Some(span),
-        }))
+        let span = self.span_from(&start);
+        Ok(Node::BreakStatement(BreakStatement { label, span }))
     }
 
     pub fn parse_continue_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
-        let label = if self.check_identifier() {
+        let label = if self.check_identifier() && !self.has_newline_before() {
             Some(Box::new(self.parse_identifier()?))
         } else {
             None
         };
+        self.consume_semicolon()?;
+
+        let span = self.span_from(&start);
+        Ok(Node::ContinueStatement(ContinueStatement { label, span }))
+    }
+
+    pub fn is_labeled_statement_ahead(&mut self) -> bool {
+        self.check_identifier()
+            && matches!(self.peek().map(|token| &token.kind), Some(TokenKind::Colon))
+    }
 
-        let span = self.create_span_from_tokens();
-        Ok(Node::ContinueStatement(ContinueStatement {
+    pub fn parse_labeled_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        let label = Box::new(self.parse_identifier()?);
+        self.expect(TokenKind::Colon)?;
+        let body = Box::new(self.parse_statement()?);
+
+        let span = self.span_from(&start);
+        Ok(Node::LabeledStatement(LabeledStatement {
             label,
-            span: Some(span),
+            body,
+            span,
         }))
     }
 
     pub fn parse_throw_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
+        if self.has_newline_before() {
+            return Err(ParserError::invalid_syntax(
+                "Line break is not allowed after 'throw'",
+                self.current_position().unwrap_or_default(),
+            ));
+        }
+
         let argument = Box::new(self.parse_expression()?);
+        self.consume_semicolon()?;
 
-        let span = self.create_span_from_tokens();
-        Ok(Node::ThrowStatement(ThrowStatement {
-            argument,
-            span: Some(span),
-        }))
+        let span = self.span_from(&start);
+        Ok(Node::ThrowStatement(ThrowStatement { argument, span }))
     }
 
     pub fn parse_try_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
         let block = Box::new(self.parse_block_statement()?);
@@ -84,29 +109,28 @@ impl Parser {
             None
         };
 
-        let span = self.create_span_from_tokens();
+        let span = self.span_from(&start);
         Ok(Node::TryStatement(TryStatement {
             block,
             handler,
             finalizer,
-            span: Some(span),
+            span,
         }))
     }
 
     pub fn parse_catch_clause(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         self.advance();
 
         self.expect(TokenKind::LeftParen)?;
         let param = Box::new(self.parse_identifier()?);
+        #[cfg(feature = "typescript")]
+        self.skip_type_annotation()?;
         self.expect(TokenKind::RightParen)?;
 
         let body = Box::new(self.parse_block_statement()?);
 
-        let span = self.create_span_from_tokens();
-        Ok(Node::CatchClause(CatchClause {
-            param,
-            body,
-            span: Some(span),
-        }))
+        let span = self.span_from(&start);
+        Ok(Node::CatchClause(CatchClause { param, body, span }))
     }
 }
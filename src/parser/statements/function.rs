@@ -1,10 +1,45 @@
-use crate::ast::{FunctionDeclaration, Node};
+use crate::ast::{FunctionDeclaration, Node, Span};
 use crate::lexer::TokenKind;
-use crate::parser::error::ParseResult;
+use crate::parser::error::{ParseResult, ParserError};
 use crate::parser::Parser;
 
-impl Parser {
+impl Parser<'_> {
     pub fn parse_function_declaration(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        self.parse_function_declaration_inner(start, false)
+    }
+
+    pub fn parse_async_function_declaration(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        self.advance();
+        if !self.check_keyword("function") || self.has_newline_before() {
+            return Err(ParserError::invalid_syntax(
+                "Expected 'function' after 'async'",
+                self.current_position().unwrap_or_default(),
+            ));
+        }
+
+        self.parse_function_declaration_inner(start, true)
+    }
+
+    // `async function` with no line break in between; otherwise `async` is
+    // an identifier.
+    pub fn is_async_function_ahead(&mut self) -> bool {
+        self.check_keyword("async")
+            && matches!(
+                self.peek(),
+                Some(token) if !token.newline_before
+                    && matches!(&token.kind, TokenKind::Keyword(kw) if kw == "function")
+            )
+    }
+
+    // Parses from the `function` keyword, after any `async` at `start` has
+    // been consumed.
+    pub fn parse_function_declaration_inner(
+        &mut self,
+        start: Span,
+        is_async: bool,
+    ) -> ParseResult<Node> {
         self.advance();
 
         let id = if self.check_identifier() {
@@ -13,20 +48,24 @@ impl Parser {
             None
         };
 
+        #[cfg(feature = "typescript")]
+        self.skip_type_parameters()?;
         self.expect(TokenKind::LeftParen)?;
         let params = self.parse_parameters()?;
         self.expect(TokenKind::RightParen)?;
+        #[cfg(feature = "typescript")]
+        self.skip_return_type()?;
 
-        let body = Box::new(self.parse_function_body()?);
+        let body = Box::new(self.parse_with_await(is_async, Self::parse_function_body)?);
 
-        let span = self.create_span_from_tokens();
+        let span = self.span_from(&start);
         Ok(Node::FunctionDeclaration(FunctionDeclaration {
             id,
             params,
             body,
             generator: false,
-            r#async: false,
-            span: Some(span),
+            r#async: is_async,
+            span,
         }))
     }
 }
@@ -1,4 +1,5 @@
 pub mod class;
 pub mod control_flow;
 pub mod function;
+pub mod module;
 pub mod variable;
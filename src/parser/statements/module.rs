@@ -0,0 +1,486 @@
+use crate::ast::{
+    ExportDeclaration, ExportNamespaceSpecifier, ExportSpecifier, Identifier, ImportAttribute,
+    ImportDeclaration, ImportDefaultSpecifier, ImportExpression, ImportNamespaceSpecifier,
+    ImportSpecifier, MetaProperty, Node, Span, StringLiteral,
+};
+use crate::lexer::TokenKind;
+use crate::parser::error::{ParseResult, ParserError};
+use crate::parser::options::EcmaVersion;
+use crate::parser::Parser;
+
+impl Parser<'_> {
+    pub fn parse_import_declaration(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        if self.is_import_expression_ahead() {
+            return self.parse_expression_statement();
+        }
+
+        self.check_module_goal("import")?;
+        self.advance();
+
+        let mut specifiers = Vec::new();
+        if !matches!(self.current_kind(), Some(TokenKind::String(_))) {
+            if self.check_identifier() {
+                let local = Box::new(self.parse_identifier()?);
+                let span = local.span().clone();
+                specifiers.push(Node::ImportDefaultSpecifier(ImportDefaultSpecifier {
+                    local,
+                    span,
+                }));
+
+                if self.check(TokenKind::Comma) {
+                    self.advance();
+                    self.parse_import_clause_rest(&mut specifiers)?;
+                }
+            } else {
+                self.parse_import_clause_rest(&mut specifiers)?;
+            }
+
+            self.expect_keyword("from")?;
+        }
+
+        let source = Box::new(self.parse_module_source()?);
+        let attributes = self.parse_import_attributes()?;
+        self.consume_semicolon()?;
+
+        let span = self.span_from(&start);
+        Ok(Node::ImportDeclaration(ImportDeclaration {
+            specifiers,
+            source,
+            attributes,
+            span,
+        }))
+    }
+
+    pub fn parse_export_declaration(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        self.check_module_goal("export")?;
+        self.advance();
+
+        if self.check(TokenKind::Star) {
+            return self.parse_export_all_declaration(start);
+        }
+
+        if self.check_keyword("default") {
+            self.advance();
+            return self.parse_export_default_declaration(start);
+        }
+
+        if self.check(TokenKind::LeftBrace) {
+            return self.parse_export_named_declaration(start);
+        }
+
+        #[cfg(feature = "typescript")]
+        self.skip_abstract_modifier();
+        let declaration = match self.current_kind() {
+            Some(TokenKind::Keyword(kw))
+                if matches!(kw.as_str(), "var" | "let" | "const" | "function" | "class") =>
+            {
+                self.parse_declaration()?
+            }
+            Some(TokenKind::Keyword(kw)) if kw == "async" => {
+                self.parse_async_function_declaration()?
+            }
+            _ => {
+                return Err(ParserError::invalid_module(
+                    "Expected a declaration, '{', '*' or 'default' after 'export'",
+                    self.current_position().unwrap_or_default(),
+                ))
+            }
+        };
+
+        let span = self.span_from(&start);
+        Ok(Node::ExportDeclaration(ExportDeclaration {
+            declaration: Some(Box::new(declaration)),
+            specifiers: Vec::new(),
+            source: None,
+            attributes: Vec::new(),
+            default: false,
+            span,
+        }))
+    }
+
+    // `import(...)` and `import.meta` are expressions, not declarations.
+    pub fn is_import_expression_ahead(&mut self) -> bool {
+        self.check_keyword("import")
+            && matches!(
+                self.peek().map(|token| &token.kind),
+                Some(TokenKind::LeftParen | TokenKind::Dot)
+            )
+    }
+
+    // `import(source)`, valid in either goal, or `import.meta`, which only a
+    // module may use.
+    pub fn parse_import_expression(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        if matches!(
+            self.peek().map(|token| &token.kind),
+            Some(TokenKind::LeftParen)
+        ) {
+            self.require_ecma_version(EcmaVersion::Es2020, "dynamic import()")?;
+            self.advance();
+            self.expect(TokenKind::LeftParen)?;
+            let source = Box::new(self.parse_assignment_expression()?);
+            self.expect(TokenKind::RightParen)?;
+            let span = self.span_from(&start);
+            return Ok(Node::ImportExpression(ImportExpression { source, span }));
+        }
+        self.check_module_goal("import.meta")?;
+        self.advance();
+
+        self.expect(TokenKind::Dot)?;
+        let property = self.parse_identifier()?;
+        if !matches!(&property, Node::Identifier(id) if id.name == "meta") {
+            return Err(ParserError::invalid_expression(
+                "The only valid meta property for import is 'import.meta'",
+                property.span().start,
+            ));
+        }
+        let meta = Node::Identifier(Identifier {
+            name: "import".to_string(),
+            span: start.clone(),
+        });
+
+        let span = self.span_from(&start);
+        Ok(Node::MetaProperty(MetaProperty {
+            meta: Box::new(meta),
+            property: Box::new(property),
+            span,
+        }))
+    }
+
+    fn check_module_goal(&self, keyword: &str) -> ParseResult<()> {
+        let position = self.current_position().unwrap_or_default();
+        if !self.options().is_module() {
+            return Err(ParserError::invalid_module(
+                &format!("Cannot use '{keyword}' outside a module"),
+                position,
+            ));
+        }
+        if self.options().ecma_version < EcmaVersion::Es2015 {
+            return Err(ParserError::invalid_module(
+                "Modules require ES2015 or later",
+                position,
+            ));
+        }
+        Ok(())
+    }
+
+    // After an optional default binding: `* as ns` or `{ a, b as c }`.
+    fn parse_import_clause_rest(&mut self, specifiers: &mut Vec<Node>) -> ParseResult<()> {
+        if self.check(TokenKind::Star) {
+            let start = self.start_span();
+            self.advance();
+            self.expect_keyword("as")?;
+            let local = Box::new(self.parse_identifier()?);
+            let span = self.span_from(&start);
+            specifiers.push(Node::ImportNamespaceSpecifier(ImportNamespaceSpecifier {
+                local,
+                span,
+            }));
+            return Ok(());
+        }
+
+        self.expect(TokenKind::LeftBrace)?;
+        while !self.check(TokenKind::RightBrace) && !self.is_eof() {
+            #[cfg(feature = "typescript")]
+            if self.skip_type_specifier()? {
+                continue;
+            }
+            let start = self.start_span();
+            let position = self.current_position().unwrap_or_default();
+            let imported = self.parse_module_export_name()?;
+
+            let local = if self.check_keyword("as") {
+                self.advance();
+                self.parse_identifier()?
+            } else {
+                match &imported {
+                    Node::Identifier(id) if !is_reserved_binding(&id.name) => imported.clone(),
+                    _ => {
+                        return Err(ParserError::invalid_module(
+                            "Imported name needs an 'as' binding",
+                            position,
+                        ))
+                    }
+                }
+            };
+
+            let span = self.span_from(&start);
+            specifiers.push(Node::ImportSpecifier(ImportSpecifier {
+                local: Box::new(local),
+                imported: Box::new(imported),
+                span,
+            }));
+
+            if !self.check(TokenKind::RightBrace) {
+                self.expect(TokenKind::Comma)?;
+            }
+        }
+        self.expect(TokenKind::RightBrace)
+    }
+
+    // The parsers for the rest of an export declaration take the span of its
+    // `export` keyword.
+    fn parse_export_all_declaration(&mut self, start: Span) -> ParseResult<Node> {
+        let star = self.start_span();
+        self.advance();
+
+        let exported = if self.check_keyword("as") {
+            self.advance();
+            Some(Box::new(self.parse_module_export_name()?))
+        } else {
+            None
+        };
+        let span = self.span_from(&star);
+        let specifier = Node::ExportNamespaceSpecifier(ExportNamespaceSpecifier { exported, span });
+
+        self.expect_keyword("from")?;
+        let source = Box::new(self.parse_module_source()?);
+        let attributes = self.parse_import_attributes()?;
+        self.consume_semicolon()?;
+
+        let span = self.span_from(&start);
+        Ok(Node::ExportDeclaration(ExportDeclaration {
+            declaration: None,
+            specifiers: vec![specifier],
+            source: Some(source),
+            attributes,
+            default: false,
+            span,
+        }))
+    }
+
+    fn parse_export_default_declaration(&mut self, start: Span) -> ParseResult<Node> {
+        #[cfg(feature = "typescript")]
+        self.skip_abstract_modifier();
+        let async_function = self.is_async_function_ahead();
+
+        let declaration = match self.current_kind() {
+            _ if async_function => self.parse_async_function_declaration()?,
+            Some(TokenKind::Keyword(kw)) if kw == "function" => {
+                self.parse_function_declaration()?
+            }
+            Some(TokenKind::Keyword(kw)) if kw == "class" => self.parse_class_declaration()?,
+            _ => {
+                let expression = self.parse_expression()?;
+                self.consume_semicolon()?;
+                expression
+            }
+        };
+
+        let span = self.span_from(&start);
+        Ok(Node::ExportDeclaration(ExportDeclaration {
+            declaration: Some(Box::new(declaration)),
+            specifiers: Vec::new(),
+            source: None,
+            attributes: Vec::new(),
+            default: true,
+            span,
+        }))
+    }
+
+    fn parse_export_named_declaration(&mut self, start: Span) -> ParseResult<Node> {
+        self.advance();
+
+        let mut specifiers = Vec::new();
+        // Local names may only be strings or reserved words when re-exporting,
+        // which is only known once `from` is reached.
+        let mut local_only_in_reexport = None;
+        while !self.check(TokenKind::RightBrace) && !self.is_eof() {
+            #[cfg(feature = "typescript")]
+            if self.skip_type_specifier()? {
+                continue;
+            }
+            let start = self.start_span();
+            let position = self.current_position().unwrap_or_default();
+            let local = self.parse_module_export_name()?;
+            if local_only_in_reexport.is_none()
+                && !matches!(&local, Node::Identifier(id) if !is_reserved_binding(&id.name))
+            {
+                local_only_in_reexport = Some(position);
+            }
+
+            let exported = if self.check_keyword("as") {
+                self.advance();
+                self.parse_module_export_name()?
+            } else {
+                local.clone()
+            };
+
+            let span = self.span_from(&start);
+            specifiers.push(Node::ExportSpecifier(ExportSpecifier {
+                local: Box::new(local),
+                exported: Box::new(exported),
+                span,
+            }));
+
+            if !self.check(TokenKind::RightBrace) {
+                self.expect(TokenKind::Comma)?;
+            }
+        }
+        self.expect(TokenKind::RightBrace)?;
+
+        let (source, attributes) = if self.check_keyword("from") {
+            self.advance();
+            let source = Box::new(self.parse_module_source()?);
+            (Some(source), self.parse_import_attributes()?)
+        } else {
+            if let Some(position) = local_only_in_reexport {
+                return Err(ParserError::invalid_module(
+                    "Only local bindings can be exported without 'from'",
+                    position,
+                ));
+            }
+            (None, Vec::new())
+        };
+        self.consume_semicolon()?;
+
+        let span = self.span_from(&start);
+        Ok(Node::ExportDeclaration(ExportDeclaration {
+            declaration: None,
+            specifiers,
+            source,
+            attributes,
+            default: false,
+            span,
+        }))
+    }
+
+    // `with { type: "json" }` after a module specifier.
+    fn parse_import_attributes(&mut self) -> ParseResult<Vec<ImportAttribute>> {
+        let mut attributes: Vec<ImportAttribute> = Vec::new();
+        if !self.check_keyword("with") {
+            return Ok(attributes);
+        }
+        self.advance();
+        self.expect(TokenKind::LeftBrace)?;
+
+        while !self.check(TokenKind::RightBrace) && !self.is_eof() {
+            let start = self.start_span();
+            let position = self.current_position().unwrap_or_default();
+            let key = match self.current_kind() {
+                Some(TokenKind::String(_)) => self.parse_module_source()?,
+                _ => self.parse_identifier_name()?,
+            };
+            if attributes
+                .iter()
+                .any(|attribute| attribute_key_name(&attribute.key) == attribute_key_name(&key))
+            {
+                return Err(ParserError::invalid_module(
+                    &format!("Duplicate import attribute '{}'", attribute_key_name(&key)),
+                    position,
+                ));
+            }
+
+            self.expect(TokenKind::Colon)?;
+            let value = self.parse_module_source()?;
+
+            let span = self.span_from(&start);
+            attributes.push(ImportAttribute {
+                key: Box::new(key),
+                value: Box::new(value),
+                span,
+            });
+
+            if !self.check(TokenKind::RightBrace) {
+                self.expect(TokenKind::Comma)?;
+            }
+        }
+        self.expect(TokenKind::RightBrace)?;
+
+        Ok(attributes)
+    }
+
+    fn parse_module_source(&mut self) -> ParseResult<Node> {
+        match self.current_kind() {
+            Some(TokenKind::String(value)) => {
+                let value = value.to_string();
+                let span = self.start_span();
+                self.advance();
+                Ok(Node::String(StringLiteral { value, span }))
+            }
+            Some(_) => Err(ParserError::invalid_module(
+                "Expected a string literal",
+                self.current_position().unwrap_or_default(),
+            )),
+            None => Err(ParserError::unexpected_end_of_input(Some("string literal"))),
+        }
+    }
+
+    // An IdentifierName or a string literal, as allowed for imported and
+    // exported names.
+    fn parse_module_export_name(&mut self) -> ParseResult<Node> {
+        match self.current_kind() {
+            Some(TokenKind::String(_)) => self.parse_module_source(),
+            _ => self.parse_identifier_name(),
+        }
+    }
+
+    // Any identifier, including reserved words, as allowed after `.` or in
+    // module specifiers.
+    pub fn parse_identifier_name(&mut self) -> ParseResult<Node> {
+        let name = match self.current_kind() {
+            Some(TokenKind::Identifier(name)) => name.to_string(),
+            Some(TokenKind::Keyword(kw)) => kw.as_str().to_string(),
+            Some(TokenKind::Boolean(value)) => value.to_string(),
+            Some(TokenKind::Null) => "null".to_string(),
+            Some(TokenKind::Undefined) => "undefined".to_string(),
+            Some(_) => {
+                return Err(ParserError::invalid_syntax(
+                    "Expected identifier",
+                    self.current_position().unwrap_or_default(),
+                ))
+            }
+            None => return Err(ParserError::unexpected_end_of_input(Some("identifier"))),
+        };
+        let span = self.start_span();
+        self.advance();
+        Ok(Node::Identifier(Identifier { name, span }))
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> ParseResult<()> {
+        if self.check_keyword(keyword) {
+            self.advance();
+            return Ok(());
+        }
+
+        match &self.current {
+            Some(token) => Err(ParserError::unexpected_token(token, Some(keyword))),
+            None => Err(ParserError::unexpected_end_of_input(Some(keyword))),
+        }
+    }
+}
+
+fn attribute_key_name(key: &Node) -> &str {
+    match key {
+        Node::Identifier(id) => &id.name,
+        Node::String(string) => &string.value,
+        _ => "",
+    }
+}
+
+// Names that the lexer produces as keyword tokens but that can still be bound
+// as ordinary identifiers in sloppy code.
+fn is_reserved_binding(name: &str) -> bool {
+    !matches!(
+        name,
+        "as" | "from"
+            | "of"
+            | "get"
+            | "set"
+            | "async"
+            | "static"
+            | "let"
+            | "yield"
+            | "await"
+            | "undefined"
+            | "private"
+            | "public"
+            | "protected"
+            | "interface"
+            | "implements"
+            | "strict"
+    ) && crate::lexer::Keyword::from_string(name).is_some()
+        || matches!(name, "true" | "false" | "null")
+}
@@ -1,10 +1,23 @@
 use crate::ast::{Node, VariableDeclaration, VariableDeclarator};
 use crate::lexer::TokenKind;
 use crate::parser::error::ParseResult;
+use crate::parser::options::EcmaVersion;
 use crate::parser::Parser;
 
-impl Parser {
+impl Parser<'_> {
     pub fn parse_variable_declaration(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
+        let mut declaration = self.parse_variable_declaration_list()?;
+        self.consume_semicolon()?;
+        if let Node::VariableDeclaration(decl) = &mut declaration {
+            decl.span = self.span_from(&start);
+        }
+        Ok(declaration)
+    }
+
+    // The declaration without its terminating `;`, as used in `for` heads.
+    pub fn parse_variable_declaration_list(&mut self) -> ParseResult<Node> {
+        let start = self.start_span();
         let kind = if let Some(token) = &self.current {
             if let TokenKind::Keyword(kw) = &token.kind {
                 match kw.as_str() {
@@ -20,12 +33,18 @@ impl Parser {
             unreachable!()
         };
 
+        if kind != "var" {
+            self.require_ecma_version(EcmaVersion::Es2015, &format!("'{kind}' declarations"))?;
+        }
         self.advance();
 
         let mut declarations = Vec::new();
 
         loop {
-            let id = self.parse_identifier()?;
+            let declarator_start = self.start_span();
+            let id = self.parse_destructuring_pattern()?;
+            #[cfg(feature = "typescript")]
+            self.skip_binding_type()?;
             let init = if self.check(TokenKind::Assign) {
                 self.advance();
                 Some(Box::new(self.parse_expression()?))
@@ -33,11 +52,11 @@ impl Parser {
                 None
             };
 
-            let span = self.create_span_from_tokens();
+            let span = self.span_from(&declarator_start);
             declarations.push(VariableDeclarator {
                 id: Box::new(id),
                 init,
-                span: Some(span),
+                span,
             });
 
             if !self.check(TokenKind::Comma) {
@@ -46,15 +65,11 @@ impl Parser {
             self.advance();
         }
 
-        if self.check(TokenKind::Semicolon) {
-            self.advance();
-        }
-
-        let span = self.create_span_from_tokens();
+        let span = self.span_from(&start);
         Ok(Node::VariableDeclaration(VariableDeclaration {
             kind: kind.to_string(),
             declarations,
-            span: Some(span),
+            span,
         }))
     }
 }
@@ -0,0 +1,794 @@
+use crate::lexer::tokens::Keyword;
+use crate::lexer::TokenKind;
+use crate::parser::error::{ParseResult, ParserError};
+use crate::parser::Parser;
+
+// TypeScript is parsed by skipping over everything that only exists at the
+// type level, so the tree is the plain JavaScript that is left. Types are
+// never built, only consumed token by token. Syntax with runtime behavior of
+// its own, such as enums, is an error instead.
+impl<'a> Parser<'a> {
+    pub fn typescript(&self) -> bool {
+        self.options().typescript
+    }
+
+    // Skips an interface, type alias, `declare` statement, type-only import
+    // or export, or function overload signature, including any `export` in
+    // front of it. Returns whether there was one.
+    pub(crate) fn skip_type_declaration(&mut self) -> ParseResult<bool> {
+        if !self.typescript() {
+            return Ok(false);
+        }
+
+        let mut lookahead = self.lookahead();
+        let mut prefix = 0;
+        if lookahead.check_keyword("export") {
+            lookahead.advance();
+            prefix += 1;
+            if lookahead.check_keyword("default") {
+                lookahead.advance();
+                prefix += 1;
+            }
+        }
+        if !lookahead.is_type_declaration()? {
+            return Ok(false);
+        }
+
+        for _ in 0..prefix {
+            self.advance();
+        }
+        self.skip_type_only_declaration()?;
+        Ok(true)
+    }
+
+    // `abstract class` is parsed as a plain class.
+    pub(crate) fn skip_abstract_modifier(&mut self) {
+        if self.typescript()
+            && self.check_contextual("abstract")
+            && matches!(
+                self.peek_kind_on_line(),
+                Some(TokenKind::Keyword(Keyword::Class))
+            )
+        {
+            self.advance();
+        }
+    }
+
+    // A `this` parameter only declares the type of `this`. Parameter
+    // properties would assign to fields, so they cannot be stripped.
+    pub(crate) fn skip_type_only_parameter(&mut self) -> ParseResult<bool> {
+        if !self.typescript() {
+            return Ok(false);
+        }
+
+        let modifier = self.check_keyword("public")
+            || self.check_keyword("private")
+            || self.check_keyword("protected")
+            || self.check_contextual("readonly")
+            || self.check_contextual("override");
+        if modifier
+            && matches!(
+                self.peek_kind_on_line(),
+                Some(TokenKind::Identifier(_) | TokenKind::Keyword(_))
+            )
+        {
+            return Err(ParserError::invalid_function(
+                "Parameter properties cannot be stripped to JavaScript",
+                self.current_position().unwrap_or_default(),
+            ));
+        }
+
+        if !self.check_keyword("this") {
+            return Ok(false);
+        }
+        self.advance();
+        self.skip_type_annotation()?;
+        if self.check(TokenKind::Comma) {
+            self.advance();
+        }
+        Ok(true)
+    }
+
+    // The `?` of an optional parameter or the `!` of a definitely assigned
+    // variable, and the type annotation after a binding name.
+    pub(crate) fn skip_binding_type(&mut self) -> ParseResult<()> {
+        if self.typescript()
+            && (self.check(TokenKind::Question) || self.check(TokenKind::Exclamation))
+        {
+            self.advance();
+        }
+        self.skip_type_annotation()
+    }
+
+    pub(crate) fn skip_type_annotation(&mut self) -> ParseResult<()> {
+        if self.typescript() && self.check(TokenKind::Colon) {
+            self.advance();
+            self.skip_type()?;
+        }
+        Ok(())
+    }
+
+    // A function's return type, which may also be a type predicate.
+    pub(crate) fn skip_return_type(&mut self) -> ParseResult<()> {
+        if self.typescript() && self.check(TokenKind::Colon) {
+            self.advance();
+            self.skip_type_or_predicate()?;
+        }
+        Ok(())
+    }
+
+    // `<T extends U = V, ...>` on a function, class or type declaration.
+    pub(crate) fn skip_type_parameters(&mut self) -> ParseResult<()> {
+        if !self.typescript() || !self.check(TokenKind::LessThan) {
+            return Ok(());
+        }
+        self.advance();
+
+        while !self.check(TokenKind::GreaterThan) && !self.is_eof() {
+            let modifier = self.check_keyword("const")
+                || self.check_keyword("in")
+                || self.check_contextual("out");
+            if modifier && matches!(self.peek_kind_on_line(), Some(TokenKind::Identifier(_))) {
+                self.advance();
+            }
+            self.parse_identifier()?;
+            if self.check_keyword("extends") {
+                self.advance();
+                self.skip_type()?;
+            }
+            if self.check(TokenKind::Assign) {
+                self.advance();
+                self.skip_type()?;
+            }
+            if !self.check(TokenKind::Comma) {
+                break;
+            }
+            self.advance();
+        }
+        self.expect_closing_angle()
+    }
+
+    // `<A, B>` after a callee, superclass or type name.
+    pub(crate) fn skip_type_arguments(&mut self) -> ParseResult<()> {
+        if !self.typescript() || !self.check(TokenKind::LessThan) {
+            return Ok(());
+        }
+        self.advance();
+
+        loop {
+            self.skip_type()?;
+            if !self.check(TokenKind::Comma) {
+                break;
+            }
+            self.advance();
+        }
+        self.expect_closing_angle()
+    }
+
+    // Whether a `<` starts the type arguments of a call, as in `f<T>(x)`,
+    // rather than a comparison.
+    pub(crate) fn is_type_arguments_ahead(&self) -> bool {
+        if !self.typescript() || !self.check(TokenKind::LessThan) {
+            return false;
+        }
+        let mut lookahead = self.lookahead();
+        lookahead.skip_type_arguments().is_ok()
+            && matches!(
+                lookahead.current_kind(),
+                Some(
+                    TokenKind::LeftParen
+                        | TokenKind::NoSubstitutionTemplate { .. }
+                        | TokenKind::TemplateHead { .. }
+                )
+            )
+    }
+
+    // Arrow functions may have type parameters, typed parameters and a
+    // return type between the `(` and the `=>`.
+    pub(crate) fn is_typed_arrow_function_ahead(&self) -> bool {
+        let mut lookahead = self.lookahead();
+        lookahead.skip_type_parameters().is_ok()
+            && lookahead.check(TokenKind::LeftParen)
+            && lookahead.skip_balanced().is_ok()
+            && lookahead.skip_return_type().is_ok()
+            && lookahead.check(TokenKind::Arrow)
+    }
+
+    // `x as T`, `x as const` and `x satisfies T`.
+    pub(crate) fn skip_type_assertions(&mut self) -> ParseResult<()> {
+        while self.typescript()
+            && (self.check_keyword("as") || self.check_contextual("satisfies"))
+            && !self.has_newline_before()
+        {
+            self.advance();
+            self.skip_type()?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn skip_implements_clause(&mut self) -> ParseResult<()> {
+        if self.typescript() && self.check_keyword("implements") {
+            self.advance();
+            self.skip_type_list()?;
+        }
+        Ok(())
+    }
+
+    // Accessibility modifiers, `readonly` and `override` on a class member.
+    pub(crate) fn skip_member_modifiers(&mut self) {
+        while self.typescript() && self.is_member_modifier() && self.is_modifier_ahead() {
+            self.advance();
+        }
+    }
+
+    // The `?` of an optional member, the `!` of a definitely assigned field,
+    // and a method's type parameters.
+    pub(crate) fn skip_member_suffix(&mut self) -> ParseResult<()> {
+        if self.typescript()
+            && (self.check(TokenKind::Question) || self.check(TokenKind::Exclamation))
+        {
+            self.advance();
+        }
+        self.skip_type_parameters()
+    }
+
+    // Skips an index signature, a `declare` or `abstract` member, or a method
+    // overload signature. Returns whether there was one.
+    pub(crate) fn skip_type_only_member(&mut self) -> ParseResult<bool> {
+        if !self.typescript() || !self.lookahead().skip_member_head().unwrap_or(false) {
+            return Ok(false);
+        }
+        self.skip_member_head()?;
+        self.consume_semicolon()?;
+        Ok(true)
+    }
+
+    // An inline `type` import or export specifier, with its trailing comma.
+    pub(crate) fn skip_type_specifier(&mut self) -> ParseResult<bool> {
+        if !self.typescript()
+            || !self.check_contextual("type")
+            || matches!(
+                self.peek_kind_on_line(),
+                None | Some(
+                    TokenKind::Comma | TokenKind::RightBrace | TokenKind::Keyword(Keyword::As)
+                )
+            )
+        {
+            return Ok(false);
+        }
+
+        self.advance();
+        self.advance();
+        if self.check_keyword("as") {
+            self.advance();
+            self.advance();
+        }
+        if self.check(TokenKind::Comma) {
+            self.advance();
+        }
+        Ok(true)
+    }
+
+    // Runs on a lookahead copy, from the token after any `export`.
+    fn is_type_declaration(&mut self) -> ParseResult<bool> {
+        let position = self.current_position().unwrap_or_default();
+        let Some(kind) = self.current_kind().cloned() else {
+            return Ok(false);
+        };
+
+        match kind {
+            TokenKind::Keyword(Keyword::Interface) => Ok(true),
+            TokenKind::Keyword(Keyword::Enum) => Err(ParserError::invalid_declaration(
+                "Enums cannot be stripped to JavaScript",
+                position,
+            )),
+            TokenKind::Keyword(Keyword::Const)
+                if matches!(
+                    self.peek_kind_on_line(),
+                    Some(TokenKind::Keyword(Keyword::Enum))
+                ) =>
+            {
+                Err(ParserError::invalid_declaration(
+                    "Enums cannot be stripped to JavaScript",
+                    position,
+                ))
+            }
+            TokenKind::Keyword(Keyword::Import) => {
+                self.advance();
+                Ok(self.check_contextual("type")
+                    && matches!(
+                        self.peek_kind_on_line(),
+                        Some(TokenKind::Identifier(_) | TokenKind::LeftBrace | TokenKind::Star)
+                    ))
+            }
+            TokenKind::Keyword(Keyword::Function | Keyword::Async) => {
+                if self.check_keyword("async") {
+                    self.advance();
+                }
+                Ok(self.skip_function_signature().is_ok() && !self.check(TokenKind::LeftBrace))
+            }
+            TokenKind::Identifier(name) => match name.as_ref() {
+                "type" => Ok(matches!(
+                    self.peek_kind_on_line(),
+                    Some(TokenKind::Identifier(_) | TokenKind::LeftBrace | TokenKind::Star)
+                )),
+                "declare" => Ok(matches!(
+                    self.peek_kind_on_line(),
+                    Some(TokenKind::Identifier(_) | TokenKind::Keyword(_))
+                )),
+                "namespace" | "module"
+                    if matches!(self.peek_kind_on_line(), Some(TokenKind::Identifier(_))) =>
+                {
+                    Err(ParserError::invalid_declaration(
+                        "Namespaces cannot be stripped to JavaScript",
+                        position,
+                    ))
+                }
+                _ => Ok(false),
+            },
+            _ => Ok(false),
+        }
+    }
+
+    fn skip_type_only_declaration(&mut self) -> ParseResult<()> {
+        if self.check_keyword("interface") {
+            self.advance();
+            self.parse_identifier()?;
+            self.skip_type_parameters()?;
+            if self.check_keyword("extends") {
+                self.advance();
+                self.skip_type_list()?;
+            }
+            return self.skip_balanced();
+        }
+
+        if self.check_keyword("import") {
+            self.advance();
+            self.advance();
+            return self.skip_module_clause();
+        }
+
+        if self.check_keyword("async") || self.check_keyword("function") {
+            if self.check_keyword("async") {
+                self.advance();
+            }
+            self.skip_function_signature()?;
+            return self.consume_semicolon();
+        }
+
+        if self.check_contextual("declare") {
+            self.advance();
+            return self.skip_ambient_declaration();
+        }
+
+        // `type`
+        self.advance();
+        if self.check(TokenKind::LeftBrace) || self.check(TokenKind::Star) {
+            return self.skip_module_clause();
+        }
+        self.parse_identifier()?;
+        self.skip_type_parameters()?;
+        self.expect(TokenKind::Assign)?;
+        self.skip_type()?;
+        self.consume_semicolon()
+    }
+
+    // After `declare`. Everything declared this way exists elsewhere, so
+    // even enums and namespaces can go.
+    fn skip_ambient_declaration(&mut self) -> ParseResult<()> {
+        let const_enum = self.check_keyword("const")
+            && matches!(
+                self.peek_kind_on_line(),
+                Some(TokenKind::Keyword(Keyword::Enum))
+            );
+        let variable =
+            self.check_keyword("var") || self.check_keyword("let") || self.check_keyword("const");
+        if variable && !const_enum {
+            self.parse_variable_declaration()?;
+            return Ok(());
+        }
+        if self.check_keyword("async") || self.check_keyword("function") {
+            return self.skip_type_only_declaration();
+        }
+        if self.check_keyword("interface") || self.check_contextual("type") {
+            return self.skip_type_only_declaration();
+        }
+
+        // Classes, enums, namespaces, modules and `global`, up to the end of
+        // their body. `declare module "m";` has none.
+        while !self.check(TokenKind::LeftBrace)
+            && !self.check(TokenKind::Semicolon)
+            && !self.is_eof()
+        {
+            self.advance();
+            if self.can_insert_semicolon() && !self.check(TokenKind::LeftBrace) {
+                break;
+            }
+        }
+        if self.check(TokenKind::LeftBrace) {
+            self.skip_balanced()
+        } else {
+            self.consume_semicolon()
+        }
+    }
+
+    // The rest of a type-only import or export, up to its end.
+    fn skip_module_clause(&mut self) -> ParseResult<()> {
+        while !self.check(TokenKind::Semicolon) && !self.can_insert_semicolon() {
+            if self.check(TokenKind::LeftBrace) {
+                self.skip_balanced()?;
+            } else {
+                self.advance();
+            }
+        }
+        self.consume_semicolon()
+    }
+
+    // From `function` to the end of the return type.
+    fn skip_function_signature(&mut self) -> ParseResult<()> {
+        self.expect(TokenKind::Keyword(Keyword::Function))?;
+        if self.check(TokenKind::Star) {
+            self.advance();
+        }
+        if self.check_identifier() {
+            self.advance();
+        }
+        self.skip_type_parameters()?;
+        if !self.check(TokenKind::LeftParen) {
+            return Err(self.expected("'('"));
+        }
+        self.skip_balanced()?;
+        self.skip_return_type()
+    }
+
+    // Walks a class member up to its body or initializer, and returns
+    // whether it only exists at the type level.
+    fn skip_member_head(&mut self) -> ParseResult<bool> {
+        let mut erased = false;
+        loop {
+            let modifier = self.is_member_modifier()
+                || self.check_keyword("static")
+                || self.check_keyword("async")
+                || self.check_keyword("get")
+                || self.check_keyword("set");
+            let ambient = self.check_contextual("declare") || self.check_contextual("abstract");
+            if !(modifier || ambient) || !self.is_modifier_ahead() {
+                break;
+            }
+            erased |= ambient;
+            self.advance();
+        }
+        if self.check(TokenKind::Star) {
+            self.advance();
+        }
+
+        if self.check(TokenKind::LeftBracket) {
+            let mut lookahead = self.lookahead();
+            lookahead.advance();
+            let index_signature = lookahead.check_identifier() && {
+                lookahead.advance();
+                lookahead.check(TokenKind::Colon)
+            };
+            self.skip_balanced()?;
+            if index_signature {
+                self.skip_type_annotation()?;
+                return Ok(true);
+            }
+        } else {
+            self.advance();
+        }
+
+        if self.check(TokenKind::Question) || self.check(TokenKind::Exclamation) {
+            self.advance();
+        }
+        self.skip_type_parameters()?;
+        if self.check(TokenKind::LeftParen) {
+            self.skip_balanced()?;
+            self.skip_return_type()?;
+            return Ok(erased || !self.check(TokenKind::LeftBrace));
+        }
+        self.skip_type_annotation()?;
+        Ok(erased)
+    }
+
+    fn is_member_modifier(&self) -> bool {
+        self.check_keyword("public")
+            || self.check_keyword("private")
+            || self.check_keyword("protected")
+            || self.check_contextual("readonly")
+            || self.check_contextual("override")
+    }
+
+    // Modifiers are only modifiers when a member name follows on the same
+    // line; otherwise they name the member themselves.
+    fn is_modifier_ahead(&mut self) -> bool {
+        matches!(
+            self.peek_kind_on_line(),
+            Some(
+                TokenKind::Identifier(_)
+                    | TokenKind::Keyword(_)
+                    | TokenKind::String(_)
+                    | TokenKind::Number(_)
+                    | TokenKind::BigInt(_)
+                    | TokenKind::PrivateName(_)
+                    | TokenKind::LeftBracket
+                    | TokenKind::Star
+            )
+        )
+    }
+
+    fn skip_type_list(&mut self) -> ParseResult<()> {
+        loop {
+            self.skip_type()?;
+            if !self.check(TokenKind::Comma) {
+                return Ok(());
+            }
+            self.advance();
+        }
+    }
+
+    // `x is T` and `asserts x is T` as well as plain types.
+    fn skip_type_or_predicate(&mut self) -> ParseResult<()> {
+        if self.check_contextual("asserts")
+            && matches!(
+                self.peek_kind_on_line(),
+                Some(TokenKind::Identifier(_) | TokenKind::Keyword(Keyword::This))
+            )
+        {
+            self.advance();
+        }
+        let subject = self.check_identifier() || self.check_keyword("this");
+        if subject
+            && matches!(self.peek_kind_on_line(), Some(TokenKind::Identifier(name)) if name == "is")
+        {
+            self.advance();
+            self.advance();
+        }
+        if self.check(TokenKind::Colon) || self.check(TokenKind::Arrow) {
+            // `asserts x` on its own
+            return Ok(());
+        }
+        self.skip_type()
+    }
+
+    fn skip_type(&mut self) -> ParseResult<()> {
+        if self.is_function_type_ahead() {
+            return self.skip_function_type();
+        }
+
+        self.skip_union_type()?;
+        // `A extends B ? C : D`
+        if self.check_keyword("extends") && !self.has_newline_before() {
+            self.advance();
+            self.skip_union_type()?;
+            self.expect(TokenKind::Question)?;
+            self.skip_type()?;
+            self.expect(TokenKind::Colon)?;
+            self.skip_type()?;
+        }
+        Ok(())
+    }
+
+    fn is_function_type_ahead(&self) -> bool {
+        match self.current_kind() {
+            Some(TokenKind::LessThan) => true,
+            Some(TokenKind::Keyword(Keyword::New)) => true,
+            Some(TokenKind::LeftParen) => {
+                let mut lookahead = self.lookahead();
+                lookahead.skip_balanced().is_ok() && lookahead.check(TokenKind::Arrow)
+            }
+            _ => false,
+        }
+    }
+
+    // `(a: A) => R`, `<T>(a: T) => R` and `new (a: A) => R`.
+    fn skip_function_type(&mut self) -> ParseResult<()> {
+        if self.check_keyword("new") {
+            self.advance();
+        }
+        self.skip_type_parameters()?;
+        if !self.check(TokenKind::LeftParen) {
+            return Err(self.expected("'('"));
+        }
+        self.skip_balanced()?;
+        self.expect(TokenKind::Arrow)?;
+        self.skip_type_or_predicate()
+    }
+
+    fn skip_union_type(&mut self) -> ParseResult<()> {
+        if self.check(TokenKind::BitwiseOr) || self.check(TokenKind::BitwiseAnd) {
+            self.advance();
+        }
+        self.skip_type_operator()?;
+        while self.check(TokenKind::BitwiseOr) || self.check(TokenKind::BitwiseAnd) {
+            self.advance();
+            self.skip_type_operator()?;
+        }
+        Ok(())
+    }
+
+    fn skip_type_operator(&mut self) -> ParseResult<()> {
+        let operator = matches!(
+            self.current_kind(),
+            Some(TokenKind::Identifier(name))
+                if matches!(name.as_ref(), "keyof" | "unique" | "readonly" | "infer")
+        );
+        if operator && !self.is_type_end_ahead() {
+            self.advance();
+            return self.skip_type_operator();
+        }
+
+        self.skip_primary_type()?;
+        // `T[]` and `T[K]`, which may not start a new line.
+        while self.check(TokenKind::LeftBracket) && !self.has_newline_before() {
+            self.advance();
+            if !self.check(TokenKind::RightBracket) {
+                self.skip_type()?;
+            }
+            self.expect(TokenKind::RightBracket)?;
+        }
+        Ok(())
+    }
+
+    // Whether the token after `keyof` and friends ends the type, which makes
+    // them type names instead.
+    fn is_type_end_ahead(&mut self) -> bool {
+        matches!(
+            self.peek().map(|token| &token.kind),
+            None | Some(
+                TokenKind::Comma
+                    | TokenKind::RightParen
+                    | TokenKind::RightBracket
+                    | TokenKind::RightBrace
+                    | TokenKind::GreaterThan
+                    | TokenKind::Semicolon
+                    | TokenKind::Assign
+                    | TokenKind::BitwiseOr
+                    | TokenKind::BitwiseAnd
+                    | TokenKind::Question
+                    | TokenKind::Colon
+                    | TokenKind::Eof
+            )
+        )
+    }
+
+    fn skip_primary_type(&mut self) -> ParseResult<()> {
+        match self.current_kind() {
+            Some(TokenKind::LeftParen) => {
+                self.advance();
+                self.skip_type()?;
+                self.expect(TokenKind::RightParen)
+            }
+            // Object, mapped and tuple types.
+            Some(TokenKind::LeftBrace | TokenKind::LeftBracket) => self.skip_balanced(),
+            Some(TokenKind::Minus) => {
+                self.advance();
+                self.advance();
+                Ok(())
+            }
+            Some(
+                TokenKind::String(_)
+                | TokenKind::Number(_)
+                | TokenKind::BigInt(_)
+                | TokenKind::Boolean(_)
+                | TokenKind::Null
+                | TokenKind::Undefined
+                | TokenKind::NoSubstitutionTemplate { .. },
+            ) => {
+                self.advance();
+                Ok(())
+            }
+            Some(TokenKind::TemplateHead { .. }) => {
+                self.advance();
+                loop {
+                    self.skip_type()?;
+                    match self.current_kind() {
+                        Some(TokenKind::TemplateMiddle { .. }) => self.advance(),
+                        Some(TokenKind::TemplateTail { .. }) => {
+                            self.advance();
+                            return Ok(());
+                        }
+                        _ => return Err(self.expected("template continuation")),
+                    }
+                }
+            }
+            Some(TokenKind::Keyword(Keyword::Typeof)) => {
+                self.advance();
+                self.skip_type_reference()
+            }
+            // `import("m").T`
+            Some(TokenKind::Keyword(Keyword::Import)) => {
+                self.advance();
+                self.skip_balanced()?;
+                while self.check(TokenKind::Dot) {
+                    self.advance();
+                    self.parse_identifier_name()?;
+                }
+                self.skip_type_arguments()
+            }
+            Some(TokenKind::Identifier(_) | TokenKind::Keyword(_)) => self.skip_type_reference(),
+            Some(_) => Err(ParserError::invalid_syntax(
+                "Expected a type",
+                self.current_position().unwrap_or_default(),
+            )),
+            None => Err(ParserError::unexpected_end_of_input(Some("type"))),
+        }
+    }
+
+    // `A`, `a.B` or `A<T>`.
+    fn skip_type_reference(&mut self) -> ParseResult<()> {
+        self.advance();
+        while self.check(TokenKind::Dot) {
+            self.advance();
+            self.parse_identifier_name()?;
+        }
+        if self.check(TokenKind::LessThan) && !self.has_newline_before() {
+            self.skip_type_arguments()?;
+        }
+        Ok(())
+    }
+
+    // Skips from an opening bracket to its matching closing one.
+    fn skip_balanced(&mut self) -> ParseResult<()> {
+        let mut depth = 0usize;
+        loop {
+            match self.current_kind() {
+                Some(TokenKind::LeftParen | TokenKind::LeftBracket | TokenKind::LeftBrace) => {
+                    depth += 1
+                }
+                Some(TokenKind::RightParen | TokenKind::RightBracket | TokenKind::RightBrace)
+                    if depth > 0 =>
+                {
+                    depth -= 1
+                }
+                None | Some(TokenKind::Eof) => {
+                    return Err(ParserError::unexpected_end_of_input(None))
+                }
+                _ if depth == 0 => return Err(self.expected("opening bracket")),
+                _ => {}
+            }
+            self.advance();
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    // The lexer reads the `>>` in `A<B<C>>` as one token, so closing a list
+    // only takes its first `>` and leaves the rest as the current token.
+    fn expect_closing_angle(&mut self) -> ParseResult<()> {
+        let rest = match self.current_kind() {
+            Some(TokenKind::RightShift) => TokenKind::GreaterThan,
+            Some(TokenKind::UnsignedRightShift) => TokenKind::RightShift,
+            Some(TokenKind::GreaterThanEqual) => TokenKind::Assign,
+            Some(TokenKind::RightShiftAssign) => TokenKind::GreaterThanEqual,
+            Some(TokenKind::UnsignedRightShiftAssign) => TokenKind::RightShiftAssign,
+            _ => return self.expect(TokenKind::GreaterThan),
+        };
+        if let Some(token) = self.current.as_mut() {
+            token.kind = rest;
+            token.range.start += 1;
+            token.span.range.start += 1;
+            token.span.start.column += 1;
+            token.newline_before = false;
+        }
+        Ok(())
+    }
+
+    fn check_contextual(&self, name: &str) -> bool {
+        matches!(self.current_kind(), Some(TokenKind::Identifier(id)) if id == name)
+    }
+
+    fn peek_kind_on_line(&mut self) -> Option<TokenKind<'a>> {
+        self.peek()
+            .filter(|token| !token.newline_before)
+            .map(|token| token.kind.clone())
+    }
+
+    fn expected(&self, what: &str) -> ParserError {
+        match self.current.as_ref() {
+            Some(token) => ParserError::unexpected_token(token, Some(what)),
+            None => ParserError::unexpected_end_of_input(Some(what)),
+        }
+    }
+}
@@ -1 +1,54 @@
+use crate::ast::{Node, Span};
+use crate::lexer::TokenKind;
+use crate::parser::error::{ParseResult, ParserError};
+use crate::parser::Parser;
 
+impl Parser<'_> {
+    // `( ... )` is parsed as a list of expressions until it is known whether
+    // `=>` follows; if it does, the expressions are the arrow function's
+    // parameters, so nothing has to be scanned twice.
+    pub fn parse_parenthesized_or_arrow(&mut self, start: Span) -> ParseResult<Node> {
+        self.advance();
+
+        let mut items = Vec::new();
+        while !self.check(TokenKind::RightParen) && !self.is_eof() {
+            let offset = self.start_span().range.start;
+            let item = self.parse_with_in(true, Self::parse_expression)?;
+            items.push((offset, item));
+            if !self.check(TokenKind::Comma) {
+                break;
+            }
+            self.advance();
+        }
+        let position = self.current_position().unwrap_or_default();
+        self.expect(TokenKind::RightParen)?;
+
+        if self.check(TokenKind::Arrow) || items.is_empty() {
+            let params = items
+                .into_iter()
+                .map(into_parameter)
+                .collect::<ParseResult<_>>()?;
+            return self.parse_arrow_function_body(start, params, false);
+        }
+
+        match <[_; 1]>::try_from(items) {
+            Ok([(_, expr)]) => Ok(expr),
+            Err(_) => Err(ParserError::invalid_expression(
+                "Unexpected token in expression",
+                position,
+            )),
+        }
+    }
+}
+
+// A parameter is a name written where the expression started, which rules out
+// a parenthesized one like the `(a)` of `((a)) => a`.
+fn into_parameter((offset, node): (usize, Node)) -> ParseResult<Node> {
+    match node {
+        Node::Identifier(_) if node.span().range.start == offset => Ok(node),
+        other => Err(ParserError::invalid_syntax(
+            "Invalid arrow function parameter",
+            other.span().start,
+        )),
+    }
+}
@@ -1,7 +1,7 @@
 use crate::lexer::TokenKind;
 use crate::parser::Parser;
 
-impl Parser {
+impl Parser<'_> {
     pub fn is_equality_operator(&self) -> bool {
         if let Some(token) = &self.current {
             matches!(
@@ -1 +1,69 @@
+use crate::ast::{Node, Position, TemplateElement, TemplateLiteral};
+use crate::lexer::TokenKind;
+use crate::parser::error::{ParseResult, ParserError};
+use crate::parser::options::EcmaVersion;
+use crate::parser::Parser;
 
+impl Parser<'_> {
+    // Parses a template starting at its `NoSubstitutionTemplate` or
+    // `TemplateHead` token. Only tagged templates may carry quasis whose
+    // cooked value is undefined.
+    pub fn parse_template_literal(&mut self, tagged: bool) -> ParseResult<Node> {
+        self.require_ecma_version(EcmaVersion::Es2015, "template literals")?;
+        let start = self.start_span();
+        let mut quasis = Vec::new();
+        let mut expressions = Vec::new();
+
+        loop {
+            let Some(token) = self.current.clone() else {
+                return Err(ParserError::unexpected_end_of_input(Some(
+                    "template continuation",
+                )));
+            };
+
+            let expected_kind = if quasis.is_empty() {
+                "template literal"
+            } else {
+                "template continuation"
+            };
+            let (cooked, raw, tail) = match (&token.kind, quasis.is_empty()) {
+                (TokenKind::NoSubstitutionTemplate { cooked, raw }, true)
+                | (TokenKind::TemplateTail { cooked, raw }, false) => (cooked, raw, true),
+                (TokenKind::TemplateHead { cooked, raw }, true)
+                | (TokenKind::TemplateMiddle { cooked, raw }, false) => (cooked, raw, false),
+                _ => return Err(ParserError::unexpected_token(&token, Some(expected_kind))),
+            };
+
+            let position = Position {
+                line: token.start().line,
+                column: token.start().column,
+            };
+            if cooked.is_none() && !tagged {
+                return Err(ParserError::invalid_syntax(
+                    "Invalid escape sequence in template literal",
+                    position,
+                ));
+            }
+
+            quasis.push(TemplateElement {
+                cooked: cooked.as_ref().map(|cooked| cooked.to_string()),
+                raw: raw.to_string(),
+                tail,
+                span: self.start_span(),
+            });
+            self.advance();
+
+            if tail {
+                break;
+            }
+            expressions.push(self.parse_expression()?);
+        }
+
+        let span = self.span_from(&start);
+        Ok(Node::TemplateLiteral(TemplateLiteral {
+            quasis,
+            expressions,
+            span,
+        }))
+    }
+}
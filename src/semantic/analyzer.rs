@@ -3,17 +3,44 @@ use crate::semantic::errors::SemanticError;
 use crate::semantic::scope::Scope;
 use crate::semantic::types::Type;
 use crate::vm::types::{ColumnNumber, LineNumber, ScopeDepth, VariableCount};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 pub struct SemanticAnalyzer {
     scope_stack: Vec<Scope>,
     #[allow(dead_code)]
     type_env: HashMap<String, Type>,
     errors: Vec<SemanticError>,
-    #[allow(dead_code)]
     strict_mode: bool,
     scope_depth: ScopeDepth,
     variable_count: VariableCount,
+    // The `#names` declared by each enclosing class body, innermost last,
+    // with the type of the member each one names.
+    private_names: Vec<HashMap<String, Type>>,
+    context: FunctionContext,
+}
+
+// What the innermost function allows: `await`, `yield` and `return`, and
+// the loops, switches and labels that `break` and `continue` may target.
+#[derive(Debug, Clone, Default)]
+struct FunctionContext {
+    in_function: bool,
+    is_async: bool,
+    is_generator: bool,
+    // Each label in scope, with whether it labels an iteration statement.
+    labels: Vec<(String, bool)>,
+    loop_depth: usize,
+    breakable_depth: usize,
+}
+
+impl FunctionContext {
+    fn function(is_async: bool, is_generator: bool) -> Self {
+        Self {
+            in_function: true,
+            is_async,
+            is_generator,
+            ..Self::default()
+        }
+    }
 }
 
 impl SemanticAnalyzer {
@@ -25,6 +52,8 @@ impl SemanticAnalyzer {
             strict_mode: false,
             scope_depth: ScopeDepth::new(0),
             variable_count: VariableCount::new(0),
+            private_names: Vec::new(),
+            context: FunctionContext::default(),
         };
 
         analyzer.scope_stack.push(Scope::new());
@@ -32,6 +61,15 @@ impl SemanticAnalyzer {
     }
 }
 
+impl SemanticAnalyzer {
+    // Analyzes the program as strict code even without a "use strict"
+    // directive. Module programs are always strict.
+    pub fn with_strict_mode(mut self, strict_mode: bool) -> Self {
+        self.strict_mode = strict_mode;
+        self
+    }
+}
+
 impl Default for SemanticAnalyzer {
     fn default() -> Self {
         Self::new()
@@ -39,247 +77,91 @@ impl Default for SemanticAnalyzer {
 }
 
 impl SemanticAnalyzer {
-    #[allow(dead_code)]
     fn get_line_number(&self, node: &Node) -> LineNumber {
         match node {
-            Node::Program(program) => program
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::VariableDeclaration(decl) => decl
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::FunctionDeclaration(decl) => decl
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::ClassDeclaration(decl) => decl
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::BinaryExpression(expr) => expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::UnaryExpression(expr) => expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::CallExpression(expr) => expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::NewExpression(expr) => expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::MemberExpression(expr) => expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::AssignmentExpression(expr) => expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::ConditionalExpression(expr) => expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::LogicalExpression(expr) => expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::UpdateExpression(expr) => expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::BlockStatement(stmt) => stmt
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::IfStatement(stmt) => stmt
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::ForStatement(stmt) => stmt
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::WhileStatement(stmt) => stmt
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::DoWhileStatement(stmt) => stmt
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::SwitchStatement(stmt) => stmt
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::TryStatement(stmt) => stmt
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::CatchClause(clause) => clause
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::ThrowStatement(stmt) => stmt
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::ReturnStatement(stmt) => stmt
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::BreakStatement(stmt) => stmt
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::ContinueStatement(stmt) => stmt
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::LabeledStatement(stmt) => stmt
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::WithStatement(stmt) => stmt
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::DebuggerStatement(stmt) => stmt
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::ExpressionStatement(stmt) => stmt
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::ArrayLiteral(lit) => lit
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::ObjectLiteral(lit) => lit
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::TemplateLiteral(lit) => lit
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::TaggedTemplateExpression(expr) => expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::Property(prop) => prop
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::SpreadElement(elem) => elem
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::RestElement(elem) => elem
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::Super(super_expr) => super_expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::MetaProperty(prop) => prop
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::ArrowFunctionExpression(expr) => expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::FunctionExpression(expr) => expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::ClassExpression(expr) => expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::YieldExpression(expr) => expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::AwaitExpression(expr) => expr
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::RegExp(regexp) => regexp
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::ImportDeclaration(decl) => decl
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
-            Node::ExportDeclaration(decl) => decl
-                .span
-                .as_ref()
-                .map(|s| s.start.line)
-                .unwrap_or(LineNumber::new(1)),
+            Node::Program(program) => program.span.start.line,
+            Node::VariableDeclaration(decl) => decl.span.start.line,
+            Node::FunctionDeclaration(decl) => decl.span.start.line,
+            Node::ClassDeclaration(decl) => decl.span.start.line,
+            Node::ClassBody(body) => body.span.start.line,
+            Node::MethodDefinition(method) => method.span.start.line,
+            Node::PropertyDefinition(prop) => prop.span.start.line,
+            Node::StaticBlock(block) => block.span.start.line,
+            Node::BinaryExpression(expr) => expr.span.start.line,
+            Node::UnaryExpression(expr) => expr.span.start.line,
+            Node::CallExpression(expr) => expr.span.start.line,
+            Node::NewExpression(expr) => expr.span.start.line,
+            Node::MemberExpression(expr) => expr.span.start.line,
+            Node::AssignmentExpression(expr) => expr.span.start.line,
+            Node::ConditionalExpression(expr) => expr.span.start.line,
+            Node::LogicalExpression(expr) => expr.span.start.line,
+            Node::UpdateExpression(expr) => expr.span.start.line,
+            Node::BlockStatement(stmt) => stmt.span.start.line,
+            Node::IfStatement(stmt) => stmt.span.start.line,
+            Node::ForStatement(stmt) => stmt.span.start.line,
+            Node::ForInStatement(stmt) => stmt.span.start.line,
+            Node::ForOfStatement(stmt) => stmt.span.start.line,
+            Node::WhileStatement(stmt) => stmt.span.start.line,
+            Node::DoWhileStatement(stmt) => stmt.span.start.line,
+            Node::SwitchStatement(stmt) => stmt.span.start.line,
+            Node::TryStatement(stmt) => stmt.span.start.line,
+            Node::CatchClause(clause) => clause.span.start.line,
+            Node::ThrowStatement(stmt) => stmt.span.start.line,
+            Node::ReturnStatement(stmt) => stmt.span.start.line,
+            Node::BreakStatement(stmt) => stmt.span.start.line,
+            Node::ContinueStatement(stmt) => stmt.span.start.line,
+            Node::LabeledStatement(stmt) => stmt.span.start.line,
+            Node::WithStatement(stmt) => stmt.span.start.line,
+            Node::DebuggerStatement(stmt) => stmt.span.start.line,
+            Node::EmptyStatement(stmt) => stmt.span.start.line,
+            Node::ExpressionStatement(stmt) => stmt.span.start.line,
+            Node::ArrayLiteral(lit) => lit.span.start.line,
+            Node::ObjectLiteral(lit) => lit.span.start.line,
+            Node::TemplateLiteral(lit) => lit.span.start.line,
+            Node::TaggedTemplateExpression(expr) => expr.span.start.line,
+            Node::Property(prop) => prop.span.start.line,
+            Node::SpreadElement(elem) => elem.span.start.line,
+            Node::RestElement(elem) => elem.span.start.line,
+            Node::Super(super_expr) => super_expr.span.start.line,
+            Node::MetaProperty(prop) => prop.span.start.line,
+            Node::ImportExpression(expr) => expr.span.start.line,
+            Node::ArrowFunctionExpression(expr) => expr.span.start.line,
+            Node::FunctionExpression(expr) => expr.span.start.line,
+            Node::ClassExpression(expr) => expr.span.start.line,
+            Node::YieldExpression(expr) => expr.span.start.line,
+            Node::AwaitExpression(expr) => expr.span.start.line,
+            Node::RegExp(regexp) => regexp.span.start.line,
+            Node::Error(error) => error.span.start.line,
+            Node::ImportDeclaration(decl) => decl.span.start.line,
+            Node::ExportDeclaration(decl) => decl.span.start.line,
+            Node::ImportSpecifier(specifier) => specifier.span.start.line,
+            Node::ImportDefaultSpecifier(specifier) => specifier.span.start.line,
+            Node::ImportNamespaceSpecifier(specifier) => specifier.span.start.line,
+            Node::ExportSpecifier(specifier) => specifier.span.start.line,
+            Node::ExportNamespaceSpecifier(specifier) => specifier.span.start.line,
+            #[cfg(feature = "jsx")]
+            Node::JsxElement(jsx) => jsx.span.start.line,
+            #[cfg(feature = "jsx")]
+            Node::JsxFragment(jsx) => jsx.span.start.line,
+            #[cfg(feature = "jsx")]
+            Node::JsxAttribute(jsx) => jsx.span.start.line,
+            #[cfg(feature = "jsx")]
+            Node::JsxSpreadAttribute(jsx) => jsx.span.start.line,
+            #[cfg(feature = "jsx")]
+            Node::JsxExpressionContainer(jsx) => jsx.span.start.line,
+            #[cfg(feature = "jsx")]
+            Node::JsxText(jsx) => jsx.span.start.line,
+            #[cfg(feature = "jsx")]
+            Node::JsxNamespacedName(jsx) => jsx.span.start.line,
 
             Node::Identifier(_) => LineNumber::new(1),
+            Node::PrivateIdentifier(_) => LineNumber::new(1),
             Node::Number(_) => LineNumber::new(1),
             Node::String(_) => LineNumber::new(1),
             Node::Boolean(_) => LineNumber::new(1),
-            Node::Null => LineNumber::new(1),
-            Node::Undefined => LineNumber::new(1),
-            Node::This => LineNumber::new(1),
+            Node::Null(_) => LineNumber::new(1),
+            Node::Undefined(_) => LineNumber::new(1),
+            Node::This(_) => LineNumber::new(1),
             Node::BigInt(_) => LineNumber::new(1),
         }
     }
@@ -319,20 +201,41 @@ impl SemanticAnalyzer {
             Node::Program(program) => self.visit_program(program),
             Node::VariableDeclaration(decl) => self.visit_variable_declaration(decl),
             Node::FunctionDeclaration(func) => self.visit_function_declaration(func),
+            Node::ClassDeclaration(class) => self.visit_class_declaration(class),
+            Node::ClassExpression(class) => self.visit_class_expression(class),
             Node::ExpressionStatement(stmt) => self.visit_expression_statement(stmt),
             Node::BinaryExpression(expr) => self.visit_binary_expression(expr),
             Node::UnaryExpression(expr) => self.visit_unary_expression(expr),
-            Node::Identifier(id) => self.visit_identifier(id),
+            Node::Identifier(id) => self.visit_identifier(&id.name),
+            Node::PrivateIdentifier(id) => self.visit_private_identifier(&id.name),
             Node::Number(_) => Ok(Type::Number),
             Node::String(_) => Ok(Type::String),
             Node::Boolean(_) => Ok(Type::Boolean),
-            Node::Null => Ok(Type::Null),
-            Node::Undefined => Ok(Type::Undefined),
-            Node::This => self.visit_this(),
+            Node::Null(_) => Ok(Type::Null),
+            Node::Undefined(_) => Ok(Type::Undefined),
+            Node::This(_) => self.visit_this(),
             Node::CallExpression(call) => self.visit_call_expression(call),
             Node::AssignmentExpression(assign) => self.visit_assignment_expression(assign),
             Node::IfStatement(if_stmt) => self.visit_if_statement(if_stmt),
             Node::WhileStatement(while_stmt) => self.visit_while_statement(while_stmt),
+            Node::DoWhileStatement(do_while) => self.visit_do_while_statement(do_while),
+            Node::ForStatement(for_stmt) => self.visit_for_statement(for_stmt),
+            Node::SwitchStatement(switch) => self.visit_switch_statement(switch),
+            Node::TryStatement(try_stmt) => self.visit_try_statement(try_stmt),
+            Node::ThrowStatement(throw) => self.visit_node(&throw.argument),
+            Node::LabeledStatement(labeled) => self.visit_labeled_statement(labeled),
+            Node::WithStatement(with) => self.visit_with_statement(with),
+            Node::BreakStatement(stmt) => self.visit_break_statement(stmt.label.as_deref()),
+            Node::ContinueStatement(stmt) => self.visit_continue_statement(stmt.label.as_deref()),
+            Node::UpdateExpression(update) => self.visit_update_expression(update),
+            Node::AwaitExpression(expr) => self.visit_await_expression(expr),
+            Node::YieldExpression(expr) => self.visit_yield_expression(expr),
+            Node::ExportDeclaration(export) => match &export.declaration {
+                Some(declaration) => self.visit_node(declaration),
+                None => Ok(Type::Undefined),
+            },
+            Node::ForInStatement(for_in) => self.visit_for_in_statement(for_in),
+            Node::ForOfStatement(for_of) => self.visit_for_of_statement(for_of),
             Node::ReturnStatement(return_stmt) => self.visit_return_statement(return_stmt),
             Node::BlockStatement(block) => self.visit_block_statement(block),
             Node::ArrayLiteral(array) => self.visit_array_literal(array),
@@ -344,11 +247,20 @@ impl SemanticAnalyzer {
                 self.visit_conditional_expression(conditional)
             }
             Node::ArrowFunctionExpression(arrow) => self.visit_arrow_function_expression(arrow),
+            Node::FunctionExpression(func) => self.visit_function_expression(func),
             _ => Ok(Type::Unknown),
         }
     }
 
     fn visit_program(&mut self, program: &crate::ast::Program) -> Result<Type, SemanticError> {
+        // Module code is strict and may use `await` at the top level.
+        if program.source_type == crate::ast::SourceType::Module {
+            self.strict_mode = true;
+            self.context.is_async = true;
+        }
+        self.strict_mode |= has_use_strict_directive(&program.body);
+        self.check_export_names(&program.body);
+
         for statement in &program.body {
             self.visit_node(statement)?;
         }
@@ -360,7 +272,11 @@ impl SemanticAnalyzer {
         decl: &crate::ast::VariableDeclaration,
     ) -> Result<Type, SemanticError> {
         for var_decl in &decl.declarations {
-            if let Node::Identifier(var_name) = &*var_decl.id {
+            if decl.kind == "const" && var_decl.init.is_none() {
+                self.early_error("Missing initializer in const declaration".to_string());
+            }
+            if let Node::Identifier(id) = &*var_decl.id {
+                self.check_binding_name(&id.name);
                 let var_type = if let Some(init) = &var_decl.init {
                     self.visit_node(init)?
                 } else {
@@ -369,33 +285,54 @@ impl SemanticAnalyzer {
 
                 let current_scope = self.scope_stack.last_mut().unwrap();
 
-                if current_scope.has_variable(var_name) {
+                if current_scope.has_variable(&id.name) {
                     self.errors.push(SemanticError::DuplicateDeclaration {
-                        name: var_name.clone(),
+                        name: id.name.clone(),
                         position: None,
                     });
                     continue;
                 }
 
-                let line_number = decl
-                    .span
-                    .as_ref()
-                    .map(|s| s.start.line)
-                    .unwrap_or(LineNumber::new(1));
-                current_scope.declare_variable(var_name.clone(), var_type.clone(), line_number);
+                let line_number = decl.span.start.line;
+                current_scope.declare_variable(id.name.clone(), var_type.clone(), line_number);
+            } else {
+                if let Some(init) = &var_decl.init {
+                    self.visit_node(init)?;
+                }
+                let scope_index = self.scope_stack.len() - 1;
+                self.declare_pattern(&var_decl.id, scope_index, decl.span.start.line);
             }
         }
 
         Ok(Type::Undefined)
     }
 
+    // Declares the names bound by an array or object pattern, whose values
+    // are not typed.
+    fn declare_pattern(&mut self, pattern: &Node, scope_index: usize, line_number: LineNumber) {
+        let mut names = Vec::new();
+        bound_names(pattern, &mut names);
+        for name in names {
+            self.check_binding_name(name);
+            let scope = &mut self.scope_stack[scope_index];
+            if scope.has_variable(name) {
+                self.errors.push(SemanticError::DuplicateDeclaration {
+                    name: name.to_string(),
+                    position: None,
+                });
+                continue;
+            }
+            scope.declare_variable(name.to_string(), Type::Unknown, line_number);
+        }
+    }
+
     fn visit_function_declaration(
         &mut self,
         func: &crate::ast::FunctionDeclaration,
     ) -> Result<Type, SemanticError> {
         let func_name = if let Some(id) = &func.id {
-            if let Node::Identifier(name) = &**id {
-                name.clone()
+            if let Node::Identifier(id) = &**id {
+                id.name.clone()
             } else {
                 return Ok(Type::Unknown);
             }
@@ -403,32 +340,29 @@ impl SemanticAnalyzer {
             return Ok(Type::Unknown);
         };
 
-        let _current_scope = self.scope_stack.last().unwrap();
-        let function_scope = Scope::new();
-        self.scope_stack.push(function_scope);
+        let context = FunctionContext::function(func.r#async, func.generator);
+        let return_type = self.with_function_context(context, &func.body, |analyzer| {
+            analyzer.check_binding_name(&func_name);
+            analyzer.check_parameters(&func.params, false);
 
-        for param in &func.params {
-            if let Node::Identifier(param_name) = param {
-                let current_scope = self.scope_stack.last_mut().unwrap();
-                let line_number = func
-                    .span
-                    .as_ref()
-                    .map(|s| s.start.line)
-                    .unwrap_or(LineNumber::new(1));
-                current_scope.declare_variable(param_name.clone(), Type::Unknown, line_number);
-            }
-        }
+            let function_scope = Scope::new();
+            analyzer.scope_stack.push(function_scope);
 
-        let return_type = self.visit_node(&func.body)?;
+            for param in &func.params {
+                if let Node::Identifier(id) = param {
+                    let current_scope = analyzer.scope_stack.last_mut().unwrap();
+                    let line_number = func.span.start.line;
+                    current_scope.declare_variable(id.name.clone(), Type::Unknown, line_number);
+                }
+            }
 
-        self.scope_stack.pop();
+            let return_type = analyzer.visit_node(&func.body);
+            analyzer.scope_stack.pop();
+            return_type
+        })?;
 
         let current_scope = self.scope_stack.last_mut().unwrap();
-        let line_number = func
-            .span
-            .as_ref()
-            .map(|s| s.start.line)
-            .unwrap_or(LineNumber::new(1));
+        let line_number = func.span.start.line;
         current_scope.declare_variable(
             func_name,
             Type::Function {
@@ -478,7 +412,7 @@ impl SemanticAnalyzer {
                 }
                 Ok(Type::Number)
             }
-            "==" | "!=" | "===" | "!==" => Ok(Type::Boolean),
+            "==" | "!=" | "===" | "!==" | "in" | "instanceof" => Ok(Type::Boolean),
             "<" | ">" | "<=" | ">=" => {
                 if left_type != Type::Number || right_type != Type::Number {
                     self.errors.push(SemanticError::TypeMismatch {
@@ -507,6 +441,22 @@ impl SemanticAnalyzer {
         &mut self,
         expr: &crate::ast::UnaryExpression,
     ) -> Result<Type, SemanticError> {
+        if expr.operator == "delete" {
+            match &*expr.argument {
+                Node::Identifier(_) => {
+                    self.strict_mode_violation(
+                        "deleting an unqualified identifier is not allowed",
+                    )?;
+                }
+                Node::MemberExpression(member)
+                    if matches!(*member.property, Node::PrivateIdentifier(_)) =>
+                {
+                    self.early_error("Private fields cannot be deleted".to_string());
+                }
+                _ => {}
+            }
+        }
+
         let operand_type = self.visit_node(&expr.argument)?;
 
         match expr.operator.as_str() {
@@ -534,10 +484,16 @@ impl SemanticAnalyzer {
         }
     }
 
-    fn visit_identifier(&mut self, id: &str) -> Result<Type, SemanticError> {
-        let current_scope = self.scope_stack.last().unwrap();
+    // Looks a name up from the innermost scope outwards.
+    fn lookup_variable_type(&self, name: &str) -> Option<Type> {
+        self.scope_stack
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get_variable_type(name))
+    }
 
-        if let Some(var_type) = current_scope.get_variable_type(id) {
+    fn visit_identifier(&mut self, id: &str) -> Result<Type, SemanticError> {
+        if let Some(var_type) = self.lookup_variable_type(id) {
             Ok(var_type)
         } else {
             self.errors.push(SemanticError::UndefinedVariable(
@@ -549,6 +505,24 @@ impl SemanticAnalyzer {
         }
     }
 
+    fn visit_private_identifier(&mut self, name: &str) -> Result<Type, SemanticError> {
+        match self
+            .private_names
+            .iter()
+            .rev()
+            .find_map(|names| names.get(name))
+        {
+            Some(member_type) => Ok(member_type.clone()),
+            None => {
+                self.errors.push(SemanticError::UndeclaredPrivateName {
+                    name: name.to_string(),
+                    position: None,
+                });
+                Ok(Type::Unknown)
+            }
+        }
+    }
+
     fn visit_this(&mut self) -> Result<Type, SemanticError> {
         Ok(Type::Object)
     }
@@ -557,10 +531,8 @@ impl SemanticAnalyzer {
         &mut self,
         call: &crate::ast::CallExpression,
     ) -> Result<Type, SemanticError> {
-        if let Node::Identifier(func_name) = &*call.callee {
-            let current_scope = self.scope_stack.last().unwrap();
-
-            if let Some(_func_type) = current_scope.get_variable_type(func_name) {
+        if let Node::Identifier(id) = &*call.callee {
+            if let Some(_func_type) = self.lookup_variable_type(&id.name) {
                 for arg in &call.arguments {
                     self.visit_node(arg)?;
                 }
@@ -568,7 +540,7 @@ impl SemanticAnalyzer {
                 Ok(Type::Unknown)
             } else {
                 self.errors.push(SemanticError::UndefinedVariable(
-                    func_name.clone(),
+                    id.name.clone(),
                     LineNumber::new(0),
                     ColumnNumber::new(0),
                 ));
@@ -581,7 +553,12 @@ impl SemanticAnalyzer {
                 self.visit_node(arg)?;
             }
 
-            if !matches!(callee_type, Type::Function { .. }) {
+            // Members are not typed unless they are private, so an unknown
+            // callee may still be a function.
+            if !matches!(
+                callee_type,
+                Type::Function { .. } | Type::Unknown | Type::Any
+            ) {
                 self.errors.push(SemanticError::TypeMismatch {
                     expected: "function".to_string(),
                     found: format!("{callee_type:?}"),
@@ -598,12 +575,11 @@ impl SemanticAnalyzer {
     ) -> Result<Type, SemanticError> {
         let value_type = self.visit_node(&assign.right)?;
 
-        if let Node::Identifier(var_name) = &*assign.left {
-            let current_scope = self.scope_stack.last().unwrap();
-
-            if current_scope.get_variable_type(var_name).is_none() {
+        if let Node::Identifier(id) = &*assign.left {
+            self.check_binding_name(&id.name);
+            if self.lookup_variable_type(&id.name).is_none() {
                 self.errors.push(SemanticError::UndefinedVariable(
-                    var_name.clone(),
+                    id.name.clone(),
                     LineNumber::new(0),
                     ColumnNumber::new(0),
                 ));
@@ -661,7 +637,93 @@ impl SemanticAnalyzer {
         let _current_scope = self.scope_stack.last().unwrap();
         let block_scope = Scope::new();
         self.scope_stack.push(block_scope);
-        self.visit_node(&while_stmt.body)?;
+        self.visit_loop_body(&while_stmt.body)?;
+        self.scope_stack.pop();
+
+        Ok(Type::Undefined)
+    }
+
+    fn visit_for_in_statement(
+        &mut self,
+        for_in: &crate::ast::ForInStatement,
+    ) -> Result<Type, SemanticError> {
+        self.visit_node(&for_in.right)?;
+        self.visit_loop_iteration(&for_in.left, &for_in.body, Type::String)
+    }
+
+    fn visit_for_of_statement(
+        &mut self,
+        for_of: &crate::ast::ForOfStatement,
+    ) -> Result<Type, SemanticError> {
+        let iterable_type = self.visit_node(&for_of.right)?;
+
+        if matches!(
+            iterable_type,
+            Type::Undefined | Type::Null | Type::Boolean | Type::Number
+        ) {
+            self.errors.push(SemanticError::TypeMismatch {
+                expected: "iterable".to_string(),
+                found: format!("{iterable_type:?}"),
+                position: None,
+            });
+        }
+
+        let element_type = match iterable_type {
+            Type::Array(element_type) => *element_type,
+            Type::String => Type::String,
+            _ => Type::Unknown,
+        };
+        self.visit_loop_iteration(&for_of.left, &for_of.body, element_type)
+    }
+
+    // Binds the left-hand side of a for-in or for-of loop and visits its
+    // body. `let` and `const` bindings live in a scope of their own, while a
+    // `var` binding belongs to the enclosing scope.
+    fn visit_loop_iteration(
+        &mut self,
+        left: &Node,
+        body: &Node,
+        value_type: Type,
+    ) -> Result<Type, SemanticError> {
+        let line_number = self.get_line_number(left);
+        self.scope_stack.push(Scope::new());
+
+        match left {
+            Node::VariableDeclaration(decl) => {
+                for var_decl in &decl.declarations {
+                    let scope_index = if decl.kind == "var" {
+                        self.scope_stack.len() - 2
+                    } else {
+                        self.scope_stack.len() - 1
+                    };
+                    if let Node::Identifier(id) = &*var_decl.id {
+                        self.check_binding_name(&id.name);
+                        self.scope_stack[scope_index].declare_variable(
+                            id.name.clone(),
+                            value_type.clone(),
+                            line_number,
+                        );
+                    } else {
+                        self.declare_pattern(&var_decl.id, scope_index, line_number);
+                    }
+                }
+            }
+            Node::Identifier(id) => {
+                self.check_binding_name(&id.name);
+                if self.lookup_variable_type(&id.name).is_none() {
+                    self.errors.push(SemanticError::UndefinedVariable(
+                        id.name.clone(),
+                        line_number,
+                        ColumnNumber::new(0),
+                    ));
+                }
+            }
+            other => {
+                self.visit_node(other)?;
+            }
+        }
+
+        self.visit_loop_body(body)?;
         self.scope_stack.pop();
 
         Ok(Type::Undefined)
@@ -671,6 +733,10 @@ impl SemanticAnalyzer {
         &mut self,
         return_stmt: &crate::ast::ReturnStatement,
     ) -> Result<Type, SemanticError> {
+        if !self.context.in_function {
+            self.early_error("Illegal return statement".to_string());
+        }
+
         if let Some(argument) = &return_stmt.argument {
             self.visit_node(argument)
         } else {
@@ -737,12 +803,14 @@ impl SemanticAnalyzer {
     ) -> Result<Type, SemanticError> {
         let _object_type = self.visit_node(&member.object)?;
 
-        let _property_type = match &*member.property {
-            Node::Identifier(_) => Ok(Type::String),
-            _ => self.visit_node(&member.property),
-        }?;
-
-        Ok(Type::Unknown)
+        match &*member.property {
+            Node::Identifier(_) if !member.computed => Ok(Type::Unknown),
+            Node::PrivateIdentifier(_) => self.visit_node(&member.property),
+            property => {
+                self.visit_node(property)?;
+                Ok(Type::Unknown)
+            }
+        }
     }
 
     fn visit_logical_expression(
@@ -782,29 +850,652 @@ impl SemanticAnalyzer {
         &mut self,
         arrow: &crate::ast::ArrowFunctionExpression,
     ) -> Result<Type, SemanticError> {
-        let _current_scope = self.scope_stack.last().unwrap();
-        let function_scope = Scope::new();
-        self.scope_stack.push(function_scope);
-
-        for param in &arrow.params {
-            if let Node::Identifier(param_name) = param {
-                let current_scope = self.scope_stack.last_mut().unwrap();
-                let line_number = arrow
-                    .span
-                    .as_ref()
-                    .map(|s| s.start.line)
-                    .unwrap_or(LineNumber::new(1));
-                current_scope.declare_variable(param_name.clone(), Type::Unknown, line_number);
+        let context = FunctionContext::function(arrow.r#async, false);
+        self.with_function_context(context, &arrow.body, |analyzer| {
+            analyzer.check_parameters(&arrow.params, true);
+
+            let function_scope = Scope::new();
+            analyzer.scope_stack.push(function_scope);
+
+            for param in &arrow.params {
+                if let Node::Identifier(id) = param {
+                    let current_scope = analyzer.scope_stack.last_mut().unwrap();
+                    let line_number = arrow.span.start.line;
+                    current_scope.declare_variable(id.name.clone(), Type::Unknown, line_number);
+                }
             }
+
+            let result = analyzer.visit_node(&arrow.body);
+            analyzer.scope_stack.pop();
+            result
+        })?;
+
+        Ok(Type::Function {
+            params: vec![],
+            return_type: Box::new(Type::Unknown),
+        })
+    }
+
+    fn visit_function_expression(
+        &mut self,
+        func: &crate::ast::FunctionExpression,
+    ) -> Result<Type, SemanticError> {
+        let context = FunctionContext::function(func.r#async, func.generator);
+        self.with_function_context(context, &func.body, |analyzer| {
+            analyzer.visit_function_expression_body(func)
+        })
+    }
+
+    fn visit_function_expression_body(
+        &mut self,
+        func: &crate::ast::FunctionExpression,
+    ) -> Result<Type, SemanticError> {
+        if let Some(Node::Identifier(id)) = func.id.as_deref() {
+            self.check_binding_name(&id.name);
         }
+        self.check_parameters(&func.params, false);
 
-        let _return_type = self.visit_node(&arrow.body)?;
+        let mut function_scope = Scope::new();
+        let line_number = func.span.start.line;
+        if let Some(Node::Identifier(id)) = func.id.as_deref() {
+            function_scope.declare_variable(id.name.clone(), Type::Unknown, line_number);
+        }
+        for param in &func.params {
+            if let Node::Identifier(id) = param {
+                function_scope.declare_variable(id.name.clone(), Type::Unknown, line_number);
+            }
+        }
+        self.scope_stack.push(function_scope);
 
+        let result = self.visit_node(&func.body);
         self.scope_stack.pop();
+        let return_type = result?;
 
         Ok(Type::Function {
             params: vec![],
-            return_type: Box::new(Type::Unknown),
+            return_type: Box::new(return_type),
+        })
+    }
+
+    fn visit_class_declaration(
+        &mut self,
+        class: &crate::ast::ClassDeclaration,
+    ) -> Result<Type, SemanticError> {
+        if let Some(Node::Identifier(id)) = class.id.as_deref() {
+            let line_number = class.span.start.line;
+            let current_scope = self.scope_stack.last_mut().unwrap();
+            current_scope.declare_variable(id.name.clone(), Type::Object, line_number);
+        }
+
+        self.visit_class(
+            class.id.as_deref(),
+            class.super_class.as_deref(),
+            &class.body,
+        )
+    }
+
+    fn visit_class_expression(
+        &mut self,
+        class: &crate::ast::ClassExpression,
+    ) -> Result<Type, SemanticError> {
+        // A named class expression binds its name only inside the class.
+        let mut class_scope = Scope::new();
+        if let Some(Node::Identifier(id)) = class.id.as_deref() {
+            let line_number = class.span.start.line;
+            class_scope.declare_variable(id.name.clone(), Type::Object, line_number);
+        }
+        self.scope_stack.push(class_scope);
+
+        let result = self.visit_class(
+            class.id.as_deref(),
+            class.super_class.as_deref(),
+            &class.body,
+        );
+        self.scope_stack.pop();
+        result
+    }
+
+    // All parts of a class, including its name and heritage, are strict.
+    fn visit_class(
+        &mut self,
+        id: Option<&Node>,
+        super_class: Option<&Node>,
+        body: &Node,
+    ) -> Result<Type, SemanticError> {
+        let outer_strict = std::mem::replace(&mut self.strict_mode, true);
+        let result = self.visit_class_parts(id, super_class, body);
+        self.strict_mode = outer_strict;
+        result
+    }
+
+    fn visit_class_parts(
+        &mut self,
+        id: Option<&Node>,
+        super_class: Option<&Node>,
+        body: &Node,
+    ) -> Result<Type, SemanticError> {
+        if let Some(Node::Identifier(id)) = id {
+            self.check_binding_name(&id.name);
+        }
+        if let Some(super_class) = super_class {
+            self.visit_node(super_class)?;
+        }
+
+        let Node::ClassBody(body) = body else {
+            return Ok(Type::Object);
+        };
+
+        let names = self.declare_private_names(body);
+        self.private_names.push(names);
+        let result = body
+            .body
+            .iter()
+            .try_for_each(|element| self.visit_class_element(element));
+        self.private_names.pop();
+        result?;
+
+        Ok(Type::Object)
+    }
+
+    // Collects the private names of a class body up front, since methods may
+    // refer to names declared further down. Each name may be declared once,
+    // except for a single getter and setter pair. Methods are typed as
+    // functions; fields and accessors hold values of unknown type.
+    fn declare_private_names(&mut self, body: &crate::ast::ClassBody) -> HashMap<String, Type> {
+        use crate::ast::MethodKind;
+
+        let mut names = HashMap::new();
+        let mut getters = HashSet::new();
+        let mut setters = HashSet::new();
+
+        for element in &body.body {
+            let (key, kind, member_type) = match element {
+                Node::MethodDefinition(method) => {
+                    let member_type = match (&method.kind, &*method.value) {
+                        (MethodKind::Method, Node::FunctionExpression(function)) => {
+                            Type::Function {
+                                params: vec![Type::Unknown; function.params.len()],
+                                return_type: Box::new(Type::Unknown),
+                            }
+                        }
+                        _ => Type::Unknown,
+                    };
+                    (&*method.key, method.kind, member_type)
+                }
+                Node::PropertyDefinition(prop) => (&*prop.key, MethodKind::Method, Type::Unknown),
+                _ => continue,
+            };
+            let Node::PrivateIdentifier(id) = key else {
+                continue;
+            };
+
+            let duplicate = match kind {
+                MethodKind::Get => {
+                    getters.contains(&id.name)
+                        || names.contains_key(&id.name) && !setters.contains(&id.name)
+                }
+                MethodKind::Set => {
+                    setters.contains(&id.name)
+                        || names.contains_key(&id.name) && !getters.contains(&id.name)
+                }
+                MethodKind::Constructor | MethodKind::Method => names.contains_key(&id.name),
+            };
+            if duplicate {
+                self.errors.push(SemanticError::DuplicateDeclaration {
+                    name: format!("#{}", id.name),
+                    position: None,
+                });
+            }
+
+            match kind {
+                MethodKind::Get => getters.insert(id.name.clone()),
+                MethodKind::Set => setters.insert(id.name.clone()),
+                MethodKind::Constructor | MethodKind::Method => false,
+            };
+            names.insert(id.name.clone(), member_type);
+        }
+
+        names
+    }
+
+    fn visit_class_element(&mut self, element: &Node) -> Result<(), SemanticError> {
+        match element {
+            Node::MethodDefinition(method) => {
+                if method.computed {
+                    self.visit_node(&method.key)?;
+                }
+                self.visit_node(&method.value)?;
+            }
+            Node::PropertyDefinition(prop) => {
+                if prop.computed {
+                    self.visit_node(&prop.key)?;
+                }
+                // Field initializers and static blocks allow neither `await`
+                // nor `return`.
+                if let Some(value) = &prop.value {
+                    self.scope_stack.push(Scope::new());
+                    let result = self.with_context(FunctionContext::default(), |analyzer| {
+                        analyzer.visit_node(value)
+                    });
+                    self.scope_stack.pop();
+                    result?;
+                }
+            }
+            Node::StaticBlock(block) => {
+                self.scope_stack.push(Scope::new());
+                let result = self.with_context(FunctionContext::default(), |analyzer| {
+                    block
+                        .body
+                        .iter()
+                        .try_for_each(|statement| analyzer.visit_node(statement).map(|_| ()))
+                });
+                self.scope_stack.pop();
+                result?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+// Early errors: strict mode restrictions and the `await`, `yield`, `return`,
+// `break` and `continue` rules that depend on the enclosing code.
+impl SemanticAnalyzer {
+    fn with_context<T>(
+        &mut self,
+        context: FunctionContext,
+        visit: impl FnOnce(&mut Self) -> Result<T, SemanticError>,
+    ) -> Result<T, SemanticError> {
+        let outer_context = std::mem::replace(&mut self.context, context);
+        let result = visit(self);
+        self.context = outer_context;
+        result
+    }
+
+    // A function is strict when the code around it is, or when its own body
+    // starts with a "use strict" directive.
+    fn with_function_context<T>(
+        &mut self,
+        context: FunctionContext,
+        body: &Node,
+        visit: impl FnOnce(&mut Self) -> Result<T, SemanticError>,
+    ) -> Result<T, SemanticError> {
+        let outer_strict = self.strict_mode;
+        if let Node::BlockStatement(block) = body {
+            self.strict_mode |= has_use_strict_directive(&block.body);
+        }
+        let result = self.with_context(context, visit);
+        self.strict_mode = outer_strict;
+        result
+    }
+
+    fn strict_mode_violation(&mut self, message: &str) -> Result<Type, SemanticError> {
+        if self.strict_mode {
+            self.errors.push(SemanticError::StrictModeViolation {
+                message: message.to_string(),
+                position: None,
+            });
+        }
+        Ok(Type::Unknown)
+    }
+
+    fn early_error(&mut self, message: String) {
+        self.errors.push(SemanticError::EarlyError {
+            message,
+            position: None,
+        });
+    }
+
+    // A module may export each name only once.
+    fn check_export_names(&mut self, body: &[Node]) {
+        let mut exported = HashSet::new();
+        for statement in body {
+            let Node::ExportDeclaration(export) = statement else {
+                continue;
+            };
+            let mut names = Vec::new();
+            if export.default {
+                names.push("default");
+            } else if let Some(declaration) = &export.declaration {
+                names.extend(lexically_declared_names(std::slice::from_ref(declaration)));
+                if let Node::VariableDeclaration(decl) = &**declaration {
+                    if decl.kind == "var" {
+                        for declarator in &decl.declarations {
+                            bound_names(&declarator.id, &mut names);
+                        }
+                    }
+                }
+            }
+            for specifier in &export.specifiers {
+                let name = match specifier {
+                    Node::ExportSpecifier(specifier) => Some(&*specifier.exported),
+                    Node::ExportNamespaceSpecifier(specifier) => specifier.exported.as_deref(),
+                    _ => None,
+                };
+                match name {
+                    Some(Node::Identifier(id)) => names.push(&id.name),
+                    Some(Node::String(name)) => names.push(&name.value),
+                    _ => {}
+                }
+            }
+            for name in names {
+                if !exported.insert(name) {
+                    self.early_error(format!("Duplicate export of '{name}'"));
+                }
+            }
+        }
+    }
+
+    fn check_binding_name(&mut self, name: &str) {
+        if name == "eval" || name == "arguments" {
+            let _ = self.strict_mode_violation(&format!("'{name}' cannot be declared or assigned"));
+        }
+    }
+
+    // Arrow functions never allow duplicate parameter names; other functions
+    // only reject them in strict mode.
+    fn check_parameters(&mut self, params: &[Node], is_arrow: bool) {
+        let mut seen = HashSet::new();
+        for param in params {
+            let Node::Identifier(id) = param else {
+                continue;
+            };
+            self.check_binding_name(&id.name);
+            if !seen.insert(id.name.as_str()) {
+                let message = format!("duplicate parameter name '{}'", id.name);
+                if is_arrow {
+                    self.early_error(format!("Arrow functions may not have a {message}"));
+                } else {
+                    let _ = self.strict_mode_violation(&message);
+                }
+            }
+        }
+    }
+
+    fn visit_loop_body(&mut self, body: &Node) -> Result<Type, SemanticError> {
+        self.context.loop_depth += 1;
+        self.context.breakable_depth += 1;
+        let result = self.visit_node(body);
+        self.context.loop_depth -= 1;
+        self.context.breakable_depth -= 1;
+        result
+    }
+
+    fn visit_do_while_statement(
+        &mut self,
+        do_while: &crate::ast::DoWhileStatement,
+    ) -> Result<Type, SemanticError> {
+        self.scope_stack.push(Scope::new());
+        let result = self.visit_loop_body(&do_while.body);
+        self.scope_stack.pop();
+        result?;
+
+        self.visit_node(&do_while.test)?;
+        Ok(Type::Undefined)
+    }
+
+    fn visit_for_statement(
+        &mut self,
+        for_stmt: &crate::ast::ForStatement,
+    ) -> Result<Type, SemanticError> {
+        self.scope_stack.push(Scope::new());
+        let result = (|| {
+            for part in [&for_stmt.init, &for_stmt.test, &for_stmt.update]
+                .into_iter()
+                .flatten()
+            {
+                self.visit_node(part)?;
+            }
+            self.visit_loop_body(&for_stmt.body)
+        })();
+        self.scope_stack.pop();
+        result?;
+
+        Ok(Type::Undefined)
+    }
+
+    fn visit_switch_statement(
+        &mut self,
+        switch: &crate::ast::SwitchStatement,
+    ) -> Result<Type, SemanticError> {
+        self.visit_node(&switch.discriminant)?;
+
+        self.scope_stack.push(Scope::new());
+        self.context.breakable_depth += 1;
+        let result = switch.cases.iter().try_for_each(|case| {
+            if let Some(test) = &case.test {
+                self.visit_node(test)?;
+            }
+            case.consequent
+                .iter()
+                .try_for_each(|statement| self.visit_node(statement).map(|_| ()))
+        });
+        self.context.breakable_depth -= 1;
+        self.scope_stack.pop();
+        result?;
+
+        Ok(Type::Undefined)
+    }
+
+    fn visit_try_statement(
+        &mut self,
+        try_stmt: &crate::ast::TryStatement,
+    ) -> Result<Type, SemanticError> {
+        self.visit_node(&try_stmt.block)?;
+
+        if let Some(Node::CatchClause(clause)) = try_stmt.handler.as_deref() {
+            // The block may not redeclare the parameter with `let`, `const`,
+            // `class` or `function`; `var` is allowed.
+            let mut params = Vec::new();
+            bound_names(&clause.param, &mut params);
+            if let Node::BlockStatement(block) = &*clause.body {
+                for name in lexically_declared_names(&block.body) {
+                    if params.contains(&name) {
+                        self.errors.push(SemanticError::DuplicateDeclaration {
+                            name: name.to_string(),
+                            position: None,
+                        });
+                    }
+                }
+            }
+
+            let mut catch_scope = Scope::new();
+            if let Node::Identifier(id) = &*clause.param {
+                self.check_binding_name(&id.name);
+                catch_scope.declare_variable(
+                    id.name.clone(),
+                    Type::Unknown,
+                    self.get_line_number(&clause.body),
+                );
+            }
+            self.scope_stack.push(catch_scope);
+            let result = self.visit_node(&clause.body);
+            self.scope_stack.pop();
+            result?;
+        }
+
+        if let Some(finalizer) = &try_stmt.finalizer {
+            self.visit_node(finalizer)?;
+        }
+
+        Ok(Type::Undefined)
+    }
+
+    fn visit_labeled_statement(
+        &mut self,
+        labeled: &crate::ast::LabeledStatement,
+    ) -> Result<Type, SemanticError> {
+        let Node::Identifier(id) = &*labeled.label else {
+            return self.visit_node(&labeled.body);
+        };
+
+        if self.context.labels.iter().any(|(name, _)| name == &id.name) {
+            self.early_error(format!("Label '{}' has already been declared", id.name));
+        }
+
+        self.context
+            .labels
+            .push((id.name.clone(), labels_iteration(&labeled.body)));
+        let result = self.visit_node(&labeled.body);
+        self.context.labels.pop();
+        result
+    }
+
+    fn visit_with_statement(
+        &mut self,
+        with: &crate::ast::WithStatement,
+    ) -> Result<Type, SemanticError> {
+        self.strict_mode_violation("'with' statements are not allowed")?;
+
+        self.visit_node(&with.object)?;
+        self.visit_node(&with.body)?;
+        Ok(Type::Undefined)
+    }
+
+    fn visit_break_statement(&mut self, label: Option<&Node>) -> Result<Type, SemanticError> {
+        match label {
+            Some(Node::Identifier(id))
+                if !self.context.labels.iter().any(|(name, _)| name == &id.name) =>
+            {
+                self.early_error(format!("Undefined label '{}'", id.name));
+            }
+            None if self.context.breakable_depth == 0 => {
+                self.early_error("Illegal break statement".to_string());
+            }
+            _ => {}
+        }
+        Ok(Type::Undefined)
+    }
+
+    fn visit_continue_statement(&mut self, label: Option<&Node>) -> Result<Type, SemanticError> {
+        match label {
+            Some(Node::Identifier(id)) => {
+                match self
+                    .context
+                    .labels
+                    .iter()
+                    .find(|(name, _)| name == &id.name)
+                {
+                    Some((_, true)) => {}
+                    Some((_, false)) => self.early_error(format!(
+                        "Illegal continue statement: '{}' does not denote an iteration statement",
+                        id.name
+                    )),
+                    None => self.early_error(format!("Undefined label '{}'", id.name)),
+                }
+            }
+            _ if self.context.loop_depth == 0 => {
+                self.early_error(
+                    "Illegal continue statement: no surrounding iteration statement".to_string(),
+                );
+            }
+            _ => {}
+        }
+        Ok(Type::Undefined)
+    }
+
+    fn visit_update_expression(
+        &mut self,
+        update: &crate::ast::UpdateExpression,
+    ) -> Result<Type, SemanticError> {
+        if let Node::Identifier(id) = &*update.argument {
+            self.check_binding_name(&id.name);
+        }
+        self.visit_node(&update.argument)?;
+        Ok(Type::Number)
+    }
+
+    fn visit_await_expression(
+        &mut self,
+        expr: &crate::ast::AwaitExpression,
+    ) -> Result<Type, SemanticError> {
+        if !self.context.is_async {
+            self.early_error(
+                "'await' is only valid in async functions and the top level of modules".to_string(),
+            );
+        }
+        self.visit_node(&expr.argument)?;
+        Ok(Type::Unknown)
+    }
+
+    fn visit_yield_expression(
+        &mut self,
+        expr: &crate::ast::YieldExpression,
+    ) -> Result<Type, SemanticError> {
+        if !self.context.is_generator {
+            self.early_error("'yield' is only valid in generator functions".to_string());
+        }
+        if let Some(argument) = &expr.argument {
+            self.visit_node(argument)?;
+        }
+        Ok(Type::Unknown)
+    }
+}
+
+fn has_use_strict_directive(body: &[Node]) -> bool {
+    body.iter()
+        .map_while(|statement| match statement {
+            Node::ExpressionStatement(stmt) => stmt.directive.as_deref(),
+            _ => None,
         })
+        .any(|directive| directive == "use strict")
+}
+
+// The names a binding pattern declares, in source order.
+fn bound_names<'a>(pattern: &'a Node, names: &mut Vec<&'a str>) {
+    match pattern {
+        Node::Identifier(id) => names.push(&id.name),
+        Node::ArrayLiteral(array) => {
+            for element in array.elements.iter().flatten() {
+                bound_names(element, names);
+            }
+        }
+        Node::ObjectLiteral(object) => {
+            for property in &object.properties {
+                match property {
+                    Node::Property(property) => bound_names(&property.value, names),
+                    other => bound_names(other, names),
+                }
+            }
+        }
+        Node::SpreadElement(spread) => bound_names(&spread.argument, names),
+        Node::RestElement(rest) => bound_names(&rest.argument, names),
+        Node::AssignmentExpression(assign) => bound_names(&assign.left, names),
+        _ => {}
+    }
+}
+
+// The names `let`, `const`, `class` and function declarations among
+// `statements` bind in the block holding them.
+fn lexically_declared_names(statements: &[Node]) -> Vec<&str> {
+    let mut names = Vec::new();
+    for statement in statements {
+        match statement {
+            Node::VariableDeclaration(decl) if decl.kind != "var" => {
+                for declarator in &decl.declarations {
+                    bound_names(&declarator.id, &mut names);
+                }
+            }
+            Node::ClassDeclaration(crate::ast::ClassDeclaration { id: Some(id), .. })
+            | Node::FunctionDeclaration(crate::ast::FunctionDeclaration { id: Some(id), .. }) => {
+                bound_names(id, &mut names)
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+// Whether `continue` may target a label on `body`, looking through further
+// labels such as `a: b: while (x) {}`.
+fn labels_iteration(body: &Node) -> bool {
+    match body {
+        Node::LabeledStatement(labeled) => labels_iteration(&labeled.body),
+        Node::ForStatement(_)
+        | Node::ForInStatement(_)
+        | Node::ForOfStatement(_)
+        | Node::WhileStatement(_)
+        | Node::DoWhileStatement(_) => true,
+        _ => false,
     }
 }
@@ -52,6 +52,23 @@ pub enum SemanticError {
         position: Option<Position>,
     },
 
+    UndeclaredPrivateName {
+        name: String,
+        position: Option<Position>,
+    },
+
+    StrictModeViolation {
+        message: String,
+        position: Option<Position>,
+    },
+
+    // An early error the grammar alone does not catch, such as a `break`
+    // with no enclosing loop or switch.
+    EarlyError {
+        message: String,
+        position: Option<Position>,
+    },
+
     UndefinedVariable(String, LineNumber, ColumnNumber),
     DuplicateDeclarationLegacy(String, LineNumber, ColumnNumber),
     TypeMismatchLegacy(String, String, LineNumber, ColumnNumber),
@@ -139,6 +156,30 @@ impl std::fmt::Display for SemanticError {
                 }
                 Ok(())
             }
+            SemanticError::UndeclaredPrivateName { name, position } => {
+                write!(
+                    f,
+                    "Private name '#{name}' must be declared in an enclosing class"
+                )?;
+                if let Some(pos) = position {
+                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                }
+                Ok(())
+            }
+            SemanticError::StrictModeViolation { message, position } => {
+                write!(f, "Strict mode violation: {message}")?;
+                if let Some(pos) = position {
+                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                }
+                Ok(())
+            }
+            SemanticError::EarlyError { message, position } => {
+                write!(f, "{message}")?;
+                if let Some(pos) = position {
+                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                }
+                Ok(())
+            }
 
             SemanticError::UndefinedVariable(name, line, col) => {
                 write!(
@@ -0,0 +1,47 @@
+use crate::vm::types::{LocalIndex, ScopeDepth};
+use crate::vm::value::Value;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// The locals of one run of a body. Functions made while it runs keep it as
+// their parent, so they read and write the same variables rather than
+// copies.
+#[derive(Debug, Clone, Default)]
+pub struct Environment {
+    locals: Rc<RefCell<Vec<Value>>>,
+    parent: Option<Rc<Environment>>,
+}
+
+impl Environment {
+    pub fn new(parent: Option<Environment>) -> Self {
+        Self {
+            locals: Rc::new(RefCell::new(vec![Value::Undefined; 16])),
+            parent: parent.map(Rc::new),
+        }
+    }
+
+    pub fn get(&self, depth: ScopeDepth, index: LocalIndex) -> Value {
+        self.ancestor(depth)
+            .and_then(|environment| environment.locals.borrow().get(index.as_usize()).cloned())
+            .unwrap_or(Value::Undefined)
+    }
+
+    pub fn set(&self, depth: ScopeDepth, index: LocalIndex, value: Value) {
+        let Some(environment) = self.ancestor(depth) else {
+            return;
+        };
+        let mut locals = environment.locals.borrow_mut();
+        if index.as_usize() >= locals.len() {
+            locals.resize(index.as_usize() + 1, Value::Undefined);
+        }
+        locals[index.as_usize()] = value;
+    }
+
+    fn ancestor(&self, depth: ScopeDepth) -> Option<&Environment> {
+        let mut environment = self;
+        for _ in 0..depth.as_usize() {
+            environment = environment.parent.as_deref()?;
+        }
+        Some(environment)
+    }
+}
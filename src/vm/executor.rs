@@ -1,12 +1,14 @@
 use crate::vm::bytecode::Bytecode;
+use crate::vm::environment::Environment;
 use crate::vm::frame::Frame;
 use crate::vm::handle::{ArrayHandle, FunctionHandle, HeapHandleId, ObjectHandle};
-use crate::vm::heap::{Heap, HeapEntry};
+use crate::vm::heap::{Accessor, Heap, HeapEntry};
 use crate::vm::instructions::Instruction;
+use crate::vm::iterator::IteratorRecord;
 use crate::vm::registers::Registers;
 use crate::vm::stack::Stack;
-use crate::vm::types::{ArgIndex, CodeAddress};
-use crate::vm::value::Value;
+use crate::vm::types::{ArgIndex, ArraySize, CodeAddress, LocalIndex, ScopeDepth};
+use crate::vm::value::{PrivateName, PropertyKey, Value};
 
 pub struct Executor {
     pub stack: Stack,
@@ -14,6 +16,7 @@ pub struct Executor {
     pub registers: Registers,
     pub heap: Heap,
     pub globals: Vec<Value>,
+    pub iterators: Vec<IteratorRecord>,
 }
 
 impl Default for Executor {
@@ -30,12 +33,13 @@ impl Executor {
             registers: Registers::new(),
             heap: Heap::new(),
             globals: vec![Value::Undefined; 32],
+            iterators: Vec::new(),
         }
     }
 
     pub fn execute(&mut self, bytecode: &Bytecode, constants: &[Value]) {
         let mut ip = 0;
-        let mut locals = vec![Value::Undefined; 16];
+        let locals = Environment::new(self.frame.environment.clone());
         let mut call_stack = Vec::new();
 
         while ip < bytecode.instructions.len() {
@@ -111,6 +115,16 @@ impl Executor {
                     let a = self.stack.pop().unwrap();
                     self.stack.push(Value::Boolean(a == b));
                 }
+                Instruction::StrictEq => {
+                    let b = self.stack.pop().unwrap();
+                    let a = self.stack.pop().unwrap();
+                    self.stack.push(Value::Boolean(a == b));
+                }
+                Instruction::StrictNe => {
+                    let b = self.stack.pop().unwrap();
+                    let a = self.stack.pop().unwrap();
+                    self.stack.push(Value::Boolean(a != b));
+                }
                 Instruction::Ne => {
                     let b = self.stack.pop().unwrap();
                     let a = self.stack.pop().unwrap();
@@ -190,17 +204,18 @@ impl Executor {
                     }
                 }
                 Instruction::LoadLocal(idx) => {
-                    let value = locals
-                        .get(idx.as_usize())
-                        .cloned()
-                        .unwrap_or(Value::Undefined);
-                    self.stack.push(value);
+                    self.stack.push(locals.get(ScopeDepth::new(0), *idx));
                 }
                 Instruction::StoreLocal(idx) => {
                     let value = self.stack.pop().unwrap();
-                    if let Some(slot) = locals.get_mut(idx.as_usize()) {
-                        *slot = value;
-                    }
+                    locals.set(ScopeDepth::new(0), *idx, value);
+                }
+                Instruction::LoadOuter(depth, idx) => {
+                    self.stack.push(locals.get(*depth, *idx));
+                }
+                Instruction::StoreOuter(depth, idx) => {
+                    let value = self.stack.pop().unwrap();
+                    locals.set(*depth, *idx, value);
                 }
                 Instruction::LoadGlobal(idx) => {
                     self.stack.push(
@@ -217,84 +232,29 @@ impl Executor {
                     }
                 }
                 Instruction::Call(argc) => {
-                    let func_value = if let Some(top_value) = self.stack.values.last() {
-                        if let Value::Function(_) = top_value {
-                            self.stack.pop().unwrap()
-                        } else {
-                            let mut found_func = None;
-                            for (i, value) in self.stack.values.iter().enumerate().rev() {
-                                if let Value::Function(_) = value {
-                                    found_func = Some((i, value.clone()));
-                                    break;
-                                }
-                            }
-                            if let Some((index, func)) = found_func {
-                                self.stack.values.remove(index);
-                                func
-                            } else {
-                                panic!("Nenhuma função encontrada na stack para Call");
-                            }
+                    let start = self.stack.values.len().saturating_sub(argc.as_usize());
+                    let args = self.stack.values.split_off(start);
+                    let callee = self.stack.pop().unwrap();
+                    let this_value = self.stack.pop().unwrap_or(Value::Undefined);
+                    match callee {
+                        Value::Function(function) => {
+                            let result = self.call_function(&function, this_value, args, constants);
+                            self.stack.push(result);
                         }
-                    } else {
-                        panic!("Stack vazia ao executar Call");
-                    };
-
-                    if let Value::Function(handle) = func_value {
-                        let (bytecode, closure_vars) = if let Some(HeapEntry::Function {
-                            bytecode,
-                            closure_vars,
-                            ..
-                        }) = self.heap.get(handle.id())
-                        {
-                            (bytecode.clone(), closure_vars.clone())
-                        } else {
-                            panic!("Handle de função inválido no heap");
-                        };
-
-                        let mut args = Vec::new();
-                        for _ in 0..argc.as_usize() {
-                            args.push(self.stack.pop().unwrap());
-                        }
-                        args.reverse();
-
-                        let this_value = self.stack.pop();
-
-                        let mut new_frame = Frame::new();
-                        new_frame.return_address = CodeAddress::new(ip + 1);
-                        new_frame.arg_count = ArgIndex::new(argc.as_usize());
-                        new_frame.arguments = args;
-                        new_frame.closure_vars = closure_vars;
-                        new_frame.function_handle = Some(handle);
-                        new_frame.this_value = this_value;
-
-                        self.stack.push_frame(self.frame.clone());
-                        self.frame = new_frame;
-
-                        self.execute(&bytecode, constants);
-
-                        if let Some(prev_frame) = self.stack.pop_frame() {
-                            self.frame = prev_frame;
-                        }
-
-                        if let Some(return_ip) = call_stack.pop() {
-                            ip = return_ip;
-                            continue;
-                        } else {
-                            break;
-                        }
-                    } else {
-                        panic!("Topo da stack não é uma função ao executar Call");
+                        other => panic!("{other} is not a function"),
                     }
                 }
                 Instruction::CallFunction(handle, argc) => {
                     if let Some(HeapEntry::Function {
                         bytecode,
                         closure_vars,
+                        environment,
                         ..
                     }) = self.heap.get(HeapHandleId::from(handle.as_usize()))
                     {
                         let bytecode = bytecode.clone();
                         let closure_vars = closure_vars.clone();
+                        let environment = environment.clone();
 
                         let mut args = Vec::new();
                         for _ in 0..argc.as_usize() {
@@ -309,6 +269,7 @@ impl Executor {
                         new_frame.arg_count = ArgIndex::new(argc.as_usize());
                         new_frame.arguments = args;
                         new_frame.closure_vars = closure_vars;
+                        new_frame.environment = environment;
                         new_frame.function_handle = Some(FunctionHandle::from(handle.as_usize()));
                         new_frame.this_value = this_value;
 
@@ -362,8 +323,13 @@ impl Executor {
                     self.stack
                         .push(Value::Object(ObjectHandle::from(handle.as_usize())));
                 }
-                Instruction::NewArray(_size) => {
+                Instruction::NewArray(size) => {
+                    let start = self.stack.values.len().saturating_sub(size.as_usize());
+                    let elements = self.stack.values.split_off(start);
                     let handle = self.heap.alloc_array();
+                    for element in elements {
+                        self.heap.push_array_element(handle, element);
+                    }
                     self.stack
                         .push(Value::Array(ArrayHandle::from(handle.as_usize())));
                 }
@@ -372,29 +338,15 @@ impl Executor {
                         panic!("Stack underflow in SetProperty");
                     }
                     let value = self.stack.pop().unwrap();
-                    let key = self.stack.pop().unwrap();
-                    let obj = self.stack.pop().unwrap();
-                    match (obj, key) {
-                        (Value::Object(handle), Value::String(key_str)) => {
-                            self.heap.set_object_property(handle.id(), key_str, value);
-                        }
-                        (obj, key) => {
-                            panic!("Invalid types for SetProperty: obj={obj:?}, key={key:?}",);
-                        }
-                    }
+                    let key = self.stack.pop().unwrap().to_property_key();
+                    let target = self.stack.pop().unwrap();
+                    self.set_property(target, key, value, constants);
                 }
                 Instruction::GetProperty => {
-                    let key = self.stack.pop().unwrap();
-                    let obj = self.stack.pop().unwrap();
-                    if let (Value::Object(handle), Value::String(key)) = (obj, key) {
-                        if let Some(val) = self.heap.get_object_property(handle.id(), &key) {
-                            self.stack.push(val.clone());
-                        } else {
-                            self.stack.push(Value::Undefined);
-                        }
-                    } else {
-                        self.stack.push(Value::Undefined);
-                    }
+                    let key = self.stack.pop().unwrap().to_property_key();
+                    let target = self.stack.pop().unwrap();
+                    let value = self.get_property(target, &key, constants);
+                    self.stack.push(value);
                 }
                 Instruction::LoadArg(idx) => {
                     let value = self
@@ -449,12 +401,467 @@ impl Executor {
                         Value::Object(_) => "object",
                         Value::Array(_) => "object",
                         Value::Function(_) => "function",
+                        Value::PrivateName(_) => "undefined",
                     };
                     self.stack.push(Value::String(type_str.to_string()));
                 }
+                // There are no promises yet, so an awaited value is already
+                // settled.
+                Instruction::Await => {}
+                Instruction::GetIterator => {
+                    let value = self.stack.pop().unwrap();
+                    let record = match value {
+                        Value::Array(array) => IteratorRecord::Array { array, index: 0 },
+                        Value::String(s) => IteratorRecord::Values {
+                            values: s.chars().map(|c| Value::String(c.to_string())).collect(),
+                            index: 0,
+                        },
+                        // Without symbols, an object with a `next` method is
+                        // iterated as its own iterator.
+                        Value::Object(iterator) => {
+                            match self.heap.get_object_property(iterator.id(), "next") {
+                                Some(Value::Function(next)) => IteratorRecord::Protocol {
+                                    iterator,
+                                    next: next.clone(),
+                                },
+                                _ => panic!("Object is not iterable"),
+                            }
+                        }
+                        other => panic!("{other} is not iterable"),
+                    };
+                    self.iterators.push(record);
+                }
+                Instruction::GetPropertyIterator => {
+                    let value = self.stack.pop().unwrap();
+                    let (object, keys) = match &value {
+                        Value::Object(handle) => {
+                            (Some(handle.id()), self.heap.property_keys(handle.id()))
+                        }
+                        Value::Array(handle) => {
+                            (Some(handle.id()), self.heap.property_keys(handle.id()))
+                        }
+                        Value::String(s) => (
+                            None,
+                            (0..s.chars().count()).map(|i| i.to_string()).collect(),
+                        ),
+                        // null, undefined and other primitives have no
+                        // enumerable properties.
+                        _ => (None, Vec::new()),
+                    };
+                    self.iterators.push(IteratorRecord::Keys {
+                        object,
+                        keys,
+                        index: 0,
+                    });
+                }
+                Instruction::IteratorNext(exit) => match self.iterator_step(constants) {
+                    Some(value) => self.stack.push(value),
+                    None => {
+                        self.iterators.pop();
+                        ip = exit.as_usize();
+                        continue;
+                    }
+                },
+                Instruction::IteratorClose => {
+                    self.iterators.pop();
+                }
+                Instruction::MakeFunction(body, arg_count) => {
+                    let handle = self.heap.alloc_closure(
+                        Bytecode::new(body.clone()),
+                        *arg_count,
+                        locals.clone(),
+                    );
+                    self.stack
+                        .push(Value::Function(FunctionHandle::from(handle.as_usize())));
+                }
+                Instruction::NewClass => {
+                    let constructor = self.stack.pop().unwrap();
+                    let super_class = self.stack.pop().unwrap();
+                    let class = match constructor {
+                        Value::Function(constructor) => constructor.id(),
+                        _ => self.heap.alloc_function(
+                            Bytecode::new(Vec::new()),
+                            ArgIndex::new(0),
+                            LocalIndex::new(0),
+                        ),
+                    };
+
+                    let prototype = self.heap.alloc_object();
+                    self.heap.set_object_property(
+                        prototype,
+                        "constructor".to_string(),
+                        Value::Function(FunctionHandle::new(class)),
+                    );
+                    self.heap.set_object_property(
+                        class,
+                        "prototype".to_string(),
+                        Value::Object(ObjectHandle::new(prototype)),
+                    );
+
+                    match super_class {
+                        Value::Function(super_class) => {
+                            self.heap.set_prototype(class, Some(super_class.id()));
+                            if let Some(Value::Object(super_prototype)) =
+                                self.heap.get_object_property(super_class.id(), "prototype")
+                            {
+                                self.heap
+                                    .set_prototype(prototype, Some(super_prototype.id()));
+                            }
+                        }
+                        Value::Undefined | Value::Null => {}
+                        other => panic!("Class extends value {other} is not a constructor"),
+                    }
+
+                    self.stack.push(Value::Function(FunctionHandle::new(class)));
+                }
+                Instruction::GetPrototype => {
+                    let target = self.stack.pop().unwrap();
+                    let prototype = self.get_property(target, &"prototype".into(), constants);
+                    self.stack.push(prototype);
+                }
+                Instruction::SetPrototype => {
+                    let prototype = self.stack.pop().unwrap();
+                    let target = self.stack.pop().unwrap();
+                    if let Some(target) = heap_id(&target) {
+                        self.heap.set_prototype(target, heap_id(&prototype));
+                    }
+                }
+                Instruction::DefineGetter => {
+                    let (target, key, getter) = self.pop_accessor();
+                    self.heap.define_getter(target, key, getter);
+                }
+                Instruction::DefineSetter => {
+                    let (target, key, setter) = self.pop_accessor();
+                    self.heap.define_setter(target, key, setter);
+                }
+                Instruction::DefineField => {
+                    let initializer = self.stack.pop().unwrap();
+                    let key = self.stack.pop().unwrap().to_property_key();
+                    match self.stack.pop().unwrap() {
+                        Value::Function(class) => {
+                            self.heap.define_field(class.id(), key, initializer);
+                        }
+                        other => panic!("Cannot define a field on {other}"),
+                    }
+                }
+                Instruction::PushPrivateName(idx) => {
+                    let name = constants
+                        .get(idx.as_usize())
+                        .map(Value::to_string_value)
+                        .unwrap_or_default();
+                    match self.stack.pop().unwrap() {
+                        Value::Function(class) => {
+                            self.stack.push(Value::PrivateName(PrivateName {
+                                class: class.id(),
+                                name,
+                            }));
+                        }
+                        other => panic!("Cannot read private name #{name} of {other}"),
+                    }
+                }
+                Instruction::In => {
+                    let target = self.stack.pop().unwrap();
+                    let key = self.stack.pop().unwrap().to_property_key();
+                    let found = match (&target, &key) {
+                        (Value::Array(array), PropertyKey::String(key)) => {
+                            key == "length" || self.heap.has_property_key(array.id(), key)
+                        }
+                        (Value::Array(_), PropertyKey::Private(_)) => false,
+                        (Value::Object(_) | Value::Function(_), _) => {
+                            self.lookup_property(&target, &key).is_some()
+                        }
+                        (other, _) => {
+                            panic!("Cannot use 'in' operator to search for '{key:?}' in {other}")
+                        }
+                    };
+                    self.stack.push(Value::Boolean(found));
+                }
+                Instruction::New(argc) => {
+                    let start = self.stack.values.len().saturating_sub(argc.as_usize());
+                    let args = self.stack.values.split_off(start);
+                    let instance = match self.stack.pop().unwrap() {
+                        Value::Function(class) => self.construct(&class, args, constants),
+                        other => panic!("{other} is not a constructor"),
+                    };
+                    self.stack.push(instance);
+                }
                 _ => todo!("Instrução não implementada ainda"),
             }
             ip += 1;
         }
     }
+
+    // Advances the innermost iterator, returning `None` once it is done.
+    fn iterator_step(&mut self, constants: &[Value]) -> Option<Value> {
+        let (iterator, next) = match self.iterators.last_mut()? {
+            IteratorRecord::Array { array, index } => {
+                let value = self
+                    .heap
+                    .get_array_element(array.id(), ArraySize::new(*index))
+                    .cloned();
+                *index += 1;
+                return value;
+            }
+            IteratorRecord::Values { values, index } => {
+                let value = values.get(*index).cloned();
+                *index += 1;
+                return value;
+            }
+            IteratorRecord::Keys {
+                object,
+                keys,
+                index,
+            } => {
+                while let Some(key) = keys.get(*index) {
+                    *index += 1;
+                    let present = match object {
+                        Some(object) => self.heap.has_property_key(*object, key),
+                        None => true,
+                    };
+                    if present {
+                        return Some(Value::String(key.clone()));
+                    }
+                }
+                return None;
+            }
+            IteratorRecord::Protocol { iterator, next } => (iterator.clone(), next.clone()),
+        };
+
+        match self.call_function(&next, Value::Object(iterator), Vec::new(), constants) {
+            Value::Object(result) => {
+                let done = self
+                    .heap
+                    .get_object_property(result.id(), "done")
+                    .is_some_and(Value::to_boolean);
+                if done {
+                    None
+                } else {
+                    Some(
+                        self.heap
+                            .get_object_property(result.id(), "value")
+                            .cloned()
+                            .unwrap_or(Value::Undefined),
+                    )
+                }
+            }
+            other => panic!("Iterator result {other} is not an object"),
+        }
+    }
+
+    // Runs a function to completion and returns its result.
+    fn call_function(
+        &mut self,
+        function: &FunctionHandle,
+        this_value: Value,
+        args: Vec<Value>,
+        constants: &[Value],
+    ) -> Value {
+        let (bytecode, closure_vars, environment) = match self.heap.get(function.id()) {
+            Some(HeapEntry::Function {
+                bytecode,
+                closure_vars,
+                environment,
+                ..
+            }) => (bytecode.clone(), closure_vars.clone(), environment.clone()),
+            _ => panic!("Invalid function handle in heap: {function}"),
+        };
+
+        let mut frame = Frame::new();
+        frame.arg_count = ArgIndex::new(args.len());
+        frame.arguments = args;
+        frame.closure_vars = closure_vars;
+        frame.environment = environment;
+        frame.function_handle = Some(function.clone());
+        frame.this_value = Some(this_value);
+
+        let stack_depth = self.stack.values.len();
+        let frame_depth = self.stack.frames.len();
+        let iterator_depth = self.iterators.len();
+        let caller = std::mem::replace(&mut self.frame, frame);
+        self.stack.push_frame(caller);
+
+        self.execute(&bytecode, constants);
+
+        // `Return` restores the caller's frame itself; falling off the end
+        // of the function does not.
+        if self.stack.frames.len() > frame_depth {
+            if let Some(caller) = self.stack.pop_frame() {
+                self.frame = caller;
+            }
+        }
+
+        let result = if self.stack.values.len() > stack_depth {
+            self.stack.pop().unwrap_or(Value::Undefined)
+        } else {
+            Value::Undefined
+        };
+        self.stack.values.truncate(stack_depth);
+        // A `return` from inside a loop leaves its iterators behind.
+        self.iterators.truncate(iterator_depth);
+        result
+    }
+
+    // `new class(...args)`: the instance gets the class's prototype and its
+    // fields, base class fields first, before the nearest constructor runs.
+    // A constructor returning an object replaces the instance.
+    fn construct(
+        &mut self,
+        class: &FunctionHandle,
+        args: Vec<Value>,
+        constants: &[Value],
+    ) -> Value {
+        let mut classes = vec![class.id()];
+        while let Some(super_class) = self.heap.get_prototype(*classes.last().unwrap()) {
+            classes.push(super_class);
+        }
+
+        let instance = Value::Object(ObjectHandle::new(self.heap.alloc_object()));
+        if let Some(Value::Object(prototype)) =
+            self.heap.get_object_property(class.id(), "prototype")
+        {
+            let prototype = prototype.id();
+            self.heap
+                .set_prototype(heap_id(&instance).unwrap(), Some(prototype));
+        }
+
+        for &class in classes.iter().rev() {
+            for (key, initializer) in self.heap.fields(class).to_vec() {
+                let value = match initializer {
+                    Value::Function(initializer) => {
+                        self.call_function(&initializer, instance.clone(), Vec::new(), constants)
+                    }
+                    _ => Value::Undefined,
+                };
+                self.set_property(instance.clone(), key, value, constants);
+            }
+        }
+
+        // A class without a constructor of its own uses its superclass's.
+        let constructor = classes.into_iter().find(|&class| {
+            matches!(
+                self.heap.get(class),
+                Some(HeapEntry::Function { bytecode, .. }) if !bytecode.instructions.is_empty()
+            )
+        });
+        if let Some(constructor) = constructor {
+            let constructor = FunctionHandle::new(constructor);
+            let result = self.call_function(&constructor, instance.clone(), args, constants);
+            if matches!(
+                result,
+                Value::Object(_) | Value::Array(_) | Value::Function(_)
+            ) {
+                return result;
+            }
+        }
+        instance
+    }
+
+    // Finds a property along the prototype chain of an object or function.
+    fn lookup_property(&self, target: &Value, key: &PropertyKey) -> Option<Property> {
+        let mut current = heap_id(target);
+        while let Some(id) = current {
+            if let Some(accessor) = self.heap.get_accessor(id, key) {
+                return Some(Property::Accessor(accessor.clone()));
+            }
+            let value = match key {
+                PropertyKey::String(key) => self.heap.get_object_property(id, key),
+                PropertyKey::Private(name) => self.heap.get_private(id, name),
+            };
+            if let Some(value) = value {
+                return Some(Property::Value(value.clone()));
+            }
+            current = self.heap.get_prototype(id);
+        }
+        None
+    }
+
+    fn get_property(&mut self, target: Value, key: &PropertyKey, constants: &[Value]) -> Value {
+        match (&target, key) {
+            (Value::Array(array), PropertyKey::String(key)) => {
+                if key == "length" {
+                    let length = self.heap.property_keys(array.id()).len();
+                    return Value::Number(length as f64);
+                }
+                return key
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| {
+                        self.heap
+                            .get_array_element(array.id(), ArraySize::new(index))
+                            .cloned()
+                    })
+                    .unwrap_or(Value::Undefined);
+            }
+            (Value::String(s), PropertyKey::String(key)) if key == "length" => {
+                return Value::Number(s.chars().count() as f64);
+            }
+            _ => {}
+        }
+
+        match self.lookup_property(&target, key) {
+            Some(Property::Value(value)) => value,
+            Some(Property::Accessor(Accessor {
+                get: Some(getter), ..
+            })) => self.call_function(&getter, target, Vec::new(), constants),
+            Some(Property::Accessor(_)) | None => Value::Undefined,
+        }
+    }
+
+    fn set_property(&mut self, target: Value, key: PropertyKey, value: Value, constants: &[Value]) {
+        if let (Value::Array(array), PropertyKey::String(key)) = (&target, &key) {
+            match key.parse::<usize>() {
+                Ok(index) => {
+                    self.heap
+                        .set_array_element(array.id(), ArraySize::new(index), value);
+                }
+                Err(_) => panic!("Invalid array property for SetProperty: {key}"),
+            }
+            return;
+        }
+
+        let Some(id) = heap_id(&target) else {
+            panic!("Invalid types for SetProperty: obj={target:?}, key={key:?}");
+        };
+        match self.lookup_property(&target, &key) {
+            Some(Property::Accessor(Accessor {
+                set: Some(setter), ..
+            })) => {
+                self.call_function(&setter, target, vec![value], constants);
+            }
+            // A getter without a setter makes the property read-only.
+            Some(Property::Accessor(_)) => {}
+            Some(Property::Value(_)) | None => match key {
+                PropertyKey::String(key) => self.heap.set_object_property(id, key, value),
+                PropertyKey::Private(name) => self.heap.set_private(id, name, value),
+            },
+        }
+    }
+
+    // Pops the function, key and target of a `DefineGetter` or
+    // `DefineSetter`.
+    fn pop_accessor(&mut self) -> (HeapHandleId, PropertyKey, FunctionHandle) {
+        let function = self.stack.pop().unwrap();
+        let key = self.stack.pop().unwrap().to_property_key();
+        let target = self.stack.pop().unwrap();
+        match (heap_id(&target), function) {
+            (Some(target), Value::Function(function)) => (target, key, function),
+            (_, function) => {
+                panic!("Invalid accessor definition: target={target:?}, function={function:?}")
+            }
+        }
+    }
+}
+
+enum Property {
+    Value(Value),
+    Accessor(Accessor),
+}
+
+// The heap entry holding an object's or function's properties.
+fn heap_id(value: &Value) -> Option<HeapHandleId> {
+    match value {
+        Value::Object(handle) => Some(handle.id()),
+        Value::Function(handle) => Some(handle.id()),
+        _ => None,
+    }
 }
@@ -1,3 +1,4 @@
+use crate::vm::environment::Environment;
 use crate::vm::handle::FunctionHandle;
 use crate::vm::types::{ArgIndex, CodeAddress, FramePointer};
 use crate::vm::value::Value;
@@ -10,6 +11,8 @@ pub struct Frame {
     pub base_pointer: FramePointer,
     pub arguments: Vec<Value>,
     pub closure_vars: HashMap<String, Value>,
+    // Where the running function was created; its locals' parent.
+    pub environment: Option<Environment>,
     pub function_handle: Option<FunctionHandle>,
     pub this_value: Option<Value>,
 }
@@ -22,6 +25,7 @@ impl Frame {
             base_pointer: FramePointer::new(0),
             arguments: Vec::new(),
             closure_vars: HashMap::new(),
+            environment: None,
             function_handle: None,
             this_value: None,
         }
@@ -34,6 +38,7 @@ impl Frame {
             base_pointer: FramePointer::new(0),
             arguments: Vec::new(),
             closure_vars: HashMap::new(),
+            environment: None,
             function_handle: None,
             this_value: None,
         }
@@ -48,6 +53,7 @@ impl Default for Frame {
             base_pointer: FramePointer::new(0),
             arguments: Vec::new(),
             closure_vars: HashMap::new(),
+            environment: None,
             function_handle: None,
             this_value: None,
         }
@@ -1,25 +1,51 @@
 use crate::vm::bytecode::Bytecode;
-use crate::vm::handle::HeapHandleId;
+use crate::vm::environment::Environment;
+use crate::vm::handle::{FunctionHandle, HeapHandleId};
 use crate::vm::types::{ArgIndex, ArraySize, LocalIndex};
-use crate::vm::value::Value;
+use crate::vm::value::{PrivateName, PropertyKey, Value};
+use indexmap::IndexMap;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub enum HeapEntry {
-    Object(HashMap<String, Value>),
+    // Keeps properties in creation order, which is the order for-in and
+    // other enumeration see them in.
+    Object(IndexMap<String, Value>),
     Array(Vec<Value>),
     Function {
         bytecode: Bytecode,
         arg_count: ArgIndex,
         local_count: LocalIndex,
         closure_vars: HashMap<String, Value>,
+        // The environment the function was created in, if it was made by
+        // `MakeFunction`.
+        environment: Option<Environment>,
+        // Functions are objects too; classes keep `prototype` and their
+        // static members here.
+        properties: IndexMap<String, Value>,
     },
     String(String),
 }
 
+// The functions run when a property is read or written, set up by class
+// `get` and `set` members.
+#[derive(Debug, Clone, Default)]
+pub struct Accessor {
+    pub get: Option<FunctionHandle>,
+    pub set: Option<FunctionHandle>,
+}
+
 pub struct Heap {
     entries: Vec<HeapEntry>,
     next_id: usize,
+    // The `[[Prototype]]` of each entry that has one; for a derived class,
+    // its superclass.
+    prototypes: HashMap<HeapHandleId, HeapHandleId>,
+    accessors: HashMap<(HeapHandleId, PropertyKey), Accessor>,
+    // The instance field initializers of each class, in declaration order.
+    fields: HashMap<HeapHandleId, Vec<(PropertyKey, Value)>>,
+    // Private fields and methods, apart from the string-keyed properties.
+    private_properties: HashMap<(HeapHandleId, PrivateName), Value>,
 }
 
 impl Default for Heap {
@@ -33,12 +59,16 @@ impl Heap {
         Self {
             entries: Vec::new(),
             next_id: 0,
+            prototypes: HashMap::new(),
+            accessors: HashMap::new(),
+            fields: HashMap::new(),
+            private_properties: HashMap::new(),
         }
     }
 
     pub fn alloc_object(&mut self) -> HeapHandleId {
         let id = HeapHandleId::new(self.next_id);
-        self.entries.push(HeapEntry::Object(HashMap::new()));
+        self.entries.push(HeapEntry::Object(IndexMap::new()));
         self.next_id += 1;
         id
     }
@@ -62,11 +92,31 @@ impl Heap {
             arg_count,
             local_count,
             closure_vars: HashMap::new(),
+            environment: None,
+            properties: IndexMap::new(),
         });
         self.next_id += 1;
         id
     }
 
+    // A function made while `environment` runs, which it can read and write
+    // the locals of.
+    pub fn alloc_closure(
+        &mut self,
+        bytecode: Bytecode,
+        arg_count: ArgIndex,
+        environment: Environment,
+    ) -> HeapHandleId {
+        let id = self.alloc_function(bytecode, arg_count, LocalIndex::new(0));
+        if let Some(HeapEntry::Function {
+            environment: slot, ..
+        }) = self.entries.get_mut(id.as_usize())
+        {
+            *slot = Some(environment);
+        }
+        id
+    }
+
     pub fn get(&self, handle: HeapHandleId) -> Option<&HeapEntry> {
         self.entries.get(handle.as_usize())
     }
@@ -76,16 +126,24 @@ impl Heap {
     }
 
     pub fn set_object_property(&mut self, handle: HeapHandleId, key: String, value: Value) {
-        if let Some(HeapEntry::Object(obj)) = self.entries.get_mut(handle.as_usize()) {
-            obj.insert(key, value);
+        match self.entries.get_mut(handle.as_usize()) {
+            Some(HeapEntry::Object(obj))
+            | Some(HeapEntry::Function {
+                properties: obj, ..
+            }) => {
+                obj.insert(key, value);
+            }
+            _ => {}
         }
     }
 
     pub fn get_object_property(&self, handle: HeapHandleId, key: &str) -> Option<&Value> {
-        if let Some(HeapEntry::Object(obj)) = self.entries.get(handle.as_usize()) {
-            obj.get(key)
-        } else {
-            None
+        match self.entries.get(handle.as_usize()) {
+            Some(HeapEntry::Object(obj))
+            | Some(HeapEntry::Function {
+                properties: obj, ..
+            }) => obj.get(key),
+            _ => None,
         }
     }
 
@@ -117,15 +175,97 @@ impl Heap {
 
     pub fn remove_object_property(&mut self, handle: HeapHandleId, key: &str) {
         if let Some(HeapEntry::Object(obj)) = self.entries.get_mut(handle.as_usize()) {
-            obj.remove(key);
+            obj.shift_remove(key);
         }
     }
 
     pub fn has_object_property(&self, handle: HeapHandleId, key: &str) -> bool {
-        if let Some(HeapEntry::Object(obj)) = self.entries.get(handle.as_usize()) {
-            obj.contains_key(key)
-        } else {
-            false
+        match self.entries.get(handle.as_usize()) {
+            Some(HeapEntry::Object(obj))
+            | Some(HeapEntry::Function {
+                properties: obj, ..
+            }) => obj.contains_key(key),
+            _ => false,
+        }
+    }
+
+    pub fn get_prototype(&self, handle: HeapHandleId) -> Option<HeapHandleId> {
+        self.prototypes.get(&handle).copied()
+    }
+
+    pub fn set_prototype(&mut self, handle: HeapHandleId, prototype: Option<HeapHandleId>) {
+        match prototype {
+            Some(prototype) => self.prototypes.insert(handle, prototype),
+            None => self.prototypes.remove(&handle),
+        };
+    }
+
+    pub fn get_private(&self, handle: HeapHandleId, name: &PrivateName) -> Option<&Value> {
+        self.private_properties.get(&(handle, name.clone()))
+    }
+
+    pub fn set_private(&mut self, handle: HeapHandleId, name: PrivateName, value: Value) {
+        self.private_properties.insert((handle, name), value);
+    }
+
+    pub fn get_accessor(&self, handle: HeapHandleId, key: &PropertyKey) -> Option<&Accessor> {
+        self.accessors.get(&(handle, key.clone()))
+    }
+
+    pub fn define_getter(
+        &mut self,
+        handle: HeapHandleId,
+        key: PropertyKey,
+        getter: FunctionHandle,
+    ) {
+        self.accessors.entry((handle, key)).or_default().get = Some(getter);
+    }
+
+    pub fn define_setter(
+        &mut self,
+        handle: HeapHandleId,
+        key: PropertyKey,
+        setter: FunctionHandle,
+    ) {
+        self.accessors.entry((handle, key)).or_default().set = Some(setter);
+    }
+
+    // `initializer` is a function computing the field's value, or undefined
+    // for a field without one.
+    pub fn define_field(&mut self, class: HeapHandleId, key: PropertyKey, initializer: Value) {
+        self.fields
+            .entry(class)
+            .or_default()
+            .push((key, initializer));
+    }
+
+    pub fn fields(&self, class: HeapHandleId) -> &[(PropertyKey, Value)] {
+        self.fields.get(&class).map_or(&[], Vec::as_slice)
+    }
+
+    // Own property keys in enumeration order: integer keys ascending, then
+    // the remaining keys in creation order.
+    pub fn property_keys(&self, handle: HeapHandleId) -> Vec<String> {
+        match self.entries.get(handle.as_usize()) {
+            Some(HeapEntry::Object(obj)) => {
+                let (mut indices, names): (Vec<&String>, Vec<&String>) =
+                    obj.keys().partition(|key| is_array_index(key));
+                indices.sort_by_key(|key| key.parse::<u32>().unwrap_or_default());
+                indices.into_iter().chain(names).cloned().collect()
+            }
+            Some(HeapEntry::Array(arr)) => (0..arr.len()).map(|index| index.to_string()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    // Whether a key taken by `property_keys` still names a property.
+    pub fn has_property_key(&self, handle: HeapHandleId, key: &str) -> bool {
+        match self.entries.get(handle.as_usize()) {
+            Some(HeapEntry::Object(obj)) => obj.contains_key(key),
+            Some(HeapEntry::Array(arr)) => {
+                key.parse::<usize>().is_ok_and(|index| index < arr.len())
+            }
+            _ => false,
         }
     }
 
@@ -140,5 +280,14 @@ impl Heap {
     pub fn clear(&mut self) {
         self.entries.clear();
         self.next_id = 0;
+        self.prototypes.clear();
+        self.accessors.clear();
+        self.fields.clear();
+        self.private_properties.clear();
     }
 }
+
+fn is_array_index(key: &str) -> bool {
+    key.parse::<u32>()
+        .is_ok_and(|index| index != u32::MAX && index.to_string() == key)
+}
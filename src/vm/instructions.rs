@@ -1,5 +1,6 @@
 use crate::vm::types::{
     ArgIndex, ArraySize, CodeAddress, ConstantIndex, FunctionIndex, GlobalIndex, LocalIndex,
+    ScopeDepth,
 };
 use serde::{Deserialize, Serialize};
 
@@ -36,6 +37,10 @@ pub enum Instruction {
     StoreGlobal(GlobalIndex),
     LoadLocal(LocalIndex),
     StoreLocal(LocalIndex),
+    // A local of the body the running function was made in (depth 1), or
+    // of one enclosing that.
+    LoadOuter(ScopeDepth, LocalIndex),
+    StoreOuter(ScopeDepth, LocalIndex),
     LoadArg(ArgIndex),
     LoadThisFunction,
     LoadThis,
@@ -45,6 +50,8 @@ pub enum Instruction {
     JumpIfTrue(CodeAddress),
     JumpIfFalse(CodeAddress),
 
+    // Pops the arguments, the function and the `this` value beneath it, and
+    // pushes the result.
     Call(FunctionIndex),
     Return,
 
@@ -57,15 +64,39 @@ pub enum Instruction {
     InstanceOf,
     In,
     Delete,
-    New,
+    // Pops the arguments and the constructor, and pushes the new instance.
+    New(ArgIndex),
 
+    // Pushes a function running the given body with the given parameter
+    // count.
+    MakeFunction(Vec<Instruction>, ArgIndex),
+
+    // `NewClass` pops the constructor and the superclass, either of which may
+    // be undefined, and pushes the class. The `Define*` instructions pop a
+    // value, key and class or prototype; `DefineField` records a per-instance
+    // field initializer, called with the instance as `this`.
     NewClass,
     GetPrototype,
     SetPrototype,
+    DefineGetter,
+    DefineSetter,
+    DefineField,
+    // Pops a class and pushes its private name with the given constant's
+    // name, a property key that no string is equal to.
+    PushPrivateName(ConstantIndex),
 
     Await,
     Yield,
 
+    // for-of and for-in loops. `IteratorNext` pushes the next value of the
+    // innermost iterator, or drops the iterator and jumps once it is done.
+    // `IteratorClose` drops it early, for `break` and `continue` leaving the
+    // loop.
+    GetIterator,
+    GetPropertyIterator,
+    IteratorNext(CodeAddress),
+    IteratorClose,
+
     Throw,
     Try(CodeAddress, CodeAddress),
     Catch,
@@ -0,0 +1,31 @@
+use crate::vm::handle::{ArrayHandle, FunctionHandle, HeapHandleId, ObjectHandle};
+use crate::vm::value::Value;
+
+// The state of a for-in or for-of loop, kept on the executor's iterator
+// stack while the loop runs.
+#[derive(Debug, Clone)]
+pub enum IteratorRecord {
+    // Reads the array on every step, so elements pushed during the loop are
+    // still visited.
+    Array {
+        array: ArrayHandle,
+        index: usize,
+    },
+    // Code points of a string.
+    Values {
+        values: Vec<Value>,
+        index: usize,
+    },
+    // Property keys taken when the loop starts. A key deleted before it is
+    // reached is skipped; string indices have no object to check.
+    Keys {
+        object: Option<HeapHandleId>,
+        keys: Vec<String>,
+        index: usize,
+    },
+    // A user iterator whose `next()` returns `{ value, done }` objects.
+    Protocol {
+        iterator: ObjectHandle,
+        next: FunctionHandle,
+    },
+}
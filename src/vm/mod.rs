@@ -1,19 +1,23 @@
 pub mod bytecode;
+pub mod environment;
 pub mod error;
 pub mod executor;
 pub mod frame;
 pub mod handle;
 pub mod heap;
 pub mod instructions;
+pub mod iterator;
 pub mod registers;
 pub mod stack;
 pub mod types;
 pub mod value;
 
 pub use bytecode::Bytecode;
+pub use environment::Environment;
 pub use error::VmError;
 pub use executor::Executor;
 pub use handle::{ArrayHandle, FunctionHandle, HeapHandleId, ObjectHandle, INVALID_HANDLE};
 pub use instructions::Instruction;
+pub use iterator::IteratorRecord;
 pub use types::*;
-pub use value::Value;
+pub use value::{PrivateName, PropertyKey, Value};
@@ -1,4 +1,4 @@
-use crate::vm::handle::{ArrayHandle, FunctionHandle, ObjectHandle};
+use crate::vm::handle::{ArrayHandle, FunctionHandle, HeapHandleId, ObjectHandle};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -10,10 +10,28 @@ pub enum Value {
     Object(ObjectHandle),
     Array(ArrayHandle),
     Function(FunctionHandle),
+    // Only ever a property key, pushed by `PushPrivateName`.
+    PrivateName(PrivateName),
     Null,
     Undefined,
 }
 
+// A `#name` declared by one evaluation of a class. Two classes declaring the
+// same name, or one class evaluated twice, have different private names.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PrivateName {
+    pub class: HeapHandleId,
+    pub name: String,
+}
+
+// What a property is found by. Private names are kept apart from string
+// keys, so no string reaches a private member.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PropertyKey {
+    String(String),
+    Private(PrivateName),
+}
+
 impl Value {
     pub fn is_primitive(&self) -> bool {
         matches!(
@@ -77,6 +95,14 @@ impl Value {
             Value::Object(_) => "[object Object]".to_string(),
             Value::Array(_) => "[object Array]".to_string(),
             Value::Function(_) => "[function]".to_string(),
+            Value::PrivateName(private) => format!("#{}", private.name),
+        }
+    }
+
+    pub fn to_property_key(self) -> PropertyKey {
+        match self {
+            Value::PrivateName(private) => PropertyKey::Private(private),
+            other => PropertyKey::String(other.to_string_value()),
         }
     }
 
@@ -122,3 +148,9 @@ impl From<bool> for Value {
         Value::Boolean(b)
     }
 }
+
+impl From<&str> for PropertyKey {
+    fn from(key: &str) -> Self {
+        PropertyKey::String(key.to_string())
+    }
+}
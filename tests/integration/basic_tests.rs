@@ -1,3 +1,7 @@
+use jetcrab::api::Compiler;
+use jetcrab::parser::{parse, Parser, ParserOptions};
+use jetcrab::semantic::{SemanticAnalyzer, SemanticError};
+use jetcrab::vm::Instruction;
 use jetcrab::Engine;
 
 #[test]
@@ -39,3 +43,476 @@ fn test_basic_multiplication() {
         assert_eq!(value.to_string(), "20");
     }
 }
+
+#[test]
+fn test_template_literal_evaluation() {
+    let mut engine = Engine::new();
+    let result = engine.evaluate("`sum: ${2 + 3}!`");
+    assert!(result.is_ok());
+    if let Ok(value) = result {
+        assert_eq!(value.to_string(), "sum: 5!");
+    }
+}
+
+#[test]
+fn test_for_of_iterates_array_values() {
+    let mut engine = Engine::new();
+    let result = engine.evaluate("let sum = 0; for (const x of [1, 2, 3]) sum += x; sum");
+    assert_eq!(result.unwrap().to_string(), "6");
+
+    let result = engine.evaluate("let s = \"\"; for (const c of \"abc\") { s = c + s; } s");
+    assert_eq!(result.unwrap().to_string(), "cba");
+}
+
+#[test]
+fn test_for_in_enumerates_property_keys() {
+    let mut engine = Engine::new();
+    let result =
+        engine.evaluate("let keys = \"\"; for (const k in { b: 1, a: 2, c: 3 }) keys += k; keys");
+    assert_eq!(result.unwrap().to_string(), "bac");
+
+    let result = engine.evaluate("let n = 0; for (const i in null) n += 1; n");
+    assert_eq!(result.unwrap().to_string(), "0");
+}
+
+#[test]
+fn test_for_of_destructuring_and_member_heads() {
+    let evaluate = |source: &str| Engine::new().evaluate(source).unwrap().to_string();
+
+    assert_eq!(
+        evaluate("let s = 0; for (const [a, b] of [[1, 2], [3, 4]]) s += a + b; s"),
+        "10"
+    );
+    assert_eq!(
+        evaluate("let s = 0; for (const { a, b: [c = 5] } of [{ a: 1, b: [] }]) s += a + c; s"),
+        "6"
+    );
+    assert_eq!(
+        evaluate("let o = { p: 0 }; for (o.p of [1, 2, 3]); o.p"),
+        "3"
+    );
+    assert_eq!(
+        evaluate("let a = 0; let t = 0; for ({ a } of [{ a: 1 }, { a: 2 }]) t += a; t"),
+        "3"
+    );
+    assert_eq!(
+        evaluate("let a = 0; let b = 0; for ([a, b] of [[1, 2]]); a + b"),
+        "3"
+    );
+
+    // Rest elements are reported rather than silently skipped.
+    let error = Engine::new()
+        .evaluate("let s = 0; for (const [a, ...rest] of [[1]]) s += a; s")
+        .unwrap_err();
+    assert!(error.contains("RestElement"), "{error}");
+}
+
+#[test]
+fn test_loop_break_and_continue() {
+    let evaluate = |source: &str| Engine::new().evaluate(source).unwrap().to_string();
+
+    assert_eq!(
+        evaluate("let s = 0; for (const x of [1, 2, 3, 4]) { if (x === 3) break; s += x; } s"),
+        "3"
+    );
+    assert_eq!(
+        evaluate("let s = 0; for (const x of [1, 2, 3, 4]) { if (x === 2) continue; s += x; } s"),
+        "8"
+    );
+    assert_eq!(
+        evaluate("let n = 0; for (const k in { a: 1, b: 2 }) { n += 1; break; } n"),
+        "1"
+    );
+    // Leaving an inner loop closes its iterator, so the outer loop goes on
+    // with its own.
+    assert_eq!(
+        evaluate(
+            "let s = 0; for (const x of [1, 2]) { for (const y of [10, 20]) break; s += x; } s"
+        ),
+        "3"
+    );
+    assert_eq!(
+        evaluate(
+            "let s = 0; outer: for (const x of [1, 2]) { \
+             for (const y of [10, 20]) { if (y === 20) continue outer; s += x + y; } } s"
+        ),
+        "23"
+    );
+    assert_eq!(
+        evaluate(
+            "let s = 0; outer: for (const x of [1, 2]) { \
+             for (const y of [10, 20]) { s += y; break outer; } } s"
+        ),
+        "10"
+    );
+    assert_eq!(
+        evaluate("let i = 0; while (i < 5) { i += 1; if (i === 3) break; } i"),
+        "3"
+    );
+    assert_eq!(
+        evaluate("let s = 0; for (let i = 0; i < 5; i += 1) { if (i === 1) continue; s += i; } s"),
+        "9"
+    );
+}
+
+#[test]
+fn test_semantic_private_name_resolution() {
+    let analyze = |source: &str| SemanticAnalyzer::new().analyze(&parse(source).unwrap());
+
+    assert!(analyze(
+        "class Point {
+            #x = 0;
+            get #y() { return 0; }
+            set #y(value) {}
+            equals(other) { return #x in other && this.#x === other.#x; }
+            static { let inner = class { read(p) { return p.#x; } }; }
+        }"
+    )
+    .is_ok());
+
+    let error = analyze("class A { read() { return this.#missing; } }").unwrap_err();
+    assert!(matches!(
+        error,
+        SemanticError::UndeclaredPrivateName { ref name, .. } if name == "missing"
+    ));
+    assert!(analyze("class A { #a; } class B { has(o) { return #a in o; } }").is_err());
+
+    let error = analyze("class A { #a; #a() {} }").unwrap_err();
+    assert!(matches!(
+        error,
+        SemanticError::DuplicateDeclaration { ref name, .. } if name == "#a"
+    ));
+    assert!(analyze("class A { get #a() {} get #a() {} }").is_err());
+
+    // Members are typed: private methods are callable, and `in` is a test.
+    assert!(analyze("class A { #m() { return 1; } n() { return this.#m(); } }").is_ok());
+    assert!(analyze("class A { x = 1; m() { return 2; } }; let a = new A(); a.m()").is_ok());
+    assert!(analyze("class A { #x; has(o) { if (#x in o) { return 1; } return 0; } }").is_ok());
+}
+
+#[test]
+fn test_class_bytecode_generation() {
+    let source = r#"
+        class Counter {
+            #count = 0;
+            static total = 0;
+            get count() { return this.#count; }
+            static has(obj) { return #count in obj; }
+        }
+    "#;
+
+    let (bytecode, constants) = Compiler::new().compile_to_bytecode(source).unwrap();
+
+    // No superclass and no constructor.
+    assert_eq!(
+        bytecode[..3],
+        [
+            Instruction::PushUndefined,
+            Instruction::PushUndefined,
+            Instruction::NewClass
+        ]
+    );
+    // Method bodies are compiled into their `MakeFunction`.
+    fn flatten(instructions: &[Instruction]) -> Vec<Instruction> {
+        instructions
+            .iter()
+            .flat_map(|instruction| match instruction {
+                Instruction::MakeFunction(body, _) => flatten(body),
+                other => vec![other.clone()],
+            })
+            .collect()
+    }
+    let bytecode = flatten(&bytecode);
+    for instruction in [
+        Instruction::DefineField,
+        Instruction::DefineGetter,
+        Instruction::GetPrototype,
+        Instruction::SetProperty,
+        Instruction::In,
+        Instruction::StoreLocal(0.into()),
+    ] {
+        assert!(
+            bytecode.contains(&instruction),
+            "missing {instruction:?} in {bytecode:?}"
+        );
+    }
+    // Private names are not string keys.
+    assert!(!constants.contains(&"#count".to_string()));
+    let count = constants.iter().position(|c| c == "count").unwrap();
+    assert!(bytecode.contains(&Instruction::PushPrivateName(count.into())));
+}
+
+#[test]
+fn test_class_evaluation() {
+    let evaluate = |source: &str| Engine::new().evaluate(source).unwrap().to_string();
+
+    assert_eq!(
+        evaluate("class A { #x = 5; get x() { return this.#x; } }; new A().x"),
+        "5"
+    );
+    assert_eq!(evaluate("class A { static y = 3 }; A.y"), "3");
+    assert_eq!(
+        evaluate("class A { static { let z = 1; this.y = z + 1; } }; A.y"),
+        "2"
+    );
+    assert_eq!(
+        evaluate("class A { #m() { return 1; } n() { return this.#m() + 1; } }; new A().n()"),
+        "2"
+    );
+    assert_eq!(
+        evaluate("class A { x = 1; m() { return 2; } }; let a = new A(); a.m() + a.x"),
+        "3"
+    );
+    assert_eq!(
+        evaluate(
+            "class P { constructor(x, y) { this.x = x; this.y = y; } \
+             sum() { return this.x + this.y; } }; new P(3, 4).sum()"
+        ),
+        "7"
+    );
+    assert_eq!(
+        evaluate(
+            "class C { #v = 0; get v() { return this.#v; } set v(n) { this.#v = n; } }; \
+             let c = new C(); c.v = 4; c.v"
+        ),
+        "4"
+    );
+    assert_eq!(
+        evaluate(
+            "class B { b = 1; m() { return 10; } } class D extends B { d = 2; } \
+             let d = new D(); d.b + d.d + d.m()"
+        ),
+        "13"
+    );
+}
+
+#[test]
+fn test_class_body_bindings() {
+    let evaluate = |source: &str| Engine::new().evaluate(source).unwrap().to_string();
+
+    // Methods see the variables around the class, not copies of them.
+    assert_eq!(
+        evaluate("let x = 1; class A { static s() { return x; } }; A.s()"),
+        "1"
+    );
+    assert_eq!(
+        evaluate("let x = 1; class A { static s() { return x; } }; x = 2; A.s()"),
+        "2"
+    );
+    assert_eq!(
+        evaluate("let n = 0; class A { static inc() { n = n + 1; } }; A.inc(); A.inc(); n"),
+        "2"
+    );
+    assert_eq!(evaluate("let x = 4; class A { y = x; }; new A().y"), "4");
+    assert_eq!(
+        evaluate(
+            "let x = 5; class A { m() { return class { n() { return x; } }; } }; \
+             let C = new A().m(); new C().n()"
+        ),
+        "5"
+    );
+
+    // The class's own name is bound before its static parts run.
+    assert_eq!(evaluate("class A { static { A.z = 3; } }; A.z"), "3");
+    assert_eq!(
+        evaluate("class A { static y = 2; static z = A.y + 1; }; A.z"),
+        "3"
+    );
+    assert_eq!(
+        evaluate("let B = class A { static self() { return A; } }; B.self() === B"),
+        "true"
+    );
+}
+
+#[test]
+fn test_in_evaluation() {
+    let evaluate = |source: &str| Engine::new().evaluate(source).unwrap().to_string();
+
+    assert_eq!(evaluate("let o = { a: 1 }; \"a\" in o"), "true");
+    assert_eq!(evaluate("let o = { a: 1 }; \"b\" in o"), "false");
+    assert_eq!(evaluate("0 in [1]"), "true");
+    assert_eq!(
+        evaluate("class A { #x; static has(o) { return #x in o; } }; A.has(new A())"),
+        "true"
+    );
+    assert_eq!(
+        evaluate("class A { #x; static has(o) { return #x in o; } }; A.has({})"),
+        "false"
+    );
+    assert_eq!(evaluate("class A { m() {} }; \"m\" in new A()"), "true");
+}
+
+#[test]
+fn test_private_name_evaluation() {
+    let evaluate = |source: &str| Engine::new().evaluate(source).unwrap().to_string();
+
+    // No string key reaches a private member.
+    assert_eq!(
+        evaluate("class A { #x = 1; static has(o) { return #x in o; } }; A.has({ \"#x\": 0 })"),
+        "false"
+    );
+    assert_eq!(
+        evaluate("class A { #x = 1; }; new A()[\"#x\"]"),
+        "undefined"
+    );
+    assert_eq!(
+        evaluate(
+            "class A { #x = 1; get x() { return this.#x; } }; \
+             let a = new A(); a[\"#x\"] = 9; a.x + a[\"#x\"]"
+        ),
+        "10"
+    );
+    assert_eq!(
+        evaluate("let o = { \"#x\": 1 }; let k = 0; for (let key in o) { k = key; } k"),
+        "#x"
+    );
+
+    // Each class has names of its own; a nested class sees the outer ones.
+    assert_eq!(
+        evaluate(
+            "class A { #x = 1; static has(o) { return #x in o; } } class B { #x = 2; }; \
+             A.has(new B())"
+        ),
+        "false"
+    );
+    assert_eq!(
+        evaluate(
+            "class A { #x = 7; static read(o) { \
+               let R = class { peek(p) { return p.#x; } }; return new R().peek(o); } }; \
+             A.read(new A())"
+        ),
+        "7"
+    );
+    assert_eq!(
+        evaluate("class A { static #n = 3; static n() { return A.#n; } }; A.n()"),
+        "3"
+    );
+}
+
+#[test]
+fn test_semantic_strict_mode_errors() {
+    let analyze = |source: &str| SemanticAnalyzer::new().analyze(&parse(source).unwrap());
+    let is_strict_error = |source: &str| {
+        matches!(
+            analyze(source),
+            Err(SemanticError::StrictModeViolation { .. })
+        )
+    };
+
+    assert!(is_strict_error("'use strict'; with (obj) {}"));
+    assert!(is_strict_error("'use strict'; var x = 1; delete x;"));
+    assert!(is_strict_error("'use strict'; function f(a, a) {}"));
+    assert!(is_strict_error("'use strict'; var eval = 1;"));
+    assert!(is_strict_error("'use strict'; arguments = 1;"));
+    assert!(is_strict_error(
+        "function f() { 'use strict'; with (obj) {} }"
+    ));
+    assert!(is_strict_error(
+        "'use strict'; function f() { return function g(a, a) {}; }"
+    ));
+    assert!(is_strict_error("class A { m() { var eval; } }"));
+
+    assert!(analyze("var obj = {}; with (obj) {}").is_ok());
+    assert!(analyze("function f(a, a) {} var eval = 1;").is_ok());
+    assert!(analyze("function f() { 'use strict'; } with ({}) {}").is_ok());
+    assert!(analyze("'not strict'; 'use strict'.length; with ({}) {}").is_ok());
+
+    let program = Parser::with_options("with ({}) {}", ParserOptions::module())
+        .parse()
+        .unwrap();
+    assert!(matches!(
+        SemanticAnalyzer::new().analyze(&program),
+        Err(SemanticError::StrictModeViolation { .. })
+    ));
+}
+
+#[test]
+fn test_semantic_early_errors() {
+    let analyze = |source: &str| SemanticAnalyzer::new().analyze(&parse(source).unwrap());
+    let early_error = |source: &str| match analyze(source) {
+        Err(SemanticError::EarlyError { message, .. }) => message,
+        other => panic!("expected early error for {source:?}, got {other:?}"),
+    };
+
+    assert_eq!(early_error("break;"), "Illegal break statement");
+    assert_eq!(
+        early_error("while (true) { function f() { continue; } }"),
+        "Illegal continue statement: no surrounding iteration statement"
+    );
+    assert_eq!(
+        early_error("switch (1) { case 1: continue; }"),
+        "Illegal continue statement: no surrounding iteration statement"
+    );
+    assert_eq!(
+        early_error("while (true) { break missing; }"),
+        "Undefined label 'missing'"
+    );
+    assert_eq!(
+        early_error("block: { for (;;) { continue block; } }"),
+        "Illegal continue statement: 'block' does not denote an iteration statement"
+    );
+    assert_eq!(
+        early_error("a: a: ;"),
+        "Label 'a' has already been declared"
+    );
+    assert_eq!(early_error("return 1;"), "Illegal return statement");
+    assert_eq!(
+        early_error("var f = (a, a) => a;"),
+        "Arrow functions may not have a duplicate parameter name 'a'"
+    );
+    assert_eq!(
+        early_error("class A { static { return; } }"),
+        "Illegal return statement"
+    );
+    assert_eq!(
+        early_error("const a;"),
+        "Missing initializer in const declaration"
+    );
+    assert!(matches!(
+        analyze("try {} catch (e) { let e; }"),
+        Err(SemanticError::DuplicateDeclaration { name, .. }) if name == "e"
+    ));
+    assert!(analyze("try {} catch (e) { var e; }").is_ok());
+
+    let analyze_module = |source: &str| {
+        let program = Parser::with_options(source, ParserOptions::module())
+            .parse()
+            .unwrap();
+        SemanticAnalyzer::new().analyze(&program)
+    };
+    assert!(matches!(
+        analyze_module("var a; export { a }; export { a as a };"),
+        Err(SemanticError::EarlyError { message, .. }) if message == "Duplicate export of 'a'"
+    ));
+    assert!(analyze_module("export default 1; export default 2;").is_err());
+    assert!(analyze_module("var a; export { a }; export { a as b };").is_ok());
+
+    assert!(analyze(
+        "outer: for (var i = 0; i < 3; i++) {
+            inner: do { if (i > 0) continue outer; break inner; } while (false);
+            switch (i) { case 0: break; default: continue; }
+        }
+        block: { break block; }"
+    )
+    .is_ok());
+}
+
+#[cfg(feature = "typescript")]
+#[test]
+fn test_compile_typescript_like_javascript() {
+    let compile = |source: &str, options: ParserOptions| {
+        Compiler::new()
+            .with_parser_options(options)
+            .compile_to_bytecode(source)
+            .unwrap()
+    };
+
+    let typescript = "interface Sized { size: number }\n\
+        function id<T>(x: T): T { return x; }\n\
+        let total: number = (2 * 3) as number;";
+    let javascript = "function id(x) { return x; }\nlet total = 2 * 3;";
+    assert_eq!(
+        compile(typescript, ParserOptions::default().with_typescript(true)),
+        compile(javascript, ParserOptions::default())
+    );
+}
@@ -0,0 +1,107 @@
+use jetcrab::ast::Node;
+use jetcrab::parser::{parse, IncrementalParser, TextEdit};
+
+fn full_parse_body(source: &str) -> Vec<Node> {
+    match parse(source).unwrap() {
+        Node::Program(program) => program.body,
+        other => panic!("expected program, got {other:?}"),
+    }
+}
+
+fn apply(parser: &mut IncrementalParser, find: &str, replacement: &str) -> usize {
+    let start = parser.source().find(find).unwrap();
+    let changed = parser
+        .apply_edit(&TextEdit::new(start..start + find.len(), replacement))
+        .unwrap();
+    assert_eq!(
+        parser.program().unwrap().body,
+        full_parse_body(parser.source())
+    );
+    assert_eq!(
+        parser.program().unwrap().span,
+        *parse(parser.source()).unwrap().span()
+    );
+    changed.len()
+}
+
+#[test]
+fn test_incremental_edit_reports_changed_statements() {
+    let mut parser =
+        IncrementalParser::new("let a = 1;\nlet b = 2;\nlet c = 3;\nlet d = 4;\nlet e = 5;")
+            .unwrap();
+
+    // The neighbours re-parsed along with an edited statement, and statements
+    // only moved by it, are not reported.
+    assert_eq!(apply(&mut parser, "3", "30 + c"), 1);
+    assert_eq!(apply(&mut parser, "let a = 1;", "let a = [\n  1,\n];"), 1);
+    assert_eq!(apply(&mut parser, "\nlet e", "\n\n\nlet e"), 1);
+    let start = parser.source().find("let e").unwrap();
+    let changed = parser
+        .apply_edit(&TextEdit::new(start..start, "\n\n"))
+        .unwrap();
+    assert!(changed.is_empty());
+    assert_eq!(
+        parser.program().unwrap().body,
+        full_parse_body(parser.source())
+    );
+    assert_eq!(apply(&mut parser, "let d = 4;", "let d = 4; let f;"), 2);
+    assert_eq!(parser.program().unwrap().body.len(), 6);
+}
+
+#[test]
+fn test_incremental_edit_follows_semicolon_insertion() {
+    let mut parser = IncrementalParser::new("x = a;\n(b);\ny = 1;\nz = 2;").unwrap();
+
+    apply(&mut parser, "a;", "a");
+    assert_eq!(parser.program().unwrap().body.len(), 3);
+
+    apply(&mut parser, "y = 1;\n", "y = 1\n+");
+    assert_eq!(parser.program().unwrap().body.len(), 2);
+}
+
+#[test]
+fn test_incremental_edit_recovers_after_syntax_errors() {
+    let mut parser = IncrementalParser::new("let a = 1;\nlet b = 2;").unwrap();
+
+    let start = parser.source().find('2').unwrap();
+    assert!(parser
+        .apply_edit(&TextEdit::new(start..start + 1, "'open"))
+        .is_err());
+    assert!(parser.program().is_none());
+
+    apply(&mut parser, "'open", "'closed'");
+    assert_eq!(parser.program().unwrap().body.len(), 2);
+
+    assert!(parser.apply_edit(&TextEdit::new(5..500, "")).is_err());
+}
+
+#[test]
+fn test_incremental_edit_rejects_errors_inside_blocks() {
+    assert!(IncrementalParser::new("{ a b }").is_err());
+
+    let mut parser = IncrementalParser::new("if (x) { a; }\nlet b = 1;").unwrap();
+    let start = parser.source().find("a;").unwrap();
+    assert!(parser
+        .apply_edit(&TextEdit::new(start..start + 2, "a b"))
+        .is_err());
+    assert!(parser.program().is_none());
+
+    apply(&mut parser, "a b", "a; b;");
+    assert_eq!(parser.program().unwrap().body.len(), 2);
+}
+
+#[test]
+fn test_incremental_edit_keeps_directive_strictness() {
+    let mut parser =
+        IncrementalParser::new("\"use strict\";\nlet a = 1;\nlet b = 2;\nlet c = 3;").unwrap();
+    let start = parser.source().find('3').unwrap();
+    assert!(parser
+        .apply_edit(&TextEdit::new(start..start + 1, "010"))
+        .is_err());
+    assert!(parser.program().is_none());
+
+    let mut parser =
+        IncrementalParser::new("\"not strict\";\nlet a = 1;\nlet b = 2;\nlet c = 3;").unwrap();
+    apply(&mut parser, "3", "010");
+    assert_eq!(parser.program().unwrap().body.len(), 4);
+}
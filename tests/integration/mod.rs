@@ -1,2 +1,4 @@
 pub mod basic_tests;
+pub mod incremental_tests;
+pub mod parser_tests;
 pub mod vm_tests;
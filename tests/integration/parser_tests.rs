@@ -0,0 +1,2230 @@
+use jetcrab::ast::arena::{AstArena, NodeKind};
+use jetcrab::ast::serialization::{from_estree_json, to_estree_json};
+use jetcrab::ast::{
+    ArrayLiteral, AstError, BinaryExpression, CallExpression, ErrorNode, Fold, Identifier, Indent,
+    MemberExpression, MethodKind, Node, NumberLiteral, Position, Printer, PrinterOptions,
+    QuoteStyle, SourceMap, SourceMapBuilder, SourceType, Span, StringLiteral, VisitMut,
+};
+use jetcrab::parser::recovery::{ParsingContext, RecoveryContext, RecoveryStrategy};
+use jetcrab::parser::{
+    parse, parse_with_recovery, EcmaVersion, Parser, ParserError, ParserOptions,
+};
+
+fn identifier_name(node: &Node) -> Option<&str> {
+    match node {
+        Node::Identifier(id) => Some(&id.name),
+        _ => None,
+    }
+}
+
+fn private_name(node: &Node) -> Option<&str> {
+    match node {
+        Node::PrivateIdentifier(id) => Some(&id.name),
+        _ => None,
+    }
+}
+
+fn string_value(node: &Node) -> Option<&str> {
+    match node {
+        Node::String(string) => Some(&string.value),
+        _ => None,
+    }
+}
+
+fn first_expression(source: &str) -> Node {
+    match parse(source).unwrap() {
+        Node::Program(program) => match program.body.into_iter().next() {
+            Some(Node::ExpressionStatement(stmt)) => *stmt.expression,
+            other => panic!("expected expression statement, got {other:?}"),
+        },
+        other => panic!("expected program, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_parse_regex_literal() {
+    match first_expression("/ab+c/gi") {
+        Node::RegExp(re) => {
+            assert_eq!(re.pattern, "ab+c");
+            assert_eq!(re.flags, "gi");
+        }
+        other => panic!("expected regex, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_parse_division_is_not_regex() {
+    match first_expression("a / b / 2") {
+        Node::BinaryExpression(expr) => assert_eq!(expr.operator, "/"),
+        other => panic!("expected binary expression, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_parse_unicode_identifier() {
+    match first_expression("\\u{63}afé") {
+        Node::Identifier(id) => assert_eq!(id.name, "café"),
+        other => panic!("expected identifier, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_lexer_errors_are_reported_with_position() {
+    match parse("let x = 1;\nx = \\u0066or;") {
+        Err(ParserError::LexerError { position, .. }) => {
+            assert_eq!(position, Position::new(2, 5));
+        }
+        other => panic!("expected lexer error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_parse_string_escapes_are_decoded() {
+    match first_expression(r"'\x48i\u{21}'") {
+        Node::String(string) => assert_eq!(string.value, "Hi!"),
+        other => panic!("expected string, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_parse_nested_template_literal() {
+    match first_expression("`a ${ `b ${c}` } d`") {
+        Node::TemplateLiteral(outer) => {
+            let raws: Vec<_> = outer.quasis.iter().map(|q| q.raw.as_str()).collect();
+            assert_eq!(raws, ["a ", " d"]);
+            assert!(outer.quasis[1].tail);
+            match &outer.expressions[0] {
+                Node::TemplateLiteral(inner) => {
+                    assert_eq!(inner.quasis[0].cooked.as_deref(), Some("b "));
+                    assert!(
+                        matches!(&inner.expressions[0], Node::Identifier(id) if id.name == "c")
+                    );
+                }
+                other => panic!("expected nested template, got {other:?}"),
+            }
+        }
+        other => panic!("expected template literal, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_parse_tagged_template_keeps_raw_strings() {
+    match first_expression(r"tag`\unicode ${x} \n`") {
+        Node::TaggedTemplateExpression(expr) => {
+            assert!(matches!(*expr.tag, Node::Identifier(ref id) if id.name == "tag"));
+            match *expr.quasi {
+                Node::TemplateLiteral(quasi) => {
+                    assert_eq!(quasi.quasis[0].cooked, None);
+                    assert_eq!(quasi.quasis[0].raw, r"\unicode ");
+                    assert_eq!(quasi.quasis[1].cooked.as_deref(), Some(" \n"));
+                    assert_eq!(quasi.quasis[1].raw, r" \n");
+                }
+                other => panic!("expected template literal, got {other:?}"),
+            }
+        }
+        other => panic!("expected tagged template, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_untagged_template_rejects_invalid_escape() {
+    let (_, errors) = parse_with_recovery(r"`\unicode`");
+    assert!(errors
+        .iter()
+        .any(|error| matches!(error, ParserError::InvalidSyntax { .. })));
+}
+
+fn program_body(source: &str) -> Vec<Node> {
+    parse_body(source, ParserOptions::default())
+}
+
+fn module_body(source: &str) -> Vec<Node> {
+    parse_body(source, ParserOptions::module())
+}
+
+fn parse_body(source: &str, options: ParserOptions) -> Vec<Node> {
+    let (program, errors) = Parser::with_options(source, options).parse_with_recovery();
+    assert!(
+        errors.is_empty(),
+        "unexpected errors for {source:?}: {errors:?}"
+    );
+    program.body
+}
+
+fn has_errors(source: &str) -> bool {
+    !parse_with_recovery(source).1.is_empty()
+}
+
+fn module_has_errors(source: &str) -> bool {
+    !Parser::with_options(source, ParserOptions::module())
+        .parse_with_recovery()
+        .1
+        .is_empty()
+}
+
+#[test]
+fn test_asi_on_newlines_and_closing_brace() {
+    let body = program_body("let a = 1\nlet b = 2\na + b");
+    assert_eq!(body.len(), 3);
+
+    let body = program_body("{ a } do {} while (false) b");
+    assert_eq!(body.len(), 3);
+
+    assert!(has_errors("let a = 1 let b = 2"));
+    assert!(has_errors("a b"));
+}
+
+#[test]
+fn test_asi_restricted_return() {
+    let body = program_body("function f() {\n  return\n  42\n}");
+    match &body[0] {
+        Node::FunctionDeclaration(func) => match &*func.body {
+            Node::BlockStatement(block) => {
+                assert_eq!(block.body.len(), 2);
+                assert!(matches!(
+                    &block.body[0],
+                    Node::ReturnStatement(ret) if ret.argument.is_none()
+                ));
+            }
+            other => panic!("expected block, got {other:?}"),
+        },
+        other => panic!("expected function, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_asi_restricted_postfix_update() {
+    let body = program_body("a\n++b");
+    assert_eq!(body.len(), 2);
+    match &body[1] {
+        Node::ExpressionStatement(stmt) => assert!(matches!(
+            &*stmt.expression,
+            Node::UpdateExpression(update) if update.prefix
+        )),
+        other => panic!("expected expression statement, got {other:?}"),
+    }
+
+    assert!(matches!(first_expression("a++"), Node::UpdateExpression(update) if !update.prefix));
+}
+
+#[test]
+fn test_asi_restricted_throw_and_arrow() {
+    assert!(has_errors("throw\nnew Error()"));
+    assert!(!has_errors("throw err"));
+    assert!(has_errors("let f = x\n=> x"));
+    assert!(has_errors("let f = (a, b)\n=> a"));
+    assert!(matches!(
+        first_expression("(a, b) => a"),
+        Node::ArrowFunctionExpression(arrow) if arrow.params.len() == 2
+    ));
+}
+
+#[test]
+fn test_parenthesized_expression_or_arrow_parameters() {
+    assert!(matches!(
+        first_expression("() => 1"),
+        Node::ArrowFunctionExpression(arrow) if arrow.params.is_empty()
+    ));
+    assert!(matches!(
+        first_expression("(a, b,) => a"),
+        Node::ArrowFunctionExpression(arrow) if arrow.params.len() == 2
+    ));
+    assert!(matches!(
+        first_expression("x => x"),
+        Node::ArrowFunctionExpression(arrow) if arrow.params.len() == 1
+    ));
+    assert!(matches!(
+        first_expression("(a + b) * c"),
+        Node::BinaryExpression(_)
+    ));
+
+    assert!(has_errors("()"));
+    assert!(has_errors("((a)) => a"));
+    assert!(has_errors("(a, b)"));
+    assert!(has_errors("(a + 1) => a"));
+
+    // Nested parentheses are parsed once, not rescanned for each level.
+    let nested = format!("{}a{}", "(".repeat(32), ")".repeat(32));
+    assert!(matches!(first_expression(&nested), Node::Identifier(_)));
+}
+
+#[test]
+fn test_hashbang_and_html_comments() {
+    assert_eq!(program_body("#!/usr/bin/env node\nlet x = 1;").len(), 1);
+    assert_eq!(
+        program_body("x = 1 <!-- ignored\n--> also ignored\ny").len(),
+        2
+    );
+}
+
+#[test]
+fn test_recovery_reports_every_lexer_error() {
+    let (_, errors) = parse_with_recovery("let a = 0b12;\nlet b = @;\nlet c = 3;");
+    let positions: Vec<_> = errors
+        .iter()
+        .filter_map(|error| match error {
+            ParserError::LexerError { position, .. } => Some(*position),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(positions.len(), 2);
+    assert_eq!(positions[1].line.as_usize(), 2);
+
+    assert!(matches!(
+        parse("let a = 0b12;"),
+        Err(ParserError::LexerError { .. })
+    ));
+}
+
+#[test]
+fn test_parse_import_declarations() {
+    let body = module_body(
+        "import def, { a, b as c, \"str name\" as d } from \"mod\" with { type: \"json\" };\n\
+         import * as ns from \"ns\";\n\
+         import \"side-effect\";",
+    );
+    assert_eq!(body.len(), 3);
+
+    match &body[0] {
+        Node::ImportDeclaration(decl) => {
+            assert_eq!(decl.specifiers.len(), 4);
+            assert!(matches!(
+                &decl.specifiers[0],
+                Node::ImportDefaultSpecifier(_)
+            ));
+            assert!(matches!(
+                &decl.specifiers[3],
+                Node::ImportSpecifier(spec)
+                    if string_value(&spec.imported) == Some("str name")
+                        && identifier_name(&spec.local) == Some("d")
+            ));
+            assert_eq!(string_value(&decl.source), Some("mod"));
+            assert_eq!(decl.attributes.len(), 1);
+            assert_eq!(string_value(&decl.attributes[0].value), Some("json"));
+        }
+        other => panic!("expected import declaration, got {other:?}"),
+    }
+    assert!(matches!(
+        &body[1],
+        Node::ImportDeclaration(decl)
+            if matches!(&decl.specifiers[..], [Node::ImportNamespaceSpecifier(_)])
+    ));
+    assert!(matches!(&body[2], Node::ImportDeclaration(decl) if decl.specifiers.is_empty()));
+
+    assert!(module_has_errors("import { \"str\" } from \"m\";"));
+    assert!(module_has_errors("import { default } from \"m\";"));
+    assert!(module_has_errors(
+        "import a from \"m\" with { type: \"json\", type: \"css\" };"
+    ));
+}
+
+#[test]
+fn test_parse_export_declarations() {
+    let body = module_body(
+        "export * from \"a\";\n\
+         export * as ns from \"b\";\n\
+         export { x as \"string name\", y as default } from \"c\";\n\
+         export { z };\n\
+         export const one = 1;\n\
+         export async function load() {}\n\
+         export default class {}",
+    );
+    assert_eq!(body.len(), 7);
+
+    match &body[1] {
+        Node::ExportDeclaration(decl) => match &decl.specifiers[..] {
+            [Node::ExportNamespaceSpecifier(spec)] => {
+                assert_eq!(
+                    spec.exported.as_deref().and_then(identifier_name),
+                    Some("ns")
+                );
+            }
+            other => panic!("expected namespace specifier, got {other:?}"),
+        },
+        other => panic!("expected export declaration, got {other:?}"),
+    }
+    assert!(matches!(
+        &body[2],
+        Node::ExportDeclaration(decl)
+            if decl.source.is_some()
+                && matches!(
+                    &decl.specifiers[0],
+                    Node::ExportSpecifier(spec)
+                        if string_value(&spec.exported) == Some("string name")
+                )
+    ));
+    assert!(matches!(
+        &body[5],
+        Node::ExportDeclaration(jetcrab::ast::ExportDeclaration { declaration: Some(decl), .. })
+            if matches!(&**decl, Node::FunctionDeclaration(function) if function.r#async)
+    ));
+    assert!(matches!(&body[6], Node::ExportDeclaration(decl) if decl.default));
+
+    for source in [
+        "export default function () {}",
+        "export default async function named() {}",
+        "export default 1 + 2;",
+        "export { \"str\" as local } from \"m\";",
+    ] {
+        assert!(!module_has_errors(source), "{source}");
+    }
+    assert!(module_has_errors("export { \"str\" };"));
+    assert!(module_has_errors("export { if };"));
+}
+
+#[test]
+fn test_script_and_module_goals() {
+    let (program, errors) = parse_with_recovery("import a from \"m\";\nexport default a;");
+    assert_eq!(
+        errors
+            .iter()
+            .filter(|error| matches!(error, ParserError::InvalidModule { .. }))
+            .count(),
+        2
+    );
+    assert_eq!(program.source_type, SourceType::Script);
+
+    let (program, errors) =
+        Parser::with_options("await load();", ParserOptions::module()).parse_with_recovery();
+    assert!(errors.is_empty(), "{errors:?}");
+    assert_eq!(program.source_type, SourceType::Module);
+    assert!(matches!(
+        &program.body[0],
+        Node::ExpressionStatement(statement)
+            if matches!(*statement.expression, Node::AwaitExpression(_))
+    ));
+
+    // `await` is only an operator at the top level of a module and in async
+    // functions.
+    assert!(has_errors("await load();"));
+    assert!(module_has_errors("function f() { await load(); }"));
+    assert!(!module_has_errors("async function f() { await load(); }"));
+    assert!(!has_errors("let f = async () => await load();"));
+    let es2021 = ParserOptions::module().with_ecma_version(EcmaVersion::Es2021);
+    assert!(!Parser::with_options("await load();", es2021)
+        .parse_with_recovery()
+        .1
+        .is_empty());
+
+    // Module declarations are only allowed at the top level.
+    assert!(module_has_errors("{ import \"m\"; }"));
+    assert!(module_has_errors("if (a) { export { a }; }"));
+
+    // Module code is strict and has no HTML-like comments.
+    assert!(module_has_errors("let a = 017;"));
+    assert!(module_has_errors("<!-- comment"));
+    assert!(!has_errors("<!-- comment"));
+    assert!(!Parser::with_options(
+        "let a = 017;",
+        ParserOptions::script().with_strict_mode(true)
+    )
+    .parse_with_recovery()
+    .1
+    .is_empty());
+}
+
+#[test]
+fn test_ecma_version_rejects_newer_syntax() {
+    let error = |source: &str, version| match Parser::with_options(
+        source,
+        ParserOptions::script().with_ecma_version(version),
+    )
+    .parse()
+    {
+        Err(ParserError::InvalidSyntax { message, .. }) => message,
+        other => panic!("expected a syntax error for {source:?}, got {other:?}"),
+    };
+    let accepts = |source: &str, version| {
+        Parser::with_options(source, ParserOptions::script().with_ecma_version(version))
+            .parse()
+            .is_ok()
+    };
+
+    assert_eq!(
+        error("var f = (a) => a;", EcmaVersion::Es5),
+        "ES2015 is required for arrow functions"
+    );
+    assert!(error("var f = a => a;", EcmaVersion::Es5).ends_with("arrow functions"));
+    assert!(error("class A {}", EcmaVersion::Es5).ends_with("classes"));
+    assert!(error("var A = class {};", EcmaVersion::Es5).ends_with("classes"));
+    assert!(error("let a = 1;", EcmaVersion::Es5).ends_with("'let' declarations"));
+    assert!(error("const a = 1;", EcmaVersion::Es5).ends_with("'const' declarations"));
+    assert!(error("for (let i = 0; i; ) {}", EcmaVersion::Es5).ends_with("'let' declarations"));
+    assert!(error("var s = `a${b}`;", EcmaVersion::Es5).ends_with("template literals"));
+    assert!(error("tag`a`;", EcmaVersion::Es5).ends_with("template literals"));
+    assert!(error("var x = 2 ** 3;", EcmaVersion::Es2015).ends_with("the '**' operator"));
+    assert!(error("x **= 3;", EcmaVersion::Es2015).ends_with("the '**=' operator"));
+
+    assert!(accepts(
+        "var a = function (b) { return b * 2; }; a(1);",
+        EcmaVersion::Es5
+    ));
+    assert!(accepts(
+        "let f = (a) => `${a}`; class A {}",
+        EcmaVersion::Es2015
+    ));
+    assert!(accepts("var x = 2 ** 3; x **= 2;", EcmaVersion::Es2016));
+
+    assert!(error("a ?? b;", EcmaVersion::Es2019).ends_with("the '??' operator"));
+    assert!(error("import(\"m\");", EcmaVersion::Es2019).ends_with("dynamic import()"));
+    assert!(error("var n = 1_000;", EcmaVersion::Es2020).ends_with("numeric separators"));
+    assert!(error("var n = 1_000n;", EcmaVersion::Es2020).ends_with("numeric separators"));
+    assert!(error("class A { x = 1; }", EcmaVersion::Es2021).ends_with("class fields"));
+    assert!(error("class A { #m() {} }", EcmaVersion::Es2021).ends_with("private class members"));
+    assert!(error("class A { static {} }", EcmaVersion::Es2021).ends_with("class static blocks"));
+
+    assert!(accepts("a ?? b; import(\"m\");", EcmaVersion::Es2020));
+    assert!(accepts("var n = 1_000;", EcmaVersion::Es2021));
+    assert!(accepts(
+        "class A { x = 1; #y; static {} }",
+        EcmaVersion::Es2022
+    ));
+}
+
+#[test]
+fn test_import_meta_and_dynamic_import() {
+    let body = parse_body("let url = import.meta.url;", ParserOptions::module());
+    let Node::VariableDeclaration(decl) = &body[0] else {
+        panic!("expected variable declaration, got {:?}", body[0]);
+    };
+    let Some(Node::MemberExpression(member)) = decl.declarations[0].init.as_deref() else {
+        panic!("expected member expression");
+    };
+    assert!(matches!(
+        &*member.object,
+        Node::MetaProperty(meta)
+            if identifier_name(&meta.meta) == Some("import")
+                && identifier_name(&meta.property) == Some("meta")
+    ));
+    assert!(!module_has_errors("if (a) { import.meta; }"));
+
+    assert!(
+        Parser::with_options("import.meta;", ParserOptions::module())
+            .parse()
+            .is_ok()
+    );
+    assert!(matches!(
+        parse("import.meta;"),
+        Err(ParserError::InvalidModule { message, .. }) if message.contains("import.meta")
+    ));
+    assert!(matches!(
+        Parser::with_options("import.target;", ParserOptions::module()).parse(),
+        Err(ParserError::InvalidExpression { message, .. }) if message.contains("import.meta")
+    ));
+
+    for options in [ParserOptions::script(), ParserOptions::module()] {
+        let body = parse_body("let m = import(\"./m.js\").then(f);", options);
+        let Node::VariableDeclaration(decl) = &body[0] else {
+            panic!("expected variable declaration, got {:?}", body[0]);
+        };
+        let Some(Node::CallExpression(call)) = decl.declarations[0].init.as_deref() else {
+            panic!("expected call expression");
+        };
+        assert!(matches!(
+            &*call.callee,
+            Node::MemberExpression(member) if matches!(
+                &*member.object,
+                Node::ImportExpression(import)
+                    if matches!(&*import.source, Node::String(s) if s.value == "./m.js")
+            )
+        ));
+    }
+    assert!(parse("import();").is_err());
+}
+
+#[test]
+fn test_parse_async_function_declaration() {
+    let body = program_body("async function f() { await g(); }");
+    assert!(matches!(
+        &body[0],
+        Node::FunctionDeclaration(function) if function.r#async
+    ));
+    let program = parse("async function f() {}").unwrap();
+    assert_eq!(
+        Printer::new(PrinterOptions::default()).print(&program),
+        "async function f() {}\n"
+    );
+    let body = program_body("if (a) { async function f() {} }");
+    let Node::IfStatement(statement) = &body[0] else {
+        panic!("expected if statement, got {:?}", body[0]);
+    };
+    assert!(matches!(
+        &*statement.consequent,
+        Node::BlockStatement(block) if matches!(&block.body[0], Node::FunctionDeclaration(_))
+    ));
+
+    // A line break after `async` makes it a plain name.
+    let body = program_body("async\nfunction f() {}");
+    assert!(matches!(
+        &body[0],
+        Node::ExpressionStatement(statement) if identifier_name(&statement.expression) == Some("async")
+    ));
+    assert!(matches!(&body[1], Node::FunctionDeclaration(function) if !function.r#async));
+}
+
+#[test]
+fn test_parse_for_in_and_for_of() {
+    let body = program_body(
+        "for (const x of xs) f(x);\n\
+         for (key in object) {}\n\
+         for (a.b of [1, 2]) {}\n\
+         for (var i = 0; i < n; i++) {}\n\
+         for (let k in a || b) {}",
+    );
+    assert_eq!(body.len(), 5);
+
+    match &body[0] {
+        Node::ForOfStatement(stmt) => {
+            assert!(!stmt.r#await);
+            assert!(matches!(&*stmt.left, Node::VariableDeclaration(decl) if decl.kind == "const"));
+            assert_eq!(identifier_name(&stmt.right), Some("xs"));
+        }
+        other => panic!("expected for-of, got {other:?}"),
+    }
+    assert!(matches!(
+        &body[1],
+        Node::ForInStatement(stmt) if identifier_name(&stmt.left) == Some("key")
+    ));
+    assert!(
+        matches!(&body[2], Node::ForOfStatement(stmt) if matches!(*stmt.left, Node::MemberExpression(_)))
+    );
+    assert!(matches!(&body[3], Node::ForStatement(_)));
+    assert!(
+        matches!(&body[4], Node::ForInStatement(stmt) if matches!(*stmt.right, Node::LogicalExpression(_)))
+    );
+
+    // `in` inside parentheses is still an operator in a for head.
+    assert!(matches!(
+        &program_body("for (let i = (a in b); i; ) {}")[0],
+        Node::ForStatement(_)
+    ));
+
+    assert!(has_errors("for (let a, b of c) {}"));
+    assert!(has_errors("for (let a = 1 of c) {}"));
+    assert!(has_errors("for (f() of c) {}"));
+    assert!(has_errors("for (x = a in b) {}"));
+
+    // Annex B initializers in for-in are only allowed for sloppy `var`.
+    assert!(!has_errors("for (var a = 1 in o) {}"));
+    assert!(has_errors("for (let a = 1 in o) {}"));
+    assert!(module_has_errors("for (var a = 1 in o) {}"));
+}
+
+#[test]
+fn test_parse_for_await_of() {
+    match &module_body("for await (const chunk of stream) {}")[0] {
+        Node::ForOfStatement(stmt) => assert!(stmt.r#await),
+        other => panic!("expected for-await-of, got {other:?}"),
+    }
+    assert!(!has_errors(
+        "let f = async () => { for await (const x of xs) {} };"
+    ));
+
+    assert!(has_errors("for await (const x of xs) {}"));
+    assert!(module_has_errors(
+        "function f() { for await (const x of xs) {} }"
+    ));
+    assert!(module_has_errors("for await (const x in xs) {}"));
+    assert!(module_has_errors("for await (let i = 0; i < 1; i++) {}"));
+}
+
+fn class_elements(source: &str) -> Vec<Node> {
+    match program_body(source).into_iter().next() {
+        Some(Node::ClassDeclaration(decl)) => match *decl.body {
+            Node::ClassBody(body) => body.body,
+            other => panic!("expected class body, got {other:?}"),
+        },
+        other => panic!("expected class declaration, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_parse_class_elements() {
+    let elements = class_elements(
+        "class Counter extends Base {
+            count = 0;
+            #step = 1;
+            static instances;
+            static #registry = new Map()
+            constructor(start) { this.count = start; }
+            #advance() { this.count += this.#step; }
+            get value() { return this.count; }
+            set value(v) { this.count = v; }
+            static async *ticks() {}
+            [key]() {}
+            static { Counter.instances = 0; }
+            static isCounter(obj) { return #step in obj; }
+            get() {}
+            static = 1;
+        }",
+    );
+    assert_eq!(elements.len(), 14);
+
+    match &elements[0] {
+        Node::PropertyDefinition(prop) => {
+            assert_eq!(identifier_name(&prop.key), Some("count"));
+            assert!(matches!(prop.value.as_deref(), Some(Node::Number(n)) if n.value == 0.0));
+            assert!(!prop.r#static);
+        }
+        other => panic!("expected field, got {other:?}"),
+    }
+    assert!(matches!(
+        &elements[1],
+        Node::PropertyDefinition(prop) if private_name(&prop.key) == Some("step")
+    ));
+    assert!(matches!(
+        &elements[2],
+        Node::PropertyDefinition(prop) if prop.r#static && prop.value.is_none()
+    ));
+    assert!(matches!(
+        &elements[3],
+        Node::PropertyDefinition(prop) if prop.r#static && private_name(&prop.key) == Some("registry")
+    ));
+
+    let method = |index: usize| match &elements[index] {
+        Node::MethodDefinition(method) => method.clone(),
+        other => panic!("expected method, got {other:?}"),
+    };
+    assert_eq!(method(4).kind, MethodKind::Constructor);
+    assert_eq!(private_name(&method(5).key), Some("advance"));
+    assert_eq!(method(6).kind, MethodKind::Get);
+    assert_eq!(method(7).kind, MethodKind::Set);
+    match &*method(8).value {
+        Node::FunctionExpression(function) => {
+            assert!(function.r#async && function.generator);
+            assert!(method(8).r#static);
+        }
+        other => panic!("expected function, got {other:?}"),
+    }
+    assert!(method(9).computed);
+    assert!(matches!(&elements[10], Node::StaticBlock(block) if block.body.len() == 1));
+    assert_eq!(method(12).kind, MethodKind::Method);
+    assert_eq!(identifier_name(&method(12).key), Some("get"));
+    assert!(matches!(
+        &elements[13],
+        Node::PropertyDefinition(prop) if !prop.r#static && identifier_name(&prop.key) == Some("static")
+    ));
+
+    // `#step in obj` is a brand check and `this.#step` a private member.
+    match &*method(11).value {
+        Node::FunctionExpression(function) => {
+            let Node::BlockStatement(body) = &*function.body else {
+                panic!("expected block body");
+            };
+            assert!(matches!(
+                &body.body[0],
+                Node::ReturnStatement(stmt) if matches!(
+                    stmt.argument.as_deref(),
+                    Some(Node::BinaryExpression(expr))
+                        if expr.operator == "in" && private_name(&expr.left) == Some("step")
+                )
+            ));
+        }
+        other => panic!("expected function, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_class_element_errors() {
+    assert!(has_errors("class A { constructor() {} constructor() {} }"));
+    assert!(has_errors("class A { get constructor() {} }"));
+    assert!(has_errors("class A { #constructor() {} }"));
+    assert!(has_errors("class A { constructor = 1; }"));
+    assert!(has_errors("class A { static prototype() {} }"));
+    assert!(has_errors("class A { get x(a) {} }"));
+    assert!(has_errors("class A { set x() {} }"));
+    assert!(has_errors("class A { x = 1 y = 2 }"));
+    assert!(has_errors("let a = #x;"));
+
+    // Only a static `constructor` or a non-static `prototype` are ordinary names.
+    assert!(!has_errors(
+        "class A { static constructor() {} prototype = 1; }"
+    ));
+}
+
+fn directive(node: &Node) -> Option<&str> {
+    match node {
+        Node::ExpressionStatement(stmt) => stmt.directive.as_deref(),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_parse_directive_prologue() {
+    let body = program_body("'use strict'; \"other\"; x; 'late';");
+    let directives: Vec<_> = body.iter().map(directive).collect();
+    assert_eq!(directives, [Some("use strict"), Some("other"), None, None]);
+
+    assert_eq!(directive(&program_body("('use strict');")[0]), None);
+    assert_eq!(directive(&program_body("'use strict'.length;")[0]), None);
+
+    let body = program_body("function f() { 'use strict'; return 1; }");
+    let Node::FunctionDeclaration(function) = &body[0] else {
+        panic!("expected function, got {:?}", body[0]);
+    };
+    let Node::BlockStatement(block) = &*function.body else {
+        panic!("expected block, got {:?}", function.body);
+    };
+    assert_eq!(directive(&block.body[0]), Some("use strict"));
+}
+
+#[test]
+fn test_parse_labeled_statement() {
+    let body = program_body("outer: for (;;) { inner: while (x) { continue outer; } }");
+    let Node::LabeledStatement(labeled) = &body[0] else {
+        panic!("expected labeled statement, got {:?}", body[0]);
+    };
+    assert!(matches!(&*labeled.label, Node::Identifier(id) if id.name == "outer"));
+    assert!(matches!(&*labeled.body, Node::ForStatement(_)));
+}
+
+fn error_nodes(body: &[Node]) -> Vec<&ErrorNode> {
+    body.iter()
+        .filter_map(|node| match node {
+            Node::Error(error) => Some(error),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_recovery_keeps_error_nodes() {
+    let (program, errors) = parse_with_recovery("let a = 1;\nlet = ;\nlet b = 2;");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(program.body.len(), 3);
+    assert!(matches!(program.body[2], Node::VariableDeclaration(_)));
+
+    let error = error_nodes(&program.body)[0];
+    assert_eq!(error.skipped, ["let", "=", ";"]);
+    assert_eq!(error.message, errors[0].to_string());
+    let span = &error.span;
+    assert_eq!(
+        (span.start.line.as_usize(), span.start.column.as_usize()),
+        (2, 1)
+    );
+    assert_eq!(
+        (span.end.line.as_usize(), span.end.column.as_usize()),
+        (2, 8)
+    );
+
+    // Errors inside a block stay inside it, next to the statements around.
+    let (program, _) = parse_with_recovery("function f() { let x = ; return x; }\nf();");
+    assert_eq!(program.body.len(), 2);
+    let Node::FunctionDeclaration(function) = &program.body[0] else {
+        panic!("expected function, got {:?}", program.body[0]);
+    };
+    let Node::BlockStatement(block) = &*function.body else {
+        panic!("expected block, got {:?}", function.body);
+    };
+    assert_eq!(error_nodes(&block.body)[0].skipped, ["let", "x", "=", ";"]);
+    assert!(matches!(block.body[1], Node::ReturnStatement(_)));
+
+    // Stray tokens are skipped one statement at a time.
+    let (program, errors) = parse_with_recovery("}\nx;\n)");
+    assert_eq!(errors.len(), 2);
+    assert_eq!(error_nodes(&program.body).len(), 2);
+    assert!(matches!(program.body[1], Node::ExpressionStatement(_)));
+}
+
+#[test]
+fn test_directive_strict_literals() {
+    assert!(parse("\"use strict\"; 010").is_err());
+    assert!(parse("\"use strict\"; \"\\01\"").is_err());
+    assert!(parse("\"\\01\"; \"use strict\"").is_err());
+    assert!(parse("function f() { \"use strict\"; return 010; }").is_err());
+    assert!(parse("class A { m() { return 010; } }").is_err());
+
+    assert!(parse("010; \"\\01\"").is_ok());
+    assert!(parse("function f() { \"use strict\"; } 010").is_ok());
+}
+
+#[test]
+fn test_strict_parse_rejects_recovered_errors() {
+    assert!(matches!(
+        parse("let a = 1;\nlet = ;\nlet b = 2;"),
+        Err(ParserError::InvalidSyntax { .. })
+    ));
+    assert!(parse("{ a(; }").is_err());
+    assert!(parse("let a = 1;").is_ok());
+}
+
+#[test]
+fn test_recovery_strategy_for_each_context() {
+    let strategy = |context| RecoveryContext::new(None, None, context).determine_strategy();
+    let skip_until = |tokens: &[&str]| {
+        RecoveryStrategy::SkipUntil(tokens.iter().map(|t| t.to_string()).collect())
+    };
+
+    assert_eq!(
+        strategy(ParsingContext::TopLevel),
+        RecoveryStrategy::NoRecovery
+    );
+    assert_eq!(
+        strategy(ParsingContext::Statement),
+        skip_until(&[";", "}", ")"])
+    );
+    assert_eq!(strategy(ParsingContext::Block), skip_until(&[";", "}"]));
+    assert_eq!(strategy(ParsingContext::Function), skip_until(&["}", ";"]));
+    assert_eq!(strategy(ParsingContext::Class), skip_until(&["}"]));
+    assert_eq!(
+        strategy(ParsingContext::Module),
+        skip_until(&["}", "import", "export"])
+    );
+    assert_eq!(
+        strategy(ParsingContext::Expression),
+        skip_until(&[";", ",", ")", "]", "}"])
+    );
+    assert_eq!(
+        strategy(ParsingContext::Declaration),
+        skip_until(&[";", "}"])
+    );
+}
+
+#[test]
+fn test_recovery_skips_to_the_end_of_a_top_level_statement() {
+    // At the top level the rest of the statement is skipped up to a `;`,
+    // which is kept in the error node, or a `}`, which is left for the next
+    // statement.
+    let (program, errors) = parse_with_recovery("let x = ) y }\nz;");
+    assert_eq!(errors.len(), 2);
+    let nodes = error_nodes(&program.body);
+    assert_eq!(nodes[0].skipped, ["let", "x", "=", ")", "y"]);
+    assert_eq!(nodes[0].span.range, 0..11);
+
+    // A stray `}` is skipped on its own.
+    assert_eq!(nodes[1].skipped, ["}"]);
+    assert_eq!(nodes[1].span.range, 12..13);
+    assert_eq!(
+        (
+            nodes[1].span.start.column.as_usize(),
+            nodes[1].span.end.column.as_usize()
+        ),
+        (13, 14)
+    );
+    assert!(matches!(program.body[2], Node::ExpressionStatement(_)));
+}
+
+#[test]
+fn test_recovery_skips_to_the_end_of_a_block_statement() {
+    let (program, errors) = parse_with_recovery("{ a(; b; }\nc;");
+    assert_eq!(errors.len(), 1);
+    let Node::BlockStatement(block) = &program.body[0] else {
+        panic!("expected block, got {:?}", program.body[0]);
+    };
+    assert_eq!(block.body.len(), 2);
+    let error = error_nodes(&block.body)[0];
+    assert_eq!(error.skipped, ["a", "(", ";"]);
+    assert_eq!(error.span.range, 2..5);
+    assert!(matches!(program.body[1], Node::ExpressionStatement(_)));
+
+    // The closing brace ends the skipped tokens and still closes the block.
+    let (program, errors) = parse_with_recovery("if (a) { let x = }\nd;");
+    assert_eq!(errors.len(), 1);
+    let Node::IfStatement(statement) = &program.body[0] else {
+        panic!("expected if statement, got {:?}", program.body[0]);
+    };
+    let Node::BlockStatement(block) = &*statement.consequent else {
+        panic!("expected block, got {:?}", statement.consequent);
+    };
+    let error = error_nodes(&block.body)[0];
+    assert_eq!(error.skipped, ["let", "x", "="]);
+    assert_eq!(error.span.range, 9..16);
+    assert_eq!(program.body.len(), 2);
+}
+
+#[test]
+fn test_recovery_stops_after_too_many_errors() {
+    // Once the error limit is reached nothing is recovered any more, and the
+    // last error node covers the rest of the input.
+    let source = format!("{}x; y;", "}\n".repeat(101));
+    let (program, errors) = parse_with_recovery(&source);
+    assert_eq!(errors.len(), 101);
+    assert_eq!(program.body.len(), 101);
+    let Some(Node::Error(error)) = program.body.last() else {
+        panic!("expected error node, got {:?}", program.body.last());
+    };
+    assert_eq!(error.skipped, ["}", "x", ";", "y", ";"]);
+    assert_eq!(error.span.range, 200..source.len());
+    assert_eq!(
+        (
+            error.span.start.line.as_usize(),
+            error.span.end.line.as_usize()
+        ),
+        (101, 102)
+    );
+}
+
+#[test]
+fn test_node_spans_cover_their_source() {
+    let source = "let é = 'ü' + 1n;\nfunction f(a, b) {\n  return a ?? this;\n}\nclass C { #x = 1; }\n`t${é}`;\n";
+    let program = parse(source).unwrap();
+    let text = |node: &Node| &source[node.span().range.clone()];
+    let Node::Program(body) = &program else {
+        panic!("expected program, got {program:?}");
+    };
+    assert_eq!(text(&program), source);
+
+    let Node::VariableDeclaration(decl) = &body.body[0] else {
+        panic!("expected variable declaration, got {:?}", body.body[0]);
+    };
+    assert_eq!(text(&body.body[0]), "let é = 'ü' + 1n;");
+    assert_eq!(
+        &source[decl.declarations[0].span.range.clone()],
+        "é = 'ü' + 1n"
+    );
+    assert_eq!(text(&decl.declarations[0].id), "é");
+    let Some(Node::BinaryExpression(sum)) = decl.declarations[0].init.as_deref() else {
+        panic!("expected binary expression");
+    };
+    assert_eq!(text(&sum.left), "'ü'");
+    assert_eq!(text(&sum.right), "1n");
+    assert_eq!(sum.right.span().start, Position::new(1, 15));
+
+    let function = &body.body[1];
+    assert_eq!(text(function), "function f(a, b) {\n  return a ?? this;\n}");
+    assert_eq!(
+        (function.span().start, function.span().end),
+        (Position::new(2, 1), Position::new(4, 2))
+    );
+    let Node::FunctionDeclaration(function) = function else {
+        panic!("expected function, got {function:?}");
+    };
+    assert_eq!(text(&function.params[1]), "b");
+    let Node::BlockStatement(block) = &*function.body else {
+        panic!("expected block, got {:?}", function.body);
+    };
+    let Node::ReturnStatement(ret) = &block.body[0] else {
+        panic!("expected return, got {:?}", block.body[0]);
+    };
+    assert_eq!(text(&block.body[0]), "return a ?? this;");
+    let Some(Node::LogicalExpression(expr)) = ret.argument.as_deref() else {
+        panic!("expected logical expression");
+    };
+    assert_eq!(text(&expr.right), "this");
+    assert_eq!(expr.right.span().start, Position::new(3, 15));
+
+    let Node::ClassDeclaration(class) = &body.body[2] else {
+        panic!("expected class, got {:?}", body.body[2]);
+    };
+    let Node::ClassBody(class_body) = &*class.body else {
+        panic!("expected class body");
+    };
+    assert_eq!(text(&class.body), "{ #x = 1; }");
+    assert_eq!(text(&class_body.body[0]), "#x = 1;");
+    let Node::PropertyDefinition(field) = &class_body.body[0] else {
+        panic!("expected field");
+    };
+    assert_eq!(text(&field.key), "#x");
+
+    let Node::ExpressionStatement(stmt) = &body.body[3] else {
+        panic!("expected expression statement, got {:?}", body.body[3]);
+    };
+    assert_eq!(text(&stmt.expression), "`t${é}`");
+    let Node::TemplateLiteral(template) = &*stmt.expression else {
+        panic!("expected template literal");
+    };
+    assert_eq!(text(&template.expressions[0]), "é");
+}
+
+#[test]
+fn test_node_span_offsets_and_positions() {
+    // Ranges count bytes and columns count characters, so they drift apart
+    // after `名前` (3 bytes a character) and `😀` (4 bytes).
+    let source = "const 名前 = f('😀', (a + b) * c);\n  x.y;";
+    let program = parse(source).unwrap();
+    let Node::Program(body) = &program else {
+        panic!("expected program, got {program:?}");
+    };
+    let span = |node: &Node| {
+        let span = node.span();
+        (span.range.clone(), span.start, span.end)
+    };
+
+    let Node::VariableDeclaration(decl) = &body.body[0] else {
+        panic!("expected variable declaration, got {:?}", body.body[0]);
+    };
+    assert_eq!(
+        span(&body.body[0]),
+        (0..38, Position::new(1, 1), Position::new(1, 32))
+    );
+    assert_eq!(
+        span(&decl.declarations[0].id),
+        (6..12, Position::new(1, 7), Position::new(1, 9))
+    );
+
+    let Some(Node::CallExpression(call)) = decl.declarations[0].init.as_deref() else {
+        panic!("expected call expression");
+    };
+    assert_eq!(
+        span(decl.declarations[0].init.as_deref().unwrap()),
+        (15..37, Position::new(1, 12), Position::new(1, 31))
+    );
+    assert_eq!(
+        span(&call.arguments[0]),
+        (17..23, Position::new(1, 14), Position::new(1, 17))
+    );
+
+    let product = &call.arguments[1];
+    assert_eq!(
+        span(product),
+        (25..36, Position::new(1, 19), Position::new(1, 30))
+    );
+    let Node::BinaryExpression(product) = product else {
+        panic!("expected binary expression, got {product:?}");
+    };
+    assert_eq!(
+        span(&product.left),
+        (26..31, Position::new(1, 20), Position::new(1, 25))
+    );
+    let Node::BinaryExpression(sum) = &*product.left else {
+        panic!("expected binary expression, got {:?}", product.left);
+    };
+    assert_eq!(
+        span(&sum.left),
+        (26..27, Position::new(1, 20), Position::new(1, 21))
+    );
+    assert_eq!(
+        span(&sum.right),
+        (30..31, Position::new(1, 24), Position::new(1, 25))
+    );
+    assert_eq!(
+        span(&product.right),
+        (35..36, Position::new(1, 29), Position::new(1, 30))
+    );
+
+    let Node::ExpressionStatement(stmt) = &body.body[1] else {
+        panic!("expected expression statement, got {:?}", body.body[1]);
+    };
+    assert_eq!(
+        span(&stmt.expression),
+        (41..44, Position::new(2, 3), Position::new(2, 6))
+    );
+    let Node::MemberExpression(member) = &*stmt.expression else {
+        panic!("expected member expression, got {:?}", stmt.expression);
+    };
+    assert_eq!(
+        span(&member.property),
+        (43..44, Position::new(2, 5), Position::new(2, 6))
+    );
+}
+
+#[test]
+fn test_arena_round_trips_the_owned_tree() {
+    let source = "import a, { b as c } from 'm' with { type: 'json' };\n\
+        export * as ns from 'n';\n\
+        outer: for (const k in o) for (let i = 0; i < 2; i++) {\n\
+          switch (i) { case 1: break outer; default: continue; }\n\
+        }\n\
+        export class K extends B { #p = 1; static { this.q = tag`t${x}u`; } get v() { return this.#p; } }\n\
+        export async function g(r) { await r; try { throw /re/g; } catch (e) {} finally { debugger; } }\n\
+        let z = { a: [x, , 2n] }, w = -y++ + (x = 2) * null;\n\
+        while (!z) z = new K(z.q, `a${x}`);\n\
+        do { x--; } while (x && false);\n\
+        let f = async (x) => x;\n";
+    let (program, errors) =
+        Parser::with_options(source, ParserOptions::module()).parse_with_recovery();
+    assert!(errors.is_empty(), "unexpected errors: {errors:?}");
+    let program = Node::Program(program);
+
+    let mut arena = AstArena::new();
+    let root = arena.alloc_node(&program);
+    assert_eq!(arena.to_node(root), program);
+    assert_eq!(arena.span(root), program.span());
+
+    // Every use of `x` shares one interned symbol.
+    let x = arena.interner().get("x").unwrap();
+    let uses = arena
+        .iter()
+        .filter(|(_, node)| matches!(node.kind, NodeKind::Identifier { name } if name == x))
+        .count();
+    assert_eq!(uses, 8);
+
+    // Recovered errors survive the trip too.
+    let (program, errors) = parse_with_recovery("let = ;\nx;");
+    assert_eq!(errors.len(), 1);
+    let program = Node::Program(program);
+    let mut arena = AstArena::new();
+    let root = arena.alloc_node(&program);
+    assert_eq!(arena.to_node(root), program);
+}
+
+#[test]
+fn test_arena_allocation() {
+    let mut arena = AstArena::with_capacity(4);
+    assert!(arena.is_empty());
+
+    let span = |start: usize, end: usize| {
+        Span::new(Position::new(1, start + 1), Position::new(1, end + 1)).with_range(start..end)
+    };
+    let f = arena.intern("f");
+    let x = arena.intern("x");
+    assert_eq!(arena.intern("f"), f);
+    assert_eq!(arena.interner().len(), 2);
+    assert_eq!(arena.resolve(x), "x");
+    assert_eq!(arena.interner().get("y"), None);
+
+    // `f([x, , 'x'])`, built bottom up.
+    let callee = arena.alloc(NodeKind::Identifier { name: f }, span(0, 1));
+    let first = arena.alloc(NodeKind::Identifier { name: x }, span(3, 4));
+    let last = arena.alloc(NodeKind::String { value: x }, span(8, 11));
+    let elements = arena.alloc_elements([Some(first), None, Some(last)]);
+    let array = arena.alloc(NodeKind::ArrayLiteral { elements }, span(2, 12));
+    let arguments = arena.alloc_list([array]);
+    let call = arena.alloc(NodeKind::CallExpression { callee, arguments }, span(0, 13));
+
+    assert_eq!(arena.len(), 5);
+    assert_eq!(
+        [callee, first, last, array, call].map(|id| id.as_usize()),
+        [0, 1, 2, 3, 4]
+    );
+    assert_eq!(arena.elements(elements), [Some(first), None, Some(last)]);
+    assert_eq!(arena.list(arguments), [array]);
+    let empty = arena.alloc_list([]);
+    assert!(empty.is_empty() && arena.list(empty).is_empty());
+    let symbols = arena.alloc_symbols([x, f]);
+    assert_eq!(arena.symbols(symbols), [x, f]);
+
+    let expected = |name: &str| {
+        Node::CallExpression(CallExpression {
+            callee: Box::new(Node::Identifier(Identifier {
+                name: name.to_string(),
+                span: span(0, 1),
+            })),
+            arguments: vec![Node::ArrayLiteral(ArrayLiteral {
+                elements: vec![
+                    Some(Node::Identifier(Identifier {
+                        name: "x".to_string(),
+                        span: span(3, 4),
+                    })),
+                    None,
+                    Some(Node::String(StringLiteral {
+                        value: "x".to_string(),
+                        span: span(8, 11),
+                    })),
+                ],
+                span: span(2, 12),
+            })],
+            span: span(0, 13),
+        })
+    };
+    assert_eq!(arena.to_node(call), expected("f"));
+
+    // Nodes are changed in place and the rest of the tree sees the change.
+    let g = arena.intern("g");
+    arena.get_mut(callee).kind = NodeKind::Identifier { name: g };
+    assert_eq!(arena.to_node(call), expected("g"));
+}
+
+#[test]
+fn test_arena_parent_and_child_lookups() {
+    let program = parse("a.b(c, 1);\nif (c) d();").unwrap();
+    let mut arena = AstArena::new();
+    let root = arena.alloc_node(&program);
+    let name = |arena: &AstArena, id| match *arena.kind(id) {
+        NodeKind::Identifier { name } => arena.resolve(name).to_string(),
+        ref other => panic!("expected identifier, got {other:?}"),
+    };
+
+    // Down from the root, by the ids each node holds.
+    let NodeKind::Program { body, .. } = *arena.kind(root) else {
+        panic!("expected program, got {:?}", arena.kind(root));
+    };
+    let [first, second] = *arena.list(body) else {
+        panic!("expected two statements");
+    };
+    let NodeKind::ExpressionStatement { expression, .. } = *arena.kind(first) else {
+        panic!("expected expression statement, got {:?}", arena.kind(first));
+    };
+    let NodeKind::CallExpression { callee, arguments } = *arena.kind(expression) else {
+        panic!("expected call, got {:?}", arena.kind(expression));
+    };
+    let NodeKind::MemberExpression {
+        object, property, ..
+    } = *arena.kind(callee)
+    else {
+        panic!("expected member expression, got {:?}", arena.kind(callee));
+    };
+    assert_eq!(
+        (name(&arena, object), name(&arena, property)),
+        ("a".into(), "b".into())
+    );
+    let [c, one] = *arena.list(arguments) else {
+        panic!("expected two arguments");
+    };
+    assert_eq!(name(&arena, c), "c");
+    assert_eq!(*arena.kind(one), NodeKind::Number { value: 1.0 });
+    assert_eq!(arena.span(one).range, 7..8);
+    assert_eq!(arena.to_node(expression), first_expression("a.b(c, 1)"));
+
+    // Children are allocated before their parents, so every child id is
+    // smaller than the id of the node holding it.
+    for child in [callee, object, property, c, one] {
+        assert!(child < expression);
+    }
+    assert!(first < second && second < root);
+
+    // Up from a node, by finding the node that holds its id.
+    let parent = |child| {
+        arena
+            .iter()
+            .find(|(_, node)| match node.kind {
+                NodeKind::CallExpression { callee, arguments } => {
+                    callee == child || arena.list(arguments).contains(&child)
+                }
+                NodeKind::IfStatement { test, .. } => test == child,
+                _ => false,
+            })
+            .map(|(id, _)| id)
+    };
+    assert_eq!(parent(c), Some(expression));
+    assert_eq!(parent(callee), Some(expression));
+    let NodeKind::IfStatement { test, .. } = *arena.kind(second) else {
+        panic!("expected if statement, got {:?}", arena.kind(second));
+    };
+    assert_eq!(parent(test), Some(second));
+    // `c` is one symbol but two nodes, each with its own parent.
+    assert_ne!(test, c);
+    assert_eq!(arena.kind(test), arena.kind(c));
+    assert_eq!(parent(root), None);
+}
+
+#[test]
+fn test_estree_json_export_and_import() {
+    let source = "import a from 'm';\n\
+        export * as ns from 'n';\n\
+        export default class K { #p = 1; get v() { return this.#p; } }\n\
+        let f = 10n, d = 1; [d, , f] = o;\n\
+        g(undefined, /re/g, `t${d}u`, async (h) => await h);\n";
+    let (program, errors) =
+        Parser::with_options(source, ParserOptions::module()).parse_with_recovery();
+    assert!(errors.is_empty(), "unexpected errors: {errors:?}");
+    let program = Node::Program(program);
+
+    let json = to_estree_json(&program).unwrap();
+    let estree: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(estree["type"], "Program");
+    assert_eq!(estree["sourceType"], "module");
+    assert_eq!(estree["range"], serde_json::json!([0, source.len()]));
+
+    let body = &estree["body"];
+    assert_eq!(body[0]["type"], "ImportDeclaration");
+    assert_eq!(body[1]["type"], "ExportAllDeclaration");
+    assert_eq!(body[1]["exported"]["name"], "ns");
+    assert_eq!(body[2]["type"], "ExportDefaultDeclaration");
+
+    // Columns are 0-based in ESTree and ranges are byte offsets.
+    let declaration = &body[3];
+    assert_eq!(
+        declaration["loc"]["start"],
+        serde_json::json!({ "line": 4, "column": 0 })
+    );
+    assert_eq!(declaration["declarations"][0]["init"]["bigint"], "10");
+    let assignment = &body[4]["expression"];
+    assert_eq!(assignment["left"]["type"], "ArrayPattern");
+    assert_eq!(assignment["left"]["elements"][1], serde_json::Value::Null);
+
+    let arguments = &body[5]["expression"]["arguments"];
+    assert_eq!(arguments[0]["type"], "Identifier");
+    assert_eq!(arguments[1]["regex"]["flags"], "g");
+    assert_eq!(arguments[2]["quasis"][1]["tail"], true);
+    assert_eq!(arguments[3]["type"], "ArrowFunctionExpression");
+
+    // Reading it back and writing it again gives the same JSON.
+    let imported = from_estree_json(&json).unwrap();
+    assert_eq!(to_estree_json(&imported).unwrap(), json);
+
+    assert!(matches!(
+        from_estree_json(r#"{ "type": "JSXElement" }"#),
+        Err(AstError::InvalidNode { node_type, .. }) if node_type == "JSXElement"
+    ));
+    assert!(matches!(
+        from_estree_json("not json"),
+        Err(AstError::DeserializationError { .. })
+    ));
+}
+
+// Exports `source` as ESTree, checks that importing it gives back the parsed
+// tree and the same JSON once exported again, and returns the ESTree body.
+// Spans ESTree has no node for, like that of the `*` in `export *`, are not
+// compared.
+fn estree_round_trip(source: &str, options: ParserOptions) -> serde_json::Value {
+    let (program, errors) = Parser::with_options(source, options).parse_with_recovery();
+    assert!(errors.is_empty(), "unexpected errors: {errors:?}");
+    let program = Node::Program(program);
+    let json = to_estree_json(&program).unwrap();
+    let imported = from_estree_json(&json).unwrap();
+    assert_eq!(without_spans(&imported), without_spans(&program));
+    assert_eq!(to_estree_json(&imported).unwrap(), json);
+    let mut estree: serde_json::Value = serde_json::from_str(&json).unwrap();
+    estree["body"].take()
+}
+
+fn estree_types(nodes: &serde_json::Value) -> Vec<&str> {
+    nodes
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| node["type"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_estree_round_trips_statements() {
+    let body = estree_round_trip(
+        "var a = 1, b;\n\
+        ;\n\
+        { let c = 2; }\n\
+        if (a) b = 1; else if (b) {} else ;\n\
+        for (let i = 0; i < 2; i++) continue;\n\
+        for (const k in o) break;\n\
+        for (x of y) {}\n\
+        while (a) a--;\n\
+        do a++; while (a < 3);\n\
+        outer: switch (a) { case 1: break outer; default: }\n\
+        try { throw a; } catch (e) {} finally { debugger; }\n\
+        with (o) f();\n\
+        function g() { return; }\n",
+        ParserOptions::default(),
+    );
+    assert_eq!(
+        estree_types(&body),
+        [
+            "VariableDeclaration",
+            "EmptyStatement",
+            "BlockStatement",
+            "IfStatement",
+            "ForStatement",
+            "ForInStatement",
+            "ForOfStatement",
+            "WhileStatement",
+            "DoWhileStatement",
+            "LabeledStatement",
+            "TryStatement",
+            "WithStatement",
+            "FunctionDeclaration",
+        ]
+    );
+    assert_eq!(body[0]["declarations"][1]["init"], serde_json::Value::Null);
+    assert_eq!(body[3]["alternate"]["alternate"]["type"], "EmptyStatement");
+    assert_eq!(body[9]["body"]["cases"][1]["test"], serde_json::Value::Null);
+    assert_eq!(body[10]["handler"]["param"]["name"], "e");
+    assert_eq!(
+        body[10]["finalizer"]["body"][0]["type"],
+        "DebuggerStatement"
+    );
+    assert_eq!(
+        body[12]["body"]["body"][0]["argument"],
+        serde_json::Value::Null
+    );
+}
+
+#[test]
+fn test_estree_round_trips_expressions() {
+    let body = estree_round_trip(
+        "x = a + b * -c - (d + e) ** 2;\n\
+        x = (a ?? b) || !e && f;\n\
+        x = typeof a === 'string';\n\
+        x = new A(b)[c](d).e;\n\
+        x = [a, , b];\n\
+        x = { a, 'b': 1, c: { d: 2 } };\n\
+        x = async (a, b) => { await a; };\n\
+        x = (a) => a * 2;\n\
+        x = function g(a) { return a; };\n\
+        x = [/a[/]b/giu, 0x10, 1.5e3, 10n, true, null, this, void 0];\n\
+        ({ a, b: [c] } = d);\n\
+        [a, b = 1] = d;\n\
+        x += a++ + --b;\n",
+        ParserOptions::default(),
+    );
+    assert!(estree_types(&body)
+        .iter()
+        .all(|kind| *kind == "ExpressionStatement"));
+    let right = |index: usize| &body[index]["expression"]["right"];
+
+    assert_eq!(right(0)["type"], "BinaryExpression");
+    assert_eq!(
+        right(0)["left"]["right"]["right"]["type"],
+        "UnaryExpression"
+    );
+    assert_eq!(right(0)["right"]["operator"], "**");
+    assert_eq!(right(1)["type"], "LogicalExpression");
+    assert_eq!(right(1)["left"]["operator"], "??");
+    assert_eq!(right(1)["right"]["left"]["prefix"], true);
+    assert_eq!(right(2)["left"]["operator"], "typeof");
+    let member = right(3);
+    assert_eq!(member["computed"], false);
+    assert_eq!(member["object"]["type"], "CallExpression");
+    assert_eq!(member["object"]["callee"]["computed"], true);
+    assert_eq!(
+        member["object"]["callee"]["object"]["type"],
+        "NewExpression"
+    );
+    assert_eq!(right(4)["elements"][1], serde_json::Value::Null);
+    let properties = &right(5)["properties"];
+    assert_eq!(
+        estree_types(properties),
+        ["Property", "Property", "Property"]
+    );
+    assert_eq!(properties[0]["shorthand"], true);
+    assert_eq!(properties[1]["key"]["value"], "b");
+    assert_eq!(properties[2]["value"]["type"], "ObjectExpression");
+    assert_eq!(right(6)["type"], "ArrowFunctionExpression");
+    assert_eq!(right(6)["async"], true);
+    assert_eq!(right(6)["expression"], false);
+    assert_eq!(right(7)["expression"], true);
+    assert_eq!(right(8)["id"]["name"], "g");
+
+    let literals = &right(9)["elements"];
+    assert_eq!(literals[0]["regex"]["pattern"], "a[/]b");
+    assert_eq!(literals[0]["regex"]["flags"], "giu");
+    assert_eq!(literals[1]["value"], 16.0);
+    assert_eq!(literals[3]["bigint"], "10");
+    assert_eq!(literals[5]["value"], serde_json::Value::Null);
+    assert_eq!(literals[6]["type"], "ThisExpression");
+    assert_eq!(literals[7]["operator"], "void");
+
+    let pattern = &body[10]["expression"]["left"];
+    assert_eq!(pattern["type"], "ObjectPattern");
+    assert_eq!(pattern["properties"][1]["value"]["type"], "ArrayPattern");
+    let pattern = &body[11]["expression"]["left"];
+    assert_eq!(pattern["elements"][1]["type"], "AssignmentPattern");
+    assert_eq!(body[12]["expression"]["operator"], "+=");
+    assert_eq!(right(12)["left"]["type"], "UpdateExpression");
+}
+
+#[test]
+fn test_estree_round_trips_classes() {
+    let body = estree_round_trip(
+        "class A extends B {\n\
+          static #count = 0;\n\
+          value;\n\
+          [key] = 1;\n\
+          constructor(x) { this.x = x; }\n\
+          static create() { return new A(A.#count); }\n\
+          get #size() { return 1; }\n\
+          set size(v) {}\n\
+          static { A.ready = #count in A; }\n\
+        }\n\
+        let C = class extends A {};\n",
+        ParserOptions::default(),
+    );
+    assert_eq!(body[0]["type"], "ClassDeclaration");
+    assert_eq!(body[0]["superClass"]["name"], "B");
+
+    let members = &body[0]["body"]["body"];
+    assert_eq!(
+        estree_types(members),
+        [
+            "PropertyDefinition",
+            "PropertyDefinition",
+            "PropertyDefinition",
+            "MethodDefinition",
+            "MethodDefinition",
+            "MethodDefinition",
+            "MethodDefinition",
+            "StaticBlock",
+        ]
+    );
+    assert_eq!(members[0]["static"], true);
+    assert_eq!(members[0]["key"]["type"], "PrivateIdentifier");
+    assert_eq!(members[1]["value"], serde_json::Value::Null);
+    assert_eq!(members[2]["computed"], true);
+    assert_eq!(members[3]["kind"], "constructor");
+    let create = &members[4]["value"]["body"]["body"][0]["argument"];
+    assert_eq!(
+        create["arguments"][0]["property"]["type"],
+        "PrivateIdentifier"
+    );
+    assert_eq!(members[5]["kind"], "get");
+    assert_eq!(members[5]["key"]["name"], "size");
+    assert_eq!(members[6]["kind"], "set");
+
+    let class = &body[1]["declarations"][0]["init"];
+    assert_eq!(class["type"], "ClassExpression");
+    assert_eq!(class["id"], serde_json::Value::Null);
+    let ready = &members[7]["body"][0]["expression"]["right"];
+    assert_eq!(ready["operator"], "in");
+    assert_eq!(ready["left"]["type"], "PrivateIdentifier");
+}
+
+#[test]
+fn test_estree_round_trips_modules() {
+    let body = estree_round_trip(
+        "import a, * as b from 'a';\n\
+        import { c, d as e, 'f g' as h } from 'c' with { type: 'json' };\n\
+        import 'side-effect';\n\
+        export { a, c as default };\n\
+        export * from 'n';\n\
+        export * as ns from 'n';\n\
+        export { x as y } from 'x';\n\
+        export const z = import.meta.url;\n\
+        export default function () {}\n",
+        ParserOptions::module(),
+    );
+    assert_eq!(
+        estree_types(&body),
+        [
+            "ImportDeclaration",
+            "ImportDeclaration",
+            "ImportDeclaration",
+            "ExportNamedDeclaration",
+            "ExportAllDeclaration",
+            "ExportAllDeclaration",
+            "ExportNamedDeclaration",
+            "ExportNamedDeclaration",
+            "ExportDefaultDeclaration",
+        ]
+    );
+    assert_eq!(
+        estree_types(&body[0]["specifiers"]),
+        ["ImportDefaultSpecifier", "ImportNamespaceSpecifier"]
+    );
+    let named = &body[1]["specifiers"];
+    assert_eq!(named[1]["imported"]["name"], "d");
+    assert_eq!(named[1]["local"]["name"], "e");
+    assert_eq!(named[2]["imported"]["value"], "f g");
+    assert_eq!(body[1]["attributes"][0]["value"]["value"], "json");
+    assert_eq!(body[2]["specifiers"], serde_json::json!([]));
+    assert_eq!(body[3]["specifiers"][1]["exported"]["name"], "default");
+    assert_eq!(body[3]["source"], serde_json::Value::Null);
+    assert_eq!(body[4]["exported"], serde_json::Value::Null);
+    assert_eq!(body[6]["source"]["value"], "x");
+    let meta = &body[7]["declaration"]["declarations"][0]["init"]["object"];
+    assert_eq!(meta["type"], "MetaProperty");
+    assert_eq!(meta["meta"]["name"], "import");
+    assert_eq!(body[8]["declaration"]["type"], "FunctionDeclaration");
+}
+
+#[test]
+fn test_estree_round_trips_templates() {
+    let body = estree_round_trip(
+        "`plain`;\n`a${b}c${`d${e}`}`;\ntag`\\unicode ${x}\\n`;\n",
+        ParserOptions::default(),
+    );
+    let plain = &body[0]["expression"];
+    assert_eq!(plain["type"], "TemplateLiteral");
+    assert_eq!(plain["quasis"][0]["value"]["cooked"], "plain");
+    assert_eq!(plain["quasis"][0]["tail"], true);
+
+    let nested = &body[1]["expression"];
+    assert_eq!(nested["quasis"].as_array().unwrap().len(), 3);
+    assert_eq!(nested["expressions"][1]["type"], "TemplateLiteral");
+    assert_eq!(nested["expressions"][1]["quasis"][0]["value"]["raw"], "d");
+
+    let tagged = &body[2]["expression"];
+    assert_eq!(tagged["type"], "TaggedTemplateExpression");
+    let quasis = &tagged["quasi"]["quasis"];
+    assert_eq!(quasis[0]["value"]["cooked"], serde_json::Value::Null);
+    assert_eq!(quasis[0]["value"]["raw"], "\\unicode ");
+    assert_eq!(quasis[1]["value"]["cooked"], "\n");
+    assert_eq!(quasis[1]["value"]["raw"], "\\n");
+}
+
+// The tree as JSON without spans, so trees parsed from differently
+// formatted source compare equal.
+fn without_spans(node: &Node) -> serde_json::Value {
+    fn strip(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                map.remove("span");
+                map.values_mut().for_each(strip);
+            }
+            serde_json::Value::Array(values) => values.iter_mut().for_each(strip),
+            _ => {}
+        }
+    }
+    let mut value = serde_json::to_value(node).unwrap();
+    strip(&mut value);
+    value
+}
+
+#[test]
+fn test_printer_round_trips_parsed_source() {
+    let source = "'use strict';\n\
+        import a, { b as c, d } from 'm' with { type: 'json' };\n\
+        export * as ns from 'n';\n\
+        export { c as default, d };\n\
+        outer: for (const k in o) for (let i = 0; i < 2; i++) {\n\
+          switch (i) { case 1: break outer; default: continue; }\n\
+        }\n\
+        export class K extends B { #p = 1; static { this.q = tag`t${x}u`; } get v() { return this.#p; } }\n\
+        export async function g(r) { await r; try { throw /re/g; } catch (e) {} finally { debugger; } }\n\
+        let z = { a: [x, , 2n, ,], 'b-c': \"it's\\n\" }, w = -y++ + (x = 2) * null;\n\
+        while (!z) z = new K(z.q, `a${x}`);\n\
+        do { x--; } while (x && false);\n\
+        let f = async (x) => x, h = () => ({ a: 1 });\n\
+        y = (a + b) * (c - d) / -(-e) - (f - g) + 2 ** (3 ** 4);\n\
+        y = (a || b) && c; y = typeof (a + b); y = (1).toFixed(2);\n\
+        (function () {})();\n\
+        ({ a: 1 }).a;\n\
+        if (a) { if (b) c(); } else d();\n\
+        for (let i = ('x' in o); i; ) break;\n";
+    let (program, errors) =
+        Parser::with_options(source, ParserOptions::module()).parse_with_recovery();
+    assert!(errors.is_empty(), "unexpected errors: {errors:?}");
+    let program = Node::Program(program);
+
+    for options in [
+        PrinterOptions::default(),
+        PrinterOptions::default()
+            .with_indent(Indent::Tabs)
+            .with_quote_style(QuoteStyle::Single),
+    ] {
+        let printed = Printer::new(options).print(&program);
+        let (reparsed, errors) =
+            Parser::with_options(&printed, ParserOptions::module()).parse_with_recovery();
+        assert!(errors.is_empty(), "errors {errors:?} in:\n{printed}");
+        let reparsed = Node::Program(reparsed);
+        assert_eq!(
+            without_spans(&reparsed),
+            without_spans(&program),
+            "{printed}"
+        );
+        // Printing is stable once the source has been through the printer.
+        assert_eq!(Printer::new(options).print(&reparsed), printed);
+    }
+}
+
+#[test]
+fn test_printer_options() {
+    let program = parse("function f(s) { if (s) { return \"a'b\"; } }").unwrap();
+
+    assert_eq!(
+        Printer::default().print(&program),
+        "function f(s) {\n  if (s) {\n    return \"a'b\";\n  }\n}\n"
+    );
+    let options = PrinterOptions::default()
+        .with_indent(Indent::Tabs)
+        .with_quote_style(QuoteStyle::Single);
+    assert_eq!(
+        Printer::new(options).print(&program),
+        "function f(s) {\n\tif (s) {\n\t\treturn 'a\\'b';\n\t}\n}\n"
+    );
+}
+
+#[test]
+fn test_printer_empty_statement() {
+    let program = parse(";\nif (a) ; else {}\nfor (;;) ;").unwrap();
+    let printed = Printer::default().print(&program);
+    assert_eq!(printed, ";\nif (a)\n  ;\nelse {}\nfor (;;)\n  ;\n");
+    assert_eq!(
+        without_spans(&parse(&printed).unwrap()),
+        without_spans(&program)
+    );
+
+    let json = to_estree_json(&program).unwrap();
+    let estree: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(estree["body"][0]["type"], "EmptyStatement");
+    assert_eq!(from_estree_json(&json).unwrap(), program);
+}
+
+#[test]
+fn test_printer_string_statements() {
+    let program = parse("(\"use strict\"); x; 'late';").unwrap();
+    let printed = Printer::default().print(&program);
+    assert_eq!(printed, "(\"use strict\");\nx;\n(\"late\");\n");
+    let reparsed = parse(&printed).unwrap();
+    assert_eq!(directive(&program_body(&printed)[0]), None);
+    assert_eq!(without_spans(&reparsed), without_spans(&program));
+
+    let printed = Printer::default().print(&parse("'use strict'; ('a');").unwrap());
+    assert_eq!(printed, "\"use strict\";\n(\"a\");\n");
+}
+
+#[test]
+fn test_unary_base_of_exponentiation() {
+    for source in [
+        "-x ** 2",
+        "!x ** 2",
+        "typeof x ** 2",
+        "async () => await x ** 2",
+    ] {
+        let error = parse(source).unwrap_err();
+        assert!(
+            error.to_string().contains("cannot be the base of '**'"),
+            "{source}: {error}"
+        );
+    }
+    for source in ["(-x) ** 2", "2 ** -x", "x++ ** 2", "-(x ** 2)"] {
+        let program = parse(source).unwrap();
+        let printed = Printer::default().print(&program);
+        assert_eq!(
+            without_spans(&parse(&printed).unwrap()),
+            without_spans(&program)
+        );
+    }
+}
+
+#[test]
+fn test_printer_source_map() {
+    let source = "let a = 1;\nfunction f(x) {\n    return x + a;\n}\n";
+    let program = parse(source).unwrap();
+    let (printed, mut map) = Printer::default().print_with_source_map(&program, "input.js");
+    assert_eq!(printed, "let a = 1;\nfunction f(x) {\n  return x + a;\n}\n");
+    map.file = Some("out.js".to_string());
+
+    // `x` in the return moved left by the re-indent.
+    let original = map.original_position(Position::new(3, 10)).unwrap();
+    assert_eq!(original.source, "input.js");
+    assert_eq!(original.position, Position::new(3, 12));
+    assert_eq!(original.name, Some("x"));
+    // Between nodes the closest mapping before the position applies.
+    let original = map.original_position(Position::new(3, 11)).unwrap();
+    assert_eq!(original.position, Position::new(3, 12));
+    assert_eq!(map.original_position(Position::new(9, 1)), None);
+
+    let json = map.to_json();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["version"], 3);
+    assert_eq!(value["file"], "out.js");
+    assert_eq!(value["sources"], serde_json::json!(["input.js"]));
+    assert_eq!(value["names"], serde_json::json!(["a", "f", "x"]));
+    assert!(value["mappings"]
+        .as_str()
+        .unwrap()
+        .starts_with("AAAA,IAAIA"));
+    assert_eq!(SourceMap::from_json(&json).unwrap(), map);
+    assert!(SourceMap::from_json(r#"{"version":3,"sources":[],"mappings":"A!"}"#).is_err());
+
+    let trace = "Error: boom\n    at f (/dist/out.js:3:10)\n    at lib.js:1:1";
+    assert_eq!(
+        map.translate_stack_trace(trace),
+        "Error: boom\n    at f (input.js:3:12)\n    at lib.js:1:1"
+    );
+}
+
+#[test]
+fn test_printer_source_map_counts_utf16_columns() {
+    let source = "f(\"\u{1F600}\", x);\n";
+    let program = parse(source).unwrap();
+    let (printed, map) = Printer::default().print_with_source_map(&program, "input.js");
+    assert_eq!(printed, source);
+
+    // The emoji is one char but two UTF-16 code units, so `x` is at
+    // generated column 9 while the parser puts it at column 8.
+    let x = map
+        .mappings
+        .iter()
+        .find(|mapping| {
+            mapping
+                .original
+                .is_some_and(|original| original.position == Position::new(1, 8))
+        })
+        .unwrap();
+    assert_eq!(x.generated, Position::new(1, 9));
+    assert_eq!(
+        map.original_position(Position::new(1, 9)).unwrap().name,
+        Some("x")
+    );
+}
+
+#[test]
+fn test_source_map_builder_encodes_vlq() {
+    let mut builder = SourceMapBuilder::new().with_file("out.js");
+    let source = builder.add_source("in.js", Some("abc"));
+    builder.add_mapping(Position::new(1, 1), source, Position::new(1, 1), None);
+    builder.add_mapping(
+        Position::new(1, 17),
+        source,
+        Position::new(40, 1),
+        Some("n"),
+    );
+    builder.add_unmapped(Position::new(3, 5));
+    let map = builder.build();
+
+    // 16 columns on is `gB`, 39 lines on is `uC` and the name is the
+    // trailing `A`; the empty line 2 leaves two `;` in a row.
+    assert_eq!(map.encode_mappings(), "AAAA,gBAuCAA;;I");
+    assert_eq!(map.sources_content, vec![Some("abc".to_string())]);
+    assert_eq!(SourceMap::from_json(&map.to_json()).unwrap(), map);
+}
+
+struct Rename<'a> {
+    from: &'a str,
+    to: &'a str,
+}
+
+impl VisitMut for Rename<'_> {
+    fn visit_mut_identifier(&mut self, id: &mut Identifier) {
+        if id.name == self.from {
+            id.name = self.to.to_string();
+        }
+    }
+
+    // `o.a` names a property, not the binding.
+    fn visit_mut_member_expression(&mut self, expr: &mut MemberExpression) {
+        self.visit_mut_node(&mut expr.object);
+        if expr.computed {
+            self.visit_mut_node(&mut expr.property);
+        }
+    }
+}
+
+#[test]
+fn test_visit_mut_rewrites_in_place() {
+    let mut program = parse("let a = 1; function f(a) { return a.a + o[a]; }").unwrap();
+    Rename { from: "a", to: "b" }.visit_mut_node(&mut program);
+    assert_eq!(
+        Printer::default().print(&program),
+        "let b = 1;\nfunction f(b) {\n  return b.a + o[b];\n}\n"
+    );
+
+    // Replacing the node itself from `visit_mut_node`.
+    struct NullToZero;
+    impl VisitMut for NullToZero {
+        fn visit_mut_node(&mut self, node: &mut Node) {
+            if let Node::Null(null) = node {
+                *node = Node::Number(NumberLiteral {
+                    value: 0.0,
+                    span: null.span.clone(),
+                });
+            }
+            self.visit_mut_children(node);
+        }
+    }
+    let mut program = parse("f(null, [null, g(null)]);").unwrap();
+    NullToZero.visit_mut_node(&mut program);
+    assert_eq!(Printer::default().print(&program), "f(0, [0, g(0)]);\n");
+}
+
+// Folds additions and multiplications of number literals, bottom up.
+struct ConstantFolder;
+
+impl Fold for ConstantFolder {
+    fn fold_binary_expression(&mut self, expr: BinaryExpression) -> Node {
+        let left = self.fold_node(*expr.left);
+        let right = self.fold_node(*expr.right);
+        match (&left, expr.operator.as_str(), &right) {
+            (Node::Number(l), "+", Node::Number(r)) => Node::Number(NumberLiteral {
+                value: l.value + r.value,
+                span: expr.span,
+            }),
+            (Node::Number(l), "*", Node::Number(r)) => Node::Number(NumberLiteral {
+                value: l.value * r.value,
+                span: expr.span,
+            }),
+            _ => Node::BinaryExpression(BinaryExpression {
+                left: Box::new(left),
+                right: Box::new(right),
+                ..expr
+            }),
+        }
+    }
+}
+
+#[test]
+fn test_fold_replaces_nodes() {
+    let program = parse("let x = 1 + 2 * 3; if (y) { g(x * (4 + 1), y + 1); }").unwrap();
+    let folded = ConstantFolder.fold_node(program);
+    assert_eq!(
+        Printer::default().print(&folded),
+        "let x = 7;\nif (y) {\n  g(x * 5, y + 1);\n}\n"
+    );
+
+    // The default methods rebuild every node unchanged.
+    struct Identity;
+    impl Fold for Identity {}
+    let source = "class A extends B { static #x = 1; m(a) { for (const k of a) { \
+                  switch (k) { case 1: break; default: throw new Error(`${k}`); } } } } \
+                  label: while (x) { try { continue label; } catch (e) { x = [, e]; } }";
+    assert!(!has_errors(source));
+    let program = parse(source).unwrap();
+    assert_eq!(Identity.fold_node(program.clone()), program);
+}
+
+#[cfg(feature = "jsx")]
+fn parse_jsx(source: &str) -> Node {
+    let program = parse(source).unwrap();
+    let Node::Program(program) = program else {
+        panic!("expected a program");
+    };
+    match program.body.into_iter().next() {
+        Some(Node::ExpressionStatement(stmt)) => *stmt.expression,
+        other => panic!("expected an expression statement, got {other:?}"),
+    }
+}
+
+#[cfg(feature = "jsx")]
+#[test]
+fn test_jsx_element_parsing() {
+    let Node::JsxElement(element) =
+        parse_jsx(r#"<div className="a &amp; b" {...p} hidden data-x={1}>hi {x}<b/>{}</div>;"#)
+    else {
+        panic!("expected a JSX element");
+    };
+    assert_eq!(identifier_name(&element.name), Some("div"));
+    assert!(!element.self_closing);
+    assert_eq!(element.attributes.len(), 4);
+
+    let Node::JsxAttribute(class_name) = &element.attributes[0] else {
+        panic!("expected an attribute");
+    };
+    assert_eq!(identifier_name(&class_name.name), Some("className"));
+    assert_eq!(
+        class_name.value.as_deref().and_then(string_value),
+        Some("a & b")
+    );
+    assert!(matches!(
+        &element.attributes[1],
+        Node::JsxSpreadAttribute(spread) if identifier_name(&spread.argument) == Some("p")
+    ));
+    assert!(matches!(
+        &element.attributes[2],
+        Node::JsxAttribute(attr) if attr.value.is_none()
+    ));
+    assert!(matches!(
+        &element.attributes[3],
+        Node::JsxAttribute(attr) if identifier_name(&attr.name) == Some("data-x")
+    ));
+
+    assert_eq!(element.children.len(), 4);
+    assert!(matches!(&element.children[0], Node::JsxText(text) if text.value == "hi "));
+    assert!(matches!(
+        &element.children[1],
+        Node::JsxExpressionContainer(container) if container.expression.is_some()
+    ));
+    assert!(matches!(
+        &element.children[2],
+        Node::JsxElement(child) if child.self_closing
+    ));
+    assert!(matches!(
+        &element.children[3],
+        Node::JsxExpressionContainer(container) if container.expression.is_none()
+    ));
+}
+
+#[cfg(feature = "jsx")]
+#[test]
+fn test_jsx_names_fragments_and_errors() {
+    let Node::JsxElement(element) = parse_jsx("<a.b.c xlink:href='u'></a.b.c>;") else {
+        panic!("expected a JSX element");
+    };
+    assert!(matches!(&*element.name, Node::MemberExpression(_)));
+    let Node::JsxAttribute(attr) = &element.attributes[0] else {
+        panic!("expected an attribute");
+    };
+    assert!(matches!(&*attr.name, Node::JsxNamespacedName(_)));
+
+    let Node::JsxFragment(fragment) = parse_jsx("<>a<i>b</i>&#x41;&#66;</>;") else {
+        panic!("expected a JSX fragment");
+    };
+    assert_eq!(fragment.children.len(), 3);
+    assert!(matches!(&fragment.children[2], Node::JsxText(text) if text.value == "AB"));
+
+    // Comparisons still parse as comparisons outside of JSX positions.
+    assert!(!has_errors("a < b > c;"));
+
+    assert!(has_errors("<a></b>;"));
+    assert!(has_errors("<a>text"));
+    assert!(has_errors("<a b={}/>;"));
+    assert!(has_errors("<a b='x/>;"));
+}
+
+#[cfg(feature = "jsx")]
+#[test]
+fn test_jsx_lowering() {
+    use jetcrab::ast::{lower_jsx, JsxOptions};
+
+    let lower = |source: &str, options: &JsxOptions| {
+        let program = parse(source).unwrap();
+        Printer::new(PrinterOptions::default()).print(&lower_jsx(&program, options))
+    };
+    let options = JsxOptions::default();
+
+    assert_eq!(
+        lower(
+            r#"<div className="a" {...p}>hi {x}<b/></div>;"#,
+            &options
+        ),
+        "React.createElement(\"div\", Object.assign({}, { className: \"a\" }, p), \"hi \", x, React.createElement(\"b\", null));\n"
+    );
+    assert_eq!(
+        lower("<Foo.Bar a b-c={1} />;", &options),
+        "React.createElement(Foo.Bar, { a: true, \"b-c\": 1 });\n"
+    );
+    assert_eq!(
+        lower("<>\n  line one\n  line two\n  {/* c */}\n</>;", &options),
+        "React.createElement(React.Fragment, null, \"line one line two\");\n"
+    );
+    assert_eq!(
+        lower(
+            "<Comp>{a}</Comp>;",
+            &JsxOptions::default()
+                .with_pragma("h")
+                .with_pragma_frag("Fragment")
+        ),
+        "h(Comp, null, a);\n"
+    );
+}
+
+#[cfg(feature = "jsx")]
+#[test]
+fn test_jsx_printer_and_estree_round_trip() {
+    let source = "let el = <a.b x=\"1\" y={2} {...z}>\n  text <c:d /> {e}\n</a.b>;\nlet f = <>\n  <i />\n</>;\n";
+    let program = parse(source).unwrap();
+    let printed = Printer::new(PrinterOptions::default()).print(&program);
+    assert_eq!(printed, source);
+
+    let json = to_estree_json(&program).unwrap();
+    assert!(json.contains("\"JSXElement\""));
+    assert!(json.contains("\"JSXMemberExpression\""));
+    assert!(json.contains("\"JSXNamespacedName\""));
+    assert!(json.contains("\"JSXFragment\""));
+    let imported = from_estree_json(&json).unwrap();
+    assert_eq!(without_spans(&imported), without_spans(&program));
+}
+
+#[cfg(feature = "jsx")]
+#[test]
+fn test_estree_round_trips_jsx() {
+    let body = estree_round_trip(
+        "<a.b x=\"1\" y={2} {...z} c:d>text {e}{/* note */}<i /></a.b>;\n<>\n  <c:d />\n</>;\n",
+        ParserOptions::default(),
+    );
+    let element = &body[0]["expression"];
+    assert_eq!(element["type"], "JSXElement");
+    let opening = &element["openingElement"];
+    assert_eq!(opening["name"]["type"], "JSXMemberExpression");
+    assert_eq!(opening["name"]["object"]["type"], "JSXIdentifier");
+    assert_eq!(opening["selfClosing"], false);
+    assert_eq!(
+        estree_types(&opening["attributes"]),
+        [
+            "JSXAttribute",
+            "JSXAttribute",
+            "JSXSpreadAttribute",
+            "JSXAttribute"
+        ]
+    );
+    assert_eq!(opening["attributes"][0]["value"]["value"], "1");
+    assert_eq!(
+        opening["attributes"][1]["value"]["type"],
+        "JSXExpressionContainer"
+    );
+    assert_eq!(
+        opening["attributes"][3]["name"]["type"],
+        "JSXNamespacedName"
+    );
+    assert_eq!(opening["attributes"][3]["value"], serde_json::Value::Null);
+    assert_eq!(element["closingElement"]["name"]["property"]["name"], "b");
+
+    let children = &element["children"];
+    assert_eq!(
+        estree_types(children),
+        [
+            "JSXText",
+            "JSXExpressionContainer",
+            "JSXExpressionContainer",
+            "JSXElement"
+        ]
+    );
+    assert_eq!(children[0]["value"], "text ");
+    assert_eq!(children[2]["expression"]["type"], "JSXEmptyExpression");
+    assert_eq!(children[3]["openingElement"]["selfClosing"], true);
+    assert_eq!(children[3]["closingElement"], serde_json::Value::Null);
+
+    let fragment = &body[1]["expression"];
+    assert_eq!(fragment["type"], "JSXFragment");
+    assert_eq!(
+        estree_types(&fragment["children"]),
+        ["JSXText", "JSXElement", "JSXText"]
+    );
+}
+
+#[cfg(feature = "typescript")]
+fn parse_typescript(source: &str) -> (Node, Vec<ParserError>) {
+    let (program, errors) =
+        Parser::with_options(source, ParserOptions::module().with_typescript(true))
+            .parse_with_recovery();
+    (Node::Program(program), errors)
+}
+
+#[cfg(feature = "typescript")]
+#[test]
+fn test_typescript_types_are_stripped() {
+    let typescript = r#"
+        import type { A } from "./a";
+        import { type B, c } from "./b";
+        export type { A };
+        interface Point<T = number> extends Base<T> { x: T; y?: T; m(a: string): void; }
+        type Pair<K, V> = [K, V] | { key: K; value: V };
+        type Cond<T> = T extends Array<infer U> ? U : (a: T) => `x${string}`;
+        declare const VERSION: string;
+        declare module "m" { export const x: number; }
+        export declare class D { m(): void; }
+        declare enum Color { Red }
+        function id<T extends object>(x: T, y?: number): T { return x; }
+        function over(a: string): string;
+        function over(a: any): any { return a; }
+        let n: number = 1, m!: Map<string, Array<Array<number>>>;
+        const f = <T,>(a: T): T => a;
+        const g = async (a: number, b: string): Promise<void> => {};
+        let v = (n as unknown) as string;
+        let w = { a: 1 } satisfies Record<string, number>;
+        let call = id<{ a: number }>(obj) + new Map<string, number>().size;
+        let nn = maybe!.value!;
+        let cmp = a < b && c > d >> 2;
+        export abstract class Shape<T> extends Base<T> implements I, J<T> {
+          private readonly name: string = "s";
+          public static count: number;
+          protected abstract area(): number;
+          declare tag: string;
+          [key: string]: unknown;
+          get label(): string { return this.name; }
+          m<U>(this: Shape<T>, u?: U): asserts u is U {}
+          isS(x: unknown): x is string { return typeof x === "string"; }
+          optional?(): void;
+        }
+        try { f(1); } catch (e: unknown) {}
+    "#;
+    let javascript = r#"
+        import { c } from "./b";
+        function id(x, y) { return x; }
+        function over(a) { return a; }
+        let n = 1, m;
+        const f = (a) => a;
+        const g = async (a, b) => {};
+        let v = n;
+        let w = { a: 1 };
+        let call = id(obj) + new Map().size;
+        let nn = maybe.value;
+        let cmp = a < b && c > d >> 2;
+        export class Shape extends Base {
+          name = "s";
+          static count;
+          get label() { return this.name; }
+          m(u) {}
+          isS(x) { return typeof x === "string"; }
+        }
+        try { f(1); } catch (e) {}
+    "#;
+
+    let (stripped, errors) = parse_typescript(typescript);
+    assert!(errors.is_empty(), "unexpected errors: {errors:?}");
+    let expected = Parser::with_options(javascript, ParserOptions::module())
+        .parse()
+        .unwrap();
+    assert_eq!(without_spans(&stripped), without_spans(&expected));
+}
+
+#[cfg(feature = "typescript")]
+#[test]
+fn test_typescript_rejects_non_erasable_syntax() {
+    let has_typescript_errors = |source: &str| !parse_typescript(source).1.is_empty();
+
+    assert!(has_typescript_errors("enum E { A }"));
+    assert!(has_typescript_errors("export const enum E { A }"));
+    assert!(has_typescript_errors("namespace N {}"));
+    assert!(has_typescript_errors(
+        "class A { constructor(private x: number) {} }"
+    ));
+    assert!(has_typescript_errors("let x: = 1;"));
+
+    // Contextual keywords keep working as names, and without the option
+    // type syntax is an error as before.
+    assert!(!has_typescript_errors(
+        "let type = 1; type = declare; abstract();"
+    ));
+    assert!(has_errors("let x: number = 1;"));
+    assert!(!has_errors("f(a < b, c > (d));"));
+}
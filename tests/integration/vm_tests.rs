@@ -1,4 +1,4 @@
-use jetcrab::vm::{Bytecode, Executor, Instruction, Value};
+use jetcrab::vm::{Bytecode, Executor, FunctionHandle, Instruction, ObjectHandle, Value};
 
 #[test]
 fn test_execute_basic_arithmetic() {
@@ -316,3 +316,71 @@ fn test_execute_number_string_concatenation() {
         vec![Value::String("42 is the answer".to_string())]
     );
 }
+
+#[test]
+fn test_execute_for_of_over_array() {
+    let mut exec = Executor::new();
+    let bytecode = Bytecode::new(vec![
+        Instruction::PushConst(0.into()),
+        Instruction::StoreLocal(0.into()),
+        Instruction::PushConst(1.into()),
+        Instruction::PushConst(2.into()),
+        Instruction::NewArray(2.into()),
+        Instruction::GetIterator,
+        Instruction::IteratorNext(11.into()),
+        Instruction::LoadLocal(0.into()),
+        Instruction::Add,
+        Instruction::StoreLocal(0.into()),
+        Instruction::Jump(6.into()),
+        Instruction::LoadLocal(0.into()),
+    ]);
+    let constants = vec![Value::Number(0.0), Value::Number(3.0), Value::Number(4.0)];
+    exec.execute(&bytecode, &constants);
+
+    assert_eq!(exec.stack.values, vec![Value::Number(7.0)]);
+    assert!(exec.iterators.is_empty());
+}
+
+#[test]
+fn test_execute_for_of_calls_next_method() {
+    let mut exec = Executor::new();
+    let next = exec.heap.alloc_function(
+        Bytecode::new(vec![
+            Instruction::NewObject,
+            Instruction::Dup,
+            Instruction::PushConst(0.into()),
+            Instruction::PushTrue,
+            Instruction::SetProperty,
+            Instruction::Return,
+        ]),
+        0.into(),
+        0.into(),
+    );
+    let iterator = exec.heap.alloc_object();
+    exec.heap.set_object_property(
+        iterator,
+        "next".to_string(),
+        Value::Function(FunctionHandle::new(next)),
+    );
+    exec.globals[0] = Value::Object(ObjectHandle::new(iterator));
+
+    let bytecode = Bytecode::new(vec![
+        Instruction::LoadGlobal(0.into()),
+        Instruction::GetIterator,
+        Instruction::IteratorNext(5.into()),
+        Instruction::Pop,
+        Instruction::Jump(2.into()),
+        Instruction::PushConst(1.into()),
+    ]);
+    let constants = vec![
+        Value::String("done".to_string()),
+        Value::String("finished".to_string()),
+    ];
+    exec.execute(&bytecode, &constants);
+
+    assert_eq!(
+        exec.stack.values,
+        vec![Value::String("finished".to_string())]
+    );
+    assert!(exec.iterators.is_empty());
+}